@@ -1,21 +1,32 @@
 extern crate clap;
 use clap::{Arg, ArgAction, Command};
 
+use crate::auth::RateLimits;
+
 pub struct Config {
     pub genesis: Option<String>,
     pub namespace: String,
+    pub sov_namespace: String,
     #[cfg(unix)]
     pub socket: Option<String>,
     pub host: Option<String>,
     pub port: Option<u16>,
     pub init_refresh: bool,
     pub interval_refresh: u32,
+    pub interval_reload: u32,
     pub is_multiple: bool,
     pub tls_cert_path: Option<String>,
     pub tls_key_path: Option<String>,
     pub cache: bool,
     pub cache_size: usize,
     pub cache_path: Option<String>,
+    pub cache_url: Option<String>,
+    pub cache_prefix: Option<String>,
+    pub cache_current_ttl: Option<u64>,
+    pub tls_client_ca_path: Option<String>,
+    pub api_keys_path: Option<String>,
+    pub admin_keys_path: Option<String>,
+    pub rate_limits: RateLimits,
 }
 
 pub fn load_config() -> Result<Config, String> {
@@ -39,6 +50,12 @@ pub fn load_config() -> Result<Config, String> {
                 .value_name("NAMESPACE")
                 .help("Namespace of ledger for DID resolution. Only needed if not multiple-ledgers")
         )
+        .arg(
+            Arg::new("sov-namespace")
+                .long("sov-namespace")
+                .value_name("NAMESPACE")
+                .help("Namespace of the ledger resolving did:sov and unqualified DIDs with multiple-ledgers. Defaults to `sovrin`")
+        )
         .arg(
             Arg::new("multiple-ledgers")
                 .action(ArgAction::SetTrue)
@@ -73,6 +90,12 @@ pub fn load_config() -> Result<Config, String> {
                 .value_name("INTERVAL")
                 .help("Set the interval in minutes between validator node refresh attempts (0 to disable refresh, default 120)"),
         )
+        .arg(
+            Arg::new("reload-interval")
+                .long("reload-interval")
+                .value_name("INTERVAL")
+                .help("Set the interval in minutes between reloads of the networks folder or repository in multiple-ledger mode (0 to disable reload, default 0)"),
+        )
         .arg(
             Arg::new("tls-cert")
                 .long("tls-cert")
@@ -84,6 +107,37 @@ pub fn load_config() -> Result<Config, String> {
                 .long("tls-key")
                 .value_name("KEY")
                 .help("Path to the TLS private key file")
+        ).arg(
+            Arg::new("tls-client-ca")
+                .long("tls-client-ca")
+                .value_name("CA")
+                .help("Path to the CA certificate used to verify TLS client certificates. Client certificates are required when set")
+        ).arg(
+            Arg::new("api-keys")
+                .long("api-keys")
+                .value_name("API_KEYS")
+                .help("Path to a file listing a client identifier and API key on each line. API keys are required when set")
+        ).arg(
+            Arg::new("admin-keys")
+                .long("admin-keys")
+                .value_name("ADMIN_KEYS")
+                .help("Path to a file listing a client identifier and API key on each line, for the keys permitted to reload ledgers")
+        ).arg(
+            Arg::new("rate-limit")
+                .long("rate-limit")
+                .value_name("LIMIT")
+                .help("Set the maximum number of read requests per minute for each client")
+        ).arg(
+            Arg::new("write-rate-limit")
+                .long("write-rate-limit")
+                .value_name("LIMIT")
+                .help("Set the maximum number of write requests per minute for each client")
+        ).arg(
+            Arg::new("route-rate-limit")
+                .long("route-rate-limit")
+                .value_name("ROUTE=LIMIT")
+                .action(ArgAction::Append)
+                .help("Set the maximum number of requests per minute for each client to a route, such as `submit=10`")
         ).arg(
             Arg::new("use-cache")
                 .long("use-cache").action(ArgAction::SetTrue)
@@ -99,6 +153,21 @@ pub fn load_config() -> Result<Config, String> {
                 .long("cache-path")
                 .value_name("CACHE_PATH")
                 .help("Path to cache")
+        ).arg(
+            Arg::new("cache-url")
+                .long("cache-url")
+                .value_name("CACHE_URL")
+                .help("URL of a Redis server to share the cache between proxy instances, such as `redis://localhost:6379/0`")
+        ).arg(
+            Arg::new("cache-prefix")
+                .long("cache-prefix")
+                .value_name("CACHE_PREFIX")
+                .help("Prefix for the keys of cached responses")
+        ).arg(
+            Arg::new("cache-current-ttl")
+                .long("cache-current-ttl")
+                .value_name("SECONDS")
+                .help("Time to cache responses for the current state of mutable ledger objects, such as NYMs and revocation registries (default 60)")
         );
 
     #[cfg(unix)]
@@ -129,6 +198,11 @@ pub fn load_config() -> Result<Config, String> {
         .cloned()
         .unwrap_or_else(|| "test".to_string());
 
+    let sov_namespace = matches
+        .get_one::<String>("sov-namespace")
+        .cloned()
+        .unwrap_or_else(|| "sovrin".to_string());
+
     let is_multiple = matches.get_flag("multiple-ledgers");
 
     if matches.contains_id("socket") {
@@ -154,6 +228,11 @@ pub fn load_config() -> Result<Config, String> {
         .map(|ival| ival.parse::<u32>().map_err(|_| "Invalid refresh interval"))
         .transpose()?
         .unwrap_or(120);
+    let interval_reload = matches
+        .get_one::<String>("reload-interval")
+        .map(|ival| ival.parse::<u32>().map_err(|_| "Invalid reload interval"))
+        .transpose()?
+        .unwrap_or(0);
 
     let tls_cert_path = matches.get_one::<String>("tls-cert").cloned();
     let tls_key_path = matches.get_one::<String>("tls-key").cloned();
@@ -164,21 +243,64 @@ pub fn load_config() -> Result<Config, String> {
         .transpose()?
         .unwrap_or(1000);
     let cache_path = matches.get_one::<String>("cache-path").cloned();
+    let cache_url = matches.get_one::<String>("cache-url").cloned();
+    let cache_prefix = matches.get_one::<String>("cache-prefix").cloned();
+    let cache_current_ttl = matches
+        .get_one::<String>("cache-current-ttl")
+        .map(|ival| ival.parse::<u64>().map_err(|_| "Invalid cache TTL"))
+        .transpose()?;
+    if cache_url.is_some() && cache_path.is_some() {
+        return Err("Only one of --cache-path and --cache-url may be used".to_owned());
+    }
+    let tls_client_ca_path = matches.get_one::<String>("tls-client-ca").cloned();
+    let api_keys_path = matches.get_one::<String>("api-keys").cloned();
+    let admin_keys_path = matches.get_one::<String>("admin-keys").cloned();
+    let parse_limit = |name: &str| {
+        matches
+            .get_one::<String>(name)
+            .map(|limit| {
+                limit
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| format!("Invalid {}", name))
+            })
+            .transpose()
+    };
+    let rate_limits = RateLimits {
+        read: parse_limit("rate-limit")?,
+        write: parse_limit("write-rate-limit")?,
+        routes: matches
+            .get_many::<String>("route-rate-limit")
+            .into_iter()
+            .flatten()
+            .map(|limit| RateLimits::parse_route_limit(limit))
+            .collect::<Result<_, _>>()?,
+    };
 
     Ok(Config {
         genesis,
         namespace,
+        sov_namespace,
         #[cfg(unix)]
         socket,
         host,
         port,
         init_refresh,
         interval_refresh,
+        interval_reload,
         is_multiple,
         tls_cert_path,
         tls_key_path,
         cache,
         cache_size,
         cache_path,
+        cache_url,
+        cache_prefix,
+        cache_current_ttl,
+        tls_client_ca_path,
+        api_keys_path,
+        admin_keys_path,
+        rate_limits,
     })
 }
@@ -0,0 +1,461 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use hyper::header::{HeaderMap, AUTHORIZATION};
+use hyper::server::conn::AddrStream;
+#[cfg(feature = "tls")]
+use sha2::{Digest, Sha256};
+
+/// The maximum number of tracked rate limit buckets. Idle buckets are discarded
+/// first, followed by the least recently used.
+const MAX_RATE_BUCKETS: usize = 10000;
+
+/// The header used to provide an API key
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Details of the connection used by a client
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+    /// The remote IP address
+    pub address: Option<IpAddr>,
+    /// The hex-encoded SHA-256 fingerprint of a verified TLS client certificate
+    pub certificate: Option<String>,
+}
+
+/// Extract the details of a client connection
+pub trait ConnectionInfo {
+    fn client_info(&self) -> ClientInfo;
+}
+
+impl ConnectionInfo for AddrStream {
+    fn client_info(&self) -> ClientInfo {
+        ClientInfo {
+            address: Some(self.remote_addr().ip()),
+            certificate: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl ConnectionInfo for tokio::net::UnixStream {
+    fn client_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+#[cfg(feature = "tls")]
+impl ConnectionInfo for tokio_rustls::server::TlsStream<tokio::net::TcpStream> {
+    fn client_info(&self) -> ClientInfo {
+        let (stream, conn) = self.get_ref();
+        let certificate = conn
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .map(|cert| {
+                Sha256::digest(&cert.0)
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect()
+            });
+        ClientInfo {
+            address: stream.peer_addr().ok().map(|addr| addr.ip()),
+            certificate,
+        }
+    }
+}
+
+/// Token bucket rate limits applied to each client, in requests per minute
+#[derive(Clone, Debug, Default)]
+pub struct RateLimits {
+    /// The limit for read requests
+    pub read: Option<u32>,
+    /// The limit for write requests, which prepare or submit transactions
+    pub write: Option<u32>,
+    /// Additional limits for specific routes
+    pub routes: HashMap<String, u32>,
+}
+
+impl RateLimits {
+    /// Parse a route limit in the format `ROUTE=LIMIT`
+    pub fn parse_route_limit(value: &str) -> Result<(String, u32), String> {
+        value
+            .split_once('=')
+            .and_then(|(route, limit)| Some((route.to_owned(), limit.parse::<u32>().ok()?)))
+            .filter(|(route, limit)| !route.is_empty() && *limit > 0)
+            .ok_or_else(|| format!("Invalid route rate limit: {}", value))
+    }
+}
+
+/// The reason a request was refused
+#[derive(Debug, PartialEq)]
+pub enum AccessDenied {
+    /// No valid credentials were provided
+    Unauthorized,
+    /// The credentials provided do not permit the request
+    Forbidden,
+    /// The client exceeded a rate limit, and may retry after the given delay
+    RateLimited(Duration),
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        Self {
+            tokens: per_minute as f64,
+            updated: now,
+        }
+    }
+
+    /// Add the tokens accumulated since the last update, returning the delay
+    /// until a token is available
+    fn refill(&mut self, per_minute: u32, now: Instant) -> Option<Duration> {
+        let rate = per_minute as f64 / 60.0;
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(per_minute as f64);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+/// Client authentication and rate limiting for proxy requests
+#[derive(Debug, Default)]
+pub struct AccessControl {
+    api_keys: Option<HashMap<String, String>>,
+    admin_keys: Option<HashMap<String, String>>,
+    limits: RateLimits,
+    buckets: RefCell<HashMap<(String, String), TokenBucket>>,
+}
+
+impl AccessControl {
+    /// Create a new instance, requiring an API key if `api_keys` is provided.
+    /// The keys are mapped to client identifiers.
+    pub fn new(api_keys: Option<HashMap<String, String>>, limits: RateLimits) -> Self {
+        Self {
+            api_keys,
+            admin_keys: None,
+            limits,
+            buckets: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Set the API keys permitted to perform administrative requests, which are
+    /// refused when no admin keys are provided
+    pub fn with_admin_keys(mut self, admin_keys: Option<HashMap<String, String>>) -> Self {
+        self.admin_keys = admin_keys;
+        self
+    }
+
+    /// Load API keys from a file containing a client identifier and key on each line
+    pub fn load_api_keys(path: &str) -> Result<HashMap<String, String>, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Error reading API keys file: {}", err))?;
+        let mut keys = HashMap::new();
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(client_id), Some(key), None) => {
+                    if keys.insert(key.to_owned(), client_id.to_owned()).is_some() {
+                        return Err(format!("Duplicate API key for client: {}", client_id));
+                    }
+                }
+                _ => return Err("Invalid line in API keys file".to_owned()),
+            }
+        }
+        if keys.is_empty() {
+            return Err("No API keys found in file".to_owned());
+        }
+        Ok(keys)
+    }
+
+    /// Authenticate a request and apply the rate limits for the client, returning
+    /// the client identifier
+    pub fn check(
+        &self,
+        client: &ClientInfo,
+        headers: &HeaderMap,
+        route: &str,
+        is_write: bool,
+    ) -> Result<String, AccessDenied> {
+        let client_id = self.identify(client, headers)?;
+        let class_limit = if is_write {
+            ("class:write".to_owned(), self.limits.write)
+        } else {
+            ("class:read".to_owned(), self.limits.read)
+        };
+        let route_limit = (
+            format!("route:{}", route),
+            self.limits.routes.get(route).copied(),
+        );
+        let limits = [class_limit, route_limit]
+            .into_iter()
+            .filter_map(|(name, limit)| Some((name, limit?)))
+            .collect::<Vec<_>>();
+        if limits.is_empty() {
+            return Ok(client_id);
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.borrow_mut();
+        let mut retry_after = None;
+        for (name, limit) in &limits {
+            let key = (client_id.clone(), name.clone());
+            if !buckets.contains_key(&key) {
+                _make_room(&mut buckets, now);
+            }
+            let bucket = buckets
+                .entry(key)
+                .or_insert_with(|| TokenBucket::new(*limit, now));
+            if let Some(delay) = bucket.refill(*limit, now) {
+                retry_after = retry_after.max(Some(delay));
+            }
+        }
+        if let Some(delay) = retry_after {
+            return Err(AccessDenied::RateLimited(delay));
+        }
+        for (name, _) in &limits {
+            if let Some(bucket) = buckets.get_mut(&(client_id.clone(), name.clone())) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(client_id)
+    }
+
+    /// Authenticate an administrative request, returning the client identifier
+    pub fn check_admin(&self, headers: &HeaderMap) -> Result<String, AccessDenied> {
+        let admin_keys = self.admin_keys.as_ref().ok_or(AccessDenied::Forbidden)?;
+        match _request_key(headers) {
+            Some(key) => admin_keys
+                .get(key)
+                .map(|client_id| format!("admin:{}", client_id))
+                .ok_or(AccessDenied::Forbidden),
+            None => Err(AccessDenied::Unauthorized),
+        }
+    }
+
+    fn identify(&self, client: &ClientInfo, headers: &HeaderMap) -> Result<String, AccessDenied> {
+        if let Some(api_keys) = &self.api_keys {
+            return _request_key(headers)
+                .and_then(|key| api_keys.get(key))
+                .map(|client_id| format!("key:{}", client_id))
+                .ok_or(AccessDenied::Unauthorized);
+        }
+        if let Some(cert) = &client.certificate {
+            return Ok(format!("cert:{}", cert));
+        }
+        Ok(client
+            .address
+            .map(|addr| format!("ip:{}", addr))
+            .unwrap_or_else(|| "local".to_owned()))
+    }
+}
+
+/// Get the API key provided in the request headers
+fn _request_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(API_KEY_HEADER)
+        .and_then(|key| key.to_str().ok())
+        .or_else(|| {
+            headers
+                .get(AUTHORIZATION)
+                .and_then(|auth| auth.to_str().ok())
+                .and_then(|auth| auth.strip_prefix("Bearer "))
+        })
+        .map(str::trim)
+}
+
+/// Ensure there is room for a new rate limit bucket
+fn _make_room(buckets: &mut HashMap<(String, String), TokenBucket>, now: Instant) {
+    if buckets.len() < MAX_RATE_BUCKETS {
+        return;
+    }
+    // buckets which have been idle for a minute are full and can be recreated
+    buckets.retain(|_, bucket| {
+        now.saturating_duration_since(bucket.updated) < Duration::from_secs(60)
+    });
+    if buckets.len() >= MAX_RATE_BUCKETS {
+        let oldest = buckets
+            .iter()
+            .min_by_key(|(_, bucket)| bucket.updated)
+            .map(|(key, _)| key.clone());
+        if let Some(oldest) = oldest {
+            buckets.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use hyper::header::HeaderValue;
+
+    use super::*;
+
+    fn _client(addr: &str) -> ClientInfo {
+        ClientInfo {
+            address: Some(addr.parse().unwrap()),
+            certificate: None,
+        }
+    }
+
+    fn _api_keys() -> HashMap<String, String> {
+        [("secret-a".to_owned(), "alice".to_owned())]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn token_bucket_refills_over_time() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(60, now);
+        assert_eq!(bucket.refill(60, now), None);
+        bucket.tokens = 0.5;
+        assert_eq!(bucket.refill(60, now), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.refill(60, now + Duration::from_millis(500)), None);
+        assert_eq!(bucket.tokens, 1.0);
+        // tokens do not accumulate above the limit
+        bucket.refill(60, now + Duration::from_secs(600));
+        assert_eq!(bucket.tokens, 60.0);
+    }
+
+    #[test]
+    fn load_api_keys_from_file() {
+        let path = temp_dir().join(format!("vdr-proxy-keys-{:020}", rand::random::<u64>()));
+        let path_str = path.to_str().unwrap();
+        fs::write(&path, "# client key\nalice secret-a\n\n  bob secret-b  \n").unwrap();
+        let keys = AccessControl::load_api_keys(path_str).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys["secret-b"], "bob");
+
+        fs::write(&path, "alice secret-a\nbob secret-a\n").unwrap();
+        assert!(AccessControl::load_api_keys(path_str).is_err());
+        fs::write(&path, "alice secret-a extra\n").unwrap();
+        assert!(AccessControl::load_api_keys(path_str).is_err());
+        fs::write(&path, "# no keys\n").unwrap();
+        assert!(AccessControl::load_api_keys(path_str).is_err());
+        fs::remove_file(&path).unwrap();
+        assert!(AccessControl::load_api_keys(path_str).is_err());
+    }
+
+    #[test]
+    fn identify_clients() {
+        let access = AccessControl::new(Some(_api_keys()), RateLimits::default());
+        let client = _client("10.0.0.1");
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            access.identify(&client, &headers),
+            Err(AccessDenied::Unauthorized)
+        );
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret-a"));
+        assert_eq!(access.identify(&client, &headers).unwrap(), "key:alice");
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("secret-b"));
+        assert_eq!(
+            access.identify(&client, &headers),
+            Err(AccessDenied::Unauthorized)
+        );
+
+        let access = AccessControl::new(None, RateLimits::default());
+        assert_eq!(access.identify(&client, &headers).unwrap(), "ip:10.0.0.1");
+        let client = ClientInfo {
+            certificate: Some("abcd".to_owned()),
+            ..client
+        };
+        assert_eq!(access.identify(&client, &headers).unwrap(), "cert:abcd");
+        assert_eq!(
+            access.identify(&ClientInfo::default(), &headers).unwrap(),
+            "local"
+        );
+    }
+
+    #[test]
+    fn admin_requests_require_admin_key() {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("secret-a"));
+        // admin requests are refused when no admin keys are configured
+        let access = AccessControl::new(Some(_api_keys()), RateLimits::default());
+        assert_eq!(access.check_admin(&headers), Err(AccessDenied::Forbidden));
+        let access = AccessControl::new(None, RateLimits::default());
+        assert_eq!(access.check_admin(&headers), Err(AccessDenied::Forbidden));
+
+        let admin_keys = [("secret-admin".to_owned(), "ops".to_owned())]
+            .into_iter()
+            .collect();
+        let access = AccessControl::new(Some(_api_keys()), RateLimits::default())
+            .with_admin_keys(Some(admin_keys));
+        assert_eq!(access.check_admin(&headers), Err(AccessDenied::Forbidden));
+        assert_eq!(
+            access.check_admin(&HeaderMap::new()),
+            Err(AccessDenied::Unauthorized)
+        );
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("secret-admin"));
+        assert_eq!(access.check_admin(&headers).unwrap(), "admin:ops");
+        // admin keys are not accepted for other requests
+        assert_eq!(
+            access.identify(&_client("10.0.0.1"), &headers),
+            Err(AccessDenied::Unauthorized)
+        );
+    }
+
+    #[test]
+    fn route_limits_are_separate_from_class_limits() {
+        let limits = RateLimits {
+            read: Some(3),
+            write: None,
+            routes: [("read".to_owned(), 1)].into_iter().collect(),
+        };
+        let access = AccessControl::new(None, limits);
+        let client = _client("10.0.0.1");
+        let headers = HeaderMap::new();
+        assert!(access.check(&client, &headers, "read", false).is_ok());
+        assert!(matches!(
+            access.check(&client, &headers, "read", false),
+            Err(AccessDenied::RateLimited(_))
+        ));
+        // the refused request does not consume the class limit
+        assert!(access.check(&client, &headers, "status", false).is_ok());
+        assert!(access.check(&client, &headers, "status", false).is_ok());
+        assert!(matches!(
+            access.check(&client, &headers, "status", false),
+            Err(AccessDenied::RateLimited(_))
+        ));
+        // write requests are not limited, and other clients have their own buckets
+        assert!(access.check(&client, &headers, "submit", true).is_ok());
+        assert!(access
+            .check(&_client("10.0.0.2"), &headers, "read", false)
+            .is_ok());
+    }
+
+    #[test]
+    fn rate_limit_buckets_are_bounded() {
+        let limits = RateLimits {
+            read: Some(1),
+            ..RateLimits::default()
+        };
+        let access = AccessControl::new(None, limits);
+        let headers = HeaderMap::new();
+        let first = _client("10.0.0.1");
+        access.check(&first, &headers, "status", false).unwrap();
+        for idx in 0..MAX_RATE_BUCKETS as u32 {
+            let client = _client(&std::net::Ipv4Addr::from(0x0b00_0000 + idx).to_string());
+            access.check(&client, &headers, "status", false).unwrap();
+        }
+        let buckets = access.buckets.borrow();
+        assert_eq!(buckets.len(), MAX_RATE_BUCKETS);
+        // the least recently used bucket is evicted
+        assert!(!buckets.contains_key(&("ip:10.0.0.1".to_owned(), "class:read".to_owned())));
+    }
+}
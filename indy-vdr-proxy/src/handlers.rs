@@ -3,25 +3,49 @@ extern crate percent_encoding;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::UNIX_EPOCH;
+use std::time::{Instant, UNIX_EPOCH};
 
+use futures_channel::oneshot;
+use futures_util::pin_mut;
+use futures_util::stream::StreamExt;
 use hyper::{Body, Method, Request, Response, StatusCode};
 use indy_vdr::pool::cache::Cache;
 use percent_encoding::percent_decode_str;
 use regex::Regex;
 
 use super::AppState;
+use crate::auth::{AccessControl, AccessDenied, ClientInfo};
+use crate::metrics::Metrics;
+use crate::reload::PoolReloader;
+use crate::routes::{check_ledger_route, error_kind_name, find_server_route, openapi};
+use crate::utils::{PendingRequests, PENDING_REQUEST_TIMEOUT};
 use indy_vdr::common::error::prelude::*;
+use indy_vdr::ledger::constants::UpdateRole;
+#[cfg(feature = "rich_schema")]
+use indy_vdr::ledger::identifiers::RichSchemaId;
 use indy_vdr::ledger::identifiers::{CredentialDefinitionId, RevocationRegistryId, SchemaId};
-use indy_vdr::pool::helpers::{perform_get_txn, perform_ledger_request};
+use indy_vdr::ledger::requests::author_agreement::TxnAuthrAgrmtAcceptanceData;
+use indy_vdr::ledger::requests::cred_def::CredentialDefinition;
+use indy_vdr::ledger::requests::schema::Schema;
+use indy_vdr::pool::helpers::{
+    perform_get_txn, perform_get_txn_range, perform_get_txn_root, perform_ledger_request,
+};
 use indy_vdr::pool::{
-    LedgerType, Pool, PreparedRequest, RequestResult, RequestResultMeta, TimingResult,
+    LedgerType, LocalPool, Pool, PreparedRequest, RequestResult, RequestResultMeta, TimingResult,
+};
+use indy_vdr::resolver::did::{DidMethod, DidUrl};
+use indy_vdr::resolver::types::{
+    DidResolutionMetadata, ResolutionResult, ERROR_INTERNAL, ERROR_INVALID_DID,
+    ERROR_METHOD_NOT_SUPPORTED, ERROR_NOT_FOUND, ERROR_REPRESENTATION_NOT_SUPPORTED,
 };
-use indy_vdr::resolver::did::DidUrl;
 use indy_vdr::resolver::PoolResolver as Resolver;
-use indy_vdr::utils::did::DidValue;
+use indy_vdr::utils::base58;
+use indy_vdr::utils::did::{DidValue, DEFAULT_LIBINDY_DID};
 use indy_vdr::utils::Qualifiable;
 
+/// The maximum number of transactions returned by a single range request
+const MAX_TXN_RANGE: i32 = 1000;
+
 #[derive(PartialEq, Eq)]
 enum ResponseFormat {
     Html,
@@ -31,10 +55,12 @@ enum ResponseFormat {
 enum ResponseType {
     Genesis(String),
     Json(String),
-    RequestReply(String, Option<TimingResult>),
-    RequestFailed(VdrError, Option<TimingResult>),
+    RequestReply(String, RequestResultMeta),
+    RequestFailed(VdrError, RequestResultMeta),
     Status(StatusCode, String),
-    Resolver(String),
+    Error(StatusCode, VdrError),
+    Resolver(String, StatusCode),
+    Stream(Body),
 }
 
 impl<T> From<(RequestResult<T>, RequestResultMeta)> for ResponseType
@@ -44,32 +70,50 @@ where
     fn from(result: (RequestResult<T>, RequestResultMeta)) -> ResponseType {
         match result {
             (RequestResult::Reply(message), meta) => {
-                ResponseType::RequestReply(message.to_string(), meta.timing)
+                ResponseType::RequestReply(message.to_string(), meta)
             }
-            (RequestResult::Failed(err), meta) => ResponseType::RequestFailed(err, meta.timing),
+            (RequestResult::Failed(err), meta) => ResponseType::RequestFailed(err, meta),
         }
     }
 }
 
 impl From<VdrError> for ResponseType {
     fn from(err: VdrError) -> ResponseType {
-        let (errcode, msg) = convert_error(err);
-        ResponseType::Status(errcode, msg)
+        ResponseType::Error(error_status(&err), err)
     }
 }
 
-fn convert_error(err: VdrError) -> (StatusCode, String) {
-    let msg = err.to_string();
-    match err.into() {
-        VdrErrorKind::PoolRequestFailed(failed) => (StatusCode::BAD_REQUEST, failed),
-        VdrErrorKind::Input => (StatusCode::BAD_REQUEST, msg),
-        VdrErrorKind::PoolTimeout => (StatusCode::GATEWAY_TIMEOUT, msg),
-        VdrErrorKind::PoolNoConsensus => (StatusCode::CONFLICT, msg),
+fn error_status(err: &VdrError) -> StatusCode {
+    match err.kind() {
+        VdrErrorKind::PoolRequestFailed(_) => StatusCode::BAD_REQUEST,
+        VdrErrorKind::Input => StatusCode::BAD_REQUEST,
+        VdrErrorKind::PoolTimeout => StatusCode::GATEWAY_TIMEOUT,
+        VdrErrorKind::PoolNoConsensus => StatusCode::CONFLICT,
         // FIXME - UNAUTHORIZED error when BadRequest msg points to a missing signature
-        _ => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
 
+/// Format an error response body, including the error kind and any additional
+/// details for errors produced by indy-vdr
+fn error_json(status: StatusCode, err: Option<&VdrError>, message: String, pretty: bool) -> String {
+    let extra = err.and_then(VdrError::extra).map(|extra| {
+        serde_json::from_str::<serde_json::Value>(&extra).unwrap_or_else(|_| extra.into())
+    });
+    let body = json!({
+        "status": status.as_u16(),
+        "kind": err.map(|err| error_kind_name(err.kind())),
+        "message": message,
+        "extra": extra,
+    });
+    if pretty {
+        serde_json::to_string_pretty(&body)
+    } else {
+        serde_json::to_string(&body)
+    }
+    .unwrap()
+}
+
 fn format_json_reply(message: String, pretty: bool) -> String {
     if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(&message) {
         let result = json["result"].as_object_mut();
@@ -216,23 +260,63 @@ fn format_result(
             };
             format_text(body, format, StatusCode::OK, None)
         }
-        ResponseType::RequestReply(reply, timing) => {
+        ResponseType::RequestReply(reply, meta) => {
             let reply = format_json_reply(reply, pretty);
-            format_text(reply, format, StatusCode::OK, timing)
+            format_text(reply, format, StatusCode::OK, meta.timing)
+        }
+        ResponseType::RequestFailed(err, meta) => {
+            let status = error_status(&err);
+            let body = error_json(status, Some(&err), err.to_string(), pretty);
+            format_text(body, format, status, meta.timing)
+        }
+        ResponseType::Status(code, msg) => {
+            format_text(error_json(code, None, msg, pretty), format, code, None)
         }
-        ResponseType::RequestFailed(err, timing) => {
-            let (errcode, msg) = convert_error(err);
-            format_text(msg, format, errcode, timing)
+        ResponseType::Error(code, err) => {
+            let body = error_json(code, Some(&err), err.to_string(), pretty);
+            format_text(body, format, code, None)
         }
-        ResponseType::Status(code, msg) => format_text(msg, format, code, None),
-        ResponseType::Resolver(reply) => {
+        ResponseType::Resolver(reply, status) => {
             let reply = format_json_reply(reply, pretty);
-            format_text(reply, format, StatusCode::OK, None)
+            format_text(reply, format, status, None)
         }
+        ResponseType::Stream(body) => Response::builder()
+            .header("Content-Type", "application/json")
+            .body(body)
+            .unwrap(),
     };
     Ok(response)
 }
 
+/// Build a response for a DID resolution result, using the HTTP status of its error code
+fn resolution_response(result: ResolutionResult) -> VdrResult<ResponseType> {
+    let status = match result.error() {
+        None => StatusCode::OK,
+        Some(ERROR_INVALID_DID) => StatusCode::BAD_REQUEST,
+        Some(ERROR_NOT_FOUND) => StatusCode::NOT_FOUND,
+        Some(ERROR_METHOD_NOT_SUPPORTED) => StatusCode::NOT_IMPLEMENTED,
+        Some(ERROR_REPRESENTATION_NOT_SUPPORTED) => StatusCode::NOT_ACCEPTABLE,
+        Some(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let reply = serde_json::to_string(&result)
+        .with_err_msg(VdrErrorKind::Unexpected, "Error serializing JSON")?;
+    Ok(ResponseType::Resolver(reply, status))
+}
+
+/// Report a DID URL which could not be dereferenced as a resolution error
+fn dereference_error(err: VdrError) -> VdrResult<ResponseType> {
+    let error = match err.kind() {
+        VdrErrorKind::Resolver => ERROR_NOT_FOUND,
+        VdrErrorKind::Input => ERROR_INVALID_DID,
+        _ => ERROR_INTERNAL,
+    };
+    resolution_response(ResolutionResult::from_error(
+        DidResolutionMetadata::default(),
+        error,
+        err,
+    ))
+}
+
 fn timestamp_now() -> i64 {
     UNIX_EPOCH.elapsed().unwrap().as_secs() as i64
 }
@@ -251,6 +335,12 @@ async fn get_pool_genesis<T: Pool>(pool: &T) -> VdrResult<ResponseType> {
     Ok(ResponseType::Genesis(txns.join("\n")))
 }
 
+fn get_pool_stats(pool: &LocalPool) -> VdrResult<ResponseType> {
+    let stats = serde_json::to_string(&pool.monitor().stats())
+        .with_err_msg(VdrErrorKind::Unexpected, "Error serializing JSON")?;
+    Ok(ResponseType::Json(stats))
+}
+
 fn get_ledgers(state: Rc<RefCell<AppState>>) -> VdrResult<ResponseType> {
     Ok(ResponseType::Json(
         state
@@ -263,6 +353,20 @@ fn get_ledgers(state: Rc<RefCell<AppState>>) -> VdrResult<ResponseType> {
     ))
 }
 
+async fn reload_ledgers(
+    state: &Rc<RefCell<AppState>>,
+    reloader: Option<&PoolReloader>,
+) -> VdrResult<ResponseType> {
+    let reloader = match reloader {
+        Some(reloader) => reloader,
+        None => return http_status(StatusCode::NOT_FOUND),
+    };
+    match reloader.reload(state).await? {
+        Some(summary) => Ok(ResponseType::Json(summary.to_json().to_string())),
+        None => http_status_msg(StatusCode::CONFLICT, "A reload is already in progress"),
+    }
+}
+
 fn get_pool_status(state: Rc<RefCell<AppState>>, namespace: &str) -> VdrResult<ResponseType> {
     let pool_states = &state.borrow().pool_states;
     let opt_pool = &pool_states
@@ -399,12 +503,67 @@ async fn get_revoc_reg_delta<T: Pool>(
     Ok(result.into())
 }
 
-/*
-async fn test_get_validator_info<T: Pool>(pool: &T, pretty: bool) -> VdrResult<String> {
-    let result = perform_get_validator_info(pool).await?;
-    format_result(format_request_result(result, pretty))
+async fn get_flag<T: Pool>(
+    pool: &T,
+    name: &str,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ResponseType> {
+    let request = pool.get_request_builder().build_get_flag_request(
+        None,
+        name.to_string(),
+        seq_no,
+        timestamp,
+    )?;
+    let result = perform_ledger_request(pool, &request, cache).await?;
+    Ok(result.into())
+}
+
+async fn get_frozen_ledgers<T: Pool>(
+    pool: &T,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ResponseType> {
+    let request = pool
+        .get_request_builder()
+        .build_get_frozen_ledgers_request(&DEFAULT_LIBINDY_DID)?;
+    let result = perform_ledger_request(pool, &request, cache).await?;
+    Ok(result.into())
+}
+
+#[cfg(feature = "rich_schema")]
+async fn get_rich_schema_by_id<T: Pool>(
+    pool: &T,
+    rs_id: &str,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ResponseType> {
+    let rs_id = RichSchemaId::from_str(rs_id)?;
+    let request = pool
+        .get_request_builder()
+        .build_get_rich_schema_by_id(&DEFAULT_LIBINDY_DID, &rs_id)?;
+    let result = perform_ledger_request(pool, &request, cache).await?;
+    Ok(result.into())
+}
+
+#[cfg(feature = "rich_schema")]
+async fn get_rich_schema_by_metadata<T: Pool>(
+    pool: &T,
+    rs_type: &str,
+    rs_name: &str,
+    rs_version: &str,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ResponseType> {
+    let request = pool
+        .get_request_builder()
+        .build_get_rich_schema_by_metadata(
+            &DEFAULT_LIBINDY_DID,
+            rs_type.to_string(),
+            rs_name.to_string(),
+            rs_version.to_string(),
+        )?;
+    let result = perform_ledger_request(pool, &request, cache).await?;
+    Ok(result.into())
 }
-*/
 
 async fn get_taa<T: Pool>(
     pool: &T,
@@ -457,16 +616,436 @@ async fn get_txn<T: Pool>(
     Ok(result.into())
 }
 
+/// Stream a range of transactions as a JSON array, so that the replies are not
+/// buffered. Errors before the first transaction produce an error response, while
+/// later errors abort the response.
+async fn get_txn_range<T: Pool + Clone + 'static>(
+    pool: &T,
+    ledger: LedgerType,
+    from: i32,
+    to: i32,
+) -> VdrResult<ResponseType> {
+    if to.saturating_sub(from) >= MAX_TXN_RANGE {
+        return http_status_msg(
+            StatusCode::BAD_REQUEST,
+            format!("Range exceeds {} transactions", MAX_TXN_RANGE),
+        );
+    }
+    let pool = pool.clone();
+    let (mut sender, body) = Body::channel();
+    let (started_send, started_recv) = oneshot::channel::<VdrResult<()>>();
+    tokio::task::spawn_local(async move {
+        let txns = match perform_get_txn_root(&pool, ledger.to_id()).await.and_then(
+            |(root_hash, ledger_size)| {
+                perform_get_txn_range(&pool, ledger.to_id(), from, to, root_hash, ledger_size)
+            },
+        ) {
+            Ok(txns) => txns,
+            Err(err) => {
+                started_send.send(Err(err)).ok();
+                return;
+            }
+        };
+        pin_mut!(txns);
+        let mut started = Some(started_send);
+        while let Some(txn) = txns.next().await {
+            let data = txn.and_then(|(_seq_no, reply)| {
+                let mut reply = serde_json::from_str::<serde_json::Value>(&reply)
+                    .with_input_err("Error parsing transaction reply")?;
+                Ok(reply["result"]["data"].take())
+            });
+            let prefix = match (data.is_ok(), started.take()) {
+                (true, Some(send)) => {
+                    if send.send(Ok(())).is_err() {
+                        return;
+                    }
+                    "["
+                }
+                (true, None) => ",",
+                (false, Some(send)) => {
+                    send.send(data.map(|_| ())).ok();
+                    return;
+                }
+                (false, None) => {
+                    if let Err(err) = data {
+                        log::warn!("Error streaming transactions: {}", err);
+                    }
+                    sender.abort();
+                    return;
+                }
+            };
+            let chunk = format!("{}{}", prefix, data.unwrap_or_default());
+            if sender.send_data(chunk.into()).await.is_err() {
+                return;
+            }
+        }
+        sender.send_data("]".into()).await.ok();
+    });
+    started_recv
+        .await
+        .with_err_msg(VdrErrorKind::Unexpected, "Transaction stream ended")??;
+    Ok(ResponseType::Stream(body))
+}
+
 async fn submit_request<T: Pool>(pool: &T, message: Vec<u8>) -> VdrResult<ResponseType> {
     let request = PreparedRequest::from_request_json(message)?;
     let result = perform_ledger_request(pool, &request, None).await?;
     Ok(result.into())
 }
 
+fn param_str(params: &serde_json::Value, name: &str) -> VdrResult<Option<String>> {
+    match &params[name] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(value) => Ok(Some(value.clone())),
+        _ => Err(input_err(format!("Invalid parameter: {}", name))),
+    }
+}
+
+fn required_param(params: &serde_json::Value, name: &str) -> VdrResult<String> {
+    param_str(params, name)?.ok_or_else(|| input_err(format!("Missing parameter: {}", name)))
+}
+
+fn optional_json<'a>(params: &'a serde_json::Value, name: &str) -> Option<&'a serde_json::Value> {
+    Some(&params[name]).filter(|value| !value.is_null())
+}
+
+fn parse_params(body: &[u8]) -> VdrResult<serde_json::Value> {
+    let params = serde_json::from_slice::<serde_json::Value>(body)
+        .with_input_err("Invalid request parameters")?;
+    if params.is_object() {
+        Ok(params)
+    } else {
+        Err(input_err("Request parameters must be a JSON object"))
+    }
+}
+
+fn parse_taa_acceptance<T: Pool>(
+    pool: &T,
+    params: &serde_json::Value,
+) -> VdrResult<Option<TxnAuthrAgrmtAcceptanceData>> {
+    let taa = match optional_json(params, "taa_acceptance") {
+        Some(taa) => taa,
+        None => return Ok(None),
+    };
+    let text = param_str(taa, "text")?;
+    let version = param_str(taa, "version")?;
+    let taa_digest = param_str(taa, "taa_digest")?;
+    let mechanism = required_param(taa, "mechanism")?;
+    let time = taa["time"]
+        .as_u64()
+        .ok_or_else(|| input_err("Missing parameter: time"))?;
+    let acceptance = pool
+        .get_request_builder()
+        .prepare_txn_author_agreement_acceptance_data(
+            text.as_deref(),
+            version.as_deref(),
+            taa_digest.as_deref(),
+            &mechanism,
+            time,
+        )?;
+    Ok(Some(acceptance))
+}
+
+async fn prepare_request<T: Pool>(
+    pool: &T,
+    namespace: &str,
+    txn_type: &str,
+    body: &[u8],
+    pending: &PendingRequests,
+    client_id: &str,
+) -> VdrResult<ResponseType> {
+    let params = parse_params(body)?;
+    let submitter = DidValue::from_str(&required_param(&params, "submitter_did")?)?;
+    let builder = pool.get_request_builder();
+    let mut request = match txn_type {
+        "nym" => {
+            let dest = DidValue::from_str(&required_param(&params, "dest")?)?;
+            let role = param_str(&params, "role")?
+                .map(|role| role.parse::<UpdateRole>())
+                .transpose()?;
+            let version = match optional_json(&params, "version") {
+                Some(version) => Some(
+                    version
+                        .as_i64()
+                        .and_then(|version| i32::try_from(version).ok())
+                        .ok_or_else(|| input_err("Invalid parameter: version"))?,
+                ),
+                None => None,
+            };
+            builder.build_nym_request(
+                &submitter,
+                &dest,
+                param_str(&params, "verkey")?,
+                param_str(&params, "alias")?,
+                role,
+                optional_json(&params, "diddoc_content"),
+                version,
+            )?
+        }
+        "attrib" => {
+            let dest = DidValue::from_str(&required_param(&params, "dest")?)?;
+            builder.build_attrib_request(
+                &submitter,
+                &dest,
+                param_str(&params, "hash")?,
+                optional_json(&params, "raw"),
+                param_str(&params, "enc")?,
+            )?
+        }
+        "schema" => {
+            let schema = serde_json::from_value::<Schema>(params["schema"].clone())
+                .with_input_err("Invalid parameter: schema")?;
+            builder.build_schema_request(&submitter, schema)?
+        }
+        "cred_def" => {
+            let cred_def =
+                serde_json::from_value::<CredentialDefinition>(params["cred_def"].clone())
+                    .with_input_err("Invalid parameter: cred_def")?;
+            builder.build_cred_def_request(&submitter, cred_def)?
+        }
+        // validator info must be signed by a trustee, steward or network monitor
+        "validator_info" => builder.build_get_validator_info_request(&submitter)?,
+        _ => return http_status(StatusCode::NOT_FOUND),
+    };
+    if let Some(endorser) = param_str(&params, "endorser")? {
+        request.set_endorser(&DidValue::from_str(&endorser)?)?;
+    }
+    if let Some(acceptance) = parse_taa_acceptance(pool, &params)? {
+        request.set_txn_author_agreement_acceptance(&acceptance)?;
+    }
+    let signature_input = request.get_signature_input()?;
+    let req_json = request.req_json.clone();
+    let handle = match pending.insert(client_id, namespace, request) {
+        Some(handle) => handle,
+        None => {
+            return http_status_msg(StatusCode::SERVICE_UNAVAILABLE, "Too many pending requests")
+        }
+    };
+    let result = json!({
+        "handle": handle,
+        "signature_input": signature_input,
+        "request": req_json,
+        "expires_in": PENDING_REQUEST_TIMEOUT.as_secs(),
+    });
+    Ok(ResponseType::Json(result.to_string()))
+}
+
+async fn submit_prepared<T: Pool>(
+    pool: &T,
+    namespace: &str,
+    handle: &str,
+    body: &[u8],
+    pending: &PendingRequests,
+    client_id: &str,
+) -> VdrResult<ResponseType> {
+    let params = parse_params(body)?;
+    let signature = param_str(&params, "signature")?
+        .map(base58::decode)
+        .transpose()?;
+    let mut signatures = Vec::new();
+    if let Some(multi) = optional_json(&params, "signatures") {
+        let multi = multi
+            .as_object()
+            .ok_or_else(|| input_err("Invalid parameter: signatures"))?;
+        for (did, sig) in multi {
+            let sig = sig
+                .as_str()
+                .ok_or_else(|| input_err("Invalid parameter: signatures"))?;
+            signatures.push((DidValue::from_str(did)?, base58::decode(sig)?));
+        }
+    }
+    if signature.is_none() && signatures.is_empty() {
+        return Err(input_err("A request signature is required"));
+    }
+    // the signature input returned by prepare_request must cover the acceptance
+    if optional_json(&params, "taa_acceptance").is_some() {
+        return Err(input_err(
+            "The TAA acceptance must be provided when the request is prepared",
+        ));
+    }
+
+    let mut request = match pending.get(client_id, namespace, handle)? {
+        Some(request) => request,
+        None => return http_status_msg(StatusCode::NOT_FOUND, "Unknown or expired request handle"),
+    };
+    if let Some(signature) = signature {
+        request.set_signature(&signature)?;
+    }
+    for (did, sig) in signatures {
+        request.set_multi_signature(&did, &sig)?;
+    }
+    let result = perform_ledger_request(pool, &request, None).await?;
+    // keep the request available for another attempt unless the ledger replied
+    if let RequestResult::Reply(_) = result.0 {
+        pending.remove(client_id, handle);
+    }
+    Ok(result.into())
+}
+
+fn access_denied(denied: AccessDenied) -> Response<Body> {
+    match denied {
+        AccessDenied::Unauthorized => Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header("Content-Type", "application/json")
+            .header("WWW-Authenticate", "Bearer")
+            .body(
+                error_json(
+                    StatusCode::UNAUTHORIZED,
+                    None,
+                    "Missing or invalid API key".to_owned(),
+                    false,
+                )
+                .into(),
+            )
+            .unwrap(),
+        AccessDenied::Forbidden => Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .header("Content-Type", "application/json")
+            .body(
+                error_json(
+                    StatusCode::FORBIDDEN,
+                    None,
+                    "An admin API key is required".to_owned(),
+                    false,
+                )
+                .into(),
+            )
+            .unwrap(),
+        AccessDenied::RateLimited(retry_after) => Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header(
+                "Retry-After",
+                (retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)).to_string(),
+            )
+            .header("Content-Type", "application/json")
+            .body(
+                error_json(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    None,
+                    "Rate limit exceeded".to_owned(),
+                    false,
+                )
+                .into(),
+            )
+            .unwrap(),
+    }
+}
+
+/// Determine the ledger namespace and route labels used to record metrics for a request
+fn request_labels(req: &Request<Body>, state: &AppState) -> (String, &'static str) {
+    let mut parts = req.uri().path().split('/').filter_map(|part| {
+        percent_decode_str(part)
+            .decode_utf8()
+            .ok()
+            .filter(|p| !p.is_empty())
+    });
+    let known_namespace = |namespace: &str| {
+        if state.pool_states.contains_key(namespace) {
+            namespace.to_owned()
+        } else {
+            String::new()
+        }
+    };
+    if let Some(route) = find_server_route(req.uri().path()) {
+        return (String::new(), route.name);
+    }
+    if let Some(did) = req.uri().path().strip_prefix("/1.0/identifiers/") {
+        let namespace = DidUrl::parse(did)
+            .map(|did_url| known_namespace(&state.did_namespace(&did_url)))
+            .unwrap_or_default();
+        return (namespace, "identifiers");
+    }
+    let namespace = if state.is_multiple {
+        parts
+            .next()
+            .map(|ns| known_namespace(&ns))
+            .unwrap_or_default()
+    } else {
+        state.pool_states.keys().next().cloned().unwrap_or_default()
+    };
+    let route = match parts.next() {
+        Some(route) => Metrics::route_label(&route),
+        None if state.is_multiple && namespace.is_empty() => "ledgers",
+        None => "status",
+    };
+    (namespace, route)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_request(
     req: Request<Body>,
     state: Rc<RefCell<AppState>>,
     cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    metrics: Rc<Metrics>,
+    pending: Rc<PendingRequests>,
+    access: Rc<AccessControl>,
+    reloader: Option<Rc<PoolReloader>>,
+    client: ClientInfo,
+) -> Result<Response<Body>, hyper::Error> {
+    let start = Instant::now();
+    let is_metrics = (req.method(), req.uri().path()) == (&Method::GET, "/metrics");
+    let (namespace, route) = if is_metrics {
+        (String::new(), "metrics")
+    } else {
+        request_labels(&req, &state.borrow())
+    };
+    let is_write = req.method() == Method::POST;
+    let checked = if route == "reload" {
+        access.check_admin(req.headers())
+    } else {
+        access.check(&client, req.headers(), route, is_write)
+    };
+    let client_id = match checked {
+        Ok(client_id) => client_id,
+        Err(denied) => {
+            let response = access_denied(denied);
+            metrics.record_request(
+                &namespace,
+                route,
+                response.status().as_u16(),
+                start.elapsed(),
+            );
+            return Ok(response);
+        }
+    };
+    if is_metrics {
+        let body = metrics.render(&state.borrow(), cache.as_ref().map(Cache::stats));
+        let response = Response::builder()
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(body.into())
+            .unwrap();
+        metrics.record_request(&namespace, route, 200, start.elapsed());
+        return Ok(response);
+    }
+    let response = route_request(
+        req,
+        state,
+        cache,
+        &metrics,
+        &pending,
+        reloader.as_deref(),
+        &client_id,
+    )
+    .await;
+    if let Ok(response) = &response {
+        metrics.record_request(
+            &namespace,
+            route,
+            response.status().as_u16(),
+            start.elapsed(),
+        );
+    }
+    response
+}
+
+async fn route_request(
+    req: Request<Body>,
+    state: Rc<RefCell<AppState>>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    metrics: &Metrics,
+    pending: &PendingRequests,
+    reloader: Option<&PoolReloader>,
+    client_id: &str,
 ) -> Result<Response<Body>, hyper::Error> {
     let mut parts = req
         .uri()
@@ -489,6 +1068,11 @@ pub async fn handle_request(
                 .collect()
         })
         .unwrap_or_else(HashMap::new);
+    let accept = req
+        .headers()
+        .get("accept")
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_owned);
     let format = if query == Some("html") {
         ResponseFormat::Html
     } else if query == Some("raw") {
@@ -512,6 +1096,23 @@ pub async fn handle_request(
         ResponseFormat::Raw
     };
 
+    match (req.method(), find_server_route(req.uri().path())) {
+        (&Method::POST, Some(route)) if route.name == "reload" => {
+            return format_result(reload_ledgers(&state, reloader).await, format);
+        }
+        (&Method::GET, Some(route)) if route.name == "openapi" => {
+            let is_multiple = state.borrow().is_multiple;
+            return format_result(
+                Ok(ResponseType::Json(openapi(is_multiple).to_string())),
+                format,
+            );
+        }
+        (_, Some(route)) if route.name != "metrics" => {
+            return format_result(http_status(StatusCode::METHOD_NOT_ALLOWED), format);
+        }
+        _ => (),
+    }
+
     let mut namespace = if state.borrow().is_multiple {
         parts.next().unwrap_or_else(|| "".to_owned())
     } else {
@@ -519,6 +1120,8 @@ pub async fn handle_request(
         let (ns, _) = pool_states.iter().next().unwrap();
         ns.to_owned()
     };
+    let segments = parts.collect::<Vec<_>>();
+    let mut parts = segments.iter().cloned();
     let fst = parts.next().unwrap_or_else(|| "".to_owned());
 
     let req_method = req.method();
@@ -527,13 +1130,22 @@ pub async fn handle_request(
 
     let uri = req.uri().to_string();
     let captures = resolver_regex.captures(uri.as_str());
-    let did = captures.map(|cap| cap.get(1).unwrap().as_str());
+    // the fragment of a DID URL must be percent-encoded in the request path
+    let did = captures.map(|cap| cap.get(1).unwrap().as_str().replacen("%23", "#", 1));
+    let did = did.as_deref();
+    let mut is_dereference = false;
+    let mut did_method = None;
 
-    if did.is_some() {
-        namespace = match DidUrl::parse(did.unwrap()) {
-            Ok(did_url) => did_url.namespace,
-            Err(_) => {
-                return format_result(http_status(StatusCode::BAD_REQUEST), format);
+    if let Some(did) = did {
+        namespace = match DidUrl::parse(did) {
+            Ok(did_url) => {
+                is_dereference = did_url.path.is_some() || did_url.is_document_part();
+                did_method = Some(did_url.method());
+                state.borrow().did_namespace(&did_url)
+            }
+            Err(err) => {
+                let result = ResolutionResult::invalid_did(did, err);
+                return format_result(resolution_response(result), format);
             }
         };
     } else if (req_method, fst.is_empty()) == (&Method::GET, true) {
@@ -544,18 +1156,43 @@ pub async fn handle_request(
         }
     }
 
-    let pool_states = &state.borrow().pool_states;
-    let pool_state = match pool_states.get(&namespace) {
-        Some(ps) => ps,
+    // release the state before awaiting, so that the ledgers may be refreshed or reloaded
+    let opt_pool = match state.borrow().pool_states.get(&namespace) {
+        Some(pool_state) => pool_state.pool.clone(),
         None => {
-            return format_result(
-                http_status_msg(StatusCode::BAD_REQUEST, "Unknown ledger"),
-                format,
-            );
+            let result = match did_method {
+                Some(method) => {
+                    // did:sov DIDs are not supported without a ledger to resolve them
+                    let state = state.borrow();
+                    let error = if method != DidMethod::Indy
+                        && !state.pool_states.contains_key(&state.sov_namespace)
+                    {
+                        ERROR_METHOD_NOT_SUPPORTED
+                    } else {
+                        ERROR_NOT_FOUND
+                    };
+                    resolution_response(ResolutionResult::from_error(
+                        DidResolutionMetadata::default(),
+                        error,
+                        format!("Unknown ledger: {}", namespace),
+                    ))
+                }
+                None => http_status_msg(StatusCode::BAD_REQUEST, "Unknown ledger"),
+            };
+            return format_result(result, format);
         }
     };
 
-    let opt_pool = pool_state.pool.clone();
+    if did.is_none() {
+        match check_ledger_route(req_method, &segments) {
+            Ok(_) => (),
+            Err(None) => return format_result(http_status(StatusCode::NOT_FOUND), format),
+            Err(Some(_)) => {
+                return format_result(http_status(StatusCode::METHOD_NOT_ALLOWED), format)
+            }
+        }
+    }
+
     let pool = match opt_pool {
         None => {
             return format_result(http_status(StatusCode::SERVICE_UNAVAILABLE), format);
@@ -563,37 +1200,51 @@ pub async fn handle_request(
         Some(pool) => pool,
     };
 
-    let result = if did.is_some() {
-        let did = did.unwrap();
+    let result = if let Some(did) = did {
         let resolver = Resolver::new(pool);
-        // is DID Url
-        if did.find('/').is_some() {
+        if is_dereference {
             match resolver.dereference(did, cache.clone()).await {
-                Ok(result) => Ok(ResponseType::Resolver(result)),
-                Err(err) => http_status_msg(StatusCode::BAD_REQUEST, err.to_string()),
+                Ok(result) => Ok(ResponseType::Resolver(result, StatusCode::OK)),
+                Err(err) => dereference_error(err),
             }
         } else {
-            match resolver.resolve(did, cache).await {
-                Ok(result) => Ok(ResponseType::Resolver(result)),
-                Err(err) => http_status_msg(StatusCode::BAD_REQUEST, err.to_string()),
-            }
+            resolution_response(resolver.resolve_result(did, accept.as_deref(), cache).await)
         }
 
     // No DID resolution
     } else {
         match (req_method, fst.as_str()) {
-            // (&Method::GET, "status") => test_get_validator_info(pool, pretty).await.make_response(),
             (&Method::GET, "submit") => http_status(StatusCode::METHOD_NOT_ALLOWED),
             (&Method::POST, "submit") => {
+                let handle = parts.next();
                 let body_bytes = hyper::body::to_bytes(req.into_body()).await?;
                 let body = body_bytes.iter().cloned().collect::<Vec<u8>>();
-                if !body.is_empty() {
+                if body.is_empty() {
+                    http_status(StatusCode::BAD_REQUEST)
+                } else if let Some(handle) = handle {
+                    submit_prepared(&pool, &namespace, &handle, &body, pending, client_id).await
+                } else {
                     submit_request(&pool, body).await
+                }
+            }
+            (&Method::POST, "prepare") => {
+                if let Some(txn_type) = parts.next() {
+                    let body_bytes = hyper::body::to_bytes(req.into_body()).await?;
+                    prepare_request(
+                        &pool,
+                        &namespace,
+                        &txn_type,
+                        &body_bytes,
+                        pending,
+                        client_id,
+                    )
+                    .await
                 } else {
-                    http_status(StatusCode::BAD_REQUEST)
+                    http_status(StatusCode::NOT_FOUND)
                 }
             }
             (&Method::GET, "genesis") => get_pool_genesis(&pool).await,
+            (&Method::GET, "stats") => get_pool_stats(&pool),
             (&Method::GET, "taa") => get_taa(&pool, cache.clone()).await,
             (&Method::GET, "aml") => get_aml(&pool, cache.clone()).await,
             (&Method::GET, "attrib") => {
@@ -676,6 +1327,35 @@ pub async fn handle_request(
                     http_status(StatusCode::NOT_FOUND)
                 }
             }
+            (&Method::GET, "flag") => {
+                if let Some(name) = parts.next() {
+                    let seq_no: Option<i32> = query_params
+                        .get("seq_no")
+                        .and_then(|seq_no| seq_no.as_str().parse().ok());
+                    let timestamp: Option<u64> = query_params
+                        .get("timestamp")
+                        .and_then(|ts| ts.as_str().parse().ok());
+                    get_flag(&pool, &name, seq_no, timestamp, cache.clone()).await
+                } else {
+                    http_status(StatusCode::NOT_FOUND)
+                }
+            }
+            (&Method::GET, "frozen_ledgers") => get_frozen_ledgers(&pool, cache.clone()).await,
+            #[cfg(feature = "rich_schema")]
+            (&Method::GET, "rich_schema") => match (parts.next(), parts.next(), parts.next()) {
+                (Some(rs_id), None, _) => get_rich_schema_by_id(&pool, &rs_id, cache.clone()).await,
+                (Some(rs_type), Some(rs_name), Some(rs_version)) => {
+                    get_rich_schema_by_metadata(
+                        &pool,
+                        &rs_type,
+                        &rs_name,
+                        &rs_version,
+                        cache.clone(),
+                    )
+                    .await
+                }
+                _ => http_status(StatusCode::NOT_FOUND),
+            },
             (&Method::GET, "txn") => {
                 if let (Some(ledger), Some(txn)) = (parts.next(), parts.next()) {
                     if let (Ok(ledger), Ok(txn)) =
@@ -689,10 +1369,439 @@ pub async fn handle_request(
                     http_status(StatusCode::NOT_FOUND)
                 }
             }
+            (&Method::GET, "txns") => {
+                if let (Some(ledger), Some(range)) = (parts.next(), parts.next()) {
+                    let range = range.split_once('-').and_then(|(from, to)| {
+                        Some((from.parse::<i32>().ok()?, to.parse::<i32>().ok()?))
+                    });
+                    if let (Ok(ledger), Some((from, to))) =
+                        (LedgerType::try_from(ledger.as_str()), range)
+                    {
+                        get_txn_range(&pool, ledger, from, to).await
+                    } else {
+                        http_status(StatusCode::NOT_FOUND)
+                    }
+                } else {
+                    http_status(StatusCode::NOT_FOUND)
+                }
+            }
             (&Method::GET, _) => http_status(StatusCode::NOT_FOUND),
             _ => http_status(StatusCode::METHOD_NOT_ALLOWED),
         }
     };
 
+    if let Ok(ResponseType::RequestReply(_, meta) | ResponseType::RequestFailed(_, meta)) = &result
+    {
+        metrics.record_ledger_result(&namespace, meta);
+    }
     format_result(result, format)
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_executor::block_on;
+    use indy_vdr::config::PoolConfig;
+    use indy_vdr::pool::networker::{
+        MakeLocal, MockLedger, MockNetworkerFactory, MockNodeBehaviour,
+    };
+    use indy_vdr::pool::{Ed25519Signer, RequestSigner};
+
+    use super::*;
+    use crate::utils::PoolState;
+
+    fn _mock_pool(ledger: &MockLedger) -> LocalPool {
+        let merkle_tree = ledger.pool_transactions().unwrap().merkle_tree().unwrap();
+        LocalPool::build(
+            PoolConfig::default(),
+            merkle_tree,
+            MakeLocal(MockNetworkerFactory::new(ledger.clone())),
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    fn _set_behaviour(ledger: &MockLedger, behaviour: MockNodeBehaviour) {
+        for alias in ledger.node_aliases() {
+            ledger.set_behaviour(&alias, behaviour.clone()).unwrap();
+        }
+    }
+
+    #[test]
+    fn prepared_request_is_kept_until_submitted() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = _mock_pool(&ledger);
+        let pending = PendingRequests::new();
+        let signer = Ed25519Signer::generate(None, None).unwrap();
+        let params = json!({"submitter_did": signer.did(), "dest": signer.did()}).to_string();
+        let prepared = match block_on(prepare_request(
+            &pool,
+            "test",
+            "nym",
+            params.as_bytes(),
+            &pending,
+            "key:a",
+        ))
+        .unwrap()
+        {
+            ResponseType::Json(prepared) => {
+                serde_json::from_str::<serde_json::Value>(&prepared).unwrap()
+            }
+            _ => panic!("Expected a prepared request"),
+        };
+        let handle = prepared["handle"].as_str().unwrap();
+        let sig_input = prepared["signature_input"].as_str().unwrap();
+        let signature = block_on(signer.sign(sig_input.as_bytes())).unwrap();
+        let body = json!({"signature": base58::encode(signature)}).to_string();
+        let submit = |body: &str, client_id: &str| {
+            block_on(submit_prepared(
+                &pool,
+                "test",
+                handle,
+                body.as_bytes(),
+                &pending,
+                client_id,
+            ))
+        };
+
+        // the acceptance would invalidate the signature
+        let mut with_taa: serde_json::Value = serde_json::from_str(&body).unwrap();
+        with_taa["taa_acceptance"] = json!({"text": "agreement", "version": "1.0"});
+        assert!(submit(&with_taa.to_string(), "key:a").is_err());
+
+        // only the client which prepared the request may submit it
+        assert!(matches!(
+            submit(&body, "key:b").unwrap(),
+            ResponseType::Status(StatusCode::NOT_FOUND, _)
+        ));
+
+        _set_behaviour(&ledger, MockNodeBehaviour::Nack("Busy".to_owned()));
+        assert!(!matches!(
+            submit(&body, "key:a"),
+            Ok(ResponseType::RequestReply(..))
+        ));
+        _set_behaviour(&ledger, MockNodeBehaviour::Normal);
+        assert!(matches!(
+            submit(&body, "key:a").unwrap(),
+            ResponseType::RequestReply(..)
+        ));
+        assert!(matches!(
+            submit(&body, "key:a").unwrap(),
+            ResponseType::Status(StatusCode::NOT_FOUND, _)
+        ));
+    }
+
+    fn _reply_result(response: VdrResult<ResponseType>) -> serde_json::Value {
+        match response.unwrap() {
+            ResponseType::RequestReply(reply, _) => {
+                serde_json::from_str::<serde_json::Value>(&reply).unwrap()["result"].clone()
+            }
+            _ => panic!("Expected a request reply"),
+        }
+    }
+
+    #[test]
+    fn get_flag_and_frozen_ledgers() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = _mock_pool(&ledger);
+        let result = _reply_result(block_on(get_flag(&pool, "my_flag", None, None, None)));
+        assert_eq!(result["type"], "131");
+        assert_eq!(result["name"], "my_flag");
+        let result = _reply_result(block_on(get_flag(
+            &pool,
+            "my_flag",
+            None,
+            Some(1700000000),
+            None,
+        )));
+        assert_eq!(result["timestamp"], 1700000000);
+        let result = _reply_result(block_on(get_frozen_ledgers(&pool, None)));
+        assert_eq!(result["type"], "10");
+    }
+
+    #[cfg(feature = "rich_schema")]
+    #[test]
+    fn get_rich_schema_by_id_and_metadata() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = _mock_pool(&ledger);
+        let rs_id = "did:sov:2sY8D4vUmLqdJCRhnvuTi5";
+        let result = _reply_result(block_on(get_rich_schema_by_id(&pool, rs_id, None)));
+        assert_eq!(result["type"], "300");
+        assert_eq!(result["id"], rs_id);
+        let result = _reply_result(block_on(get_rich_schema_by_metadata(
+            &pool, "sch", "schema", "1.0", None,
+        )));
+        assert_eq!(result["type"], "301");
+        assert_eq!(result["rsName"], "schema");
+        assert_eq!(result["rsVersion"], "1.0");
+    }
+
+    #[test]
+    fn prepare_validator_info_request() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = _mock_pool(&ledger);
+        let pending = PendingRequests::new();
+        let signer = Ed25519Signer::generate(None, None).unwrap();
+        let params = json!({"submitter_did": signer.did()}).to_string();
+        let prepared = match block_on(prepare_request(
+            &pool,
+            "test",
+            "validator_info",
+            params.as_bytes(),
+            &pending,
+            "key:a",
+        ))
+        .unwrap()
+        {
+            ResponseType::Json(prepared) => {
+                serde_json::from_str::<serde_json::Value>(&prepared).unwrap()
+            }
+            _ => panic!("Expected a prepared request"),
+        };
+        assert_eq!(prepared["request"]["operation"]["type"], "119");
+        assert_eq!(
+            prepared["request"]["identifier"].as_str(),
+            Some(signer.did().to_string().as_str())
+        );
+        assert!(prepared["handle"].is_string());
+
+        // unknown transaction types are not prepared
+        assert!(matches!(
+            block_on(prepare_request(
+                &pool,
+                "test",
+                "pool_restart",
+                params.as_bytes(),
+                &pending,
+                "key:a",
+            ))
+            .unwrap(),
+            ResponseType::Status(StatusCode::NOT_FOUND, _)
+        ));
+    }
+
+    fn _route_example(path: &str) -> String {
+        path.split('/')
+            .map(|part| match part {
+                "{ledger}" => "test",
+                "{did}" => "did:sov:V4SGRU86Z58d6TV7PBUe6f",
+                "{dest}" | "{nym}" => "V4SGRU86Z58d6TV7PBUe6f",
+                "{attrib}" => "endpoint",
+                "{auth_type}" => "1",
+                "{auth_action}" => "ADD",
+                "{ledger_type}" => "domain",
+                "{seq_no}" => "1",
+                "{range}" => "1-2",
+                "{txn_type}" => "nym",
+                part if part.starts_with('{') => "example",
+                part => part,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Check whether a response was produced by a route handler, rather than
+    /// refused by the dispatcher
+    fn _is_dispatched(status: StatusCode, body: &[u8]) -> bool {
+        let unrouted = [StatusCode::NOT_FOUND, StatusCode::METHOD_NOT_ALLOWED];
+        let body = serde_json::from_slice::<serde_json::Value>(body).unwrap_or_default();
+        !(unrouted.contains(&status)
+            && body["kind"].is_null()
+            && body["message"] == status.to_string())
+    }
+
+    #[test]
+    fn openapi_routes_are_dispatched() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+        let ledger = MockLedger::new(4).unwrap();
+        let signer = Ed25519Signer::generate(None, None).unwrap();
+        let admin_keys = [("admin-key".to_owned(), "ops".to_owned())]
+            .into_iter()
+            .collect();
+        let access =
+            Rc::new(AccessControl::new(None, Default::default()).with_admin_keys(Some(admin_keys)));
+        let metrics = Rc::new(Metrics::new());
+        let reloader = Rc::new(PoolReloader::new(
+            "missing".to_owned(),
+            false,
+            metrics.clone(),
+        ));
+
+        for is_multiple in [false, true] {
+            let state = Rc::new(RefCell::new(AppState {
+                is_multiple,
+                pool_states: [(
+                    "test".to_owned(),
+                    PoolState {
+                        pool: Some(_mock_pool(&ledger)),
+                        last_refresh: None,
+                        transactions: ledger.pool_transactions().unwrap(),
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                sov_namespace: "test".to_owned(),
+            }));
+            let send = |method: &str, path: &str| {
+                let body = if method == "post" {
+                    json!({"submitter_did": signer.did(), "dest": signer.did()}).to_string()
+                } else {
+                    String::new()
+                };
+                let req = Request::builder()
+                    .method(method.to_ascii_uppercase().as_str())
+                    .uri(path)
+                    .header("x-api-key", "admin-key")
+                    .body(Body::from(body))
+                    .unwrap();
+                local.block_on(&runtime, async {
+                    let response = handle_request(
+                        req,
+                        state.clone(),
+                        None,
+                        metrics.clone(),
+                        Rc::new(PendingRequests::new()),
+                        access.clone(),
+                        Some(reloader.clone()),
+                        ClientInfo::default(),
+                    )
+                    .await
+                    .unwrap();
+                    let status = response.status();
+                    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                    (status, body)
+                })
+            };
+
+            let doc = openapi(is_multiple);
+            for (path, operations) in doc["paths"].as_object().unwrap() {
+                for method in operations.as_object().unwrap().keys() {
+                    let (status, body) = send(method, &_route_example(path));
+                    assert!(
+                        _is_dispatched(status, &body),
+                        "{} {} was not dispatched",
+                        method,
+                        path
+                    );
+                }
+            }
+
+            // paths which do not match a route are refused
+            let prefix = if is_multiple { "/test" } else { "" };
+            for (method, path, status) in [
+                ("get", "/unknown", StatusCode::NOT_FOUND),
+                (
+                    "get",
+                    "/nym/V4SGRU86Z58d6TV7PBUe6f/extra",
+                    StatusCode::NOT_FOUND,
+                ),
+                ("get", "/auth/1", StatusCode::NOT_FOUND),
+                ("get", "/submit", StatusCode::METHOD_NOT_ALLOWED),
+                ("post", "/taa", StatusCode::METHOD_NOT_ALLOWED),
+            ] {
+                let result = send(method, &format!("{}{}", prefix, path));
+                assert_eq!(result.0, status, "{} {}", method, path);
+                assert!(!_is_dispatched(result.0, &result.1));
+            }
+        }
+    }
+
+    #[test]
+    fn did_errors_are_resolution_results() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+        let ledger = MockLedger::new(4).unwrap();
+        let access = Rc::new(AccessControl::new(None, Default::default()));
+        let metrics = Rc::new(Metrics::new());
+        // the ledger for did:sov DIDs is not served
+        let state = Rc::new(RefCell::new(AppState {
+            is_multiple: true,
+            pool_states: [(
+                "test".to_owned(),
+                PoolState {
+                    pool: Some(_mock_pool(&ledger)),
+                    last_refresh: None,
+                    transactions: ledger.pool_transactions().unwrap(),
+                },
+            )]
+            .into_iter()
+            .collect(),
+            sov_namespace: "sovrin".to_owned(),
+        }));
+        let resolve = |did: &str| {
+            let req = Request::builder()
+                .uri(format!("/1.0/identifiers/{}", did))
+                .body(Body::empty())
+                .unwrap();
+            local.block_on(&runtime, async {
+                let response = handle_request(
+                    req,
+                    state.clone(),
+                    None,
+                    metrics.clone(),
+                    Rc::new(PendingRequests::new()),
+                    access.clone(),
+                    None,
+                    ClientInfo::default(),
+                )
+                .await
+                .unwrap();
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+                let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                (status, result["didResolutionMetadata"]["error"].clone())
+            })
+        };
+
+        assert_eq!(
+            resolve("did:indy:unknown:V4SGRU86Z58d6TV7PBUe6f"),
+            (StatusCode::NOT_FOUND, json!(ERROR_NOT_FOUND))
+        );
+        assert_eq!(
+            resolve("did:sov:V4SGRU86Z58d6TV7PBUe6f"),
+            (
+                StatusCode::NOT_IMPLEMENTED,
+                json!(ERROR_METHOD_NOT_SUPPORTED)
+            )
+        );
+        assert_eq!(
+            resolve("did:indy:test:0cgbu8ZPoWTnR5Rv5JcSMB"),
+            (StatusCode::BAD_REQUEST, json!(ERROR_INVALID_DID))
+        );
+        assert_eq!(
+            resolve("did:indy:test:V4SGRU86Z58d6TV7PBUe6f%23unknown"),
+            (StatusCode::NOT_FOUND, json!(ERROR_NOT_FOUND))
+        );
+    }
+
+    #[test]
+    fn dereference_errors_are_resolution_errors() {
+        let errors = [
+            (
+                err_msg(VdrErrorKind::Resolver, "Not found"),
+                ERROR_NOT_FOUND,
+            ),
+            (input_err("Invalid DID URL"), ERROR_INVALID_DID),
+            (
+                err_msg(VdrErrorKind::PoolTimeout, "Timeout"),
+                ERROR_INTERNAL,
+            ),
+        ];
+        for (err, error) in errors {
+            match dereference_error(err).unwrap() {
+                ResponseType::Resolver(body, _) => {
+                    let result: ResolutionResult = serde_json::from_str(&body).unwrap();
+                    assert_eq!(result.error(), Some(error));
+                }
+                _ => panic!("expected a resolution result"),
+            }
+        }
+    }
+}
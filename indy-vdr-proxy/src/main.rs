@@ -4,7 +4,11 @@
 extern crate serde_json;
 
 mod app;
+mod auth;
 mod handlers;
+mod metrics;
+mod reload;
+mod routes;
 mod utils;
 
 use indy_vdr::pool::cache::storage::{new_fs_ordered_store, OrderedHashMap};
@@ -13,17 +17,16 @@ use std::collections::HashMap;
 #[cfg(unix)]
 use std::fs;
 use std::net::IpAddr;
-use std::path::PathBuf;
 use std::process::exit;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 #[cfg(feature = "tls")]
-use std::{fs::File, io::BufReader, sync::Arc};
+use std::{fs::File, io::BufReader};
 
 use futures_util::future::FutureExt;
 #[cfg(feature = "tls")]
 use futures_util::stream;
-use git2::Repository;
 
 #[cfg(feature = "fetch")]
 use hyper::body::Buf;
@@ -37,7 +40,10 @@ use hyper_tls::HttpsConnector;
 #[cfg(unix)]
 use hyper_unix_connector::UnixConnector;
 
-use indy_vdr::pool::cache::{strategy::CacheStrategyTTL, Cache};
+use indy_vdr::pool::cache::{
+    policy::CachePolicy, redis::CacheStrategyRedis, strategy::CacheStrategyTTL, Cache,
+    CacheStrategy,
+};
 #[cfg(feature = "tls")]
 use rustls_pemfile::{certs, pkcs8_private_keys};
 #[cfg(feature = "tls")]
@@ -47,17 +53,24 @@ use tokio::select;
 use tokio::signal::unix::SignalKind;
 #[cfg(feature = "tls")]
 use tokio_rustls::{
-    rustls::{Certificate, PrivateKey, ServerConfig},
+    rustls::{
+        server::AllowAnyAuthenticatedClient, Certificate, PrivateKey, RootCertStore, ServerConfig,
+    },
     TlsAcceptor,
 };
 
 use indy_vdr::common::error::prelude::*;
 use indy_vdr::config::PoolConfig;
-use indy_vdr::pool::{helpers::perform_refresh, LocalPool, PoolBuilder, PoolTransactions};
+#[cfg(feature = "async-networker")]
+use indy_vdr::pool::networker::{AsyncNetworkerFactory, MakeLocal};
+#[cfg(not(feature = "async-networker"))]
+use indy_vdr::pool::PoolBuilder;
+use indy_vdr::pool::{helpers::perform_refresh, LocalPool, PoolTransactions, RequestResultMeta};
 
-use crate::utils::{
-    init_pool_state_from_folder_structure, AppState, PoolState, INDY_NETWORKS_GITHUB,
-};
+use crate::auth::{AccessControl, ConnectionInfo};
+use crate::metrics::{Metrics, RefreshOutcome};
+use crate::reload::PoolReloader;
+use crate::utils::{init_pool_states, AppState, PendingRequests, PoolState, INDY_NETWORKS_GITHUB};
 
 fn main() {
     let config = app::load_config().unwrap_or_else(|err| {
@@ -118,6 +131,7 @@ async fn fetch_transactions(_genesis: String) -> VdrResult<PoolTransactions> {
 async fn init_app_state(
     genesis: Option<String>,
     namespace: String,
+    sov_namespace: String,
     is_multiple: bool,
 ) -> VdrResult<AppState> {
     let mut pool_states: HashMap<String, PoolState> = HashMap::new();
@@ -134,97 +148,75 @@ async fn init_app_state(
             last_refresh: None,
             transactions,
         };
-        pool_states.insert(namespace, pool_state);
+        pool_states.insert(namespace.clone(), pool_state);
         AppState {
             is_multiple,
             pool_states,
+            sov_namespace: namespace,
         }
     } else {
         let genesis = genesis.unwrap_or_else(|| String::from(INDY_NETWORKS_GITHUB));
-        let pool_states = if genesis.starts_with("https:") {
-            let repo_url = genesis;
-            let mut just_cloned = false;
-            let repo =
-                git2::Repository::discover("github").or_else(|_| -> VdrResult<Repository> {
-                    just_cloned = true;
-                    Repository::clone(&repo_url, "github").map_err(|_err| {
-                        err_msg(VdrErrorKind::Unexpected, "Could not clone networks repo")
-                    })
-                })?;
-
-            // Fetch remote if not cloned just now
-            if !just_cloned {
-                let mut origin_remote = repo.find_remote("origin").map_err(|_err| {
-                    err_msg(
-                        VdrErrorKind::Unexpected,
-                        "Networks repo has no remote origin",
-                    )
-                })?;
-
-                origin_remote.fetch(&["main"], None, None).map_err(|_err| {
-                    err_msg(
-                        VdrErrorKind::Unexpected,
-                        "Could not fetch from remote networks repo",
-                    )
-                })?;
-            }
-
-            let path = repo.path().parent().unwrap().to_owned();
-
-            init_pool_state_from_folder_structure(path)?
-        } else {
-            init_pool_state_from_folder_structure(PathBuf::from(genesis))?
-        };
+        let pool_states = init_pool_states(&genesis)?;
         AppState {
             is_multiple,
             pool_states,
+            sov_namespace,
         }
     };
     Ok(state)
 }
 
-async fn run_pools(state: Rc<RefCell<AppState>>, init_refresh: bool, interval_refresh: u32) {
-    let mut pool_states = HashMap::new();
-
-    for (namespace, pool_state) in &state.clone().borrow().pool_states {
-        let pool_state = match create_pool(state.clone(), namespace.as_str(), init_refresh).await {
+async fn run_pools(
+    state: Rc<RefCell<AppState>>,
+    init_refresh: bool,
+    interval_refresh: u32,
+    metrics: Rc<Metrics>,
+    reloader: Option<(Rc<PoolReloader>, u32)>,
+) {
+    let init_pools = state
+        .borrow()
+        .pool_states
+        .iter()
+        .map(|(namespace, pool_state)| (namespace.clone(), pool_state.transactions.clone()))
+        .collect::<Vec<_>>();
+
+    for (namespace, transactions) in init_pools {
+        match create_pool(
+            state.clone(),
+            namespace.as_str(),
+            transactions.clone(),
+            init_refresh,
+            &metrics,
+        )
+        .await
+        {
             Ok(pool) => {
-                let pool = Some(pool.clone());
-                PoolState {
-                    pool: pool.clone(),
-                    last_refresh: pool_state.last_refresh,
-                    transactions: pool_state.transactions.clone(),
+                // the ledger may have been reloaded in the meantime
+                if let Some(pool_state) = state.borrow_mut().pool_states.get_mut(&namespace) {
+                    if pool_state.transactions == transactions {
+                        pool_state.pool = Some(pool);
+                    }
                 }
             }
             Err(err) => {
                 eprintln!("Error initializing pool {} with error : {}", namespace, err);
-                PoolState {
-                    pool: None,
-                    last_refresh: pool_state.last_refresh,
-                    transactions: pool_state.transactions.clone(),
-                }
             }
-        };
-
-        pool_states.insert(namespace.to_owned(), pool_state);
+        }
     }
 
-    state.borrow_mut().pool_states = pool_states;
+    if let Some((reloader, interval_reload)) = reloader {
+        if interval_reload > 0 {
+            let state = state.clone();
+            tokio::task::spawn_local(async move { reloader.run(state, interval_reload).await });
+        }
+    }
 
     let shutdown = shutdown_signal().fuse().shared();
     if interval_refresh > 0 {
         loop {
             select! {
-                refresh_result = refresh_pools(state.clone(), interval_refresh) => {
-                    match refresh_result {
-                        Ok(upd_pool_states) => {
-                            state.borrow_mut().pool_states = upd_pool_states;
-                            log::info!("Refreshed validator pools");
-                        },
-                        Err(err) => {
-                            log::error!("Error refreshing validator pool: {}", err);
-                        }
-                    }
+                _ = refresh_pools(state.clone(), interval_refresh, &metrics) => {
+                    log::info!("Refreshed validator pools");
                 }
                 _ = shutdown.clone() => {
                     println!("Shutting down");
@@ -259,50 +251,76 @@ async fn shutdown_signal() {
 async fn create_pool(
     state: Rc<RefCell<AppState>>,
     namespace: &str,
+    transactions: PoolTransactions,
     refresh: bool,
+    metrics: &Metrics,
 ) -> VdrResult<LocalPool> {
-    let pool_states = &state.borrow().pool_states;
-    let pool_state = pool_states.get(namespace).unwrap();
-    let pool =
-        PoolBuilder::new(PoolConfig::default(), pool_state.transactions.clone()).into_local()?;
+    let pool = build_pool(transactions, false)?;
     let refresh_pool = if refresh {
-        refresh_pool(state.clone(), &pool, 0).await?
+        let result = refresh_pool(state, &pool, 0).await;
+        metrics.record_refresh(namespace, RefreshOutcome::from_result(&result));
+        result?
     } else {
         None
     };
     Ok(refresh_pool.unwrap_or(pool))
 }
 
-async fn refresh_pools(
-    state: Rc<RefCell<AppState>>,
-    // pool_states: HashMap<String, PoolState>,
-    delay_mins: u32,
-) -> VdrResult<HashMap<String, PoolState>> {
-    let mut upd_pool_states = HashMap::new();
-    let pool_states = &state.borrow().pool_states;
-    for (namespace, pool_state) in pool_states {
-        if let Some(pool) = &pool_state.pool {
-            let upd_pool = match refresh_pool(state.clone(), pool, delay_mins).await {
-                Ok(p) => p,
-                Err(err) => {
-                    eprintln!(
-                        "Error refreshing validator pool {} with error {}",
-                        namespace, err
-                    );
-                    None
-                }
-            };
-            let upd_pool_state = PoolState {
-                pool: upd_pool.or_else(|| Some(pool.clone())),
-                last_refresh: Some(SystemTime::now()),
-                transactions: pool_state.transactions.clone(),
-            };
+#[cfg(feature = "async-networker")]
+fn build_pool(transactions: PoolTransactions, refreshed: bool) -> VdrResult<LocalPool> {
+    // poll the validator connections from the server runtime instead of a worker thread
+    LocalPool::build(
+        PoolConfig::default(),
+        transactions.merkle_tree()?,
+        MakeLocal(AsyncNetworkerFactory::new()),
+        None,
+        refreshed,
+    )
+}
+
+#[cfg(not(feature = "async-networker"))]
+fn build_pool(transactions: PoolTransactions, refreshed: bool) -> VdrResult<LocalPool> {
+    PoolBuilder::new(PoolConfig::default(), transactions)
+        .refreshed(refreshed)
+        .into_local()
+}
 
-            upd_pool_states.insert(namespace.to_owned(), upd_pool_state);
+async fn refresh_pools(state: Rc<RefCell<AppState>>, delay_mins: u32, metrics: &Metrics) {
+    let pools = state
+        .borrow()
+        .pool_states
+        .iter()
+        .filter_map(|(namespace, pool_state)| {
+            Some((
+                namespace.clone(),
+                pool_state.pool.clone()?,
+                pool_state.transactions.clone(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    for (namespace, pool, transactions) in pools {
+        let result = refresh_pool(state.clone(), &pool, delay_mins).await;
+        metrics.record_refresh(&namespace, RefreshOutcome::from_result(&result));
+        let upd_pool = match result {
+            Ok(p) => p,
+            Err(err) => {
+                eprintln!(
+                    "Error refreshing validator pool {} with error {}",
+                    namespace, err
+                );
+                None
+            }
+        };
+        // skip ledgers which have been removed or reloaded during the refresh
+        if let Some(pool_state) = state.borrow_mut().pool_states.get_mut(&namespace) {
+            if pool_state.transactions == transactions {
+                if let Some(upd_pool) = upd_pool {
+                    pool_state.pool.replace(upd_pool);
+                }
+                pool_state.last_refresh = Some(SystemTime::now());
+            }
         }
     }
-
-    Ok(upd_pool_states)
 }
 
 async fn refresh_pool(
@@ -317,9 +335,8 @@ async fn refresh_pool(
 
     let (txns, _meta) = perform_refresh(pool).await?;
     if let Some(txns) = txns {
-        let pool = PoolBuilder::new(PoolConfig::default(), txns)
-            .refreshed(true)
-            .into_local()?;
+        // keep the node statistics collected before the refresh
+        let pool = build_pool(txns, true)?.with_monitor(pool.monitor().clone());
         Ok(Some(pool))
     } else {
         Ok(None)
@@ -331,6 +348,7 @@ async fn init_server(config: app::Config) -> Result<(), String> {
         init_app_state(
             config.genesis.clone(),
             config.namespace.clone(),
+            config.sov_namespace.clone(),
             config.is_multiple,
         )
         .await
@@ -363,7 +381,11 @@ async fn init_server(config: app::Config) -> Result<(), String> {
     #[cfg(feature = "tls")]
     if let (Some(tls_cert_path), Some(tls_key_path)) = (&config.tls_cert_path, &config.tls_key_path)
     {
-        let tls_cfg = build_tls_config(tls_cert_path, tls_key_path)?;
+        let tls_cfg = build_tls_config(
+            tls_cert_path,
+            tls_key_path,
+            config.tls_client_ca_path.as_deref(),
+        )?;
         let tls_acceptor = TlsAcceptor::from(Arc::new(tls_cfg));
         let tcp_listener = TcpListener::bind(&addr)
             .await
@@ -391,7 +413,31 @@ async fn init_server(config: app::Config) -> Result<(), String> {
 }
 
 #[cfg(feature = "tls")]
-fn build_tls_config(cert_path: &str, key_path: &str) -> Result<ServerConfig, String> {
+fn build_tls_config(
+    cert_path: &str,
+    key_path: &str,
+    client_ca_path: Option<&str>,
+) -> Result<ServerConfig, String> {
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = if let Some(client_ca_path) = client_ca_path {
+        let ca_certs = certs(&mut BufReader::new(
+            File::open(client_ca_path)
+                .map_err(|err| format!("Error opening TLS client CA file: {}", err))?,
+        ))
+        .map_err(|err| format!("Error parsing TLS client CA file: {}", err))?;
+        let mut roots = RootCertStore::empty();
+        for cert in ca_certs {
+            roots
+                .add(&Certificate(cert))
+                .map_err(|err| format!("Error loading TLS client CA certificate: {}", err))?;
+        }
+        if roots.is_empty() {
+            return Err("Error parsing TLS client CA file: no certificates found".to_string());
+        }
+        builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+    } else {
+        builder.with_no_client_auth()
+    };
     let certs = certs(&mut BufReader::new(
         File::open(cert_path)
             .map_err(|err| format!("Error opening TLS certificate file: {}", err))?,
@@ -401,9 +447,7 @@ fn build_tls_config(cert_path: &str, key_path: &str) -> Result<ServerConfig, Str
         File::open(key_path).map_err(|err| format!("Error opening TLS key file: {}", err))?,
     ))
     .map_err(|err| format!("Error parsing TLS key file: {}", err))?;
-    ServerConfig::builder()
-        .with_safe_defaults()
-        .with_no_client_auth()
+    builder
         .with_single_cert(
             vec![Certificate(certs.into_iter().next().ok_or_else(|| {
                 "Error parsing TLS certificate file: no certificates found".to_string()
@@ -425,40 +469,102 @@ async fn run_server<I>(
 ) -> Result<(), String>
 where
     I: hyper::server::accept::Accept + 'static,
-    I::Conn: tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + Unpin,
+    I::Conn: ConnectionInfo + tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + Unpin,
     I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
 {
     let cache = if config.cache {
-        let storage_type = match config.cache_path {
-            Some(path) => {
-                let storage = OrderedHashMap::new(
-                    new_fs_ordered_store(path.clone())
-                        .expect(format!("Error creating cache at {}", path).as_str()),
-                );
-                Some(storage)
-            }
-            None => None,
-        };
-        let strategy = CacheStrategyTTL::new(
-            config.cache_size,
-            Duration::from_secs(86400).as_millis(),
-            storage_type,
-            None,
-        );
-        let cache = Cache::new(strategy, None);
+        let expire_after = Duration::from_secs(86400).as_millis();
+        let strategy: Arc<dyn CacheStrategy<String, (String, RequestResultMeta)>> =
+            if let Some(url) = config.cache_url.as_ref() {
+                Arc::new(
+                    CacheStrategyRedis::new(url, expire_after)
+                        .map_err(|err| format!("Error connecting to cache at {}: {}", url, err))?,
+                )
+            } else {
+                let storage_type = match config.cache_path {
+                    Some(path) => {
+                        let storage = OrderedHashMap::new(
+                            new_fs_ordered_store(path.clone())
+                                .expect(format!("Error creating cache at {}", path).as_str()),
+                        );
+                        Some(storage)
+                    }
+                    None => None,
+                };
+                Arc::new(CacheStrategyTTL::new(
+                    config.cache_size,
+                    expire_after,
+                    storage_type,
+                    None,
+                ))
+            };
+        let mut policy = CachePolicy::pinned();
+        if let Some(ttl) = config.cache_current_ttl {
+            policy.current_expiry = Some(Duration::from_secs(ttl).as_millis());
+        }
+        let cache = Cache::new(strategy, config.cache_prefix.clone()).with_policy(policy);
         Some(cache)
     } else {
         None
     };
 
-    let until_done = run_pools(state.clone(), config.init_refresh, config.interval_refresh);
-    let svc = make_service_fn(move |_| {
+    let api_keys = config
+        .api_keys_path
+        .as_deref()
+        .map(AccessControl::load_api_keys)
+        .transpose()?;
+    let admin_keys = config
+        .admin_keys_path
+        .as_deref()
+        .map(AccessControl::load_api_keys)
+        .transpose()?;
+    let access = Rc::new(
+        AccessControl::new(api_keys, config.rate_limits.clone()).with_admin_keys(admin_keys),
+    );
+    let metrics = Rc::new(Metrics::new());
+    let reloader = if config.is_multiple {
+        Some(Rc::new(PoolReloader::new(
+            config
+                .genesis
+                .clone()
+                .unwrap_or_else(|| String::from(INDY_NETWORKS_GITHUB)),
+            config.init_refresh,
+            metrics.clone(),
+        )))
+    } else {
+        None
+    };
+    let until_done = run_pools(
+        state.clone(),
+        config.init_refresh,
+        config.interval_refresh,
+        metrics.clone(),
+        reloader
+            .clone()
+            .map(|reloader| (reloader, config.interval_reload)),
+    );
+    let pending = Rc::new(PendingRequests::new());
+    let svc = make_service_fn(move |conn: &I::Conn| {
         let state = state.clone();
         let cache = cache.clone();
+        let metrics = metrics.clone();
+        let pending = pending.clone();
+        let access = access.clone();
+        let reloader = reloader.clone();
+        let client = conn.client_info();
         async move {
             let state = state.clone();
             Ok::<_, hyper::Error>(service_fn(move |req| {
-                handlers::handle_request(req, state.to_owned(), cache.clone())
+                handlers::handle_request(
+                    req,
+                    state.to_owned(),
+                    cache.clone(),
+                    metrics.clone(),
+                    pending.clone(),
+                    access.clone(),
+                    reloader.clone(),
+                    client.clone(),
+                )
             }))
         }
     });
@@ -0,0 +1,459 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, UNIX_EPOCH};
+
+use indy_vdr::pool::cache::CacheStats;
+use indy_vdr::pool::{RequestResultMeta, StateProofResult};
+
+use crate::routes::ROUTES;
+use crate::utils::AppState;
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// The outcome of a validator pool refresh
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RefreshOutcome {
+    Updated,
+    Unchanged,
+    Failed,
+}
+
+impl RefreshOutcome {
+    /// Classify the result of a refresh which returns any updated pool
+    pub fn from_result<T, E>(result: &Result<Option<T>, E>) -> Self {
+        match result {
+            Ok(Some(_)) => Self::Updated,
+            Ok(None) => Self::Unchanged,
+            Err(_) => Self::Failed,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Updated => "updated",
+            Self::Unchanged => "unchanged",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            buckets: [0; LATENCY_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        for (count, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, labels, bound, count
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, labels, self.count
+        )
+        .unwrap();
+        writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum).unwrap();
+        writeln!(out, "{}_count{{{}}} {}", name, labels, self.count).unwrap();
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    requests: BTreeMap<(String, &'static str, u16), u64>,
+    request_duration: BTreeMap<(String, &'static str), Histogram>,
+    node_replies: BTreeMap<(String, String), Histogram>,
+    state_proofs: BTreeMap<(String, &'static str), u64>,
+    cached_replies: BTreeMap<String, u64>,
+    refreshes: BTreeMap<(String, RefreshOutcome), u64>,
+}
+
+/// Request, validator pool and cache metrics exported in the Prometheus text format
+#[derive(Debug, Default)]
+pub struct Metrics {
+    state: RefCell<MetricsState>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map a request path segment onto a route label with bounded cardinality
+    pub fn route_label(route: &str) -> &'static str {
+        ROUTES
+            .iter()
+            .find(|known| known.name == route)
+            .map(|known| known.name)
+            .unwrap_or("other")
+    }
+
+    pub fn record_request(
+        &self,
+        namespace: &str,
+        route: &'static str,
+        status: u16,
+        duration: Duration,
+    ) {
+        let mut state = self.state.borrow_mut();
+        *state
+            .requests
+            .entry((namespace.to_owned(), route, status))
+            .or_default() += 1;
+        state
+            .request_duration
+            .entry((namespace.to_owned(), route))
+            .or_insert_with(Histogram::new)
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record the node replies and state proofs of a ledger request. Replies served
+    /// from the cache are only counted, as their metadata belongs to the original request.
+    pub fn record_ledger_result(&self, namespace: &str, meta: &RequestResultMeta) {
+        let mut state = self.state.borrow_mut();
+        if meta.cached {
+            *state
+                .cached_replies
+                .entry(namespace.to_owned())
+                .or_default() += 1;
+            return;
+        }
+        for (node, time) in meta.timing.iter().flatten() {
+            state
+                .node_replies
+                .entry((namespace.to_owned(), node.clone()))
+                .or_insert_with(Histogram::new)
+                .observe(*time as f64);
+        }
+        for result in meta.state_proof.values() {
+            let result = match result {
+                StateProofResult::Missing => "missing",
+                StateProofResult::Invalid(..) => "invalid",
+                StateProofResult::Expired(_) => "expired",
+                StateProofResult::Verified(_) => "verified",
+            };
+            *state
+                .state_proofs
+                .entry((namespace.to_owned(), result))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn record_refresh(&self, namespace: &str, outcome: RefreshOutcome) {
+        *self
+            .state
+            .borrow_mut()
+            .refreshes
+            .entry((namespace.to_owned(), outcome))
+            .or_default() += 1;
+    }
+
+    /// Render the collected metrics along with the current pool and cache state
+    pub fn render(&self, app_state: &AppState, cache_stats: Option<CacheStats>) -> String {
+        let state = self.state.borrow();
+        let mut out = String::new();
+
+        _header(
+            &mut out,
+            "indy_vdr_proxy_requests_total",
+            "counter",
+            "HTTP requests handled by route, ledger namespace and status",
+        );
+        for ((namespace, route, status), count) in &state.requests {
+            writeln!(
+                out,
+                "indy_vdr_proxy_requests_total{{namespace=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                _escape(namespace),
+                route,
+                status,
+                count
+            )
+            .unwrap();
+        }
+
+        _header(
+            &mut out,
+            "indy_vdr_proxy_request_duration_seconds",
+            "histogram",
+            "HTTP request handling time by route and ledger namespace",
+        );
+        for ((namespace, route), hist) in &state.request_duration {
+            hist.write(
+                &mut out,
+                "indy_vdr_proxy_request_duration_seconds",
+                &format!("namespace=\"{}\",route=\"{}\"", _escape(namespace), route),
+            );
+        }
+
+        _header(
+            &mut out,
+            "indy_vdr_proxy_node_reply_seconds",
+            "histogram",
+            "Validator node reply times for ledger requests",
+        );
+        for ((namespace, node), hist) in &state.node_replies {
+            hist.write(
+                &mut out,
+                "indy_vdr_proxy_node_reply_seconds",
+                &format!(
+                    "namespace=\"{}\",node=\"{}\"",
+                    _escape(namespace),
+                    _escape(node)
+                ),
+            );
+        }
+
+        _header(
+            &mut out,
+            "indy_vdr_proxy_state_proofs_total",
+            "counter",
+            "State proof verification outcomes for node replies",
+        );
+        for ((namespace, result), count) in &state.state_proofs {
+            writeln!(
+                out,
+                "indy_vdr_proxy_state_proofs_total{{namespace=\"{}\",result=\"{}\"}} {}",
+                _escape(namespace),
+                result,
+                count
+            )
+            .unwrap();
+        }
+
+        _header(
+            &mut out,
+            "indy_vdr_proxy_cached_replies_total",
+            "counter",
+            "Ledger replies served from the response cache",
+        );
+        for (namespace, count) in &state.cached_replies {
+            writeln!(
+                out,
+                "indy_vdr_proxy_cached_replies_total{{namespace=\"{}\"}} {}",
+                _escape(namespace),
+                count
+            )
+            .unwrap();
+        }
+
+        _header(
+            &mut out,
+            "indy_vdr_proxy_pool_refreshes_total",
+            "counter",
+            "Validator pool refresh attempts by outcome",
+        );
+        for ((namespace, outcome), count) in &state.refreshes {
+            writeln!(
+                out,
+                "indy_vdr_proxy_pool_refreshes_total{{namespace=\"{}\",result=\"{}\"}} {}",
+                _escape(namespace),
+                outcome.as_str(),
+                count
+            )
+            .unwrap();
+        }
+
+        let mut pools = app_state.pool_states.iter().collect::<Vec<_>>();
+        pools.sort_by(|a, b| a.0.cmp(b.0));
+        _header(
+            &mut out,
+            "indy_vdr_proxy_pool_active",
+            "gauge",
+            "Whether the validator pool for a ledger namespace is connected",
+        );
+        for (namespace, pool_state) in &pools {
+            writeln!(
+                out,
+                "indy_vdr_proxy_pool_active{{namespace=\"{}\"}} {}",
+                _escape(namespace),
+                u8::from(pool_state.pool.is_some())
+            )
+            .unwrap();
+        }
+        _header(
+            &mut out,
+            "indy_vdr_proxy_pool_last_refresh_timestamp_seconds",
+            "gauge",
+            "The time of the last validator pool refresh",
+        );
+        for (namespace, pool_state) in &pools {
+            if let Some(Ok(refreshed)) = pool_state
+                .last_refresh
+                .map(|time| time.duration_since(UNIX_EPOCH))
+            {
+                writeln!(
+                    out,
+                    "indy_vdr_proxy_pool_last_refresh_timestamp_seconds{{namespace=\"{}\"}} {}",
+                    _escape(namespace),
+                    refreshed.as_secs()
+                )
+                .unwrap();
+            }
+        }
+
+        if let Some(stats) = cache_stats {
+            _header(
+                &mut out,
+                "indy_vdr_proxy_cache_lookups_total",
+                "counter",
+                "Response cache lookups by result",
+            );
+            writeln!(
+                out,
+                "indy_vdr_proxy_cache_lookups_total{{result=\"hit\"}} {}",
+                stats.hits
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "indy_vdr_proxy_cache_lookups_total{{result=\"miss\"}} {}",
+                stats.misses
+            )
+            .unwrap();
+            _header(
+                &mut out,
+                "indy_vdr_proxy_cache_hit_ratio",
+                "gauge",
+                "The fraction of response cache lookups which found a cached value",
+            );
+            writeln!(
+                out,
+                "indy_vdr_proxy_cache_hit_ratio {}",
+                stats.hit_ratio().unwrap_or(0.0)
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+fn _header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+}
+
+fn _escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn _app_state() -> AppState {
+        AppState {
+            is_multiple: false,
+            pool_states: HashMap::new(),
+            sov_namespace: String::new(),
+        }
+    }
+
+    fn _meta(cached: bool) -> RequestResultMeta {
+        RequestResultMeta {
+            state_proof: [("Node1".to_string(), StateProofResult::Missing)]
+                .into_iter()
+                .collect(),
+            timing: Some([("Node1".to_string(), 0.2)].into_iter().collect()),
+            cached,
+        }
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut hist = Histogram::new();
+        for value in [0.003, 0.2, 40.0] {
+            hist.observe(value);
+        }
+        assert_eq!(hist.buckets[0], 1);
+        assert_eq!(hist.buckets[5], 2);
+        assert_eq!(hist.buckets[LATENCY_BUCKETS.len() - 1], 2);
+        let mut out = String::new();
+        hist.write(&mut out, "latency", "route=\"test\"");
+        assert!(out.contains("latency_bucket{route=\"test\",le=\"0.005\"} 1\n"));
+        assert!(out.contains("latency_bucket{route=\"test\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("latency_count{route=\"test\"} 3\n"));
+    }
+
+    #[test]
+    fn cached_results_are_counted_separately() {
+        let metrics = Metrics::new();
+        metrics.record_ledger_result("sovrin", &_meta(false));
+        metrics.record_ledger_result("sovrin", &_meta(true));
+        metrics.record_ledger_result("sovrin", &_meta(true));
+        let out = metrics.render(&_app_state(), None);
+        assert!(out.contains(
+            "indy_vdr_proxy_node_reply_seconds_count{namespace=\"sovrin\",node=\"Node1\"} 1\n"
+        ));
+        assert!(out.contains(
+            "indy_vdr_proxy_state_proofs_total{namespace=\"sovrin\",result=\"missing\"} 1\n"
+        ));
+        assert!(out.contains("indy_vdr_proxy_cached_replies_total{namespace=\"sovrin\"} 2\n"));
+    }
+
+    #[test]
+    fn render_requests_and_refreshes() {
+        let metrics = Metrics::new();
+        let route = Metrics::route_label(ROUTES[0].name);
+        metrics.record_request("sovrin", route, 200, Duration::from_millis(20));
+        metrics.record_request("sovrin", route, 200, Duration::from_millis(30));
+        metrics.record_refresh("sovrin", RefreshOutcome::Failed);
+        let out = metrics.render(&_app_state(), None);
+        assert!(out.contains(&format!(
+            "indy_vdr_proxy_requests_total{{namespace=\"sovrin\",route=\"{}\",status=\"200\"}} 2\n",
+            route
+        )));
+        assert!(out.contains(
+            "indy_vdr_proxy_pool_refreshes_total{namespace=\"sovrin\",result=\"failed\"} 1\n"
+        ));
+        assert!(!out.contains("indy_vdr_proxy_cache_hit_ratio"));
+    }
+
+    #[test]
+    fn labels_are_bounded_and_escaped() {
+        assert_eq!(Metrics::route_label("not-a-route"), "other");
+        assert_eq!(_escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+        assert_eq!(
+            RefreshOutcome::from_result::<(), ()>(&Ok(Some(()))),
+            RefreshOutcome::Updated
+        );
+        assert_eq!(
+            RefreshOutcome::from_result::<(), ()>(&Ok(None)),
+            RefreshOutcome::Unchanged
+        );
+    }
+}
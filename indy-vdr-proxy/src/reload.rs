@@ -0,0 +1,291 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::time::Duration;
+
+use indy_vdr::common::error::prelude::*;
+use indy_vdr::pool::PoolTransactions;
+
+use crate::metrics::Metrics;
+use crate::utils::{load_networks, AppState, PoolState};
+
+/// The ledgers changed by a reload of the network genesis transactions
+#[derive(Debug, Default)]
+pub struct ReloadSummary {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub updated: Vec<String>,
+    /// The ledgers which could not be initialized, and the error for each.
+    /// An existing pool is kept when its replacement fails.
+    pub failed: BTreeMap<String, String>,
+}
+
+impl ReloadSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.updated.is_empty()
+            && self.failed.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "added": self.added,
+            "removed": self.removed,
+            "updated": self.updated,
+            "failed": self.failed,
+        })
+    }
+}
+
+/// Resets the reloading flag when a reload completes or is cancelled
+struct ReloadGuard<'a>(&'a Cell<bool>);
+
+impl Drop for ReloadGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// Reloads the ledgers served in multiple-ledger mode when the genesis
+/// transactions in the networks folder or repository change
+pub struct PoolReloader {
+    genesis: String,
+    init_refresh: bool,
+    metrics: Rc<Metrics>,
+    reloading: Cell<bool>,
+}
+
+impl PoolReloader {
+    pub fn new(genesis: String, init_refresh: bool, metrics: Rc<Metrics>) -> Self {
+        Self {
+            genesis,
+            init_refresh,
+            metrics,
+            reloading: Cell::new(false),
+        }
+    }
+
+    /// Reload the ledgers at a fixed interval
+    pub async fn run(&self, state: Rc<RefCell<AppState>>, interval_mins: u32) {
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval_mins as u64 * 60)).await;
+            match self.reload(&state).await {
+                Ok(Some(summary)) if !summary.is_empty() => {
+                    log::info!("Reloaded ledgers: {}", summary.to_json())
+                }
+                Ok(_) => (),
+                Err(err) => log::error!("Error reloading ledgers: {}", err),
+            }
+        }
+    }
+
+    /// Add, remove and rebuild validator pools to match the current genesis
+    /// transactions, or return `None` if a reload is already in progress
+    pub async fn reload(&self, state: &Rc<RefCell<AppState>>) -> VdrResult<Option<ReloadSummary>> {
+        if self.reloading.replace(true) {
+            return Ok(None);
+        }
+        let _guard = ReloadGuard(&self.reloading);
+
+        // updating the networks repository may block, so avoid stalling the server
+        let genesis = self.genesis.clone();
+        let networks = tokio::task::spawn_blocking(move || load_networks(&genesis))
+            .await
+            .with_err_msg(VdrErrorKind::Unexpected, "Error loading networks")??;
+        if networks.is_empty() {
+            return Err(err_msg(
+                VdrErrorKind::Config,
+                "No ledgers found in networks folder",
+            ));
+        }
+
+        let current = state
+            .borrow()
+            .pool_states
+            .iter()
+            .map(|(namespace, pool_state)| (namespace.clone(), pool_state.transactions.clone()))
+            .collect::<HashMap<String, PoolTransactions>>();
+        let mut summary = ReloadSummary {
+            removed: current
+                .keys()
+                .filter(|namespace| !networks.contains_key(*namespace))
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+
+        // build the new pools first, so that requests use the previous pools until ready
+        let mut pool_states = Vec::new();
+        for (namespace, transactions) in networks {
+            let is_new = match current.get(&namespace) {
+                None => true,
+                Some(txns) if *txns != transactions => false,
+                _ => continue,
+            };
+            let pool = match crate::create_pool(
+                state.clone(),
+                &namespace,
+                transactions.clone(),
+                self.init_refresh,
+                &self.metrics,
+            )
+            .await
+            {
+                Ok(pool) => pool,
+                Err(err) => {
+                    // keep serving the previous pool, and retry on the next reload
+                    log::error!("Error initializing pool {} with error: {}", namespace, err);
+                    summary.failed.insert(namespace, err.to_string());
+                    continue;
+                }
+            };
+            if is_new {
+                summary.added.push(namespace.clone());
+            } else {
+                summary.updated.push(namespace.clone());
+            }
+            pool_states.push((
+                namespace,
+                PoolState {
+                    pool: Some(pool),
+                    last_refresh: None,
+                    transactions,
+                },
+            ));
+        }
+
+        let mut state = state.borrow_mut();
+        for namespace in &summary.removed {
+            state.pool_states.remove(namespace);
+        }
+        state.pool_states.extend(pool_states);
+
+        summary.added.sort();
+        summary.removed.sort();
+        summary.updated.sort();
+        Ok(Some(summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use std::fs;
+    use std::path::Path;
+
+    use indy_vdr::pool::networker::MockLedger;
+
+    use super::*;
+
+    const GENESIS_FILENAME: &str = "pool_transactions_genesis.json";
+
+    fn _write_genesis(folder: &Path, namespace: &str, genesis: &str) {
+        fs::create_dir_all(folder.join(namespace)).unwrap();
+        fs::write(folder.join(namespace).join(GENESIS_FILENAME), genesis).unwrap();
+    }
+
+    fn _genesis(node_count: usize) -> String {
+        MockLedger::new(node_count)
+            .unwrap()
+            .pool_transactions()
+            .unwrap()
+            .encode_json_string()
+            .unwrap()
+    }
+
+    fn _reload(
+        reloader: &PoolReloader,
+        state: &Rc<RefCell<AppState>>,
+    ) -> VdrResult<Option<ReloadSummary>> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(reloader.reload(state))
+    }
+
+    fn _new_state() -> Rc<RefCell<AppState>> {
+        Rc::new(RefCell::new(AppState {
+            is_multiple: true,
+            pool_states: HashMap::new(),
+            sov_namespace: "sovrin".to_owned(),
+        }))
+    }
+
+    #[test]
+    fn reload_keeps_pool_when_update_fails() {
+        let folder = temp_dir().join(format!("vdr-proxy-networks-{:020}", rand::random::<u64>()));
+        _write_genesis(&folder, "alpha", &_genesis(4));
+        _write_genesis(&folder, "beta", &_genesis(4));
+        _write_genesis(&folder, "gamma", &_genesis(4));
+        let reloader = PoolReloader::new(
+            folder.to_string_lossy().into_owned(),
+            false,
+            Rc::new(Metrics::new()),
+        );
+        let state = _new_state();
+
+        let summary = _reload(&reloader, &state).unwrap().unwrap();
+        assert_eq!(summary.added, ["alpha", "beta", "gamma"]);
+        assert!(summary.failed.is_empty());
+        let beta_txns = state.borrow().pool_states["beta"].transactions.clone();
+
+        // a ledger with invalid genesis transactions continues to use the previous pool
+        _write_genesis(&folder, "alpha", &_genesis(5));
+        _write_genesis(&folder, "beta", "{\"txn\": {}}");
+        _write_genesis(&folder, "delta", "{\"txn\": {}}");
+        fs::remove_dir_all(folder.join("gamma")).unwrap();
+        let summary = _reload(&reloader, &state).unwrap().unwrap();
+        assert_eq!(summary.added, Vec::<String>::new());
+        assert_eq!(summary.removed, ["gamma"]);
+        assert_eq!(summary.updated, ["alpha"]);
+        assert_eq!(summary.failed.keys().collect::<Vec<_>>(), ["beta", "delta"]);
+        assert_eq!(
+            summary.to_json()["failed"]["beta"].as_str(),
+            summary.failed.get("beta").map(String::as_str)
+        );
+        {
+            let state = state.borrow();
+            let mut namespaces = state.pool_states.keys().collect::<Vec<_>>();
+            namespaces.sort();
+            assert_eq!(namespaces, ["alpha", "beta"]);
+            let beta = &state.pool_states["beta"];
+            assert!(beta.pool.is_some());
+            assert!(beta.transactions == beta_txns);
+        }
+
+        // failed ledgers are retried on the next reload
+        _write_genesis(&folder, "beta", &_genesis(5));
+        let summary = _reload(&reloader, &state).unwrap().unwrap();
+        assert_eq!(summary.updated, ["beta"]);
+        assert_eq!(summary.failed.keys().collect::<Vec<_>>(), ["delta"]);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn reload_requires_ledgers() {
+        let folder = temp_dir().join(format!("vdr-proxy-networks-{:020}", rand::random::<u64>()));
+        fs::create_dir_all(&folder).unwrap();
+        let reloader = PoolReloader::new(
+            folder.to_string_lossy().into_owned(),
+            false,
+            Rc::new(Metrics::new()),
+        );
+        let state = _new_state();
+        let err = _reload(&reloader, &state).unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::Config));
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn reload_in_progress() {
+        let reloader = PoolReloader::new("missing".to_owned(), false, Rc::new(Metrics::new()));
+        reloader.reloading.set(true);
+        assert!(_reload(&reloader, &_new_state()).unwrap().is_none());
+        // the flag is reset when a reload completes
+        reloader.reloading.set(false);
+        assert!(_reload(&reloader, &_new_state()).is_err());
+        assert!(!reloader.reloading.get());
+    }
+}
@@ -0,0 +1,375 @@
+use hyper::Method;
+use indy_vdr::common::error::VdrErrorKind;
+use serde_json::{Map, Value as SJsonValue};
+
+/// Whether a route is served for each ledger or once for the server
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteScope {
+    /// The path follows the ledger namespace in multiple-ledger mode
+    Ledger,
+    /// The path is absolute
+    Server,
+}
+
+/// An endpoint served by the proxy
+#[derive(Debug)]
+pub struct Route {
+    /// The route label, which is also the first path segment of ledger routes
+    pub name: &'static str,
+    pub method: Method,
+    pub scope: RouteScope,
+    /// The path template, with parameters in braces
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub summary: &'static str,
+    /// Optional query parameters, as pairs of name and JSON schema type
+    pub query: &'static [(&'static str, &'static str)],
+    /// Whether the request takes a JSON body
+    pub body: bool,
+    /// The content type of a successful response
+    pub content_type: &'static str,
+}
+
+const JSON: &str = "application/json";
+const SEQ_NO_QUERY: &[(&str, &str)] = &[("seq_no", "integer"), ("timestamp", "integer")];
+
+macro_rules! route {
+    ($name:literal, $method:ident, $scope:ident, $path:literal, $op:literal, $summary:literal $(, $field:ident: $value:expr)*) => {
+        Route {
+            name: $name,
+            method: Method::$method,
+            scope: RouteScope::$scope,
+            path: $path,
+            operation_id: $op,
+            summary: $summary,
+            $($field: $value,)*
+            ..DEFAULT_ROUTE
+        }
+    };
+}
+
+const DEFAULT_ROUTE: Route = Route {
+    name: "",
+    method: Method::GET,
+    scope: RouteScope::Ledger,
+    path: "",
+    operation_id: "",
+    summary: "",
+    query: &[],
+    body: false,
+    content_type: JSON,
+};
+
+/// The routes served by the proxy, used to validate requests, label metrics and
+/// generate the OpenAPI document
+#[rustfmt::skip]
+pub const ROUTES: &[Route] = &[
+    route!("ledgers", GET, Server, "/", "getLedgers", "List the ledger namespaces served in multiple-ledger mode", content_type: "text/plain"),
+    route!("status", GET, Ledger, "", "getPoolStatus", "Get the status of the validator pool"),
+    route!("genesis", GET, Ledger, "/genesis", "getGenesis", "Get the current pool transactions, one JSON transaction per line", content_type: "text/plain"),
+    route!("stats", GET, Ledger, "/stats", "getPoolStats", "Get the observed statistics for each validator node"),
+    route!("taa", GET, Ledger, "/taa", "getTaa", "Get the current transaction author agreement"),
+    route!("aml", GET, Ledger, "/aml", "getAml", "Get the current transaction author agreement acceptance mechanisms"),
+    route!("attrib", GET, Ledger, "/attrib/{dest}/{attrib}", "getAttrib", "Get a raw attribute for a DID", query: SEQ_NO_QUERY),
+    route!("auth", GET, Ledger, "/auth", "getAuthRules", "Get all ledger authorization rules"),
+    route!("auth", GET, Ledger, "/auth/{auth_type}/{auth_action}", "getAuthRule", "Get the authorization rules for a transaction type and action"),
+    route!("cred_def", GET, Ledger, "/cred_def/{cred_def_id}", "getCredDef", "Get a credential definition"),
+    route!("nym", GET, Ledger, "/nym/{nym}", "getNym", "Get a NYM transaction", query: SEQ_NO_QUERY),
+    route!("rev_reg_def", GET, Ledger, "/rev_reg_def/{rev_reg_def_id}", "getRevRegDef", "Get a revocation registry definition"),
+    route!("rev_reg", GET, Ledger, "/rev_reg/{rev_reg_def_id}", "getRevReg", "Get the current revocation registry entry"),
+    route!("rev_reg_delta", GET, Ledger, "/rev_reg_delta/{rev_reg_def_id}", "getRevRegDelta", "Get the revocation registry delta from the start of the registry"),
+    route!("schema", GET, Ledger, "/schema/{schema_id}", "getSchema", "Get a schema"),
+    route!("flag", GET, Ledger, "/flag/{name}", "getFlag", "Get the value of a config ledger flag", query: SEQ_NO_QUERY),
+    route!("frozen_ledgers", GET, Ledger, "/frozen_ledgers", "getFrozenLedgers", "Get the frozen ledgers"),
+    #[cfg(feature = "rich_schema")]
+    route!("rich_schema", GET, Ledger, "/rich_schema/{rs_id}", "getRichSchemaById", "Get a rich schema object by its identifier"),
+    #[cfg(feature = "rich_schema")]
+    route!("rich_schema", GET, Ledger, "/rich_schema/{rs_type}/{rs_name}/{rs_version}", "getRichSchemaByMetadata", "Get a rich schema object by its type, name and version"),
+    route!("txn", GET, Ledger, "/txn/{ledger_type}/{seq_no}", "getTxn", "Get a ledger transaction by sequence number"),
+    route!("txns", GET, Ledger, "/txns/{ledger_type}/{range}", "getTxnRange", "Get a verified range of ledger transactions, given as `FROM-TO`"),
+    route!("prepare", POST, Ledger, "/prepare/{txn_type}", "prepareRequest", "Build an unsigned write or validator info request to be signed by the client", body: true),
+    route!("submit", POST, Ledger, "/submit", "submitRequest", "Submit a signed ledger request", body: true),
+    route!("submit", POST, Ledger, "/submit/{handle}", "submitPrepared", "Submit a prepared request with the client signatures", body: true),
+    route!("identifiers", GET, Server, "/1.0/identifiers/{did}", "resolveDid", "Resolve a DID or dereference a DID URL"),
+    route!("metrics", GET, Server, "/metrics", "getMetrics", "Get the proxy metrics in the Prometheus text format", content_type: "text/plain"),
+    route!("reload", POST, Server, "/admin/reload", "reloadLedgers", "Reload the ledgers in multiple-ledger mode"),
+    route!("openapi", GET, Server, "/openapi.json", "getOpenApi", "Get the OpenAPI document for the proxy"),
+];
+
+/// Find the server route matching an absolute path without parameters
+pub fn find_server_route(path: &str) -> Option<&'static Route> {
+    ROUTES
+        .iter()
+        .find(|route| route.scope == RouteScope::Server && route.path == path && path != "/")
+}
+
+/// Find the ledger route matching a method and the path segments following the
+/// ledger namespace, returning the allowed methods if the method is not supported
+pub fn check_ledger_route<S: AsRef<str>>(
+    method: &Method,
+    segments: &[S],
+) -> Result<&'static Route, Option<Vec<&'static str>>> {
+    let routes = ROUTES
+        .iter()
+        .filter(|route| route.scope == RouteScope::Ledger && route.matches_path(segments))
+        .collect::<Vec<_>>();
+    if let Some(route) = routes.iter().find(|route| route.method == method) {
+        Ok(route)
+    } else if routes.is_empty() {
+        Err(None)
+    } else {
+        let mut allowed = routes
+            .iter()
+            .map(|route| route.method.as_str())
+            .collect::<Vec<_>>();
+        allowed.dedup();
+        Err(Some(allowed))
+    }
+}
+
+impl Route {
+    /// Check whether the path template matches a sequence of path segments
+    fn matches_path<S: AsRef<str>>(&self, segments: &[S]) -> bool {
+        let template = self
+            .path
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>();
+        template.len() == segments.len()
+            && template
+                .iter()
+                .zip(segments)
+                .all(|(part, segment)| part.starts_with('{') || *part == segment.as_ref())
+    }
+}
+
+/// Generate the OpenAPI document describing the routes
+pub fn openapi(is_multiple: bool) -> SJsonValue {
+    let mut paths = Map::new();
+    for route in ROUTES {
+        if route.name == "ledgers" && !is_multiple {
+            continue;
+        }
+        let path = match (route.scope, is_multiple) {
+            (RouteScope::Ledger, true) => format!("/{{ledger}}{}", route.path),
+            (RouteScope::Ledger, false) if route.path.is_empty() => "/".to_owned(),
+            _ => route.path.to_owned(),
+        };
+        let path_params = path
+            .split('/')
+            .filter_map(|part| part.strip_prefix('{')?.strip_suffix('}'))
+            .map(|name| {
+                json!({"name": name, "in": "path", "required": true, "schema": {"type": "string"}})
+            });
+        let query_params = route.query.iter().map(|(name, kind)| {
+            json!({"name": name, "in": "query", "required": false, "schema": {"type": kind}})
+        });
+        let mut operation = json!({
+            "operationId": route.operation_id,
+            "summary": route.summary,
+            "tags": [if route.scope == RouteScope::Ledger { "ledger" } else { "server" }],
+            "parameters": path_params.chain(query_params).collect::<Vec<_>>(),
+            "responses": {
+                "200": {
+                    "description": "Successful response",
+                    "content": {route.content_type: {"schema": if route.content_type == JSON {
+                        json!({"type": "object"})
+                    } else {
+                        json!({"type": "string"})
+                    }}},
+                },
+                "default": {"$ref": "#/components/responses/Error"},
+            },
+        });
+        if route.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": {JSON: {"schema": {"type": "object"}}},
+            });
+        }
+        let entry = paths.entry(path).or_insert_with(|| json!({}));
+        entry[route.method.as_str().to_ascii_lowercase()] = operation;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Indy VDR Proxy",
+            "description": env!("CARGO_PKG_DESCRIPTION"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "responses": {
+                "Error": {
+                    "description": "Error response",
+                    "content": {JSON: {"schema": {"$ref": "#/components/schemas/Error"}}},
+                },
+            },
+            "schemas": {
+                "Error": {
+                    "type": "object",
+                    "required": ["status", "kind", "message"],
+                    "properties": {
+                        "status": {"type": "integer", "description": "The HTTP status code"},
+                        "kind": {
+                            "type": "string",
+                            "nullable": true,
+                            "enum": ERROR_KINDS
+                                .iter()
+                                .map(|kind| SJsonValue::from(*kind))
+                                .chain([SJsonValue::Null])
+                                .collect::<Vec<_>>(),
+                            "description": "The indy-vdr error kind, or null for HTTP errors",
+                        },
+                        "message": {"type": "string"},
+                        "extra": {
+                            "nullable": true,
+                            "description": "The rejection from the ledger when a request fails, or the reply from each node when no consensus is reached",
+                        },
+                    },
+                },
+            },
+        },
+    })
+}
+
+/// The names of the `VdrErrorKind` variants reported in error responses
+pub const ERROR_KINDS: [&str; 12] = [
+    "Config",
+    "Connection",
+    "FileSystem",
+    "Input",
+    "Resource",
+    "Unavailable",
+    "Unexpected",
+    "Incompatible",
+    "PoolNoConsensus",
+    "PoolRequestFailed",
+    "PoolTimeout",
+    "Resolver",
+];
+
+/// Get the name of an error kind as reported in error responses
+pub fn error_kind_name(kind: &VdrErrorKind) -> &'static str {
+    match kind {
+        VdrErrorKind::Config => "Config",
+        VdrErrorKind::Connection => "Connection",
+        VdrErrorKind::FileSystem => "FileSystem",
+        VdrErrorKind::Input => "Input",
+        VdrErrorKind::Resource => "Resource",
+        VdrErrorKind::Unavailable => "Unavailable",
+        VdrErrorKind::Unexpected => "Unexpected",
+        VdrErrorKind::Incompatible => "Incompatible",
+        VdrErrorKind::PoolNoConsensus => "PoolNoConsensus",
+        VdrErrorKind::PoolRequestFailed(_) => "PoolRequestFailed",
+        VdrErrorKind::PoolTimeout => "PoolTimeout",
+        VdrErrorKind::Resolver => "Resolver",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_kind_names_are_listed() {
+        let kinds = [
+            VdrErrorKind::Config,
+            VdrErrorKind::Connection,
+            VdrErrorKind::FileSystem,
+            VdrErrorKind::Input,
+            VdrErrorKind::Resource,
+            VdrErrorKind::Unavailable,
+            VdrErrorKind::Unexpected,
+            VdrErrorKind::Incompatible,
+            VdrErrorKind::PoolNoConsensus,
+            VdrErrorKind::PoolRequestFailed("reason".to_owned()),
+            VdrErrorKind::PoolTimeout,
+            VdrErrorKind::Resolver,
+        ];
+        let names = kinds.iter().map(error_kind_name).collect::<Vec<_>>();
+        assert_eq!(names, ERROR_KINDS);
+    }
+
+    #[test]
+    fn ledger_routes_match_full_path() {
+        let route = check_ledger_route(&Method::GET, &["nym", "V4SGRU86Z58d6TV7PBUe6f"]).unwrap();
+        assert_eq!(route.operation_id, "getNym");
+        let route = check_ledger_route::<&str>(&Method::GET, &[]).unwrap();
+        assert_eq!(route.name, "status");
+        let route = check_ledger_route(&Method::POST, &["submit", "handle"]).unwrap();
+        assert_eq!(route.operation_id, "submitPrepared");
+        assert_eq!(
+            check_ledger_route(&Method::GET, &["auth"])
+                .unwrap()
+                .operation_id,
+            "getAuthRules"
+        );
+        assert_eq!(
+            check_ledger_route(&Method::GET, &["auth", "1"]).unwrap_err(),
+            None
+        );
+        assert_eq!(
+            check_ledger_route(&Method::GET, &["nym", "V4SGRU86Z58d6TV7PBUe6f", "extra"])
+                .unwrap_err(),
+            None
+        );
+        assert_eq!(
+            check_ledger_route(&Method::GET, &["unknown"]).unwrap_err(),
+            None
+        );
+        assert_eq!(
+            check_ledger_route(&Method::GET, &["submit"]).unwrap_err(),
+            Some(vec!["POST"])
+        );
+        assert_eq!(
+            check_ledger_route(&Method::POST, &["nym", "V4SGRU86Z58d6TV7PBUe6f"]).unwrap_err(),
+            Some(vec!["GET"])
+        );
+        // server routes are not served for a ledger
+        assert_eq!(
+            check_ledger_route(&Method::GET, &["metrics"]).unwrap_err(),
+            None
+        );
+    }
+
+    #[test]
+    fn openapi_paths_match_routes() {
+        for is_multiple in [false, true] {
+            let doc = openapi(is_multiple);
+            let paths = doc["paths"].as_object().unwrap();
+            let mut operations = paths
+                .values()
+                .flat_map(|path| path.as_object().unwrap().values())
+                .map(|operation| operation["operationId"].as_str().unwrap())
+                .collect::<Vec<_>>();
+            operations.sort_unstable();
+            let mut expected = ROUTES
+                .iter()
+                .filter(|route| is_multiple || route.name != "ledgers")
+                .map(|route| route.operation_id)
+                .collect::<Vec<_>>();
+            expected.sort_unstable();
+            assert_eq!(operations, expected);
+            for route in ROUTES
+                .iter()
+                .filter(|route| route.scope == RouteScope::Ledger)
+            {
+                let path = if is_multiple {
+                    format!("/{{ledger}}{}", route.path)
+                } else if route.path.is_empty() {
+                    "/".to_owned()
+                } else {
+                    route.path.to_owned()
+                };
+                let method = route.method.as_str().to_ascii_lowercase();
+                assert_eq!(
+                    paths[&path][&method]["operationId"], route.operation_id,
+                    "{}",
+                    path
+                );
+            }
+        }
+    }
+}
@@ -1,13 +1,27 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
+
+use git2::{Repository, ResetType};
+use serde_json::Value as SJsonValue;
 
 use indy_vdr::common::error::prelude::*;
-use indy_vdr::pool::{LocalPool, PoolTransactions};
+use indy_vdr::pool::{LocalPool, PoolTransactions, PreparedRequest};
+use indy_vdr::resolver::did::{DidMethod, DidUrl};
+use indy_vdr::resolver::multi_pool::find_networks;
+use indy_vdr::utils::base58;
 
 pub const INDY_NETWORKS_GITHUB: &str = "https://github.com/IDunion/indy-did-networks";
-pub const GENESIS_FILENAME: &str = "pool_transactions_genesis.json";
+/// The local path of the networks repository clone
+pub const NETWORKS_REPO_PATH: &str = "github";
+
+/// The time a prepared request may wait for its signatures before it is discarded
+pub const PENDING_REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+/// The maximum number of prepared requests waiting for signatures from one client
+pub const MAX_PENDING_REQUESTS: usize = 100;
+/// The maximum number of prepared requests waiting for signatures from all clients
+pub const MAX_PENDING_REQUESTS_TOTAL: usize = 10000;
 
 pub struct PoolState {
     pub pool: Option<LocalPool>,
@@ -18,58 +32,256 @@ pub struct PoolState {
 pub struct AppState {
     pub is_multiple: bool,
     pub pool_states: HashMap<String, PoolState>,
+    /// The ledger resolving did:sov and unqualified DIDs
+    pub sov_namespace: String,
 }
 
-pub fn init_pool_state_from_folder_structure(
-    path: PathBuf,
-) -> VdrResult<HashMap<String, PoolState>> {
-    let mut networks = HashMap::new();
+impl AppState {
+    /// Get the namespace of the ledger resolving a DID (URL). The sub-namespace of a
+    /// did:sov DID, such as `did:sov:staging:<id>`, is appended to the did:sov ledger.
+    pub fn did_namespace(&self, did: &DidUrl) -> String {
+        if did.method() == DidMethod::Indy {
+            did.namespace.clone()
+        } else if did.namespace.is_empty() {
+            self.sov_namespace.clone()
+        } else {
+            format!("{}:{}", self.sov_namespace, did.namespace)
+        }
+    }
+}
 
-    let entries = fs::read_dir(path).map_err(|err| {
-        err_msg(
-            VdrErrorKind::FileSystem,
-            "Could not read local networks folder",
-        )
-        .with_source(err)
-    })?;
+struct PendingRequest {
+    namespace: String,
+    req_json: SJsonValue,
+    created: Instant,
+}
+
+impl PendingRequest {
+    fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.created) >= PENDING_REQUEST_TIMEOUT
+    }
+}
+
+/// Unsigned write requests prepared by the proxy, waiting for client signatures.
+/// Requests are held for the client which prepared them, and are discarded once
+/// they expire.
+#[derive(Default)]
+pub struct PendingRequests {
+    requests: RefCell<HashMap<String, HashMap<String, PendingRequest>>>,
+}
 
-    for entry in entries {
-        let entry = entry.unwrap();
-        // filter hidden directories starting with "." and files
-        if !entry.file_name().to_str().unwrap().starts_with('.')
-            && entry.metadata().unwrap().is_dir()
+impl PendingRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a prepared request for a client and return its handle, or `None` if
+    /// the client or the store has too many pending requests
+    pub fn insert(
+        &self,
+        client_id: &str,
+        namespace: &str,
+        request: PreparedRequest,
+    ) -> Option<String> {
+        let mut requests = self.requests.borrow_mut();
+        let now = Instant::now();
+        requests.retain(|_, client_requests| {
+            client_requests.retain(|_, pending| !pending.is_expired(now));
+            !client_requests.is_empty()
+        });
+        let total = requests.values().map(HashMap::len).sum::<usize>();
+        let client_requests = requests.entry(client_id.to_owned()).or_default();
+        if client_requests.len() >= MAX_PENDING_REQUESTS || total >= MAX_PENDING_REQUESTS_TOTAL {
+            return None;
+        }
+        let handle = base58::encode(rand::random::<[u8; 16]>());
+        client_requests.insert(
+            handle.clone(),
+            PendingRequest {
+                namespace: namespace.to_owned(),
+                req_json: request.req_json,
+                created: now,
+            },
+        );
+        Some(handle)
+    }
+
+    /// Get a copy of a prepared request of the client which has not expired
+    pub fn get(
+        &self,
+        client_id: &str,
+        namespace: &str,
+        handle: &str,
+    ) -> VdrResult<Option<PreparedRequest>> {
+        let requests = self.requests.borrow();
+        match requests
+            .get(client_id)
+            .and_then(|client_requests| client_requests.get(handle))
         {
-            let namespace = entry.path().file_name().unwrap().to_owned();
-            let sub_entries = fs::read_dir(entry.path()).unwrap();
-            for sub_entry in sub_entries {
-                let sub_entry_path = sub_entry.unwrap().path();
-                let sub_namespace = if sub_entry_path.is_dir() {
-                    sub_entry_path.file_name()
-                } else {
-                    None
-                };
-                let (ledger_prefix, genesis_txns) = match sub_namespace {
-                    Some(sub_namespace) => (
-                        format!(
-                            "{}:{}",
-                            namespace.to_str().unwrap(),
-                            sub_namespace.to_str().unwrap()
-                        ),
-                        PoolTransactions::from_json_file(sub_entry_path.join(GENESIS_FILENAME))?,
-                    ),
-                    None => (
-                        String::from(namespace.to_str().unwrap()),
-                        PoolTransactions::from_json_file(entry.path().join(GENESIS_FILENAME))?,
-                    ),
-                };
-                let pool_state = PoolState {
+            Some(pending)
+                if pending.namespace == namespace && !pending.is_expired(Instant::now()) =>
+            {
+                PreparedRequest::from_request_json_ext(pending.req_json.clone(), false, None)
+                    .map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Discard a prepared request of the client
+    pub fn remove(&self, client_id: &str, handle: &str) {
+        let mut requests = self.requests.borrow_mut();
+        if let Some(client_requests) = requests.get_mut(client_id) {
+            client_requests.remove(handle);
+            if client_requests.is_empty() {
+                requests.remove(client_id);
+            }
+        }
+    }
+}
+
+/// Load the initial state for each ledger from a local folder or networks repository
+pub fn init_pool_states(genesis: &str) -> VdrResult<HashMap<String, PoolState>> {
+    Ok(load_networks(genesis)?
+        .into_iter()
+        .map(|(namespace, transactions)| {
+            (
+                namespace,
+                PoolState {
                     pool: None,
                     last_refresh: None,
-                    transactions: genesis_txns,
-                };
-                networks.insert(ledger_prefix, pool_state);
+                    transactions,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Load the genesis transactions for each ledger from a local folder or networks
+/// repository, updating the local copy of the repository from its remote
+pub fn load_networks(genesis: &str) -> VdrResult<HashMap<String, PoolTransactions>> {
+    if genesis.starts_with("https:") {
+        load_networks_from_folder(sync_networks_repo(genesis)?)
+    } else {
+        load_networks_from_folder(PathBuf::from(genesis))
+    }
+}
+
+/// Clone the networks repository, or fetch and check out the latest revision of an
+/// existing clone, returning the path to the working directory
+pub fn sync_networks_repo(repo_url: &str) -> VdrResult<PathBuf> {
+    let repo = match Repository::discover(NETWORKS_REPO_PATH) {
+        Ok(repo) => {
+            update_networks_repo(&repo)?;
+            repo
+        }
+        Err(_) => Repository::clone(repo_url, NETWORKS_REPO_PATH)
+            .map_err(|_err| err_msg(VdrErrorKind::Unexpected, "Could not clone networks repo"))?,
+    };
+
+    Ok(repo.path().parent().unwrap().to_owned())
+}
+
+fn update_networks_repo(repo: &Repository) -> VdrResult<()> {
+    let mut origin_remote = repo.find_remote("origin").map_err(|_err| {
+        err_msg(
+            VdrErrorKind::Unexpected,
+            "Networks repo has no remote origin",
+        )
+    })?;
+
+    origin_remote
+        .fetch(&["+refs/heads/main:refs/remotes/origin/main"], None, None)
+        .map_err(|_err| {
+            err_msg(
+                VdrErrorKind::Unexpected,
+                "Could not fetch from remote networks repo",
+            )
+        })?;
+
+    let head = repo
+        .find_reference("refs/remotes/origin/main")
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(|_err| {
+            err_msg(
+                VdrErrorKind::Unexpected,
+                "Networks repo has no remote main branch",
+            )
+        })?;
+    repo.reset(head.as_object(), ResetType::Hard, None)
+        .map_err(|_err| {
+            err_msg(
+                VdrErrorKind::Unexpected,
+                "Could not update local networks repo",
+            )
+        })
+}
+
+/// Load the genesis transactions for each ledger from a folder structure
+/// of `<namespace>/<sub_namespace>/pool_transactions_genesis.json`
+pub fn load_networks_from_folder(path: PathBuf) -> VdrResult<HashMap<String, PoolTransactions>> {
+    find_networks(&path)?
+        .into_iter()
+        .map(|(namespace, genesis)| Ok((namespace, PoolTransactions::from_json_file(genesis)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indy_vdr::ledger::RequestBuilder;
+    use indy_vdr::pool::ProtocolVersion;
+    use indy_vdr::utils::did::DidValue;
+
+    use super::*;
+
+    fn _request() -> PreparedRequest {
+        let did = DidValue("V4SGRU86Z58d6TV7PBUe6f".to_owned());
+        RequestBuilder::new(ProtocolVersion::Node1_4)
+            .build_nym_request(&did, &did, None, None, None, None, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn pending_requests_are_scoped_per_client() {
+        let pending = PendingRequests::new();
+        let request = _request();
+        let req_json = request.req_json.clone();
+        let handle = pending.insert("key:a", "test", request).unwrap();
+        assert!(pending.get("key:b", "test", &handle).unwrap().is_none());
+        assert!(pending.get("key:a", "other", &handle).unwrap().is_none());
+        let request = pending.get("key:a", "test", &handle).unwrap().unwrap();
+        assert_eq!(request.req_json, req_json);
+        assert!(pending.get("key:a", "test", &handle).unwrap().is_some());
+        pending.remove("key:b", &handle);
+        assert!(pending.get("key:a", "test", &handle).unwrap().is_some());
+        pending.remove("key:a", &handle);
+        assert!(pending.get("key:a", "test", &handle).unwrap().is_none());
+    }
+
+    #[test]
+    fn pending_requests_are_limited_per_client() {
+        let pending = PendingRequests::new();
+        for _ in 0..MAX_PENDING_REQUESTS {
+            pending.insert("key:a", "test", _request()).unwrap();
+        }
+        assert!(pending.insert("key:a", "test", _request()).is_none());
+        assert!(pending.insert("key:b", "test", _request()).is_some());
+    }
+
+    #[test]
+    fn pending_requests_expire() {
+        let pending = PendingRequests::new();
+        let handle = pending.insert("key:a", "test", _request()).unwrap();
+        let Some(created) = Instant::now().checked_sub(PENDING_REQUEST_TIMEOUT) else {
+            return;
+        };
+        for client_requests in pending.requests.borrow_mut().values_mut() {
+            for request in client_requests.values_mut() {
+                request.created = created;
             }
         }
+        assert!(pending.get("key:a", "test", &handle).unwrap().is_none());
+        pending.insert("key:b", "test", _request()).unwrap();
+        assert!(!pending.requests.borrow().contains_key("key:a"));
     }
-    Ok(networks)
 }
@@ -14,6 +14,7 @@ pub mod prelude {
 pub struct VdrError {
     kind: VdrErrorKind,
     msg: Option<String>,
+    extra: Option<String>,
     #[source]
     source: Option<Box<dyn std::error::Error + Send + Sync>>,
     // backtrace (when supported)
@@ -56,7 +57,12 @@ impl VdrError {
         msg: Option<String>,
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     ) -> Self {
-        Self { kind, msg, source }
+        Self {
+            kind,
+            msg,
+            extra: None,
+            source,
+        }
     }
 
     pub fn kind(&self) -> &VdrErrorKind {
@@ -66,10 +72,16 @@ impl VdrError {
     pub fn extra(&self) -> Option<String> {
         match self.kind {
             VdrErrorKind::PoolRequestFailed(ref response) => Some(response.clone()),
-            _ => None,
+            _ => self.extra.clone(),
         }
     }
 
+    /// Attach additional details, such as the node replies for a failed request
+    pub fn with_extra(mut self, extra: String) -> Self {
+        self.extra.replace(extra);
+        self
+    }
+
     pub fn with_source<E>(mut self, source: E) -> Self
     where
         E: Into<Box<dyn std::error::Error + Send + Sync>>,
@@ -124,6 +136,7 @@ impl From<std::io::Error> for VdrError {
     }
 }
 
+#[cfg(feature = "zmq")]
 impl From<zmq::Error> for VdrError {
     fn from(err: zmq::Error) -> VdrError {
         VdrError::new(VdrErrorKind::Connection, None, Some(Box::new(err)))
@@ -1,7 +1,7 @@
 pub(crate) mod constants;
 pub(crate) mod types;
 
-pub use types::PoolConfig;
+pub use types::{NodeSelection, PoolConfig};
 
 /// Library package version
 pub static LIB_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -30,6 +30,24 @@ pub struct PoolConfig {
     /// The socks proxy host name and port for ZMQ (example: proxy1.intranet.company.com:1080)
     #[serde(default = "PoolConfig::default_socks_proxy")]
     pub socks_proxy: Option<String>,
+    /// The strategy used to order nodes when dispatching requests
+    #[serde(default)]
+    pub node_selection: NodeSelection,
+}
+
+/// Strategies for ordering validator nodes when dispatching requests
+///
+/// The node order determines which nodes receive the initial ledger read requests
+/// and the order of any resends. Write requests are always sent to every node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeSelection {
+    /// Choose nodes randomly according to the static node weights
+    #[default]
+    Weighted,
+    /// Adjust the static node weights according to the observed response times and
+    /// timeout rates of each node
+    Adaptive,
 }
 
 impl Validatable for PoolConfig {
@@ -109,6 +127,7 @@ impl Default for PoolConfig {
             conn_active_timeout: Self::default_conn_active_timeout(),
             request_read_nodes: Self::default_request_read_nodes(),
             socks_proxy: Self::default_socks_proxy(),
+            node_selection: NodeSelection::default(),
         }
     }
 }
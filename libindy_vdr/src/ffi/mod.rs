@@ -15,16 +15,19 @@ mod resolver;
 
 use crate::common::error::prelude::*;
 use crate::config::{PoolConfig, LIB_VERSION};
+use crate::ledger::constants::{txn_name_to_code, READ_REQUESTS};
 use crate::pool::cache::storage::new_mem_ordered_store;
 use crate::pool::cache::{
+    policy::CachePolicy,
     storage::{new_fs_ordered_store, OrderedHashMap},
     strategy::CacheStrategyTTL,
+    Cache,
 };
 use crate::pool::{FilesystemCache, PoolTransactionsCache, ProtocolVersion};
 use crate::utils::Validatable;
 
 use self::error::{set_last_error, ErrorCode};
-use self::pool::{LEDGER_CACHE_STRATEGY, POOL_CACHE, POOL_CONFIG};
+use self::pool::{LEDGER_CACHE, POOLS, POOL_CACHE, POOL_CONFIG};
 
 pub type CallbackId = i64;
 
@@ -77,6 +80,8 @@ pub extern "C" fn indy_vdr_set_cache_directory(path: FfiStr) -> ErrorCode {
     }
 }
 
+/// Set the cache for ledger read replies, used by all pools. Replies expire after
+/// `expire_offset` milliseconds unless accessed.
 #[no_mangle]
 pub extern "C" fn indy_vdr_set_ledger_txn_cache(
     capacity: i32,
@@ -90,7 +95,116 @@ pub extern "C" fn indy_vdr_set_ledger_txn_cache(
             path => OrderedHashMap::new(new_fs_ordered_store(path.into())?),
         };
 
-        *write_lock!(LEDGER_CACHE_STRATEGY)? = Some(Arc::new(CacheStrategyTTL::new(capacity.try_into().ok().unwrap_or_default(), expire_offset.try_into().ok().unwrap_or_default(), Some(store), None)));
+        let strategy = CacheStrategyTTL::new(capacity.try_into().ok().unwrap_or_default(), expire_offset.try_into().ok().unwrap_or_default(), Some(store), None);
+        let cache = Cache::new(strategy, None);
+        *write_lock!(LEDGER_CACHE)? = Some(cache.clone());
+        // switch existing pools to the new cache
+        for instance in read_lock!(POOLS)?.values() {
+            instance.runner.set_cache(Some(cache.with_key_prefix(instance.cache_prefix.clone())))?;
+        }
+        Ok(ErrorCode::Success)
+    }
+}
+
+/// Set the policy for the expiry of replies in the ledger transactions cache.
+/// Immutable and historical replies expire after `pinned_expiry` milliseconds unless
+/// accessed, and replies for the current state of mutable objects expire
+/// `current_expiry` milliseconds after they are cached, even when accessed. A negative
+/// value uses the expiry of the cache.
+#[no_mangle]
+pub extern "C" fn indy_vdr_set_ledger_txn_cache_policy(
+    pinned_expiry: i64,
+    current_expiry: i64,
+) -> ErrorCode {
+    catch_err! {
+        debug!("Setting pool ledger transactions cache policy: pinned_expiry={}, current_expiry={}", pinned_expiry, current_expiry);
+        let policy = CachePolicy {
+            pinned_expiry: pinned_expiry.try_into().ok(),
+            current_expiry: current_expiry.try_into().ok(),
+        };
+        let mut ledger_cache = write_lock!(LEDGER_CACHE)?;
+        let cache = ledger_cache
+            .take()
+            .ok_or_else(|| input_err("Ledger transactions cache is not set"))?
+            .with_policy(policy);
+        *ledger_cache = Some(cache.clone());
+        for instance in read_lock!(POOLS)?.values() {
+            instance.runner.set_cache(Some(cache.with_key_prefix(instance.cache_prefix.clone())))?;
+        }
+        Ok(ErrorCode::Success)
+    }
+}
+
+/// Get the statistics for the ledger transactions cache as a JSON object, containing
+/// the `hits` and `misses` for lookups, the `hit_ratio`, and the number of stored
+/// `entries` and `evictions` after expiry or when the cache was full.
+///
+/// @param stats_p assigned a pointer to the statistics JSON on success
+#[no_mangle]
+pub extern "C" fn indy_vdr_get_ledger_txn_cache_stats(stats_p: *mut *const c_char) -> ErrorCode {
+    catch_err! {
+        trace!("Get ledger transactions cache stats");
+        check_useful_c_ptr!(stats_p);
+        let stats = match read_lock!(LEDGER_CACHE)?.as_ref() {
+            Some(cache) => {
+                let stats = cache.stats();
+                let usage = cache.usage();
+                json!({
+                    "enabled": true,
+                    "hits": stats.hits,
+                    "misses": stats.misses,
+                    "hit_ratio": stats.hit_ratio(),
+                    "entries": usage.map(|usage| usage.entries),
+                    "evictions": usage.map(|usage| usage.evictions),
+                })
+            }
+            None => json!({"enabled": false}),
+        };
+        unsafe {
+            *stats_p = rust_string_to_c(stats.to_string());
+        }
+        Ok(ErrorCode::Success)
+    }
+}
+
+/// Remove entries from the ledger transactions cache. All entries are removed
+/// unless a key prefix or transaction type is provided.
+///
+/// @param key_prefix optional prefix of the cache keys, such as the `mt_root` in
+///     the status of a pool to remove the entries for that pool
+/// @param txn_type optional transaction type of the cached replies, such as `GET_NYM` or `105`.
+///     An input error is returned for an unknown or write transaction type, which is never cached
+/// @param count_p assigned the number of entries removed on success
+#[no_mangle]
+pub extern "C" fn indy_vdr_flush_ledger_txn_cache(
+    key_prefix: FfiStr,
+    txn_type: FfiStr,
+    count_p: *mut i64,
+) -> ErrorCode {
+    catch_err! {
+        let key_prefix = key_prefix.as_opt_str().unwrap_or_default();
+        let txn_type = match txn_type.as_opt_str() {
+            Some(txn_type) => Some(
+                txn_name_to_code(txn_type)
+                    .filter(|code| READ_REQUESTS.contains(code))
+                    .ok_or_else(|| input_err(format!("Unknown transaction type: {}", txn_type)))?,
+            ),
+            None => None,
+        };
+        debug!("Flushing ledger transactions cache: key_prefix={}, txn_type={:?}", key_prefix, txn_type);
+        check_useful_c_ptr!(count_p);
+        let count = match read_lock!(LEDGER_CACHE)?.as_ref() {
+            Some(cache) => cache.remove_where(key_prefix, |_key, (reply, _meta)| {
+                txn_type.map_or(true, |txn_type| {
+                    serde_json::from_str::<serde_json::Value>(reply)
+                        .map_or(false, |reply| reply["result"]["type"].as_str() == Some(txn_type))
+                })
+            }),
+            None => 0,
+        };
+        unsafe {
+            *count_p = count as i64;
+        }
         Ok(ErrorCode::Success)
     }
 }
@@ -109,3 +223,120 @@ pub extern "C" fn indy_vdr_set_socks_proxy(socks_proxy: FfiStr) -> ErrorCode {
 pub extern "C" fn indy_vdr_version() -> *mut c_char {
     rust_string_to_c(LIB_VERSION.to_owned())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::constants;
+    use crate::pool::RequestResultMeta;
+    use std::collections::HashMap;
+    use std::ffi::CString;
+    use std::ptr;
+    use std::sync::Mutex;
+
+    fn _reply(txn_type: &str) -> (String, RequestResultMeta) {
+        (
+            json!({"op": "REPLY", "result": {"type": txn_type}}).to_string(),
+            RequestResultMeta {
+                state_proof: HashMap::new(),
+                timing: None,
+                cached: false,
+            },
+        )
+    }
+
+    fn _ffi_str(s: &Option<CString>) -> FfiStr<'_> {
+        match s {
+            Some(s) => FfiStr::from_cstr(s),
+            None => unsafe { FfiStr::from_raw(ptr::null()) },
+        }
+    }
+
+    fn _flush(key_prefix: Option<&str>, txn_type: Option<&str>) -> (ErrorCode, i64) {
+        let key_prefix = key_prefix.map(|s| CString::new(s).unwrap());
+        let txn_type = txn_type.map(|s| CString::new(s).unwrap());
+        let mut count = -1;
+        let code =
+            indy_vdr_flush_ledger_txn_cache(_ffi_str(&key_prefix), _ffi_str(&txn_type), &mut count);
+        (code, count)
+    }
+
+    #[test]
+    fn flush_ledger_txn_cache() {
+        let no_path = unsafe { FfiStr::from_raw(ptr::null()) };
+        assert_eq!(
+            indy_vdr_set_ledger_txn_cache(10, 60000, no_path),
+            ErrorCode::Success
+        );
+        let cache = LEDGER_CACHE.read().unwrap().clone().unwrap();
+        let pool_cache = cache.with_key_prefix(Some("pool1:".to_owned()));
+        pool_cache.insert("nym1".to_owned(), _reply(constants::GET_NYM), None);
+        pool_cache.insert("schema1".to_owned(), _reply(constants::GET_SCHEMA), None);
+        cache.insert("pool2:nym2".to_owned(), _reply(constants::GET_NYM), None);
+
+        // an unknown transaction type does not flush the cache
+        assert_eq!(_flush(None, Some("UNKNOWN")).0, ErrorCode::Input);
+        assert_eq!(cache.usage().unwrap().entries, 3);
+
+        assert_eq!(_flush(None, Some("999")).0, ErrorCode::Input);
+        assert_eq!(cache.usage().unwrap().entries, 3);
+
+        // write transactions are never cached
+        assert_eq!(_flush(None, Some("NYM")).0, ErrorCode::Input);
+        assert_eq!(_flush(None, Some(constants::NYM)).0, ErrorCode::Input);
+        assert_eq!(cache.usage().unwrap().entries, 3);
+
+        assert_eq!(
+            _flush(Some("pool1:"), Some("GET_NYM")),
+            (ErrorCode::Success, 1)
+        );
+        assert!(pool_cache.get(&"nym1".to_owned()).is_none());
+        assert!(pool_cache.get(&"schema1".to_owned()).is_some());
+        assert!(cache.get(&"pool2:nym2".to_owned()).is_some());
+
+        assert_eq!(
+            _flush(None, Some(constants::GET_NYM)),
+            (ErrorCode::Success, 1)
+        );
+        assert_eq!(_flush(None, None), (ErrorCode::Success, 1));
+        assert_eq!(cache.usage().unwrap().entries, 0);
+
+        *LEDGER_CACHE.write().unwrap() = None;
+        assert_eq!(_flush(None, None), (ErrorCode::Success, 0));
+    }
+
+    static RESOLUTION: Mutex<Option<(ErrorCode, String)>> = Mutex::new(None);
+
+    extern "C" fn _resolution_cb(_cb_id: i64, err: ErrorCode, response: *const c_char) {
+        let response = unsafe { std::ffi::CStr::from_ptr(response) };
+        *RESOLUTION.lock().unwrap() = Some((err, response.to_string_lossy().into_owned()));
+    }
+
+    #[test]
+    fn resolver_reports_unknown_namespace() {
+        let no_path = unsafe { FfiStr::from_raw(ptr::null()) };
+        let mut handle = resolver::ResolverHandle(0);
+        assert_eq!(
+            resolver::indy_vdr_resolver_create(no_path, &mut handle),
+            ErrorCode::Success
+        );
+        let did = CString::new("did:indy:unknown:BDrEcHc8Tb4Lb2VyQZWEDE").unwrap();
+        assert_eq!(
+            resolver::indy_vdr_resolver_resolve(
+                handle,
+                FfiStr::from_cstr(&did),
+                Some(_resolution_cb),
+                0
+            ),
+            ErrorCode::Success
+        );
+        let (err, response) = RESOLUTION.lock().unwrap().take().unwrap();
+        assert_eq!(err, ErrorCode::Success);
+        let result: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(result["didResolutionMetadata"]["error"], "notFound");
+        assert_eq!(
+            resolver::indy_vdr_resolver_close(handle),
+            ErrorCode::Success
+        );
+    }
+}
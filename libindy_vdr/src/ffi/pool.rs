@@ -9,10 +9,10 @@ use once_cell::sync::Lazy;
 use crate::common::error::prelude::*;
 use crate::common::handle::ResourceHandle;
 use crate::config::PoolConfig;
-use crate::pool::cache::{Cache, CacheStrategy};
+use crate::pool::cache::Cache;
 use crate::pool::{
-    InMemoryCache, PoolBuilder, PoolRunner, PoolTransactions, PoolTransactionsCache, RequestMethod,
-    RequestResult, RequestResultMeta,
+    InMemoryCache, PoolBuilder, PoolMonitor, PoolRunner, PoolTransactions, PoolTransactionsCache,
+    RequestMethod, RequestResult, RequestResultMeta,
 };
 
 use super::error::{set_last_error, ErrorCode};
@@ -29,6 +29,8 @@ pub struct PoolInstance {
     pub runner: PoolRunner,
     pub init_txns: PoolTransactions,
     pub node_weights: Option<NodeWeights>,
+    pub monitor: PoolMonitor,
+    pub cache_prefix: Option<String>,
 }
 
 pub type NodeWeights = HashMap<String, f32>;
@@ -41,9 +43,16 @@ pub static POOLS: Lazy<RwLock<BTreeMap<PoolHandle, PoolInstance>>> =
 pub static POOL_CACHE: Lazy<RwLock<Option<Arc<dyn PoolTransactionsCache>>>> =
     Lazy::new(|| RwLock::new(Some(Arc::new(InMemoryCache::new()))));
 
-pub static LEDGER_CACHE_STRATEGY: Lazy<
-    RwLock<Option<Arc<dyn CacheStrategy<String, (String, RequestResultMeta)>>>>,
-> = Lazy::new(|| RwLock::new(None));
+pub type LedgerCache = Cache<String, (String, RequestResultMeta)>;
+
+pub static LEDGER_CACHE: Lazy<RwLock<Option<LedgerCache>>> = Lazy::new(|| RwLock::new(None));
+
+/// Get the ledger cache for a pool, with keys prefixed by the root hash of the pool transactions
+pub(super) fn pool_ledger_cache(cache_prefix: &Option<String>) -> VdrResult<Option<LedgerCache>> {
+    Ok(read_lock!(LEDGER_CACHE)?
+        .as_ref()
+        .map(|cache| cache.with_key_prefix(cache_prefix.clone())))
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct PoolCreateParams {
@@ -74,7 +83,8 @@ pub extern "C" fn indy_vdr_pool_create(params: FfiStr, handle_p: *mut PoolHandle
             ));
         };
         // set this cache with unique key prefix
-        let txn_cache = read_lock!(LEDGER_CACHE_STRATEGY)?.as_ref().map(|s| Cache::new(s.clone(), txns.root_hash_base58().ok()));
+        let cache_prefix = txns.root_hash_base58().ok();
+        let txn_cache = pool_ledger_cache(&cache_prefix)?;
 
         let mut cached = false;
         if let Some(cache) = read_lock!(POOL_CACHE)?.as_ref() {
@@ -84,10 +94,11 @@ pub extern "C" fn indy_vdr_pool_create(params: FfiStr, handle_p: *mut PoolHandle
             }
         }
         let config = read_lock!(POOL_CONFIG)?.clone();
-        let runner = PoolBuilder::new(config, txns.clone()).node_weights(params.node_weights.clone()).refreshed(cached).into_runner(txn_cache)?;
+        let monitor = PoolMonitor::new();
+        let runner = PoolBuilder::new(config, txns.clone()).node_weights(params.node_weights.clone()).refreshed(cached).monitor(monitor.clone()).into_runner(txn_cache)?;
         let handle = PoolHandle::next();
         let mut pools = write_lock!(POOLS)?;
-        pools.insert(handle, PoolInstance { runner, init_txns: txns, node_weights: params.node_weights });
+        pools.insert(handle, PoolInstance { runner, init_txns: txns, node_weights: params.node_weights, monitor, cache_prefix });
         unsafe {
             *handle_p = handle;
         }
@@ -100,6 +111,7 @@ fn handle_pool_refresh(
     init_txns: PoolTransactions,
     new_txns: Option<PoolTransactions>,
     node_weights: Option<NodeWeights>,
+    monitor: PoolMonitor,
 ) -> ErrorCode {
     catch_err! {
         let latest_txns = new_txns.as_ref().unwrap_or(&init_txns);
@@ -111,11 +123,13 @@ fn handle_pool_refresh(
         }
         if let Some(new_txns) = new_txns {
             // set this cache with unique key prefix
-            let txn_cache = read_lock!(LEDGER_CACHE_STRATEGY)?.as_ref().map(|s| Cache::new(s.clone(), new_txns.root_hash_base58().ok()));
-            let runner = PoolBuilder::new(config, new_txns).node_weights(node_weights).refreshed(true).into_runner(txn_cache)?;
+            let cache_prefix = new_txns.root_hash_base58().ok();
+            let txn_cache = pool_ledger_cache(&cache_prefix)?;
+            let runner = PoolBuilder::new(config, new_txns).node_weights(node_weights).refreshed(true).monitor(monitor).into_runner(txn_cache)?;
             let mut pools = write_lock!(POOLS)?;
             if let Entry::Occupied(mut entry) = pools.entry(pool_handle) {
                 entry.get_mut().runner = runner;
+                entry.get_mut().cache_prefix = cache_prefix;
             } else {
                 return Err(err_msg(VdrErrorKind::Unexpected, "Pool was freed before refresh completed"))
             }
@@ -134,10 +148,11 @@ pub extern "C" fn indy_vdr_pool_refresh(
         trace!("Refresh pool");
         let cb = cb.ok_or_else(|| input_err("No callback provided"))?;
         let pools = read_lock!(POOLS)?;
-        let PoolInstance { runner, init_txns, node_weights } = pools.get(&pool_handle)
+        let PoolInstance { runner, init_txns, node_weights, monitor, .. } = pools.get(&pool_handle)
             .ok_or_else(|| input_err("Unknown pool handle"))?;
         let init_txns = init_txns.clone();
         let node_weights = node_weights.clone();
+        let monitor = monitor.clone();
         runner.refresh(Box::new(
             move |result| {
                 let errcode = match result {
@@ -146,7 +161,7 @@ pub extern "C" fn indy_vdr_pool_refresh(
                         // is being run in the PoolRunner's thread, and if we drop
                         // the instance now it will create a deadlock
                         thread::spawn(move || {
-                            let result = handle_pool_refresh(pool_handle, init_txns, new_txns, node_weights);
+                            let result = handle_pool_refresh(pool_handle, init_txns, new_txns, node_weights, monitor);
                             cb(cb_id, result)
                         });
                         return
@@ -194,6 +209,36 @@ pub extern "C" fn indy_vdr_pool_get_status(
     }
 }
 
+#[no_mangle]
+pub extern "C" fn indy_vdr_pool_get_stats(
+    pool_handle: PoolHandle,
+    cb: Option<extern "C" fn(cb_id: CallbackId, err: ErrorCode, response: *const c_char)>,
+    cb_id: CallbackId,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Get pool stats: {}", pool_handle);
+        let cb = cb.ok_or_else(|| input_err("No callback provided"))?;
+        let pools = read_lock!(POOLS)?;
+        let PoolInstance { runner, .. } = pools.get(&pool_handle)
+            .ok_or_else(|| input_err("Unknown pool handle"))?;
+        runner.get_stats(Box::new(
+            move |result| {
+                let (errcode, reply) = match result.and_then(|stats| {
+                    serde_json::to_string(&stats).with_err_msg(VdrErrorKind::Unexpected, "Error serializing pool stats")
+                }) {
+                    Ok(stats) => (ErrorCode::Success, stats),
+                    Err(err) => {
+                        let code = ErrorCode::from(err.kind());
+                        set_last_error(Some(err));
+                        (code, String::new())
+                    }
+                };
+                cb(cb_id, errcode, rust_string_to_c(reply))
+            }))?;
+        Ok(ErrorCode::Success)
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn indy_vdr_pool_get_transactions(
     pool_handle: PoolHandle,
@@ -256,6 +301,44 @@ pub extern "C" fn indy_vdr_pool_get_verifiers(
     }
 }
 
+/// Fetch a range of verified ledger transactions. Each transaction is verified
+/// against the multi-signed ledger root fetched when the range is requested.
+/// The callback is invoked with each transaction reply in turn, followed by a final call with a
+/// `seq_no` of zero once the range is complete, or with an error code.
+#[no_mangle]
+pub extern "C" fn indy_vdr_pool_get_txn_range(
+    pool_handle: PoolHandle,
+    ledger_type: i32,
+    seq_no_from: i32,
+    seq_no_to: i32,
+    cb: Option<
+        extern "C" fn(cb_id: CallbackId, err: ErrorCode, seq_no: i32, response: *const c_char),
+    >,
+    cb_id: CallbackId,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Get transaction range: {} {} {}-{}", pool_handle, ledger_type, seq_no_from, seq_no_to);
+        let cb = cb.ok_or_else(|| input_err("No callback provided"))?;
+        let pools = read_lock!(POOLS)?;
+        let PoolInstance { runner, .. } = pools.get(&pool_handle)
+            .ok_or_else(|| input_err("Unknown pool handle"))?;
+        runner.get_txn_range(ledger_type, seq_no_from, seq_no_to, Box::new(
+            move |result| {
+                let (errcode, seq_no, reply) = match result {
+                    Ok(Some((seq_no, reply))) => (ErrorCode::Success, seq_no, reply),
+                    Ok(None) => (ErrorCode::Success, 0, String::new()),
+                    Err(err) => {
+                        let code = ErrorCode::from(err.kind());
+                        set_last_error(Some(err));
+                        (code, 0, String::new())
+                    }
+                };
+                cb(cb_id, errcode, seq_no, rust_string_to_c(reply))
+            }))?;
+        Ok(ErrorCode::Success)
+    }
+}
+
 fn handle_request_result(
     result: VdrResult<(RequestResult<String>, RequestResultMeta)>,
 ) -> (ErrorCode, String) {
@@ -183,6 +183,23 @@ pub extern "C" fn indy_vdr_request_set_signature(
     }
 }
 
+/// Set whether the reply to a read request may be fetched from or stored in
+/// the ledger transactions cache. Requests use the cache by default.
+#[no_mangle]
+pub extern "C" fn indy_vdr_request_set_use_cache(
+    request_handle: RequestHandle,
+    use_cache: i8,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Set request use cache: {} {}", request_handle, use_cache);
+        let mut reqs = write_lock!(REQUESTS)?;
+        let req = reqs.get_mut(&request_handle)
+            .ok_or_else(|| input_err("Unknown request handle"))?;
+        req.use_cache = use_cache != 0;
+        Ok(ErrorCode::Success)
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn indy_vdr_request_set_txn_author_agreement_acceptance(
     request_handle: RequestHandle,
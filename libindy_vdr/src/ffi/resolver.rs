@@ -1,16 +1,63 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+
 use crate::common::error::prelude::*;
-use crate::resolver::{handle_resolution_result, PoolRunnerResolver as Resolver};
+use crate::common::handle::ResourceHandle;
+use crate::pool::{PoolBuilder, PoolRunner, PoolTransactions, RequestResult, RequestResultMeta};
+use crate::resolver::did::DidUrl;
+use crate::resolver::types::{Callback, DidResolutionMetadata, ResolutionResult, ERROR_NOT_FOUND};
+use crate::resolver::{
+    handle_resolution_result, MultiPoolResolver, PoolRunnerResolver as Resolver,
+};
 
 use super::error::{set_last_error, ErrorCode};
-use super::pool::{PoolHandle, POOLS};
+use super::pool::{pool_ledger_cache, PoolHandle, POOLS, POOL_CONFIG};
 use crate::ffi::c_char;
 use ffi_support::{rust_string_to_c, FfiStr};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct ResolverHandle(pub i64);
+
+impl_sequence_handle!(ResolverHandle, FFI_RH_COUNTER);
+
+pub static RESOLVERS: Lazy<RwLock<BTreeMap<ResolverHandle, MultiPoolResolver<Arc<PoolRunner>>>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+type ResolveCallback = extern "C" fn(cb_id: i64, err: ErrorCode, response: *const c_char);
+
+/// Create a pool runner for a network of a multiple pool resolver
+fn create_runner(builder: PoolBuilder) -> VdrResult<Arc<PoolRunner>> {
+    let cache_prefix = builder.transactions().root_hash_base58().ok();
+    let txn_cache = pool_ledger_cache(&cache_prefix)?;
+    Ok(Arc::new(builder.into_runner(txn_cache)?))
+}
+
+fn resolution_callback(
+    did_url: String,
+    cb: ResolveCallback,
+    cb_id: i64,
+) -> Callback<VdrResult<(RequestResult<String>, RequestResultMeta)>> {
+    Box::new(move |ledger_reply| {
+        let (errcode, reply) = match handle_resolution_result(ledger_reply, did_url) {
+            Ok(result) => (ErrorCode::Success, result),
+            Err(err) => {
+                let code = ErrorCode::from(err.kind());
+                set_last_error(Some(err));
+                (code, String::new())
+            }
+        };
+        cb(cb_id, errcode, rust_string_to_c(reply))
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn indy_vdr_resolve(
     pool_handle: PoolHandle,
     did: FfiStr,
-    cb: Option<extern "C" fn(cb_id: i64, err: ErrorCode, response: *const c_char)>,
+    cb: Option<ResolveCallback>,
     cb_id: i64,
 ) -> ErrorCode {
     catch_err! {
@@ -20,21 +67,7 @@ pub extern "C" fn indy_vdr_resolve(
         let pool = pools.get(&pool_handle).ok_or_else(|| input_err("Unknown pool handle"))?;
         let did = did.as_str().to_owned();
         let resolver = Resolver::new(&pool.runner);
-        resolver
-        .dereference(
-            did.clone(),
-            Box::new(move |ledger_reply| {
-                let (errcode, reply) = match handle_resolution_result(ledger_reply, did) {
-                    Ok(result) => (ErrorCode::Success, result),
-                    Err(err) => {
-                        let code = ErrorCode::from(err.kind());
-                        set_last_error(Some(err));
-                        (code, String::new())
-                    }
-                };
-                cb(cb_id, errcode, rust_string_to_c(reply))
-            }),
-        )?;
+        resolver.dereference(did.clone(), resolution_callback(did, cb, cb_id))?;
         Ok(ErrorCode::Success)
     }
 }
@@ -43,7 +76,7 @@ pub extern "C" fn indy_vdr_resolve(
 pub extern "C" fn indy_vdr_dereference(
     pool_handle: PoolHandle,
     did_url: FfiStr,
-    cb: Option<extern "C" fn(cb_id: i64, err: ErrorCode, response: *const c_char)>,
+    cb: Option<ResolveCallback>,
     cb_id: i64,
 ) -> ErrorCode {
     catch_err! {
@@ -53,20 +86,136 @@ pub extern "C" fn indy_vdr_dereference(
         let pool = pools.get(&pool_handle).ok_or_else(|| input_err("Unknown pool handle"))?;
         let did_url = did_url.as_str().to_owned();
         let resolver = Resolver::new(&pool.runner);
-        resolver.dereference(
-            did_url.clone(),
-            Box::new(move |ledger_reply| {
-                let (errcode, reply) = match handle_resolution_result(ledger_reply, did_url) {
-                    Ok(result) => (ErrorCode::Success, result),
-                    Err(err) => {
-                        let code = ErrorCode::from(err.kind());
-                        set_last_error(Some(err));
-                        (code, String::new())
-                    }
-                };
-                cb(cb_id, errcode, rust_string_to_c(reply))
-            }),
-        )?;
+        resolver.dereference(did_url.clone(), resolution_callback(did_url, cb, cb_id))?;
+        Ok(ErrorCode::Success)
+    }
+}
+
+/// Create a resolver which dispatches requests to a pool for each DID namespace.
+/// The pools are created when first used, from the networks found in an optional
+/// folder structure of `<namespace>/<sub_namespace>/pool_transactions_genesis.json`.
+#[no_mangle]
+pub extern "C" fn indy_vdr_resolver_create(
+    networks_path: FfiStr,
+    handle_p: *mut ResolverHandle,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Create multiple pool resolver");
+        check_useful_c_ptr!(handle_p);
+        let config = read_lock!(POOL_CONFIG)?.clone();
+        let resolver = MultiPoolResolver::new(config, create_runner);
+        if let Some(path) = networks_path.as_opt_str() {
+            resolver.add_networks_folder(path)?;
+        }
+        let handle = ResolverHandle::next();
+        write_lock!(RESOLVERS)?.insert(handle, resolver);
+        unsafe {
+            *handle_p = handle;
+        }
+        Ok(ErrorCode::Success)
+    }
+}
+
+/// Register the genesis transactions for a namespace, provided either as
+/// JSON transactions or as the path to a genesis transactions file
+#[no_mangle]
+pub extern "C" fn indy_vdr_resolver_add_network(
+    resolver_handle: ResolverHandle,
+    namespace: FfiStr,
+    transactions: FfiStr,
+    transactions_path: FfiStr,
+) -> ErrorCode {
+    catch_err! {
+        let namespace = namespace.as_opt_str().ok_or_else(|| input_err("Missing namespace"))?;
+        trace!("Add network to resolver: {}", namespace);
+        let resolvers = read_lock!(RESOLVERS)?;
+        let resolver = resolvers.get(&resolver_handle).ok_or_else(|| input_err("Unknown resolver handle"))?;
+        if let Some(txns) = transactions.as_opt_str() {
+            resolver.add_transactions(namespace, PoolTransactions::from_json(txns)?)?;
+        } else if let Some(path) = transactions_path.as_opt_str() {
+            resolver.add_genesis_file(namespace, path)?;
+        } else {
+            return Err(input_err("Must provide transactions or transactions_path"));
+        }
+        Ok(ErrorCode::Success)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn indy_vdr_resolver_set_default_namespace(
+    resolver_handle: ResolverHandle,
+    namespace: FfiStr,
+) -> ErrorCode {
+    catch_err! {
+        let namespace = namespace.as_opt_str();
+        trace!("Set resolver default namespace: {:?}", namespace);
+        let resolvers = read_lock!(RESOLVERS)?;
+        let resolver = resolvers.get(&resolver_handle).ok_or_else(|| input_err("Unknown resolver handle"))?;
+        resolver.set_default_namespace(namespace)?;
+        Ok(ErrorCode::Success)
+    }
+}
+
+fn resolver_runner(resolver_handle: ResolverHandle, did_url: &str) -> VdrResult<Arc<PoolRunner>> {
+    let did_url = DidUrl::parse(did_url)?;
+    let resolvers = read_lock!(RESOLVERS)?;
+    let resolver = resolvers
+        .get(&resolver_handle)
+        .ok_or_else(|| input_err("Unknown resolver handle"))?;
+    resolver.get_pool(&resolver.did_namespace(&did_url)?)
+}
+
+#[no_mangle]
+pub extern "C" fn indy_vdr_resolver_resolve(
+    resolver_handle: ResolverHandle,
+    did: FfiStr,
+    cb: Option<ResolveCallback>,
+    cb_id: i64,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Resolve DID with multiple pool resolver: {:#?}", did);
+        let cb = cb.ok_or_else(|| input_err("No callback provided"))?;
+        let did = did.as_str().to_owned();
+        let runner = match resolver_runner(resolver_handle, &did) {
+            Ok(runner) => runner,
+            // DIDs of unknown namespaces are reported as not found, like `MultiPoolResolver::resolve_result`
+            Err(err) if matches!(err.kind(), VdrErrorKind::Resolver) => {
+                let result = ResolutionResult::from_error(DidResolutionMetadata::default(), ERROR_NOT_FOUND, err);
+                let result = serde_json::to_string_pretty(&result)
+                    .map_err(|err| err_msg(VdrErrorKind::Unexpected, err))?;
+                cb(cb_id, ErrorCode::Success, rust_string_to_c(result));
+                return Ok(ErrorCode::Success);
+            }
+            Err(err) => return Err(err),
+        };
+        Resolver::new(&runner).resolve(did.clone(), resolution_callback(did, cb, cb_id))?;
+        Ok(ErrorCode::Success)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn indy_vdr_resolver_dereference(
+    resolver_handle: ResolverHandle,
+    did_url: FfiStr,
+    cb: Option<ResolveCallback>,
+    cb_id: i64,
+) -> ErrorCode {
+    catch_err! {
+        trace!("Dereference DID Url with multiple pool resolver: {:#?}", did_url);
+        let cb = cb.ok_or_else(|| input_err("No callback provided"))?;
+        let did_url = did_url.as_str().to_owned();
+        let runner = resolver_runner(resolver_handle, &did_url)?;
+        Resolver::new(&runner).dereference(did_url.clone(), resolution_callback(did_url, cb, cb_id))?;
+        Ok(ErrorCode::Success)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn indy_vdr_resolver_close(resolver_handle: ResolverHandle) -> ErrorCode {
+    catch_err! {
+        let mut resolvers = write_lock!(RESOLVERS)?;
+        resolvers.remove(&resolver_handle)
+            .ok_or_else(|| input_err("Unknown resolver handle"))?;
         Ok(ErrorCode::Success)
     }
 }
@@ -5,6 +5,7 @@ use sha2::{Digest, Sha256};
 use crate::common::error::prelude::*;
 use crate::pool::{new_request_id, PreparedRequest, ProtocolVersion, RequestMethod};
 use crate::utils::did::{DidValue, DEFAULT_LIBINDY_DID};
+use crate::utils::self_cert::check_self_certification;
 use crate::utils::Qualifiable;
 
 #[cfg(any(feature = "rich_schema", test))]
@@ -160,8 +161,12 @@ impl RequestBuilder {
         diddoc_content: Option<&SJsonValue>,
         version: Option<i32>,
     ) -> VdrResult<PreparedRequest> {
+        let dest = dest.to_short();
+        if let Some(version) = version {
+            check_self_certification(&dest, verkey.as_deref(), version)?;
+        }
         let operation = NymOperation::new(
-            dest.to_short(),
+            dest,
             verkey,
             alias,
             role,
@@ -842,6 +847,36 @@ mod tests {
         }
     }
 
+    mod build_nym_request {
+        use super::*;
+        use crate::ledger::constants::{CERT_DEFAULT, CERT_DID_INDY, CERT_DID_SOV};
+        use crate::utils::self_cert::generate_self_certified_did;
+
+        #[rstest]
+        fn test_build_nym_request_rejects_non_self_certified_dest(request_builder: RequestBuilder) {
+            let (did, _, verkey) = generate_self_certified_did(None, CERT_DID_SOV).unwrap();
+            let did = did.qualify(Some("sov".to_string()));
+            let verkey = verkey.as_base58().unwrap().to_string();
+            let build = |dest: &DidValue, verkey: Option<&String>, version: i32| {
+                request_builder.build_nym_request(
+                    &_identifier(),
+                    dest,
+                    verkey.cloned(),
+                    None,
+                    None,
+                    None,
+                    Some(version),
+                )
+            };
+
+            build(&did, Some(&verkey), CERT_DID_SOV).unwrap();
+            build(&_dest(), Some(&verkey), CERT_DEFAULT).unwrap();
+            let _err = build(&did, Some(&verkey), CERT_DID_INDY).unwrap_err();
+            let _err = build(&_dest(), Some(&verkey), CERT_DID_SOV).unwrap_err();
+            let _err = build(&did, None, CERT_DID_SOV).unwrap_err();
+        }
+    }
+
     #[rstest]
     fn test_prepared_request_get_signature_input(prepared_request: PreparedRequest) {
         let expected = String::from("identifier:V4SGRU86Z58d6TV7PBUe6f|operation:dest:VsKV7grR1BUE29mG2Fm2kX|type:1|protocolVersion:2|reqId:1585221529670242337");
@@ -1,7 +1,16 @@
+use crate::common::error::prelude::*;
+use crate::pool::StateProofAssertions;
 use crate::utils::did::DidValue;
+use serde::de::{DeserializeOwned, Deserializer};
 use serde::Deserialize;
+use serde_json::Value as SJsonValue;
 use std::collections::HashMap;
 
+use super::requests::auth_rule::AuthRule;
+use super::requests::author_agreement::AcceptanceMechanisms;
+use super::requests::cred_def::CredentialDefinitionData;
+use super::requests::rev_reg_def::RevocationRegistryDefinition;
+
 pub enum ResponseTypes {
     GetNymResult(GetNymResult),
     GetSchemaResult(GetSchemaResult),
@@ -20,7 +29,8 @@ pub struct GetNymResultV0 {
     pub identifier: Option<DidValue>,
     pub dest: DidValue,
     pub role: Option<String>,
-    pub verkey: String,
+    /// Unset when no verkey is assigned to the DID
+    pub verkey: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
@@ -29,7 +39,8 @@ pub struct GetNymResultV1 {
     pub identifier: Option<DidValue>,
     pub dest: DidValue,
     pub role: Option<String>,
-    pub verkey: String,
+    /// Unset when no verkey is assigned to the DID
+    pub verkey: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diddoc_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -38,6 +49,7 @@ pub struct GetNymResultV1 {
 
 #[derive(Deserialize, Eq, PartialEq, Debug)]
 pub struct GetSchemaResult {
+    #[serde(default)]
     pub attr_names: Vec<String>,
     pub name: String,
     pub version: String,
@@ -55,7 +67,334 @@ pub struct GetClaimDefResult {
     pub ref_schema_version: String,
 }
 
+/// The `endpoint` ATTRIB of a DID, mapping service names to endpoint URIs. Values which are
+/// not strings, such as the `routingKeys` and `types` of did:sov endpoints, are skipped.
 #[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
 pub struct Endpoint {
+    #[serde(deserialize_with = "deserialize_endpoint_uris")]
     pub endpoint: HashMap<String, String>,
 }
+
+impl From<EndpointAttrib> for Endpoint {
+    fn from(attrib: EndpointAttrib) -> Self {
+        let endpoint = attrib
+            .endpoint
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                SJsonValue::String(uri) => Some((name, uri)),
+                _ => None,
+            })
+            .collect();
+        Self { endpoint }
+    }
+}
+
+/// The `endpoint` ATTRIB of a DID including all of its values. Endpoints written for did:sov
+/// also include `routingKeys` and `types` lists.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct EndpointAttrib {
+    pub endpoint: HashMap<String, SJsonValue>,
+}
+
+fn deserialize_endpoint_uris<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let endpoint = HashMap::<String, SJsonValue>::deserialize(deserializer)?;
+    Ok(Endpoint::from(EndpointAttrib { endpoint }).endpoint)
+}
+
+/// The `result` of a ledger reply to a read request
+///
+/// The `data` of the reply is parsed as `D`, decoding it first when the
+/// ledger returns the data as a JSON-encoded string, as for `GET_NYM`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReplyResult<D> {
+    #[serde(rename = "type")]
+    pub txn_type: String,
+    pub identifier: Option<DidValue>,
+    pub req_id: u64,
+    #[serde(default)]
+    pub seq_no: Option<u64>,
+    #[serde(default)]
+    pub txn_time: Option<u64>,
+    #[serde(default, rename = "state_proof")]
+    pub state_proof: Option<ReplyStateProof>,
+    #[serde(
+        default = "Option::default",
+        deserialize_with = "deserialize_reply_data",
+        bound(deserialize = "D: DeserializeOwned")
+    )]
+    pub data: Option<D>,
+}
+
+impl<D: DeserializeOwned> GetReplyResult<D> {
+    /// Parse the `result` of a ledger reply message
+    pub fn from_reply(reply: &str) -> VdrResult<Self> {
+        let mut reply: SJsonValue =
+            serde_json::from_str(reply).with_input_err("Error parsing ledger reply")?;
+        serde_json::from_value(reply["result"].take()).with_input_err("Error parsing reply result")
+    }
+}
+
+fn deserialize_reply_data<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let data = match SJsonValue::deserialize(deserializer)? {
+        SJsonValue::Null => return Ok(None),
+        SJsonValue::String(data) => match serde_json::from_str(&data) {
+            Ok(data) => return Ok(Some(data)),
+            Err(_) => SJsonValue::String(data),
+        },
+        data => data,
+    };
+    T::deserialize(data)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// The state proof attached to a ledger reply
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ReplyStateProof {
+    pub root_hash: Option<String>,
+    pub proof_nodes: Option<String>,
+    pub multi_signature: Option<ReplyMultiSignature>,
+}
+
+/// The BLS multi-signature over a ledger state
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ReplyMultiSignature {
+    pub signature: String,
+    pub participants: Vec<String>,
+    pub value: StateProofAssertions,
+}
+
+/// Metadata recorded for a ledger transaction
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TxnMetadata {
+    pub seq_no: Option<u64>,
+    pub txn_time: Option<u64>,
+    pub txn_id: Option<String>,
+}
+
+/// A ledger transaction returned by `GET_TXN`, along with its audit path
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTxnResult {
+    pub txn: SJsonValue,
+    pub txn_metadata: TxnMetadata,
+    #[serde(default)]
+    pub req_signature: SJsonValue,
+    pub ver: Option<String>,
+    #[serde(default)]
+    pub audit_path: Vec<String>,
+    pub ledger_size: Option<u64>,
+    pub root_hash: Option<String>,
+}
+
+pub type GetAttribResult = String;
+
+pub type GetCredDefResult = CredentialDefinitionData;
+
+pub type GetRevRegDefResult = RevocationRegistryDefinition;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct RevRegAccumulator {
+    pub accum: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRevRegResult {
+    pub id: Option<String>,
+    pub revoc_def_type: String,
+    pub revoc_reg_def_id: String,
+    pub value: RevRegAccumulator,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevRegDeltaAccumulator {
+    pub revoc_def_type: Option<String>,
+    pub revoc_reg_def_id: Option<String>,
+    pub seq_no: Option<u64>,
+    pub txn_time: Option<u64>,
+    pub value: RevRegAccumulator,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct RevRegDeltaValue {
+    pub accum_to: RevRegDeltaAccumulator,
+    pub accum_from: Option<RevRegDeltaAccumulator>,
+    #[serde(default)]
+    pub issued: Vec<u32>,
+    #[serde(default)]
+    pub revoked: Vec<u32>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRevRegDeltaResult {
+    pub revoc_def_type: String,
+    pub revoc_reg_def_id: String,
+    pub value: RevRegDeltaValue,
+}
+
+pub type GetAuthRuleResult = Vec<AuthRule>;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct GetTxnAuthorAgreementResult {
+    pub text: String,
+    pub version: String,
+    pub digest: Option<String>,
+    pub ratification_ts: Option<u64>,
+    pub retirement_ts: Option<u64>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAcceptanceMechanismsResult {
+    pub aml: AcceptanceMechanisms,
+    pub aml_context: Option<String>,
+    pub version: String,
+}
+
+pub type GetFlagResult = String;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct FrozenLedger {
+    pub ledger: String,
+    pub state: String,
+    pub seq_no: u64,
+}
+
+pub type GetFrozenLedgersResult = HashMap<i32, FrozenLedger>;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRichSchemaResult {
+    pub id: String,
+    pub rs_type: String,
+    pub rs_name: String,
+    pub rs_version: String,
+    pub content: String,
+    pub from: Option<DidValue>,
+    pub endorser: Option<DidValue>,
+    pub ver: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _reply(txn_type: &str, data: SJsonValue) -> String {
+        json!({
+            "op": "REPLY",
+            "result": {
+                "type": txn_type,
+                "identifier": "LibindyDid111111111111",
+                "reqId": 1,
+                "seqNo": 10,
+                "txnTime": 1613753538,
+                "data": data,
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn get_nym_reply_parses_encoded_data() {
+        let data = json!({
+            "dest": "V4SGRU86Z58d6TV7PBUe6f",
+            "identifier": "V4SGRU86Z58d6TV7PBUe6f",
+            "role": "0",
+            "verkey": "~CoRER63DVYnWZtK8uAzNbx",
+            "version": 1,
+        });
+        let reply = _reply("105", SJsonValue::from(data.to_string()));
+        let result = GetReplyResult::<GetNymResultV1>::from_reply(&reply).unwrap();
+        assert_eq!(result.txn_type, "105");
+        assert_eq!(result.seq_no, Some(10));
+        assert_eq!(result.state_proof, None);
+        let nym = result.data.unwrap();
+        assert_eq!(nym.verkey.as_deref(), Some("~CoRER63DVYnWZtK8uAzNbx"));
+        assert_eq!(nym.version, Some(1));
+        assert_eq!(nym.diddoc_content, None);
+    }
+
+    #[test]
+    fn get_nym_reply_allows_null_verkey() {
+        let data = json!({
+            "dest": "V4SGRU86Z58d6TV7PBUe6f",
+            "identifier": "Th7MpTaRZVRYnPiabds81Y",
+            "role": null,
+            "verkey": null,
+        });
+        let reply = _reply("105", SJsonValue::from(data.to_string()));
+        let result = GetReplyResult::<GetNymResultV1>::from_reply(&reply).unwrap();
+        assert_eq!(result.data.unwrap().verkey, None);
+        let nym: GetNymResult = serde_json::from_value(data).unwrap();
+        assert!(matches!(nym, GetNymResult::GetNymResultV0(nym) if nym.verkey.is_none()));
+    }
+
+    #[test]
+    fn get_reply_without_data() {
+        let reply = _reply("105", SJsonValue::Null);
+        let result = GetReplyResult::<GetNymResultV1>::from_reply(&reply).unwrap();
+        assert_eq!(result.data, None);
+        assert!(GetReplyResult::<GetNymResultV1>::from_reply("{}").is_err());
+    }
+
+    #[test]
+    fn get_attrib_reply_keeps_raw_data() {
+        let raw = json!({"endpoint": {"endpoint": "https://example.com"}}).to_string();
+        let reply = _reply("104", SJsonValue::from(raw.clone()));
+        let result = GetReplyResult::<GetAttribResult>::from_reply(&reply).unwrap();
+        assert_eq!(result.data, Some(raw));
+    }
+
+    #[test]
+    fn get_txn_reply_parses_audit_path() {
+        let data = json!({
+            "txn": {"type": "1", "data": {"dest": "V4SGRU86Z58d6TV7PBUe6f"}},
+            "txnMetadata": {"seqNo": 2, "txnTime": 1613753538},
+            "reqSignature": {},
+            "ver": "1",
+            "auditPath": ["AYpBWZNFKqzs3qgeAwvMTEYYhwWkPTRM1FHcYW4U8hUD"],
+            "ledgerSize": 2,
+            "rootHash": "3aCJcAFuW9MBEbcPaafQUtSyhAsPVYz9uLzAnhMDLjpt",
+        });
+        let reply = _reply("3", data);
+        let txn = GetReplyResult::<GetTxnResult>::from_reply(&reply)
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(txn.txn_metadata.seq_no, Some(2));
+        assert_eq!(txn.txn_metadata.txn_id, None);
+        assert_eq!(txn.audit_path.len(), 1);
+        assert_eq!(txn.ledger_size, Some(2));
+        assert_eq!(txn.txn["data"]["dest"], "V4SGRU86Z58d6TV7PBUe6f");
+    }
+
+    #[test]
+    fn endpoint_skips_non_string_values() {
+        let data = json!({
+            "endpoint": {
+                "endpoint": "https://agent.com",
+                "routingKeys": ["3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"],
+                "types": ["DIDComm"],
+            }
+        });
+        let endpoint: Endpoint = serde_json::from_value(data.clone()).unwrap();
+        assert_eq!(endpoint.endpoint.len(), 1);
+        assert_eq!(endpoint.endpoint["endpoint"], "https://agent.com");
+
+        let attrib: EndpointAttrib = serde_json::from_value(data).unwrap();
+        assert_eq!(attrib.endpoint.len(), 3);
+        assert_eq!(Endpoint::from(attrib), endpoint);
+    }
+}
@@ -6,7 +6,8 @@ use crate::config::PoolConfig;
 use super::cache::Cache;
 use super::genesis::PoolTransactions;
 use super::manager::{LocalPool, SharedPool};
-use super::networker::{MakeLocal, MakeShared, ZMQNetworkerFactory};
+use super::monitor::PoolMonitor;
+use super::networker::{DefaultNetworkerFactory, MakeLocal, MakeShared};
 use super::runner::PoolRunner;
 use super::RequestResultMeta;
 
@@ -17,6 +18,7 @@ pub struct PoolBuilder {
     transactions: PoolTransactions,
     node_weights: Option<HashMap<String, f32>>,
     refreshed: bool,
+    monitor: Option<PoolMonitor>,
 }
 
 impl PoolBuilder {
@@ -27,9 +29,15 @@ impl PoolBuilder {
             transactions,
             node_weights: None,
             refreshed: false,
+            monitor: None,
         }
     }
 
+    /// Get the genesis transactions of the pool.
+    pub fn transactions(&self) -> &PoolTransactions {
+        &self.transactions
+    }
+
     /// Enable or disable the fast refresh option.
     pub fn refreshed(mut self, flag: bool) -> Self {
         self.refreshed = flag;
@@ -42,29 +50,43 @@ impl PoolBuilder {
         self
     }
 
+    /// Set the monitor used to collect node statistics for the pool.
+    pub fn monitor(mut self, monitor: PoolMonitor) -> Self {
+        self.monitor.replace(monitor);
+        self
+    }
+
     /// Create a `LocalPool` instance from the builder, for use in a single thread.
     pub fn into_local(self) -> VdrResult<LocalPool> {
         let merkle_tree = self.transactions.merkle_tree()?;
-        LocalPool::build(
+        let pool = LocalPool::build(
             self.config,
             merkle_tree,
-            MakeLocal(ZMQNetworkerFactory {}),
+            MakeLocal(DefaultNetworkerFactory::default()),
             self.node_weights,
             self.refreshed,
-        )
+        )?;
+        Ok(match self.monitor {
+            Some(monitor) => pool.with_monitor(monitor),
+            None => pool,
+        })
     }
 
     /// Create a `SharedPool` instance from the builder, for use across multiple threads.
     pub fn into_shared(self) -> VdrResult<SharedPool> {
         let merkle_tree = self.transactions.merkle_tree()?;
 
-        SharedPool::build(
+        let pool = SharedPool::build(
             self.config,
             merkle_tree,
-            MakeShared(ZMQNetworkerFactory {}),
+            MakeShared(DefaultNetworkerFactory::default()),
             self.node_weights,
             self.refreshed,
-        )
+        )?;
+        Ok(match self.monitor {
+            Some(monitor) => pool.with_monitor(monitor),
+            None => pool,
+        })
     }
 
     /// Create a `PoolRunner` instance from the builder, to handle pool interaction
@@ -74,13 +96,14 @@ impl PoolBuilder {
         cache: Option<Cache<String, (String, RequestResultMeta)>>,
     ) -> VdrResult<PoolRunner> {
         let merkle_tree = self.transactions.merkle_tree()?;
-        Ok(PoolRunner::new(
+        Ok(PoolRunner::start(
             self.config,
             merkle_tree,
-            MakeLocal(ZMQNetworkerFactory {}),
+            MakeLocal(DefaultNetworkerFactory::default()),
             self.node_weights,
             self.refreshed,
             cache,
+            self.monitor.unwrap_or_default(),
         ))
     }
 }
@@ -1,22 +1,114 @@
+use futures_util::future::{self, BoxFuture};
 use std::{
     fmt::Display,
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
 };
 
+pub mod policy;
+pub mod redis;
 pub mod storage;
 pub mod strategy;
 
+use policy::CachePolicy;
+
 pub trait CacheStrategy<K, V>: Send + Sync + 'static {
     fn get(&self, key: &K) -> Option<V>;
 
     fn remove(&self, key: &K) -> Option<V>;
 
     fn insert(&self, key: K, value: V, custom_exp_offset: Option<u128>) -> Option<V>;
+
+    /// Insert an entry which expires `exp_offset` milliseconds after insertion, even when
+    /// accessed. Strategies which never extend the expiry of entries may use the default
+    fn insert_fixed(&self, key: K, value: V, exp_offset: u128) -> Option<V> {
+        self.insert(key, value, Some(exp_offset))
+    }
+
+    /// Look up an entry without blocking the calling task. Strategies which perform I/O should
+    /// override the default, which calls `get`
+    fn get_async(&self, key: &K) -> BoxFuture<'static, Option<V>>
+    where
+        V: Send + 'static,
+    {
+        Box::pin(future::ready(self.get(key)))
+    }
+
+    /// Insert an entry without blocking the calling task, like `get_async`
+    fn insert_async(
+        &self,
+        key: K,
+        value: V,
+        custom_exp_offset: Option<u128>,
+    ) -> BoxFuture<'static, Option<V>>
+    where
+        V: Send + 'static,
+    {
+        Box::pin(future::ready(self.insert(key, value, custom_exp_offset)))
+    }
+
+    /// Insert an entry with a fixed expiry without blocking the calling task, like `get_async`
+    fn insert_fixed_async(
+        &self,
+        key: K,
+        value: V,
+        exp_offset: u128,
+    ) -> BoxFuture<'static, Option<V>>
+    where
+        V: Send + 'static,
+    {
+        Box::pin(future::ready(self.insert_fixed(key, value, exp_offset)))
+    }
+
+    /// Remove the entries for which the predicate holds, returning the number of entries removed.
+    /// Strategies may use `key_prefix` to limit the keys scanned, but the predicate is responsible
+    /// for checking it. Strategies which cannot enumerate their entries may use the default, which
+    /// removes nothing
+    fn remove_where(&self, _key_prefix: &str, _predicate: &dyn Fn(&K, &V) -> bool) -> usize {
+        0
+    }
+
+    /// Get the number of stored and evicted entries, if tracked by the strategy
+    fn usage(&self) -> Option<CacheUsage> {
+        None
+    }
+}
+
+/// The number of entries stored by a cache strategy, and the number removed on expiry or
+/// when the cache was full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub entries: usize,
+    pub evictions: u64,
+}
+
+/// Hit and miss counts for cache lookups
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// The fraction of lookups which found a cached value
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            None
+        } else {
+            Some(self.hits as f64 / total as f64)
+        }
+    }
 }
 
 pub struct Cache<K: Display, V> {
     storage: Arc<RwLock<dyn CacheStrategy<String, V>>>,
     key_prefix: Option<K>,
+    policy: CachePolicy,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
 }
 
 impl<K: Display + 'static, V: 'static> Cache<K, V> {
@@ -31,15 +123,81 @@ impl<K: Display + 'static, V: 'static> Cache<K, V> {
         Self {
             storage: Arc::new(RwLock::new(storage)),
             key_prefix,
+            policy: CachePolicy::default(),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Set the policy used to determine the expiry of cached ledger read replies. By default
+    /// the expiry of the cache strategy is used for all replies
+    pub fn with_policy(mut self, policy: CachePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    fn count_lookup(&self, found: bool) {
+        let counter = if found { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn get(&self, key: &K) -> Option<V> {
         let full_key = self.full_key(key);
+        let found = if let Ok(storage) = self.storage.read() {
+            storage.get(&full_key)
+        } else {
+            None
+        };
+        self.count_lookup(found.is_some());
+        found
+    }
+
+    /// Create a cache sharing the storage, policy and statistics of this cache with a different key prefix
+    pub fn with_key_prefix(&self, key_prefix: Option<K>) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            key_prefix,
+            policy: self.policy,
+            hits: self.hits.clone(),
+            misses: self.misses.clone(),
+        }
+    }
+
+    /// Get the number of stored and evicted entries, if tracked by the cache strategy
+    pub fn usage(&self) -> Option<CacheUsage> {
+        self.storage.read().ok().and_then(|storage| storage.usage())
+    }
+
+    /// Remove the entries with keys starting with the key prefix of this cache followed by
+    /// `key_prefix`, for which the predicate holds given the key without the prefix of this
+    /// cache. Returns the number of entries removed
+    pub fn remove_where(&self, key_prefix: &str, predicate: impl Fn(&str, &V) -> bool) -> usize {
+        let prefix = self
+            .key_prefix
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let full_prefix = format!("{}{}", prefix, key_prefix);
+        // strategies synchronize their own storage, so that lookups may continue while
+        // the entries are scanned
         if let Ok(storage) = self.storage.read() {
-            return storage.get(&full_key);
+            return storage.remove_where(&full_prefix, &|key, value| {
+                key.starts_with(full_prefix.as_str()) && predicate(&key[prefix.len()..], value)
+            });
+        }
+        0
+    }
+
+    /// Get the hit and miss counts for lookups shared by all clones of this cache
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
         }
-        None
     }
 
     pub fn remove(&self, key: &K) -> Option<V> {
@@ -57,6 +215,66 @@ impl<K: Display + 'static, V: 'static> Cache<K, V> {
         }
         None
     }
+
+    /// Insert an entry which expires `exp_offset` milliseconds after insertion, even when accessed
+    pub fn insert_fixed(&self, key: K, value: V, exp_offset: u128) -> Option<V> {
+        let full_key = self.full_key(&key);
+        if let Ok(storage) = self.storage.write() {
+            return storage.insert_fixed(full_key, value, exp_offset);
+        }
+        None
+    }
+}
+
+impl<K: Display + 'static, V: Send + 'static> Cache<K, V> {
+    /// Look up an entry without blocking the calling task on strategies which perform I/O
+    pub async fn get_async(&self, key: &K) -> Option<V> {
+        let full_key = self.full_key(key);
+        // the lock is released before the lookup is awaited
+        let lookup = self
+            .storage
+            .read()
+            .ok()
+            .map(|storage| storage.get_async(&full_key));
+        let found = match lookup {
+            Some(lookup) => lookup.await,
+            None => None,
+        };
+        self.count_lookup(found.is_some());
+        found
+    }
+
+    pub async fn insert_async(
+        &self,
+        key: K,
+        value: V,
+        custom_exp_offset: Option<u128>,
+    ) -> Option<V> {
+        let full_key = self.full_key(&key);
+        let insert = self
+            .storage
+            .write()
+            .ok()
+            .map(|storage| storage.insert_async(full_key, value, custom_exp_offset));
+        match insert {
+            Some(insert) => insert.await,
+            None => None,
+        }
+    }
+
+    /// Insert an entry which expires `exp_offset` milliseconds after insertion, even when accessed
+    pub async fn insert_fixed_async(&self, key: K, value: V, exp_offset: u128) -> Option<V> {
+        let full_key = self.full_key(&key);
+        let insert = self
+            .storage
+            .write()
+            .ok()
+            .map(|storage| storage.insert_fixed_async(full_key, value, exp_offset));
+        match insert {
+            Some(insert) => insert.await,
+            None => None,
+        }
+    }
 }
 
 // need to implement Clone manually because Mutex<dyn CacheStrategy> doesn't implement Clone
@@ -65,6 +283,9 @@ impl<K: Display + Clone, V> Clone for Cache<K, V> {
         Self {
             storage: self.storage.clone(),
             key_prefix: self.key_prefix.clone(),
+            policy: self.policy,
+            hits: self.hits.clone(),
+            misses: self.misses.clone(),
         }
     }
 }
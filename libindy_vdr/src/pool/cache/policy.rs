@@ -0,0 +1,321 @@
+use crate::ledger::constants;
+use serde_json::Value as SJsonValue;
+
+/// The expiry in milliseconds used for pinned replies, which are kept until evicted from the cache
+pub const PINNED_EXPIRY: u128 = 100 * 365 * 86_400_000;
+
+/// The default expiry in milliseconds for replies containing the current state of a mutable object
+pub const DEFAULT_CURRENT_EXPIRY: u128 = 60_000;
+
+/// The kind of reply to a ledger read request, which determines how long it may be cached
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheClass {
+    /// Objects which cannot change once written, such as schemas and transactions
+    Immutable,
+    /// Lookups pinned to a sequence number or a timestamp before the reply was signed
+    Historical,
+    /// Lookups of the current state of an object which may change
+    Current,
+}
+
+impl CacheClass {
+    /// Classify the reply to a read request by the request type and query parameters
+    pub fn from_reply(request: &SJsonValue, reply: &SJsonValue) -> Self {
+        let operation = &request["operation"];
+        match operation["type"].as_str().unwrap_or_default() {
+            constants::GET_SCHEMA
+            | constants::GET_CRED_DEF
+            | constants::GET_REVOC_REG_DEF
+            | constants::GET_TXN
+            | constants::GET_RICH_SCHEMA_BY_ID
+            | constants::GET_RICH_SCHEMA_BY_METADATA => Self::Immutable,
+            constants::GET_NYM | constants::GET_ATTR | constants::GET_FLAG
+                if !operation["seqNo"].is_null() =>
+            {
+                Self::Historical
+            }
+            constants::GET_NYM
+            | constants::GET_ATTR
+            | constants::GET_FLAG
+            | constants::GET_TXN_AUTHR_AGRMT
+            | constants::GET_TXN_AUTHR_AGRMT_AML
+            | constants::GET_REVOC_REG => {
+                Self::from_timestamp(operation["timestamp"].as_u64(), reply)
+            }
+            constants::GET_REVOC_REG_DELTA => Self::from_timestamp(operation["to"].as_u64(), reply),
+            _ => Self::Current,
+        }
+    }
+
+    /// A lookup by timestamp is only historical if no later transaction may be written before the
+    /// timestamp, which holds when the state was signed after it
+    fn from_timestamp(timestamp: Option<u64>, reply: &SJsonValue) -> Self {
+        let signed_time =
+            reply["result"]["state_proof"]["multi_signature"]["value"]["timestamp"].as_u64();
+        match (timestamp, signed_time) {
+            (Some(timestamp), Some(signed_time)) if timestamp < signed_time => Self::Historical,
+            _ => Self::Current,
+        }
+    }
+}
+
+/// Determines the expiry of cached ledger read replies. The default policy uses the expiry of
+/// the cache strategy for all replies
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// The expiry in milliseconds for immutable and historical replies, or `None` to use the expiry of the cache strategy
+    pub pinned_expiry: Option<u128>,
+    /// The expiry in milliseconds for current state replies, measured from when the reply was
+    /// cached rather than extended when accessed, or `None` to use the expiry of the cache strategy
+    pub current_expiry: Option<u128>,
+}
+
+impl CachePolicy {
+    /// A policy which keeps immutable and historical replies until evicted, and expires current
+    /// state replies after `DEFAULT_CURRENT_EXPIRY`
+    pub fn pinned() -> Self {
+        Self {
+            pinned_expiry: Some(PINNED_EXPIRY),
+            current_expiry: Some(DEFAULT_CURRENT_EXPIRY),
+        }
+    }
+
+    /// Get the expiry for a class of reply
+    pub fn expiry(&self, class: CacheClass) -> Option<u128> {
+        match class {
+            CacheClass::Immutable | CacheClass::Historical => self.pinned_expiry,
+            CacheClass::Current => self.current_expiry,
+        }
+    }
+
+    /// Whether the expiry for a class of reply is fixed when cached, rather than extended when
+    /// the reply is accessed
+    pub fn is_fixed(&self, class: CacheClass) -> bool {
+        class == CacheClass::Current && self.current_expiry.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PoolConfig;
+    use crate::pool::cache::{strategy::CacheStrategyTTL, Cache, CacheStats, CacheStrategy};
+    use crate::pool::helpers::perform_ledger_request;
+    use crate::pool::networker::{mock_fixtures::*, MockLedger};
+    use crate::pool::{LedgerType, Pool, RequestResult, RequestResultMeta};
+    use futures_executor::block_on;
+    use std::sync::{Arc, Mutex};
+
+    fn _reply(signed_time: u64) -> SJsonValue {
+        json!({"result": {"state_proof": {"multi_signature": {"value": {"timestamp": signed_time}}}}})
+    }
+
+    #[rstest]
+    #[case(json!({"type": constants::GET_SCHEMA, "dest": "did", "data": {}}), CacheClass::Immutable)]
+    #[case(json!({"type": constants::GET_TXN, "data": 1, "ledgerId": 1}), CacheClass::Immutable)]
+    #[case(json!({"type": constants::GET_NYM, "dest": "did"}), CacheClass::Current)]
+    #[case(json!({"type": constants::GET_NYM, "dest": "did", "seqNo": 5}), CacheClass::Historical)]
+    #[case(json!({"type": constants::GET_ATTR, "dest": "did", "timestamp": 100}), CacheClass::Historical)]
+    #[case(json!({"type": constants::GET_ATTR, "dest": "did", "timestamp": 1000}), CacheClass::Current)]
+    #[case(json!({"type": constants::GET_REVOC_REG, "timestamp": 1000}), CacheClass::Current)]
+    #[case(json!({"type": constants::GET_REVOC_REG_DELTA, "to": 100}), CacheClass::Historical)]
+    #[case(json!({"type": constants::GET_TXN_AUTHR_AGRMT}), CacheClass::Current)]
+    #[case(json!({"type": constants::GET_AUTH_RULE}), CacheClass::Current)]
+    fn test_cache_class(#[case] operation: SJsonValue, #[case] class: CacheClass) {
+        let request = json!({ "operation": operation });
+        assert_eq!(CacheClass::from_reply(&request, &_reply(500)), class);
+    }
+
+    #[rstest]
+    fn test_cache_class_unsigned_timestamp() {
+        let request =
+            json!({"operation": {"type": constants::GET_NYM, "dest": "did", "timestamp": 100}});
+        assert_eq!(
+            CacheClass::from_reply(&request, &json!({"result": {}})),
+            CacheClass::Current
+        );
+    }
+
+    #[rstest]
+    fn test_cache_policy_expiry() {
+        let policy = CachePolicy::pinned();
+        assert_eq!(policy.expiry(CacheClass::Immutable), Some(PINNED_EXPIRY));
+        assert_eq!(policy.expiry(CacheClass::Historical), Some(PINNED_EXPIRY));
+        assert_eq!(
+            policy.expiry(CacheClass::Current),
+            Some(DEFAULT_CURRENT_EXPIRY)
+        );
+        assert!(policy.is_fixed(CacheClass::Current));
+        assert!(!policy.is_fixed(CacheClass::Historical));
+        let policy = CachePolicy::default();
+        assert_eq!(policy.expiry(CacheClass::Immutable), None);
+        assert_eq!(policy.expiry(CacheClass::Current), None);
+        assert!(!policy.is_fixed(CacheClass::Current));
+    }
+
+    type Expiries = Arc<Mutex<Vec<(Option<u128>, bool)>>>;
+
+    /// Records the expiry of each cache insert, and whether it is fixed
+    struct RecordingCache {
+        inner: CacheStrategyTTL<String, (String, RequestResultMeta)>,
+        expiries: Expiries,
+    }
+
+    impl CacheStrategy<String, (String, RequestResultMeta)> for RecordingCache {
+        fn get(&self, key: &String) -> Option<(String, RequestResultMeta)> {
+            self.inner.get(key)
+        }
+
+        fn remove(&self, key: &String) -> Option<(String, RequestResultMeta)> {
+            self.inner.remove(key)
+        }
+
+        fn insert(
+            &self,
+            key: String,
+            value: (String, RequestResultMeta),
+            custom_exp_offset: Option<u128>,
+        ) -> Option<(String, RequestResultMeta)> {
+            self.expiries
+                .lock()
+                .unwrap()
+                .push((custom_exp_offset, false));
+            self.inner.insert(key, value, custom_exp_offset)
+        }
+
+        fn insert_fixed(
+            &self,
+            key: String,
+            value: (String, RequestResultMeta),
+            exp_offset: u128,
+        ) -> Option<(String, RequestResultMeta)> {
+            self.expiries.lock().unwrap().push((Some(exp_offset), true));
+            self.inner.insert_fixed(key, value, exp_offset)
+        }
+
+        fn remove_where(
+            &self,
+            key_prefix: &str,
+            predicate: &dyn Fn(&String, &(String, RequestResultMeta)) -> bool,
+        ) -> usize {
+            self.inner.remove_where(key_prefix, predicate)
+        }
+    }
+
+    #[test]
+    fn cache_policy_is_opt_in() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let did = new_did();
+        write_nym(&pool, &did);
+
+        let expiries = Arc::new(Mutex::new(Vec::new()));
+        let cache = Cache::new(
+            RecordingCache {
+                inner: CacheStrategyTTL::new(10, 10_000, None, None),
+                expiries: expiries.clone(),
+            },
+            None,
+        );
+        let builder = pool.get_request_builder();
+        for request in [
+            builder.build_get_nym_request(None, &did, None, None),
+            builder.build_get_txn_request(None, LedgerType::DOMAIN.to_id(), 1),
+        ] {
+            block_on(perform_ledger_request(
+                &pool,
+                &request.unwrap(),
+                Some(cache.clone()),
+            ))
+            .unwrap();
+        }
+        // the expiry of the cache strategy is used without a policy
+        assert_eq!(
+            *expiries.lock().unwrap(),
+            vec![(None, false), (None, false)]
+        );
+    }
+
+    #[test]
+    fn cache_policy_sets_reply_expiry() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let did = new_did();
+        write_nym(&pool, &did);
+
+        let expiries = Arc::new(Mutex::new(Vec::new()));
+        let policy = CachePolicy {
+            pinned_expiry: Some(100_000),
+            current_expiry: Some(1_000),
+        };
+        let cache = Cache::new(
+            RecordingCache {
+                inner: CacheStrategyTTL::new(10, 10_000, None, None),
+                expiries: expiries.clone(),
+            },
+            None,
+        )
+        .with_policy(policy);
+        let builder = pool.get_request_builder();
+        for request in [
+            builder.build_get_nym_request(None, &did, None, None),
+            builder.build_get_nym_request(None, &did, Some(1), None),
+            builder.build_get_txn_request(None, LedgerType::DOMAIN.to_id(), 1),
+            builder.build_get_nym_request(None, &did, None, None),
+        ] {
+            let (result, _) = block_on(perform_ledger_request(
+                &pool,
+                &request.unwrap(),
+                Some(cache.clone()),
+            ))
+            .unwrap();
+            assert!(matches!(result, RequestResult::Reply(_)));
+        }
+        assert_eq!(
+            *expiries.lock().unwrap(),
+            vec![
+                (Some(1_000), true),
+                (Some(100_000), false),
+                (Some(100_000), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn cache_stats_and_bypass() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let did = new_did();
+        write_nym(&pool, &did);
+
+        let expiries = Arc::new(Mutex::new(Vec::new()));
+        let cache = Cache::new(
+            RecordingCache {
+                inner: CacheStrategyTTL::new(10, 10_000, None, None),
+                expiries: expiries.clone(),
+            },
+            None,
+        );
+        let builder = pool.get_request_builder();
+        for _ in 0..2 {
+            let request = builder
+                .build_get_nym_request(None, &did, None, None)
+                .unwrap();
+            let (_, meta) =
+                block_on(perform_ledger_request(&pool, &request, Some(cache.clone()))).unwrap();
+            assert_eq!(meta.cached, cache.stats().hits == 1);
+        }
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+
+        // bypass the cache for a single request
+        let mut request = builder
+            .build_get_nym_request(None, &did, None, None)
+            .unwrap();
+        request.use_cache = false;
+        let (_, meta) =
+            block_on(perform_ledger_request(&pool, &request, Some(cache.clone()))).unwrap();
+        assert!(!meta.cached);
+        assert_eq!(expiries.lock().unwrap().len(), 1);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+    }
+}
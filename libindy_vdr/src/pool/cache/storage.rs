@@ -135,6 +135,10 @@ impl<K: Hash + Eq + Clone, O: Ord + Clone, V: Clone> OrderedHashMap<K, O, V> {
         let lookup = &self.lookup;
         lookup.get(key)
     }
+    /// iterates over the entries in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &(O, V))> {
+        self.lookup.iter()
+    }
     fn get_key_value(
         &self,
         selector: impl FnOnce(
@@ -1,11 +1,15 @@
 use super::storage::OrderedHashMap;
-use super::CacheStrategy;
+use super::{CacheStrategy, CacheUsage};
+use futures_util::future::BoxFuture;
 use std::{
     collections::BTreeMap,
     fmt::Debug,
     hash::Hash,
     ops::Deref,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     time::SystemTime,
 };
 
@@ -14,8 +18,11 @@ use std::{
 /// that may need to be updated/reordered
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TTLCacheItem<V> {
-    value: V,
-    expire_offset: u128,
+    pub(crate) value: V,
+    pub(crate) expire_offset: u128,
+    /// Whether the expiry is fixed when inserted, rather than extended when accessed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) fixed: bool,
 }
 
 /// A simple cache that uses timestamps to expire entries. Once the cache fills up, the oldest entry is evicted.
@@ -26,6 +33,7 @@ pub struct CacheStrategyTTL<K, V> {
     capacity: usize,
     create_time: SystemTime,
     expire_after: u128,
+    evictions: AtomicU64,
 }
 
 impl<K: Eq + Hash + Clone + Send + Sync + 'static, V: Clone + Send + Sync + 'static>
@@ -51,10 +59,59 @@ impl<K: Eq + Hash + Clone + Send + Sync + 'static, V: Clone + Send + Sync + 'sta
                 None => SystemTime::UNIX_EPOCH,
             },
             expire_after,
+            evictions: AtomicU64::new(0),
         }
     }
 }
 
+impl<K: Hash + Eq + Send + Sync + 'static + Clone + Debug, V: Clone + Send + Sync + 'static>
+    CacheStrategyTTL<K, V>
+{
+    /// Insert an entry after removing the expired entries, and the oldest entry if full
+    fn insert_item(&self, key: K, value: V, exp_offset: u128, fixed: bool) -> Option<V> {
+        if let Ok(mut store_lock) = self.store.lock() {
+            let current_ts = SystemTime::now()
+                .duration_since(self.create_time)
+                .unwrap()
+                .as_millis();
+
+            // remove expired entries
+            while store_lock.len() > 0
+                && store_lock
+                    .get_first_key_value()
+                    .map(|(_, ts, _)| ts < current_ts)
+                    .unwrap_or(false)
+            {
+                store_lock.remove_first();
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+
+            // remove the oldest item if the cache is still full
+            if store_lock.len() >= self.capacity && store_lock.get(&key).is_none() {
+                // remove the oldest item
+                let removal_key = store_lock.get_first_key_value().map(|(k, _, _)| k.clone());
+                if let Some(removal_key) = removal_key {
+                    store_lock.remove(&removal_key);
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            };
+
+            return store_lock
+                .insert(
+                    key,
+                    TTLCacheItem {
+                        value,
+                        expire_offset: exp_offset,
+                        fixed,
+                    },
+                    current_ts + exp_offset,
+                )
+                .map(|v| v.value);
+        }
+        None
+    }
+}
+
 impl<K: Send + Sync + 'static, V: Send + Sync + 'static> CacheStrategy<K, V>
     for Arc<dyn CacheStrategy<K, V>>
 {
@@ -67,6 +124,34 @@ impl<K: Send + Sync + 'static, V: Send + Sync + 'static> CacheStrategy<K, V>
     fn insert(&self, key: K, value: V, custom_exp_offset: Option<u128>) -> Option<V> {
         self.deref().insert(key, value, custom_exp_offset)
     }
+    fn insert_fixed(&self, key: K, value: V, exp_offset: u128) -> Option<V> {
+        self.deref().insert_fixed(key, value, exp_offset)
+    }
+    fn get_async(&self, key: &K) -> BoxFuture<'static, Option<V>> {
+        self.deref().get_async(key)
+    }
+    fn insert_async(
+        &self,
+        key: K,
+        value: V,
+        custom_exp_offset: Option<u128>,
+    ) -> BoxFuture<'static, Option<V>> {
+        self.deref().insert_async(key, value, custom_exp_offset)
+    }
+    fn insert_fixed_async(
+        &self,
+        key: K,
+        value: V,
+        exp_offset: u128,
+    ) -> BoxFuture<'static, Option<V>> {
+        self.deref().insert_fixed_async(key, value, exp_offset)
+    }
+    fn remove_where(&self, key_prefix: &str, predicate: &dyn Fn(&K, &V) -> bool) -> usize {
+        self.deref().remove_where(key_prefix, predicate)
+    }
+    fn usage(&self) -> Option<CacheUsage> {
+        self.deref().usage()
+    }
 }
 
 impl<K: Hash + Eq + Send + Sync + 'static + Clone + Debug, V: Clone + Send + Sync + 'static>
@@ -84,6 +169,7 @@ impl<K: Hash + Eq + Send + Sync + 'static + Clone + Debug, V: Clone + Send + Syn
                         Some((*ts, v.clone()))
                     } else {
                         store_lock.remove(key);
+                        self.evictions.fetch_add(1, Ordering::Relaxed);
                         None
                     }
                 }
@@ -91,7 +177,9 @@ impl<K: Hash + Eq + Send + Sync + 'static + Clone + Debug, V: Clone + Send + Syn
             };
             // update the timestamp if the entry is still valid
             if let Some((_, ref v)) = get_res {
-                store_lock.re_order(key, current_time + v.expire_offset);
+                if !v.fixed {
+                    store_lock.re_order(key, current_time + v.expire_offset);
+                }
             }
             return get_res.map(|(_, v)| v.value);
         }
@@ -105,44 +193,38 @@ impl<K: Hash + Eq + Send + Sync + 'static + Clone + Debug, V: Clone + Send + Syn
     }
 
     fn insert(&self, key: K, value: V, custom_exp_offset: Option<u128>) -> Option<V> {
-        if let Some(mut store_lock) = self.store.lock().ok() {
-            let current_ts = SystemTime::now()
-                .duration_since(self.create_time)
-                .unwrap()
-                .as_millis();
-
-            // remove expired entries
-            while store_lock.len() > 0
-                && store_lock
-                    .get_first_key_value()
-                    .map(|(_, ts, _)| ts.clone() < current_ts)
-                    .unwrap_or(false)
-            {
-                store_lock.remove_first();
-            }
+        self.insert_item(
+            key,
+            value,
+            custom_exp_offset.unwrap_or(self.expire_after),
+            false,
+        )
+    }
 
-            // remove the oldest item if the cache is still full
-            if store_lock.len() >= self.capacity && store_lock.get(&key).is_none() {
-                // remove the oldest item
-                let removal_key = store_lock.get_first_key_value().map(|(k, _, _)| k.clone());
-                if let Some(removal_key) = removal_key {
-                    store_lock.remove(&removal_key);
-                }
-            };
+    fn insert_fixed(&self, key: K, value: V, exp_offset: u128) -> Option<V> {
+        self.insert_item(key, value, exp_offset, true)
+    }
 
-            let exp_offset = custom_exp_offset.unwrap_or(self.expire_after);
-            return store_lock
-                .insert(
-                    key,
-                    TTLCacheItem {
-                        value: value,
-                        expire_offset: exp_offset,
-                    },
-                    current_ts + exp_offset,
-                )
-                .map(|v| v.value);
+    fn remove_where(&self, _key_prefix: &str, predicate: &dyn Fn(&K, &V) -> bool) -> usize {
+        if let Ok(mut store_lock) = self.store.lock() {
+            let keys = store_lock
+                .iter()
+                .filter(|(key, (_, item))| predicate(key, &item.value))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            for key in keys.iter() {
+                store_lock.remove(key);
+            }
+            return keys.len();
         }
-        None
+        0
+    }
+
+    fn usage(&self) -> Option<CacheUsage> {
+        self.store.lock().ok().map(|store_lock| CacheUsage {
+            entries: store_lock.len(),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        })
     }
 }
 
@@ -152,7 +234,7 @@ mod tests {
     use std::thread;
 
     use super::*;
-    use crate::pool::cache::{storage::OrderedHashMap, Cache};
+    use crate::pool::cache::{storage::OrderedHashMap, Cache, CacheStats, CacheUsage};
     use futures_executor::block_on;
 
     #[rstest]
@@ -197,4 +279,60 @@ mod tests {
             std::fs::remove_dir_all(cache_location).unwrap();
         });
     }
+
+    #[rstest]
+    fn test_cache_stats() {
+        let cache = Cache::new(CacheStrategyTTL::new(2, 1000, None, None), None);
+        assert_eq!(cache.stats().hit_ratio(), None);
+        cache.insert("key".to_string(), "value".to_string(), None);
+        assert_eq!(cache.get(&"key".to_string()), Some("value".to_string()));
+        assert_eq!(cache.get(&"key1".to_string()), None);
+        assert_eq!(
+            block_on(cache.get_async(&"key".to_string())),
+            Some("value".to_string())
+        );
+        // clones share the statistics
+        let other = cache.with_key_prefix(Some("b:".to_string()));
+        assert_eq!(other.get(&"key".to_string()), None);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2 });
+        assert_eq!(other.stats().hit_ratio(), Some(0.5));
+    }
+
+    #[rstest]
+    fn test_cache_fixed_expiry() {
+        let cache = Cache::new(CacheStrategyTTL::new(10, 200, None, None), None);
+        cache.insert("sliding".to_string(), "value".to_string(), None);
+        cache.insert_fixed("fixed".to_string(), "value".to_string(), 200);
+        thread::sleep(std::time::Duration::from_millis(120));
+        assert_eq!(cache.get(&"sliding".to_string()), Some("value".to_string()));
+        assert_eq!(cache.get(&"fixed".to_string()), Some("value".to_string()));
+        thread::sleep(std::time::Duration::from_millis(120));
+        // only the expiry of the sliding entry was extended when accessed
+        assert_eq!(cache.get(&"sliding".to_string()), Some("value".to_string()));
+        assert_eq!(cache.get(&"fixed".to_string()), None);
+    }
+
+    #[rstest]
+    fn test_cache_remove_where_and_usage() {
+        let strategy: Arc<dyn CacheStrategy<String, String>> =
+            Arc::new(CacheStrategyTTL::new(2, 1000, None, None));
+        let cache = Cache::new(strategy.clone(), Some("a:".to_string()));
+        let other = cache.with_key_prefix(Some("b:".to_string()));
+        cache.insert("key1".to_string(), "value1".to_string(), None);
+        other.insert("key1".to_string(), "value1".to_string(), None);
+        cache.insert("key2".to_string(), "value2".to_string(), None);
+        assert_eq!(
+            cache.usage(),
+            Some(CacheUsage {
+                entries: 2,
+                evictions: 1
+            })
+        );
+        assert_eq!(cache.remove_where("", |key, _| key == "key1"), 0);
+        assert_eq!(other.remove_where("key", |_, value| value == "value2"), 0);
+        assert_eq!(other.remove_where("key", |_, value| value == "value1"), 1);
+        assert_eq!(cache.get(&"key2".to_string()), Some("value2".to_string()));
+        assert_eq!(other.stats(), CacheStats { hits: 1, misses: 0 });
+        assert_eq!(strategy.usage().map(|usage| usage.entries), Some(1));
+    }
 }
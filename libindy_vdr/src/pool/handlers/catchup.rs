@@ -15,7 +15,9 @@ pub async fn handle_catchup_request<R: PoolRequest>(
     trace!("catchup request");
     let config = request.pool_config();
     let ack_timeout = config.ack_timeout;
-    request.send_to_any(config.request_read_nodes, ack_timeout)?;
+    let mut pending = request
+        .send_to_any(config.request_read_nodes, ack_timeout)?
+        .len();
     loop {
         match request.next().await {
             Some(RequestEvent::Received(node_alias, _message, parsed)) => match parsed {
@@ -30,18 +32,21 @@ pub async fn handle_catchup_request<R: PoolRequest>(
                         Ok(txns) => return Ok((RequestResult::Reply(txns), request.get_meta())),
                         Err(_) => {
                             request.clean_timeout(node_alias)?;
-                            request.send_to_any(1, ack_timeout)?;
+                            pending += request.send_to_any(1, ack_timeout)?.len();
+                            pending = pending.saturating_sub(1);
                         }
                     }
                 }
                 _ => {
                     debug!("Unexpected reply from {}", &node_alias);
                     request.clean_timeout(node_alias)?;
-                    request.send_to_any(1, ack_timeout)?;
+                    pending += request.send_to_any(1, ack_timeout)?.len();
+                    pending = pending.saturating_sub(1);
                 }
             },
             Some(RequestEvent::Timeout(_node_alias)) => {
-                request.send_to_any(1, ack_timeout)?;
+                pending += request.send_to_any(1, ack_timeout)?.len();
+                pending = pending.saturating_sub(1);
             }
             None => {
                 return Ok((
@@ -50,6 +55,15 @@ pub async fn handle_catchup_request<R: PoolRequest>(
                 ))
             }
         }
+        if pending == 0 {
+            return Ok((
+                RequestResult::Failed(err_msg(
+                    VdrErrorKind::PoolNoConsensus,
+                    "No valid catchup reply received",
+                )),
+                request.get_meta(),
+            ));
+        }
     }
 }
 
@@ -48,7 +48,7 @@ pub async fn handle_consensus_request<R: PoolRequest>(
                     trace!("reply on consensus request");
                     if let Some(result) = reply.result() {
                         let result_without_proof = result_without_state_proof(result);
-                        replies.add_reply(node_alias.clone(), true);
+                        replies.add_reply(node_alias.clone(), raw_msg.clone());
                         let hashable = HashableValue {
                             inner: result_without_proof,
                         };
@@ -168,6 +168,12 @@ pub async fn handle_consensus_request<R: PoolRequest>(
         let total_replies = replies.len();
         if total_replies >= total_nodes_count {
             let err = replies.get_error();
+            // include the conflicting replies so that clients may inspect them
+            let err = if matches!(err.kind(), VdrErrorKind::PoolNoConsensus) {
+                err.with_extra(replies.to_json())
+            } else {
+                err
+            };
             return Ok((RequestResult::Failed(err), request.get_meta()));
         }
         if resend {
@@ -248,3 +254,43 @@ pub struct ResponseMetadata {
     pub last_txn_time: Option<u64>,
     pub last_seq_no: Option<u64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::PoolConfig;
+    use crate::pool::networker::mock_fixtures::*;
+    use crate::pool::networker::{MockLedger, MockNodeBehaviour};
+    use crate::pool::Pool;
+
+    #[test]
+    fn no_consensus_includes_node_replies() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let did = new_did();
+        write_nym(&pool, &did);
+        let behaviours = [
+            MockNodeBehaviour::Divergent,
+            MockNodeBehaviour::Garbage,
+            MockNodeBehaviour::Nack("Denied".to_string()),
+            MockNodeBehaviour::Reject("Rejected".to_string()),
+        ];
+        for (alias, behaviour) in ledger.node_aliases().iter().zip(behaviours) {
+            ledger.set_behaviour(alias, behaviour).unwrap();
+        }
+
+        let request = pool
+            .get_request_builder()
+            .build_get_nym_request(None, &did, None, None)
+            .unwrap();
+        let RequestResult::Failed(err) = send(&pool, &request).0 else {
+            panic!("Expected failure");
+        };
+        assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus));
+        let replies: SJsonValue = serde_json::from_str(&err.extra().unwrap()).unwrap();
+        let replies = replies.as_object().unwrap();
+        assert_eq!(replies.len(), 4);
+        assert!(replies.values().any(|reply| reply["op"] == "REQNACK"));
+    }
+}
@@ -107,6 +107,27 @@ impl<T> ReplyState<T> {
     }
 }
 
+impl ReplyState<String> {
+    /// Collect the reply or failure message received from each node as a JSON object,
+    /// with `null` for nodes which did not reply
+    pub fn to_json(&self) -> String {
+        let replies = self
+            .inner
+            .iter()
+            .map(|(node_alias, reply)| {
+                let reply = match reply {
+                    SingleReply::Reply(msg) | SingleReply::Failed(msg) => {
+                        serde_json::from_str(msg).unwrap_or_else(|_| SJsonValue::from(msg.clone()))
+                    }
+                    SingleReply::Timeout() => SJsonValue::Null,
+                };
+                (node_alias.clone(), reply)
+            })
+            .collect::<serde_json::Map<String, SJsonValue>>();
+        SJsonValue::from(replies).to_string()
+    }
+}
+
 #[derive(Debug)]
 struct ConsensusState<K: Eq + Hash, T: Eq + Hash> {
     inner: HashMap<K, HashSet<T>>,
@@ -190,11 +211,25 @@ pub(crate) fn build_pool_status_request(
     merkle_root: String,
     merkle_tree_size: usize,
     protocol_version: ProtocolVersion,
+) -> VdrResult<Message> {
+    build_ledger_status_request(
+        LedgerType::POOL,
+        merkle_root,
+        merkle_tree_size,
+        protocol_version,
+    )
+}
+
+pub(crate) fn build_ledger_status_request(
+    ledger_type: LedgerType,
+    merkle_root: String,
+    merkle_tree_size: usize,
+    protocol_version: ProtocolVersion,
 ) -> VdrResult<Message> {
     let lr = LedgerStatus {
         txnSeqNo: merkle_tree_size,
         merkleRoot: merkle_root,
-        ledgerId: LedgerType::POOL as u8,
+        ledgerId: ledger_type as u8,
         ppSeqNo: None,
         viewNo: None,
         protocolVersion: Some(protocol_version as usize),
@@ -206,14 +241,27 @@ pub(crate) fn build_pool_catchup_request(
     from_mt_size: usize,
     target_mt_size: usize,
 ) -> VdrResult<Message> {
-    if from_mt_size >= target_mt_size {
+    build_catchup_request(
+        LedgerType::POOL,
+        from_mt_size,
+        target_mt_size,
+        target_mt_size,
+    )
+}
+
+pub(crate) fn build_catchup_request(
+    ledger_type: LedgerType,
+    from_mt_size: usize,
+    seq_no_end: usize,
+    target_mt_size: usize,
+) -> VdrResult<Message> {
+    if from_mt_size >= seq_no_end || seq_no_end > target_mt_size {
         return Err(input_err("No transactions to catch up"));
     }
     let seq_no_start = from_mt_size + 1;
-    let seq_no_end = target_mt_size;
 
     let cr = CatchupReq {
-        ledgerId: LedgerType::POOL as usize,
+        ledgerId: ledger_type as usize,
         seqNoStart: seq_no_start,
         seqNoEnd: seq_no_end,
         catchupTill: target_mt_size,
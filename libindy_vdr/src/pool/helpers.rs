@@ -1,21 +1,47 @@
 use std::iter::FromIterator;
 use std::string::ToString;
 
+use futures_util::future;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::de::DeserializeOwned;
 use serde_json;
 
-use super::cache::Cache;
+use super::cache::{policy::CacheClass, Cache};
 use super::genesis::PoolTransactions;
 use super::handlers::{
-    build_pool_catchup_request, build_pool_status_request, handle_catchup_request,
-    handle_consensus_request, handle_full_request, handle_status_request, CatchupTarget,
+    build_catchup_request, build_ledger_status_request, build_pool_catchup_request,
+    build_pool_status_request, handle_catchup_request, handle_consensus_request,
+    handle_full_request, handle_status_request, CatchupTarget,
 };
 use super::manager::Pool;
+use super::mirror::LedgerMirror;
 use super::requests::{PoolRequest, PreparedRequest, RequestMethod};
-use super::types::{NodeReplies, RequestResult, RequestResultMeta};
+use super::types::{NodeReplies, RequestResult, RequestResultMeta, StateProofResult};
 
 use crate::common::error::prelude::*;
+use crate::ledger::constants;
+#[cfg(any(feature = "rich_schema", test))]
+use crate::ledger::identifiers::RichSchemaId;
+use crate::ledger::identifiers::{CredentialDefinitionId, RevocationRegistryId, SchemaId};
+use crate::ledger::requests::author_agreement::GetTxnAuthorAgreementData;
+#[cfg(any(feature = "rich_schema", test))]
+use crate::ledger::responses::GetRichSchemaResult;
+use crate::ledger::responses::{
+    GetAcceptanceMechanismsResult, GetAttribResult, GetAuthRuleResult, GetCredDefResult,
+    GetFlagResult, GetFrozenLedgersResult, GetNymResultV1, GetReplyResult, GetRevRegDefResult,
+    GetRevRegDeltaResult, GetRevRegResult, GetSchemaResult, GetTxnAuthorAgreementResult,
+    GetTxnResult,
+};
 use crate::pool::LedgerType;
+use crate::state_proof::verify_txn_audit_path;
 use crate::utils::base58;
+use crate::utils::did::{DidValue, DEFAULT_LIBINDY_DID};
+
+/// The maximum number of pending requests for `perform_get_txn_range`
+pub const GET_TXN_RANGE_CONCURRENCY: usize = 5;
+
+/// The maximum number of transactions requested in a single ledger catchup request
+const LEDGER_CATCHUP_BATCH_SIZE: usize = 100;
 
 /// Perform a pool ledger status request to see if catchup is required
 pub async fn perform_pool_status_request<T: Pool>(
@@ -25,21 +51,22 @@ pub async fn perform_pool_status_request<T: Pool>(
 
     if pool.get_refreshed() {
         trace!("Performing fast status check");
-        match perform_get_txn(pool, LedgerType::POOL.to_id(), 1, None).await {
+        let prepared =
+            pool.get_request_builder()
+                .build_get_txn_request(None, LedgerType::POOL.to_id(), 1)?;
+        match perform_ledger_read::<_, GetTxnResult>(pool, &prepared, None).await {
             Ok((RequestResult::Reply(reply), res_meta)) => {
-                if let Ok(body) = serde_json::from_str::<serde_json::Value>(&reply) {
-                    if let (Some(status_root_hash), Some(status_txn_count)) = (
-                        body["result"]["data"]["rootHash"].as_str(),
-                        body["result"]["data"]["ledgerSize"].as_u64(),
-                    ) {
-                        let target = if status_root_hash == mt_root
-                            && status_txn_count == mt_size as u64
-                        {
+                if let Some((status_root_hash, status_txn_count)) = reply
+                    .data
+                    .and_then(|data| Some((data.root_hash?, data.ledger_size?)))
+                {
+                    let target =
+                        if status_root_hash == mt_root && status_txn_count == mt_size as u64 {
                             debug!("Fast status check succeeded, pool state is up to date");
                             None
                         } else {
                             debug!("Fast status check got catchup target: {}", status_root_hash);
-                            let target_mt_hash = base58::decode(status_root_hash)
+                            let target_mt_hash = base58::decode(&status_root_hash)
                                 .map_err(|_| invalid!("Can't decode status target root hash"))?;
                             Some((
                                 target_mt_hash,
@@ -47,12 +74,9 @@ pub async fn perform_pool_status_request<T: Pool>(
                                 res_meta.state_proof.keys().cloned().collect(),
                             ))
                         };
-                        return Ok((RequestResult::Reply(target), res_meta));
-                    } else {
-                        warn!("Error retrieving transaction count from fast status request");
-                    }
+                    return Ok((RequestResult::Reply(target), res_meta));
                 } else {
-                    warn!("Error parsing response from fast status request");
+                    warn!("Error retrieving transaction count from fast status request");
                 }
             }
             Ok((RequestResult::Failed(err), _)) | Err(err) => {
@@ -162,7 +186,76 @@ pub(crate) async fn perform_catchup<T: Pool>(
     }
 }
 
+/// Catch up a local ledger mirror to the current state of the verifier pool
+///
+/// Transactions are requested in batches, and each batch is checked against the
+/// consistency proof for the target merkle root before being added to the mirror.
+/// The result indicates the number of transactions added.
+pub async fn perform_ledger_catchup<T: Pool>(
+    pool: &T,
+    mirror: &mut LedgerMirror,
+) -> VdrResult<(RequestResult<usize>, RequestResultMeta)> {
+    let ledger_type = mirror.ledger_type();
+    let message = build_ledger_status_request(
+        ledger_type,
+        mirror.root_hash_base58(),
+        mirror.len(),
+        pool.get_config().protocol_version,
+    )?;
+    let req_json = message.serialize()?.to_string();
+    let mut request = pool.create_request("".to_string(), req_json).await?;
+    let (target_mt_root, target_mt_size, nodes) =
+        match handle_status_request(&mut request, mirror.merkle_tree()).await? {
+            (RequestResult::Reply(Some(target)), _) => target,
+            (RequestResult::Reply(None), meta) => {
+                debug!("No catchup required for ledger {}", ledger_type.to_id());
+                return Ok((RequestResult::Reply(0), meta));
+            }
+            (RequestResult::Failed(err), meta) => return Ok((RequestResult::Failed(err), meta)),
+        };
+
+    let start_size = mirror.len();
+    loop {
+        let from_mt_size = mirror.len();
+        let seq_no_end = (from_mt_size + LEDGER_CATCHUP_BATCH_SIZE).min(target_mt_size);
+        let message = build_catchup_request(ledger_type, from_mt_size, seq_no_end, target_mt_size)?;
+        let req_json = message.serialize()?.to_string();
+        let mut request = pool.create_request("".to_string(), req_json).await?;
+        request.set_preferred_nodes(&nodes);
+        let (result, meta) = handle_catchup_request(
+            &mut request,
+            mirror.merkle_tree(),
+            target_mt_root.clone(),
+            target_mt_size,
+        )
+        .await?;
+        match result {
+            RequestResult::Reply(txns) if !txns.is_empty() => {
+                debug!(
+                    "Received {} transactions for ledger {}",
+                    txns.len(),
+                    ledger_type.to_id()
+                );
+                mirror.extend(txns)?;
+                if mirror.len() >= target_mt_size {
+                    return Ok((RequestResult::Reply(mirror.len() - start_size), meta));
+                }
+            }
+            RequestResult::Reply(_) => {
+                return Err(err_msg(
+                    VdrErrorKind::Unexpected,
+                    "No transactions received for ledger catchup",
+                ))
+            }
+            RequestResult::Failed(err) => return Ok((RequestResult::Failed(err), meta)),
+        }
+    }
+}
+
 /// Fetch a ledger transaction
+///
+/// The raw reply is returned, use `perform_ledger_read` with a `GET_TXN` request to
+/// parse the transaction as a `GetTxnResult`.
 pub async fn perform_get_txn<T: Pool>(
     pool: &T,
     ledger_type: i32,
@@ -174,6 +267,101 @@ pub async fn perform_get_txn<T: Pool>(
     perform_ledger_request(pool, &prepared, cache).await
 }
 
+/// Fetch the current root hash and size of a ledger, as multi-signed by the nodes
+///
+/// The root is taken from a `GET_TXN` reply with a verified state proof, and may be
+/// passed as the trusted root to `perform_get_txn_range`.
+pub async fn perform_get_txn_root<T: Pool>(
+    pool: &T,
+    ledger_type: i32,
+) -> VdrResult<(Vec<u8>, u64)> {
+    let (reply, meta) = match perform_get_txn(pool, ledger_type, 1, None).await? {
+        (RequestResult::Reply(reply), meta) => (reply, meta),
+        (RequestResult::Failed(err), _) => return Err(err),
+    };
+    let root_hash = meta
+        .state_proof
+        .values()
+        .find_map(|result| match result {
+            StateProofResult::Verified(asserts) => base58::decode(&asserts.txn_root_hash).ok(),
+            _ => None,
+        })
+        .ok_or_else(|| input_err("No verified state proof for the ledger root"))?;
+    let body = serde_json::from_str::<serde_json::Value>(&reply)
+        .with_input_err("Error parsing transaction reply")?;
+    let ledger_size = body["result"]["data"]["ledgerSize"]
+        .as_u64()
+        .ok_or_else(|| input_err("Invalid ledger size in transaction reply"))?;
+    Ok((root_hash, ledger_size))
+}
+
+/// Fetch a range of ledger transactions as a stream of verified replies
+///
+/// Up to `GET_TXN_RANGE_CONCURRENCY` transactions are requested at once, and the
+/// replies are produced in order of sequence number. The audit path of each reply
+/// must lead to the trusted ledger root hash and size, so the range fails if the
+/// ledger grows while it is being fetched. The stream ends after the first error.
+pub fn perform_get_txn_range<T: Pool>(
+    pool: &T,
+    ledger_type: i32,
+    from: i32,
+    to: i32,
+    root_hash: Vec<u8>,
+    ledger_size: u64,
+) -> VdrResult<impl Stream<Item = VdrResult<(i32, String)>> + '_> {
+    if from < 1 || to < from {
+        return Err(input_err(format!(
+            "Invalid transaction range: {}-{}",
+            from, to
+        )));
+    }
+    if to as u64 > ledger_size {
+        return Err(input_err(format!(
+            "Transaction range exceeds the ledger size: {}",
+            ledger_size
+        )));
+    }
+    Ok(stream::iter(from..=to)
+        .map(move |seq_no| async move {
+            let result = perform_get_txn(pool, ledger_type, seq_no, None).await;
+            (seq_no, result)
+        })
+        .buffered(GET_TXN_RANGE_CONCURRENCY)
+        .scan(false, move |failed, (seq_no, result)| {
+            if *failed {
+                return future::ready(None);
+            }
+            let result = _verify_txn_range_reply(seq_no, result, &root_hash, ledger_size);
+            *failed = result.is_err();
+            future::ready(Some(result.map(|reply| (seq_no, reply))))
+        }))
+}
+
+fn _verify_txn_range_reply(
+    seq_no: i32,
+    result: VdrResult<(RequestResult<String>, RequestResultMeta)>,
+    root_hash: &[u8],
+    ledger_size: u64,
+) -> VdrResult<String> {
+    let reply = match result? {
+        (RequestResult::Reply(reply), _meta) => reply,
+        (RequestResult::Failed(err), _) => return Err(err),
+    };
+    let body = serde_json::from_str::<serde_json::Value>(&reply)
+        .with_input_err("Error parsing transaction reply")?;
+    let result = &body["result"];
+    if result["data"].is_null() {
+        return Err(input_err(format!("Transaction not found: {}", seq_no)));
+    }
+    if !verify_txn_audit_path(result, seq_no as u64, root_hash, ledger_size)? {
+        return Err(input_err(format!(
+            "Audit path verification failed for transaction {}",
+            seq_no
+        )));
+    }
+    Ok(reply)
+}
+
 /// Dispatch a request to a specific set of nodes and collect the results
 pub async fn perform_ledger_action<T: Pool>(
     pool: &T,
@@ -220,10 +408,12 @@ pub async fn perform_ledger_request<T: Pool>(
     };
 
     let cache_key = prepared.get_cache_key()?;
+    let cache_opt = cache_opt.filter(|_| prepared.use_cache);
 
     if is_read_req {
         if let Some(cache) = cache_opt.clone() {
-            if let Some((response, meta)) = cache.get(&cache_key) {
+            if let Some((response, mut meta)) = cache.get_async(&cache_key).await {
+                meta.cached = true;
                 return Ok((RequestResult::Reply(response), meta));
             }
         }
@@ -238,15 +428,234 @@ pub async fn perform_ledger_request<T: Pool>(
                 if data["result"]["data"].is_null() || data["result"]["seqNo"].is_null() {
                     return result;
                 }
-            }
-            if let Some(cache) = cache_opt {
-                cache.insert(cache_key, (response.to_string(), meta.clone()), None);
+                if let Some(cache) = cache_opt {
+                    let class = CacheClass::from_reply(&prepared.req_json, &data);
+                    let value = (response.to_string(), meta.clone());
+                    match cache.policy().expiry(class) {
+                        // current state is fetched again after the expiry, even if accessed
+                        Some(expiry) if cache.policy().is_fixed(class) => {
+                            cache.insert_fixed_async(cache_key, value, expiry).await
+                        }
+                        expiry => cache.insert_async(cache_key, value, expiry).await,
+                    };
+                }
             }
         }
     }
     return result;
 }
 
+/// The parsed reply to a ledger read request
+pub type ReadResult<D> = (RequestResult<GetReplyResult<D>>, RequestResultMeta);
+
+/// Dispatch a prepared ledger read request and parse the reply `data` as `D`
+pub async fn perform_ledger_read<T: Pool, D: DeserializeOwned>(
+    pool: &T,
+    prepared: &PreparedRequest,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<D>> {
+    let txn_type = prepared.txn_type.as_str();
+    if !constants::READ_REQUESTS.contains(&txn_type) && txn_type != constants::GET_FROZEN_LEDGERS {
+        return Err(input_err(format!(
+            "Expected a ledger read request, got: {}",
+            txn_type
+        )));
+    }
+    let (result, meta) = perform_ledger_request(pool, prepared, cache).await?;
+    Ok((
+        result.map_result(|reply| GetReplyResult::from_reply(&reply))?,
+        meta,
+    ))
+}
+
+/// Fetch the NYM record for a DID
+pub async fn perform_get_nym<T: Pool>(
+    pool: &T,
+    dest: &DidValue,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetNymResultV1>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_nym_request(None, dest, seq_no, timestamp)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch an attribute of a DID
+#[allow(clippy::too_many_arguments)]
+pub async fn perform_get_attrib<T: Pool>(
+    pool: &T,
+    dest: &DidValue,
+    raw: Option<String>,
+    hash: Option<String>,
+    enc: Option<String>,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetAttribResult>> {
+    let builder = pool.get_request_builder();
+    let prepared =
+        builder.build_get_attrib_request(None, dest, raw, hash, enc, seq_no, timestamp)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch a schema
+pub async fn perform_get_schema<T: Pool>(
+    pool: &T,
+    id: &SchemaId,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetSchemaResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_schema_request(None, id)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch a credential definition
+pub async fn perform_get_cred_def<T: Pool>(
+    pool: &T,
+    id: &CredentialDefinitionId,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetCredDefResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_cred_def_request(None, id)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch a revocation registry definition
+pub async fn perform_get_revoc_reg_def<T: Pool>(
+    pool: &T,
+    id: &RevocationRegistryId,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetRevRegDefResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_revoc_reg_def_request(None, id)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch the accumulator of a revocation registry at a point in time
+pub async fn perform_get_revoc_reg<T: Pool>(
+    pool: &T,
+    revoc_reg_def_id: &RevocationRegistryId,
+    timestamp: i64,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetRevRegResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_revoc_reg_request(None, revoc_reg_def_id, timestamp)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch the changes to a revocation registry over an interval
+pub async fn perform_get_revoc_reg_delta<T: Pool>(
+    pool: &T,
+    revoc_reg_def_id: &RevocationRegistryId,
+    from: Option<i64>,
+    to: i64,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetRevRegDeltaResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_revoc_reg_delta_request(None, revoc_reg_def_id, from, to)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch one or all of the ledger authentication rules
+pub async fn perform_get_auth_rule<T: Pool>(
+    pool: &T,
+    auth_type: Option<String>,
+    auth_action: Option<String>,
+    field: Option<String>,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetAuthRuleResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_auth_rule_request(
+        None,
+        auth_type,
+        auth_action,
+        field,
+        old_value,
+        new_value,
+    )?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch a transaction author agreement, by default the active one
+pub async fn perform_get_txn_author_agreement<T: Pool>(
+    pool: &T,
+    data: Option<&GetTxnAuthorAgreementData>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetTxnAuthorAgreementResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_txn_author_agreement_request(None, data)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch a list of acceptance mechanisms, by default the active one
+pub async fn perform_get_acceptance_mechanisms<T: Pool>(
+    pool: &T,
+    timestamp: Option<u64>,
+    version: Option<String>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetAcceptanceMechanismsResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_acceptance_mechanisms_request(None, timestamp, version)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch the value of a config ledger flag
+pub async fn perform_get_flag<T: Pool>(
+    pool: &T,
+    name: String,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetFlagResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_flag_request(None, name, seq_no, timestamp)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+/// Fetch the frozen ledgers, indexed by ledger ID
+pub async fn perform_get_frozen_ledgers<T: Pool>(
+    pool: &T,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetFrozenLedgersResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_frozen_ledgers_request(&DEFAULT_LIBINDY_DID)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+#[cfg(any(feature = "rich_schema", test))]
+/// Fetch a rich schema object by its identifier
+pub async fn perform_get_rich_schema_by_id<T: Pool>(
+    pool: &T,
+    id: &RichSchemaId,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetRichSchemaResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_rich_schema_by_id(&DEFAULT_LIBINDY_DID, id)?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
+#[cfg(any(feature = "rich_schema", test))]
+/// Fetch a rich schema object by its type, name and version
+pub async fn perform_get_rich_schema_by_metadata<T: Pool>(
+    pool: &T,
+    rs_type: String,
+    rs_name: String,
+    rs_version: String,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<ReadResult<GetRichSchemaResult>> {
+    let builder = pool.get_request_builder();
+    let prepared = builder.build_get_rich_schema_by_metadata(
+        &DEFAULT_LIBINDY_DID,
+        rs_type,
+        rs_name,
+        rs_version,
+    )?;
+    perform_ledger_read(pool, &prepared, cache).await
+}
+
 /// Format a collection of node replies in the expected response format
 pub(crate) fn format_full_reply<T>(replies: NodeReplies<T>) -> VdrResult<String>
 where
@@ -257,3 +666,282 @@ where
     )))
     .with_input_err("Error serializing response")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    use futures_executor::block_on;
+    use serde_json::Value as SJsonValue;
+
+    use crate::config::PoolConfig;
+    use crate::ledger::requests::schema::{Schema, SchemaV1};
+    use crate::pool::networker::mock_fixtures::*;
+    use crate::pool::networker::{MockLedger, MockNodeBehaviour};
+    use crate::pool::{InMemoryLedgerStore, LedgerType};
+    use crate::utils::did::generate_did;
+
+    #[test]
+    fn typed_reads_parse_reply_data() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let (did, _, verkey) = generate_did(None, None).unwrap();
+        let did = DidValue(did.to_string());
+        let verkey = verkey.as_base58().unwrap().to_string();
+        let builder = pool.get_request_builder();
+        let request = builder
+            .build_nym_request(&did, &did, Some(verkey.clone()), None, None, None, None)
+            .unwrap();
+        send(&pool, &request);
+        let raw = json!({"endpoint": {"endpoint": "https://example.com"}});
+        let request = builder
+            .build_attrib_request(&did, &did, None, Some(&raw), None)
+            .unwrap();
+        send(&pool, &request);
+        let schema = SchemaV1 {
+            id: SchemaId::new(&did, "test", "1.0"),
+            name: "test".to_string(),
+            version: "1.0".to_string(),
+            attr_names: vec!["name".to_string()].into(),
+            seq_no: None,
+        };
+        let request = builder
+            .build_schema_request(&did, Schema::SchemaV1(schema.clone()))
+            .unwrap();
+        send(&pool, &request);
+
+        let (result, meta) = block_on(perform_get_nym(&pool, &did, None, None, None)).unwrap();
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        assert_eq!(reply.seq_no, Some(1));
+        assert!(reply.txn_time.is_some());
+        assert!(reply.state_proof.is_some());
+        let data = reply.data.unwrap();
+        assert_eq!((data.dest, data.verkey), (did.clone(), Some(verkey)));
+        assert!(verified(&meta));
+
+        let (result, _) = block_on(perform_get_attrib(
+            &pool,
+            &did,
+            Some("endpoint".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ))
+        .unwrap();
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        assert_eq!(reply.seq_no, Some(2));
+        assert_eq!(reply.data.unwrap(), raw.to_string());
+
+        let (result, _) = block_on(perform_get_schema(&pool, &schema.id, None)).unwrap();
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        let data = reply.data.unwrap();
+        assert_eq!(
+            (data.name, data.attr_names),
+            (schema.name, vec!["name".to_string()])
+        );
+
+        let request = builder
+            .build_get_txn_request(None, LedgerType::DOMAIN.to_id(), 3)
+            .unwrap();
+        let (result, meta) = block_on(perform_ledger_read::<_, GetTxnResult>(
+            &pool, &request, None,
+        ))
+        .unwrap();
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        let data = reply.data.unwrap();
+        assert_eq!(data.txn_metadata.seq_no, Some(3));
+        assert_eq!(data.ledger_size, Some(3));
+        assert!(!data.audit_path.is_empty());
+        assert!(verified(&meta));
+
+        let request = builder.build_get_txn_request(None, 1, 1).unwrap();
+        assert!(block_on(perform_ledger_read::<_, SJsonValue>(&pool, &request, None)).is_ok());
+        let request = builder
+            .build_nym_request(&did, &did, None, None, None, None, None)
+            .unwrap();
+        assert!(block_on(perform_ledger_read::<_, SJsonValue>(&pool, &request, None)).is_err());
+    }
+
+    #[test]
+    fn get_txn_range_verifies_audit_paths() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        for _ in 0..7 {
+            write_nym(&pool, &new_did());
+        }
+        let domain = LedgerType::DOMAIN.to_id();
+        let (root_hash, ledger_size) = block_on(perform_get_txn_root(&pool, domain)).unwrap();
+        assert_eq!(ledger_size, 7);
+        let txns = block_on(
+            perform_get_txn_range(&pool, domain, 2, 7, root_hash.clone(), ledger_size)
+                .unwrap()
+                .collect::<Vec<_>>(),
+        );
+        let seq_nos = txns
+            .into_iter()
+            .map(|txn| txn.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(seq_nos, (2..=7).collect::<Vec<_>>());
+
+        // the ledger has grown past the trusted root
+        write_nym(&pool, &new_did());
+        let txns = block_on(
+            perform_get_txn_range(&pool, domain, 6, 7, root_hash.clone(), ledger_size)
+                .unwrap()
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(txns.len(), 1);
+        assert!(txns[0].is_err());
+
+        assert!(
+            perform_get_txn_range(&pool, domain, 0, 3, root_hash.clone(), ledger_size).is_err()
+        );
+        assert!(perform_get_txn_range(&pool, domain, 6, 8, root_hash, ledger_size).is_err());
+    }
+
+    #[test]
+    fn get_txn_range_rejects_tampered_replies() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        for _ in 0..4 {
+            write_nym(&pool, &new_did());
+        }
+        let domain = LedgerType::DOMAIN.to_id();
+        let (root_hash, ledger_size) = block_on(perform_get_txn_root(&pool, domain)).unwrap();
+        let (result, meta) = block_on(perform_get_txn(&pool, domain, 2, None)).unwrap();
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        let verify = |reply: &SJsonValue, root_hash: &[u8], ledger_size: u64| {
+            let reply = RequestResult::Reply(reply.to_string());
+            _verify_txn_range_reply(2, Ok((reply, meta.clone())), root_hash, ledger_size)
+        };
+        let reply: SJsonValue = serde_json::from_str(&reply).unwrap();
+        assert!(verify(&reply, &root_hash, ledger_size).is_ok());
+        assert!(verify(&reply, &root_hash, ledger_size + 1).is_err());
+
+        let mut tampered = reply.clone();
+        tampered["result"]["data"]["auditPath"][0] = reply["result"]["data"]["rootHash"].clone();
+        assert!(verify(&tampered, &root_hash, ledger_size).is_err());
+
+        // a reply which is consistent with its own root hash is not trusted
+        let mut tampered = reply.clone();
+        tampered["result"]["data"]["rootHash"] = reply["result"]["data"]["auditPath"][0].clone();
+        assert!(verify(&tampered, &root_hash, ledger_size).is_err());
+        let reply_root =
+            base58::decode(reply["result"]["data"]["auditPath"][0].as_str().unwrap()).unwrap();
+        assert!(verify(&reply, &reply_root, ledger_size).is_err());
+    }
+
+    #[test]
+    fn refresh_catchup_retries_invalid_replies() {
+        let ledger = MockLedger::new(4).unwrap();
+        let config = PoolConfig {
+            request_read_nodes: 4,
+            ..PoolConfig::default()
+        };
+        let pool = mock_pool(&ledger, config);
+        ledger.add_node("Node5").unwrap();
+        let target = ledger.pool_transactions().unwrap();
+        let aliases = ledger.node_aliases();
+        // the divergent reply arrives first and is skipped
+        ledger
+            .set_behaviour(&aliases[0], MockNodeBehaviour::Divergent)
+            .unwrap();
+        for alias in &aliases[1..] {
+            ledger
+                .set_behaviour(alias, MockNodeBehaviour::Delay(Duration::from_millis(50)))
+                .unwrap();
+        }
+        let (txns, _meta) = block_on(perform_catchup(
+            &pool,
+            target.root_hash().unwrap(),
+            target.len(),
+            Some(aliases),
+        ))
+        .unwrap();
+        assert_eq!(txns.root_hash().unwrap(), target.root_hash().unwrap());
+    }
+
+    #[test]
+    fn refresh_catchup_fails_without_valid_replies() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        ledger.add_node("Node5").unwrap();
+        let target = ledger.pool_transactions().unwrap();
+        for alias in ledger.node_aliases() {
+            ledger
+                .set_behaviour(&alias, MockNodeBehaviour::Divergent)
+                .unwrap();
+        }
+        let err = block_on(perform_catchup(
+            &pool,
+            target.root_hash().unwrap(),
+            target.len(),
+            None,
+        ))
+        .unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::PoolNoConsensus));
+    }
+
+    #[test]
+    fn ledger_catchup_builds_verified_mirror() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let store = Arc::new(InMemoryLedgerStore::new());
+        let mut mirror = LedgerMirror::open(LedgerType::DOMAIN, store.clone()).unwrap();
+        for _ in 0..3 {
+            write_nym(&pool, &new_did());
+        }
+        let (result, _) = block_on(perform_ledger_catchup(&pool, &mut mirror)).unwrap();
+        assert!(matches!(result, RequestResult::Reply(3)));
+
+        let did = new_did();
+        write_nym(&pool, &did);
+        let (result, _) = block_on(perform_ledger_catchup(&pool, &mut mirror)).unwrap();
+        assert!(matches!(result, RequestResult::Reply(1)));
+        let (result, _) = block_on(perform_ledger_catchup(&pool, &mut mirror)).unwrap();
+        assert!(matches!(result, RequestResult::Reply(0)));
+
+        let mirror = LedgerMirror::open(LedgerType::DOMAIN, store).unwrap();
+        assert_eq!(mirror.len(), 4);
+        assert_eq!(
+            mirror.root_hash_base58(),
+            ledger.root_hash(LedgerType::DOMAIN)
+        );
+        let txn = mirror.get_transaction(4).unwrap().unwrap();
+        assert_eq!(txn["txn"]["data"]["dest"], SJsonValue::from(did.0));
+    }
+
+    #[test]
+    fn ledger_catchup_rejects_divergent_transactions() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        for _ in 0..2 {
+            write_nym(&pool, &new_did());
+        }
+        for alias in ledger.node_aliases() {
+            ledger
+                .set_behaviour(&alias, MockNodeBehaviour::Divergent)
+                .unwrap();
+        }
+        let mut mirror =
+            LedgerMirror::open(LedgerType::DOMAIN, Arc::new(InMemoryLedgerStore::new())).unwrap();
+        let result = block_on(perform_ledger_catchup(&pool, &mut mirror));
+        assert!(!matches!(result, Ok((RequestResult::Reply(_), _))));
+        assert!(mirror.is_empty());
+    }
+}
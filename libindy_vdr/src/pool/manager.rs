@@ -10,6 +10,7 @@ use futures_util::future::{lazy, FutureExt, LocalBoxFuture};
 use rand::seq::SliceRandom;
 
 use super::genesis::{build_node_transaction_map, build_verifiers, PoolTransactions};
+use super::monitor::PoolMonitor;
 use super::networker::{
     LocalNetworker, Networker, NetworkerEvent, NetworkerFactory, SharedNetworker,
 };
@@ -19,7 +20,7 @@ use super::types::{PoolSetup, RequestHandle, Verifiers};
 use crate::common::error::prelude::*;
 use crate::common::handle::ResourceHandle;
 use crate::common::merkle_tree::MerkleTree;
-use crate::config::PoolConfig;
+use crate::config::{NodeSelection, PoolConfig};
 use crate::ledger::RequestBuilder;
 use crate::utils::base58;
 
@@ -71,6 +72,7 @@ pub trait Pool: Clone {
 pub struct PoolImpl<S: AsRef<PoolSetup> + Clone, T: Networker + Clone> {
     setup: S,
     networker: T,
+    monitor: PoolMonitor,
 }
 
 /// A verifier pool instance restricted to a single thread
@@ -85,7 +87,11 @@ where
     T: Networker + Clone,
 {
     pub(crate) fn new(setup: S, networker: T) -> Self {
-        Self { setup, networker }
+        Self {
+            setup,
+            networker,
+            monitor: PoolMonitor::new(),
+        }
     }
 
     /// Build a new verifier pool instance
@@ -107,6 +113,24 @@ where
     }
 }
 
+impl<S, T> PoolImpl<S, T>
+where
+    S: AsRef<PoolSetup> + Clone,
+    T: Networker + Clone,
+{
+    /// Get the monitor collecting node statistics for requests to this pool
+    pub fn monitor(&self) -> &PoolMonitor {
+        &self.monitor
+    }
+
+    /// Collect node statistics in an existing monitor, such as one carried over from
+    /// the pool instance replaced by a refresh
+    pub fn with_monitor(mut self, monitor: PoolMonitor) -> Self {
+        self.monitor = monitor;
+        self
+    }
+}
+
 impl<S, T> Pool for PoolImpl<S, T>
 where
     S: AsRef<PoolSetup> + Clone,
@@ -121,18 +145,31 @@ where
     ) -> LocalBoxFuture<'_, VdrResult<Self::Request>> {
         let setup = self.setup.clone();
         let networker = self.networker.clone();
+        let monitor = self.monitor.clone();
         lazy(move |_| {
             let (tx, rx) = unbounded();
             let handle = RequestHandle::next();
             let setup_ref = setup.as_ref();
-            let node_order = choose_nodes(&setup_ref.verifiers, setup_ref.node_weights.as_ref());
+            let node_order = match setup_ref.config.node_selection {
+                NodeSelection::Weighted => {
+                    choose_nodes(&setup_ref.verifiers, setup_ref.node_weights.as_ref())
+                }
+                NodeSelection::Adaptive => {
+                    let weights = adaptive_weights(
+                        &setup_ref.verifiers,
+                        setup_ref.node_weights.as_ref(),
+                        &monitor,
+                    );
+                    choose_nodes(&setup_ref.verifiers, Some(&weights))
+                }
+            };
             debug!(
                 "New {}: reqId({}), node order: {:?}",
                 handle, req_id, node_order
             );
             networker.send(NetworkerEvent::NewRequest(handle, req_id, req_json, tx))?;
             Ok(PoolRequestImpl::new(
-                handle, rx, setup, networker, node_order,
+                handle, rx, setup, networker, node_order, monitor,
             ))
         })
         .boxed_local()
@@ -159,6 +196,21 @@ where
     }
 }
 
+/// Combine the static node weights with the performance observed by a pool monitor
+pub(crate) fn adaptive_weights(
+    verifiers: &Verifiers,
+    weights: Option<&HashMap<String, f32>>,
+    monitor: &PoolMonitor,
+) -> HashMap<String, f32> {
+    let mut adaptive = monitor.adaptive_weights(verifiers.keys());
+    if let Some(weights) = weights {
+        for (name, weight) in adaptive.iter_mut() {
+            *weight *= weights.get(name).copied().unwrap_or(1.0);
+        }
+    }
+    adaptive
+}
+
 pub(crate) fn choose_nodes(
     verifiers: &Verifiers,
     weights: Option<&HashMap<String, f32>>,
@@ -194,7 +246,10 @@ pub(crate) fn choose_nodes(
 mod tests {
     use std::collections::HashMap;
 
-    use crate::pool::{VerifierInfo, Verifiers};
+    use crate::config::NodeSelection;
+    use crate::pool::networker::mock_fixtures::*;
+    use crate::pool::networker::{MockLedger, MockNodeBehaviour};
+    use crate::pool::{RequestResult, VerifierInfo, Verifiers};
 
     use super::*;
 
@@ -218,4 +273,69 @@ mod tests {
         let found = choose_nodes(&verifiers, Some(&weights));
         assert_eq!(found, ["c", "b"]);
     }
+
+    #[test]
+    fn test_adaptive_weights() {
+        let test_verif = VerifierInfo {
+            client_addr: "127.0.0.1".into(),
+            node_addr: "127.0.0.1".into(),
+            public_key: "pk".into(),
+            enc_key: "ek".into(),
+            bls_key: None,
+        };
+        let mut verifiers = Verifiers::new();
+        verifiers.insert("a".into(), test_verif.clone());
+        verifiers.insert("b".into(), test_verif.clone());
+        verifiers.insert("c".into(), test_verif);
+
+        let monitor = PoolMonitor::new();
+        for _ in 0..5 {
+            monitor.record_timeout("a");
+        }
+        let mut weights = HashMap::new();
+        weights.insert("c".into(), 0.0);
+        let adaptive = adaptive_weights(&verifiers, Some(&weights), &monitor);
+        assert!(adaptive["a"] > 0.0 && adaptive["a"] < adaptive["b"]);
+        assert_eq!(adaptive["c"], 0.0);
+        let found = choose_nodes(&verifiers, Some(&adaptive));
+        assert_eq!(found.len(), 2);
+        assert!(!found.contains(&"c".to_string()));
+    }
+
+    #[test]
+    fn adaptive_selection_deprioritises_silent_node() {
+        let ledger = MockLedger::new(4).unwrap();
+        let config = PoolConfig {
+            reply_timeout: 1,
+            node_selection: NodeSelection::Adaptive,
+            ..PoolConfig::default()
+        };
+        let pool = mock_pool(&ledger, config);
+        let aliases = ledger.node_aliases();
+        ledger
+            .set_behaviour(&aliases[0], MockNodeBehaviour::Timeout)
+            .unwrap();
+        let did = new_did();
+        let request = pool
+            .get_request_builder()
+            .build_get_validator_info_request(&did)
+            .unwrap();
+        send(&pool, &request);
+
+        let weights = pool.monitor().adaptive_weights(&aliases);
+        assert!(aliases[1..]
+            .iter()
+            .all(|alias| weights[&aliases[0]] < weights[alias]));
+
+        assert!(matches!(write_nym(&pool, &did), RequestResult::Reply(_)));
+        let request = pool
+            .get_request_builder()
+            .build_get_nym_request(None, &did, None, None)
+            .unwrap();
+        for _ in 0..3 {
+            let (result, meta) = send(&pool, &request);
+            assert!(matches!(result, RequestResult::Reply(_)));
+            assert!(verified(&meta));
+        }
+    }
 }
@@ -0,0 +1,392 @@
+use std::collections::{hash_map::Entry, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use rand::random;
+use serde_json::Value as SJsonValue;
+
+use super::genesis::PoolTransactions;
+use super::types::LedgerType;
+use crate::common::error::prelude::*;
+use crate::common::merkle_tree::MerkleTree;
+use crate::utils::base58;
+
+/// Stored ledger transactions along with their verified merkle root hash
+pub type StoredLedger = (Vec<Vec<u8>>, Vec<u8>);
+
+/// A persistent store for verified ledger transactions
+pub trait LedgerStore: Send + Sync {
+    /// Load the stored transactions for a ledger along with their verified root hash
+    fn load(&self, ledger_type: LedgerType) -> VdrResult<Option<StoredLedger>>;
+
+    /// Append verified transactions to a ledger and record the new root hash
+    fn append(&self, ledger_type: LedgerType, txns: &[Vec<u8>], root_hash: &[u8]) -> VdrResult<()>;
+
+    /// Fetch a stored transaction by its sequence number
+    fn get_transaction(&self, ledger_type: LedgerType, seq_no: usize)
+        -> VdrResult<Option<Vec<u8>>>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryLedgerStore {
+    ledgers: Mutex<HashMap<i32, StoredLedger>>,
+}
+
+impl InMemoryLedgerStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LedgerStore for InMemoryLedgerStore {
+    fn load(&self, ledger_type: LedgerType) -> VdrResult<Option<StoredLedger>> {
+        let ledgers = self.ledgers.lock().unwrap();
+        Ok(ledgers.get(&ledger_type.to_id()).cloned())
+    }
+
+    fn append(&self, ledger_type: LedgerType, txns: &[Vec<u8>], root_hash: &[u8]) -> VdrResult<()> {
+        let mut ledgers = self.ledgers.lock().unwrap();
+        let entry = ledgers.entry(ledger_type.to_id()).or_default();
+        entry.0.extend_from_slice(txns);
+        entry.1 = root_hash.to_vec();
+        Ok(())
+    }
+
+    fn get_transaction(
+        &self,
+        ledger_type: LedgerType,
+        seq_no: usize,
+    ) -> VdrResult<Option<Vec<u8>>> {
+        let ledgers = self.ledgers.lock().unwrap();
+        Ok(ledgers
+            .get(&ledger_type.to_id())
+            .and_then(|(txns, _)| txns.get(seq_no.checked_sub(1)?))
+            .cloned())
+    }
+}
+
+/// A `LedgerStore` which writes each ledger to a file of JSON transactions
+///
+/// The size and root hash of the verified ledger are recorded separately, so
+/// that any transactions written after the last update are ignored on load.
+/// The file offset of each recorded transaction is indexed on first access,
+/// after which transactions are only appended to the file.
+#[derive(Debug, Clone)]
+pub struct FilesystemLedgerStore {
+    store_dir: PathBuf,
+    offsets: Arc<Mutex<HashMap<i32, Vec<u64>>>>,
+}
+
+impl FilesystemLedgerStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            store_dir: path.into(),
+            offsets: Arc::default(),
+        }
+    }
+
+    fn ledger_path(&self, ledger_type: LedgerType, ext: &str) -> PathBuf {
+        self.store_dir
+            .join(format!("ledger-{}.{}", ledger_type.to_id(), ext))
+    }
+
+    /// Read the recorded size and root hash of a ledger
+    fn read_root(&self, ledger_type: LedgerType) -> VdrResult<Option<(usize, Vec<u8>)>> {
+        let root = match fs::read_to_string(self.ledger_path(ledger_type, "root")) {
+            Ok(root) => root,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        root.trim()
+            .split_once(' ')
+            .and_then(|(size, root_hash)| {
+                Some((size.parse::<usize>().ok()?, base58::decode(root_hash).ok()?))
+            })
+            .map(Some)
+            .ok_or_else(|| input_err("Invalid ledger store root"))
+    }
+
+    /// Read the recorded transactions of a ledger along with the end offset of each
+    fn read_txns(&self, ledger_type: LedgerType, size: usize) -> VdrResult<(String, Vec<u64>)> {
+        if size == 0 {
+            return Ok((String::new(), vec![]));
+        }
+        let mut txns = fs::read_to_string(self.ledger_path(ledger_type, "txns"))?;
+        let mut offsets = Vec::with_capacity(size);
+        let mut pos = 0;
+        for line in txns.split_inclusive('\n').take(size) {
+            if !line.ends_with('\n') {
+                break;
+            }
+            pos += line.len();
+            offsets.push(pos as u64);
+        }
+        if offsets.len() != size {
+            return Err(input_err("Missing transactions in ledger store"));
+        }
+        txns.truncate(pos);
+        Ok((txns, offsets))
+    }
+
+    /// Access the transaction offsets of a ledger, indexing the stored file if necessary
+    fn with_offsets<R>(
+        &self,
+        ledger_type: LedgerType,
+        f: impl FnOnce(&mut Vec<u64>) -> VdrResult<R>,
+    ) -> VdrResult<R> {
+        let mut index = self.offsets.lock().unwrap();
+        let offsets = match index.entry(ledger_type.to_id()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let size = self.read_root(ledger_type)?.map_or(0, |(size, _)| size);
+                entry.insert(self.read_txns(ledger_type, size)?.1)
+            }
+        };
+        f(offsets)
+    }
+}
+
+impl LedgerStore for FilesystemLedgerStore {
+    fn load(&self, ledger_type: LedgerType) -> VdrResult<Option<StoredLedger>> {
+        let mut index = self.offsets.lock().unwrap();
+        let Some((size, root_hash)) = self.read_root(ledger_type)? else {
+            index.insert(ledger_type.to_id(), vec![]);
+            return Ok(None);
+        };
+        let (txns, offsets) = self.read_txns(ledger_type, size)?;
+        let txns = PoolTransactions::from_json_transactions(txns.lines())?;
+        index.insert(ledger_type.to_id(), offsets);
+        Ok(Some((txns.iter().cloned().collect(), root_hash)))
+    }
+
+    fn append(&self, ledger_type: LedgerType, txns: &[Vec<u8>], root_hash: &[u8]) -> VdrResult<()> {
+        fs::create_dir_all(&self.store_dir)?;
+        let txns_path = self.ledger_path(ledger_type, "txns");
+        let json_txns = PoolTransactions::from(txns.to_vec()).encode_json()?;
+        self.with_offsets(ledger_type, |offsets| {
+            let mut pos = offsets.last().copied().unwrap_or(0);
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&txns_path)?;
+            // drop any transactions written after the last recorded root
+            file.set_len(pos)?;
+            let mut writer = BufWriter::new(file);
+            writer.seek(SeekFrom::Start(pos))?;
+            let mut new_offsets = Vec::with_capacity(json_txns.len());
+            for txn in json_txns {
+                writer.write_all(txn.as_bytes())?;
+                writer.write_all(b"\n")?;
+                pos += txn.len() as u64 + 1;
+                new_offsets.push(pos);
+            }
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+
+            let root_path = self.ledger_path(ledger_type, "root");
+            let temp_path = self.store_dir.join(format!("{:020}.tmp", random::<u64>()));
+            let root = format!(
+                "{} {}",
+                offsets.len() + new_offsets.len(),
+                base58::encode(root_hash)
+            );
+            fs::write(&temp_path, root.as_bytes())?;
+            fs::rename(&temp_path, &root_path)?;
+            offsets.extend(new_offsets);
+            Ok(())
+        })
+    }
+
+    fn get_transaction(
+        &self,
+        ledger_type: LedgerType,
+        seq_no: usize,
+    ) -> VdrResult<Option<Vec<u8>>> {
+        let range = self.with_offsets(ledger_type, |offsets| {
+            if seq_no == 0 || seq_no > offsets.len() {
+                return Ok(None);
+            }
+            let start = if seq_no > 1 { offsets[seq_no - 2] } else { 0 };
+            Ok(Some((start, offsets[seq_no - 1])))
+        })?;
+        let Some((start, end)) = range else {
+            return Ok(None);
+        };
+        let mut file = File::open(self.ledger_path(ledger_type, "txns"))?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut line = vec![0u8; (end - start) as usize];
+        file.read_exact(&mut line)?;
+        let line =
+            std::str::from_utf8(&line).with_input_err("Invalid transaction in ledger store")?;
+        let txns = PoolTransactions::from_json_transactions([line.trim_end()])?;
+        let txn = txns.iter().next().cloned();
+        Ok(txn)
+    }
+}
+
+/// A local replica of a ledger, built from transactions verified against the pool
+///
+/// Use `perform_ledger_catchup` to fetch new transactions from the validator pool.
+pub struct LedgerMirror {
+    ledger_type: LedgerType,
+    merkle_tree: MerkleTree,
+    store: Arc<dyn LedgerStore>,
+}
+
+impl LedgerMirror {
+    /// Open a ledger mirror, checking any stored transactions against the recorded root hash
+    pub fn open(ledger_type: LedgerType, store: Arc<dyn LedgerStore>) -> VdrResult<Self> {
+        let merkle_tree = match store.load(ledger_type)? {
+            Some((txns, root_hash)) => {
+                let merkle_tree = MerkleTree::from_vec(txns)?;
+                if merkle_tree.root_hash() != &root_hash {
+                    return Err(input_err(
+                        "Merkle tree root does not match for stored transactions",
+                    ));
+                }
+                merkle_tree
+            }
+            None => MerkleTree::default(),
+        };
+        Ok(Self {
+            ledger_type,
+            merkle_tree,
+            store,
+        })
+    }
+
+    /// Get the ledger type of the mirror
+    pub fn ledger_type(&self) -> LedgerType {
+        self.ledger_type
+    }
+
+    /// Get the merkle tree of the verified transactions
+    pub fn merkle_tree(&self) -> &MerkleTree {
+        &self.merkle_tree
+    }
+
+    /// Get the number of verified transactions
+    pub fn len(&self) -> usize {
+        self.merkle_tree.count()
+    }
+
+    /// Check if there are no verified transactions
+    pub fn is_empty(&self) -> bool {
+        self.merkle_tree.count() == 0
+    }
+
+    /// Get the base58-encoded merkle tree root hash
+    pub fn root_hash_base58(&self) -> String {
+        base58::encode(self.merkle_tree.root_hash())
+    }
+
+    /// Fetch a verified transaction by its sequence number
+    pub fn get_transaction(&self, seq_no: usize) -> VdrResult<Option<SJsonValue>> {
+        if seq_no == 0 || seq_no > self.len() {
+            return Ok(None);
+        }
+        self.store
+            .get_transaction(self.ledger_type, seq_no)?
+            .map(|txn| {
+                rmp_serde::from_slice(&txn).with_input_err("Error decoding stored transaction")
+            })
+            .transpose()
+    }
+
+    /// Append transactions which have been verified against the pool
+    pub(crate) fn extend(&mut self, txns: Vec<Vec<u8>>) -> VdrResult<()> {
+        let size = self.len();
+        for txn in &txns {
+            self.merkle_tree.append(txn.clone())?;
+        }
+        if let Err(err) = self
+            .store
+            .append(self.ledger_type, &txns, self.merkle_tree.root_hash())
+        {
+            // restore the tree for the transactions which were recorded
+            self.merkle_tree =
+                MerkleTree::from_vec(self.merkle_tree.iter().take(size).cloned().collect())?;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for LedgerMirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LedgerMirror")
+            .field("ledger_type", &self.ledger_type)
+            .field("size", &self.len())
+            .field("root_hash", &self.root_hash_base58())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use super::*;
+
+    fn _txns(count: usize) -> Vec<Vec<u8>> {
+        (1..=count)
+            .map(|seq_no| rmp_serde::to_vec(&json!({"txnMetadata": {"seqNo": seq_no}})).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn fs_store_ignores_unrecorded_transactions() {
+        let temp_dir = temp_dir().join(format!("vdr-test-{:020}", random::<u64>()));
+        let store = Arc::new(FilesystemLedgerStore::new(&temp_dir));
+        let mut mirror = LedgerMirror::open(LedgerType::DOMAIN, store.clone()).unwrap();
+        assert!(mirror.is_empty());
+        let txns = _txns(5);
+        mirror.extend(txns[..3].to_vec()).unwrap();
+        mirror.extend(txns[3..].to_vec()).unwrap();
+
+        // simulate an interrupted write
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(store.ledger_path(LedgerType::DOMAIN, "txns"))
+            .unwrap();
+        file.write_all(b"{\"partial\"").unwrap();
+
+        let mut mirror = LedgerMirror::open(LedgerType::DOMAIN, store.clone()).unwrap();
+        assert_eq!(mirror.len(), 5);
+        assert_eq!(
+            mirror.get_transaction(4).unwrap().unwrap()["txnMetadata"]["seqNo"],
+            SJsonValue::from(4)
+        );
+        mirror.extend(_txns(6)[5..].to_vec()).unwrap();
+        let mirror = LedgerMirror::open(LedgerType::DOMAIN, store).unwrap();
+        assert_eq!(mirror.len(), 6);
+        assert_eq!(
+            mirror.merkle_tree().root_hash(),
+            MerkleTree::from_vec(_txns(6)).unwrap().root_hash()
+        );
+
+        // the transaction index is rebuilt for a new store instance
+        let store = FilesystemLedgerStore::new(&temp_dir);
+        for (seq_no, txn) in _txns(6).into_iter().enumerate() {
+            assert_eq!(
+                store
+                    .get_transaction(LedgerType::DOMAIN, seq_no + 1)
+                    .unwrap(),
+                Some(txn)
+            );
+        }
+        assert_eq!(store.get_transaction(LedgerType::DOMAIN, 0).unwrap(), None);
+        assert_eq!(store.get_transaction(LedgerType::DOMAIN, 7).unwrap(), None);
+        store
+            .append(LedgerType::DOMAIN, &_txns(7)[6..], b"root")
+            .unwrap();
+        assert_eq!(
+            store.get_transaction(LedgerType::DOMAIN, 7).unwrap(),
+            Some(_txns(7).pop().unwrap())
+        );
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -8,6 +8,10 @@ pub(crate) mod handlers;
 pub mod helpers;
 /// General verifier pool management
 mod manager;
+/// Local replicas of ledgers verified against the verifier pool
+mod mirror;
+/// Rolling statistics for the validator nodes of a pool
+mod monitor;
 /// Pool networker traits and implementations
 pub mod networker;
 /// Data types and traits for handling pending verifier pool requests
@@ -20,8 +24,13 @@ pub use {
     self::builder::PoolBuilder,
     self::genesis::{FilesystemCache, InMemoryCache, PoolTransactions, PoolTransactionsCache},
     self::manager::{LocalPool, Pool, PoolImpl, SharedPool},
+    self::mirror::{
+        FilesystemLedgerStore, InMemoryLedgerStore, LedgerMirror, LedgerStore, StoredLedger,
+    },
+    self::monitor::{NodeStats, PoolMonitor, PoolStats, MONITOR_EWMA_ALPHA, MONITOR_WINDOW_SIZE},
     self::requests::{
-        new_request_id, PoolRequest, PoolRequestImpl, PreparedRequest, RequestMethod,
+        new_request_id, Ed25519Signer, PoolRequest, PoolRequestImpl, PreparedRequest,
+        RequestMethod, RequestSigner,
     },
     self::runner::{PoolRunner, PoolRunnerStatus},
     self::types::{
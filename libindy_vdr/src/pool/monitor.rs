@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::types::Message;
+
+/// The number of recent request outcomes retained for each node
+pub const MONITOR_WINDOW_SIZE: usize = 100;
+
+/// The smoothing factor applied to the moving average of node response times
+pub const MONITOR_EWMA_ALPHA: f32 = 0.2;
+
+/// Added to smoothed response times so that very fast nodes do not dominate selection
+const ADAPTIVE_LATENCY_FLOOR: f32 = 0.05;
+
+/// The minimum weight factor applied to nodes which are timing out
+const ADAPTIVE_MIN_FACTOR: f32 = 0.02;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NodeOutcome {
+    Reply(f32),
+    Nack(f32),
+    Timeout,
+}
+
+#[derive(Debug, Default)]
+struct NodeHistory {
+    outcomes: VecDeque<NodeOutcome>,
+    requests: u64,
+    replies: u64,
+    nacks: u64,
+    rejects: u64,
+    timeouts: u64,
+    latency_ewma: Option<f32>,
+    last_seen: Option<SystemTime>,
+}
+
+impl NodeHistory {
+    fn push(&mut self, outcome: NodeOutcome) {
+        if self.outcomes.len() == MONITOR_WINDOW_SIZE {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(outcome);
+        if let NodeOutcome::Reply(latency) | NodeOutcome::Nack(latency) = outcome {
+            self.latency_ewma = Some(match self.latency_ewma {
+                Some(avg) => avg + MONITOR_EWMA_ALPHA * (latency - avg),
+                None => latency,
+            });
+        }
+    }
+
+    fn timeout_rate(&self) -> f32 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        let timeouts = self
+            .outcomes
+            .iter()
+            .filter(|outcome| **outcome == NodeOutcome::Timeout)
+            .count();
+        timeouts as f32 / self.outcomes.len() as f32
+    }
+
+    fn stats(&self) -> NodeStats {
+        let mut latencies = self
+            .outcomes
+            .iter()
+            .filter_map(|outcome| match outcome {
+                NodeOutcome::Reply(latency) | NodeOutcome::Nack(latency) => Some(*latency),
+                NodeOutcome::Timeout => None,
+            })
+            .collect::<Vec<_>>();
+        latencies.sort_by(f32::total_cmp);
+        let count = self.outcomes.len();
+        let rate = |matched: usize| {
+            if count == 0 {
+                0.0
+            } else {
+                matched as f32 / count as f32
+            }
+        };
+        let nacks = self
+            .outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome, NodeOutcome::Nack(_)))
+            .count();
+        NodeStats {
+            requests: self.requests,
+            replies: self.replies,
+            nacks: self.nacks,
+            rejects: self.rejects,
+            timeouts: self.timeouts,
+            latency_p50: _percentile(&latencies, 0.5),
+            latency_p95: _percentile(&latencies, 0.95),
+            latency_ewma: self.latency_ewma,
+            timeout_rate: self.timeout_rate(),
+            nack_rate: rate(nacks),
+            last_seen: self
+                .last_seen
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|time| time.as_secs()),
+        }
+    }
+}
+
+/// Statistics collected for a single validator node
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeStats {
+    /// The number of requests dispatched to the node
+    pub requests: u64,
+    /// The number of replies received from the node
+    pub replies: u64,
+    /// The number of requests refused with a `REQNACK`
+    pub nacks: u64,
+    /// The number of requests refused with a `REJECT`
+    pub rejects: u64,
+    /// The number of requests which timed out
+    pub timeouts: u64,
+    /// The median response time in seconds over recent requests
+    pub latency_p50: Option<f32>,
+    /// The 95th percentile response time in seconds over recent requests
+    pub latency_p95: Option<f32>,
+    /// The exponentially weighted moving average of response times in seconds
+    pub latency_ewma: Option<f32>,
+    /// The fraction of recent requests which timed out
+    pub timeout_rate: f32,
+    /// The fraction of recent requests refused with a `REQNACK` or `REJECT`
+    pub nack_rate: f32,
+    /// The time of the last message received from the node, in seconds since the epoch
+    pub last_seen: Option<u64>,
+}
+
+/// Statistics collected for the nodes of a validator pool, indexed by node alias
+pub type PoolStats = BTreeMap<String, NodeStats>;
+
+/// Collects rolling statistics for each validator node from the requests handled by a pool
+///
+/// Rates and latency percentiles are calculated over the last `MONITOR_WINDOW_SIZE`
+/// outcomes for each node, while the counters cover the lifetime of the monitor.
+/// Clones of a monitor share the same statistics, so that a monitor may be carried
+/// over to a new pool instance after a refresh.
+#[derive(Clone, Debug, Default)]
+pub struct PoolMonitor {
+    nodes: Arc<Mutex<HashMap<String, NodeHistory>>>,
+}
+
+impl PoolMonitor {
+    /// Create a new, empty `PoolMonitor` instance
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the statistics for a single node
+    pub fn node_stats(&self, node_alias: &str) -> Option<NodeStats> {
+        let nodes = self.nodes.lock().unwrap();
+        nodes.get(node_alias).map(NodeHistory::stats)
+    }
+
+    /// Get the statistics for all nodes which have handled a request
+    pub fn stats(&self) -> PoolStats {
+        let nodes = self.nodes.lock().unwrap();
+        nodes
+            .iter()
+            .map(|(alias, history)| (alias.clone(), history.stats()))
+            .collect()
+    }
+
+    /// Discard all collected statistics
+    pub fn reset(&self) {
+        self.nodes.lock().unwrap().clear();
+    }
+
+    /// Calculate relative selection weights for a set of nodes from their observed performance
+    ///
+    /// Each node is weighted by the inverse of its smoothed response time, scaled down
+    /// by its recent timeout rate. Nodes without any recorded responses receive the
+    /// highest response-time weight among the other nodes, so that they continue to
+    /// be sampled.
+    pub fn adaptive_weights<'a>(
+        &self,
+        node_aliases: impl IntoIterator<Item = &'a String>,
+    ) -> HashMap<String, f32> {
+        let nodes = self.nodes.lock().unwrap();
+        let found = node_aliases
+            .into_iter()
+            .map(|alias| {
+                let history = nodes.get(alias);
+                let latency_weight = history
+                    .and_then(|h| h.latency_ewma)
+                    .map(|latency| 1.0 / (latency.max(0.0) + ADAPTIVE_LATENCY_FLOOR));
+                let factor = history
+                    .map(|h| (1.0 - h.timeout_rate()).max(ADAPTIVE_MIN_FACTOR))
+                    .unwrap_or(1.0);
+                (alias.clone(), latency_weight, factor)
+            })
+            .collect::<Vec<_>>();
+        let default_weight = found
+            .iter()
+            .filter_map(|(_, weight, _)| *weight)
+            .fold(None, |max: Option<f32>, weight| {
+                Some(max.map_or(weight, |max| max.max(weight)))
+            })
+            .unwrap_or(1.0);
+        found
+            .into_iter()
+            .map(|(alias, weight, factor)| (alias, weight.unwrap_or(default_weight) * factor))
+            .collect()
+    }
+
+    pub(crate) fn record_sent(&self, node_alias: &str) {
+        let mut nodes = self.nodes.lock().unwrap();
+        nodes.entry(node_alias.to_string()).or_default().requests += 1;
+    }
+
+    pub(crate) fn record_received(
+        &self,
+        node_alias: &str,
+        message: &Message,
+        latency: Option<f32>,
+        when: SystemTime,
+    ) {
+        let mut nodes = self.nodes.lock().unwrap();
+        let history = nodes.entry(node_alias.to_string()).or_default();
+        history.last_seen.replace(when);
+        let latency = latency.unwrap_or(0.0);
+        match message {
+            Message::ReqACK(_) | Message::Ping | Message::Pong => {}
+            Message::ReqNACK(_) => {
+                history.nacks += 1;
+                history.push(NodeOutcome::Nack(latency));
+            }
+            Message::Reject(_) => {
+                history.rejects += 1;
+                history.push(NodeOutcome::Nack(latency));
+            }
+            _ => {
+                history.replies += 1;
+                history.push(NodeOutcome::Reply(latency));
+            }
+        }
+    }
+
+    pub(crate) fn record_timeout(&self, node_alias: &str) {
+        let mut nodes = self.nodes.lock().unwrap();
+        let history = nodes.entry(node_alias.to_string()).or_default();
+        history.timeouts += 1;
+        history.push(NodeOutcome::Timeout);
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn _percentile(sorted: &[f32], pct: f32) -> Option<f32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len() as f32).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn _message(op: &str) -> Message {
+        Message::from_raw_str(&json!({"op": op, "reqId": 1, "reason": "test"}).to_string()).unwrap()
+    }
+
+    #[test]
+    fn monitor_tracks_rolling_stats() {
+        let monitor = PoolMonitor::new();
+        let now = SystemTime::now();
+        let reply = Message::from_raw_str(r#"{"op": "REPLY", "result": {}}"#).unwrap();
+        for idx in 1..=MONITOR_WINDOW_SIZE + 20 {
+            monitor.record_sent("Node1");
+            monitor.record_received("Node1", &_message("REQACK"), None, now);
+            monitor.record_received("Node1", &reply, Some(idx as f32), now);
+        }
+        monitor.record_sent("Node2");
+        monitor.record_received("Node2", &_message("REQNACK"), Some(0.5), now);
+        monitor.record_sent("Node2");
+        monitor.record_received("Node2", &_message("REJECT"), Some(1.5), now);
+        monitor.record_sent("Node2");
+        monitor.record_timeout("Node2");
+        monitor.record_sent("Node2");
+        monitor.record_timeout("Node2");
+
+        let node1 = monitor.node_stats("Node1").unwrap();
+        assert_eq!(node1.requests, MONITOR_WINDOW_SIZE as u64 + 20);
+        assert_eq!(node1.replies, MONITOR_WINDOW_SIZE as u64 + 20);
+        assert_eq!(node1.latency_p50, Some(70.0));
+        assert_eq!(node1.latency_p95, Some(115.0));
+        assert_eq!(node1.timeout_rate, 0.0);
+        assert_eq!(
+            node1.last_seen,
+            Some(now.duration_since(UNIX_EPOCH).unwrap().as_secs())
+        );
+
+        let stats = monitor.stats();
+        let node2 = &stats["Node2"];
+        assert_eq!((node2.nacks, node2.rejects, node2.timeouts), (1, 1, 2));
+        assert_eq!((node2.nack_rate, node2.timeout_rate), (0.5, 0.5));
+        assert_eq!(node2.latency_p50, Some(0.5));
+
+        monitor.reset();
+        assert!(monitor.stats().is_empty());
+    }
+
+    #[test]
+    fn adaptive_weights_prefer_responsive_nodes() {
+        let monitor = PoolMonitor::new();
+        let now = SystemTime::now();
+        let reply = Message::from_raw_str(r#"{"op": "REPLY", "result": {}}"#).unwrap();
+        for _ in 0..10 {
+            monitor.record_received("Fast", &reply, Some(0.05), now);
+            monitor.record_received("Slow", &reply, Some(2.0), now);
+            monitor.record_timeout("Down");
+        }
+        let aliases = ["Fast", "Slow", "Down", "New"].map(String::from);
+        let weights = monitor.adaptive_weights(&aliases);
+        let approx = |value: f32, expected: f32| (value - expected).abs() < 1e-4;
+        assert!(approx(weights["Fast"], 10.0));
+        assert!(weights["Slow"] < 0.5);
+        assert!(approx(weights["Down"], 10.0 * ADAPTIVE_MIN_FACTOR));
+        assert!(approx(weights["New"], 10.0));
+
+        monitor.record_received("Slow", &reply, Some(1.0), now);
+        let latency = monitor.node_stats("Slow").unwrap().latency_ewma.unwrap();
+        assert!(approx(latency, 1.8));
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::hash_map::{Entry, HashMap};
+use std::os::unix::io::{BorrowedFd, OwnedFd};
+use std::time::Duration;
+
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::future::{self, FutureExt};
+use futures_util::select;
+use futures_util::stream::StreamExt;
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+use tokio::runtime::Handle;
+
+use crate::common::error::prelude::*;
+use crate::config::PoolConfig;
+
+use super::types::Verifiers;
+use super::zmq::{_get_remotes, ZMQConnectionHandle, ZMQThread};
+use super::{Networker, NetworkerEvent, NetworkerFactory};
+
+/// Asynchronous ZeroMQ `NetworkerFactory` implementation, available on unix platforms
+///
+/// Rather than starting a dedicated worker thread, each networker spawns a task
+/// on a tokio runtime which polls the node sockets. The runtime must have the IO
+/// and time drivers enabled. By default the runtime active when the networker
+/// is created is used.
+#[derive(Clone, Debug, Default)]
+pub struct AsyncNetworkerFactory {
+    handle: Option<Handle>,
+}
+
+impl AsyncNetworkerFactory {
+    /// Create a new factory using the current tokio runtime
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new factory which spawns networker tasks on a specific runtime
+    pub fn with_handle(handle: Handle) -> Self {
+        Self {
+            handle: Some(handle),
+        }
+    }
+}
+
+impl NetworkerFactory for AsyncNetworkerFactory {
+    type Output = AsyncNetworker;
+    fn make_networker(
+        &self,
+        config: PoolConfig,
+        verifiers: &Verifiers,
+    ) -> VdrResult<AsyncNetworker> {
+        let handle = match self.handle.as_ref() {
+            Some(handle) => handle.clone(),
+            None => Handle::try_current()
+                .with_err_msg(VdrErrorKind::Resource, "No tokio runtime available")?,
+        };
+        let remotes = _get_remotes(verifiers);
+        let (evt_send, evt_recv) = unbounded::<NetworkerEvent>();
+        let zmq_thread = ZMQThread::new(config, zmq::Context::new(), remotes);
+        handle.spawn(async move {
+            let mut worker = AsyncWorker::new(zmq_thread, evt_recv);
+            if let Err(err) = worker.work().await {
+                warn!("Async networker exited with error: {}", err)
+            } else {
+                trace!("Async networker exited");
+            }
+        });
+        Ok(AsyncNetworker { evt_send })
+    }
+}
+
+/// Asynchronous ZeroMQ `Networker` implementation
+///
+/// The networker task exits once all clones of the instance have been dropped.
+#[derive(Clone, Debug)]
+pub struct AsyncNetworker {
+    evt_send: UnboundedSender<NetworkerEvent>,
+}
+
+impl Networker for AsyncNetworker {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        self.evt_send
+            .unbounded_send(event)
+            .map_err(|_| err_msg(VdrErrorKind::Resource, "Error sending networker event"))
+    }
+}
+
+struct AsyncWorker {
+    zmq_thread: ZMQThread,
+    evt_recv: UnboundedReceiver<NetworkerEvent>,
+    socket_fds: HashMap<(ZMQConnectionHandle, usize), AsyncFd<OwnedFd>>,
+}
+
+impl AsyncWorker {
+    fn new(zmq_thread: ZMQThread, evt_recv: UnboundedReceiver<NetworkerEvent>) -> Self {
+        Self {
+            zmq_thread,
+            evt_recv,
+            socket_fds: HashMap::new(),
+        }
+    }
+
+    async fn work(&mut self) -> Result<(), String> {
+        loop {
+            loop {
+                match self.evt_recv.next().now_or_never() {
+                    Some(Some(event)) => self.zmq_thread.process_networker_event(event),
+                    Some(None) => return Ok(()),
+                    None => break,
+                }
+            }
+            let (events, timeout) = self.zmq_thread.poll_connections_now()?;
+            if !events.is_empty() {
+                trace!("Got {} events", events.len());
+                for (conn_id, event) in events {
+                    self.zmq_thread.process_event(conn_id, event)
+                }
+                continue;
+            }
+            self.update_socket_fds()?;
+
+            // the socket file descriptors are edge-triggered, so any pending messages
+            // must have been consumed by the preceding poll before waiting
+            let mut readable = _wait_readable(&self.socket_fds).boxed().fuse();
+            let mut expired = _wait_timeout(timeout).boxed().fuse();
+            select! {
+                recv_evt = self.evt_recv.next() => {
+                    match recv_evt {
+                        Some(event) => self.zmq_thread.process_networker_event(event),
+                        None => return Ok(()),
+                    }
+                }
+                _ = readable => (),
+                _ = expired => (),
+            }
+        }
+    }
+
+    fn update_socket_fds(&mut self) -> Result<(), String> {
+        let fds = self.zmq_thread.get_socket_fds();
+        self.socket_fds
+            .retain(|key, _| fds.iter().any(|(fd_key, _)| fd_key == key));
+        for (key, fd) in fds {
+            if let Entry::Vacant(entry) = self.socket_fds.entry(key) {
+                // the descriptor is duplicated so that it remains open for as long
+                // as it is registered, independent of the lifetime of the socket
+                let fd = unsafe { BorrowedFd::borrow_raw(fd) }
+                    .try_clone_to_owned()
+                    .map_err(|err| format!("Error duplicating ZMQ socket descriptor: {}", err))?;
+                // `AsyncFd::register_with_interest`, which replaces this constructor, is
+                // only available in tokio releases requiring a newer compiler than the
+                // minimum supported Rust version. The deprecation concerns raw descriptors
+                // which may be closed while registered, while the `OwnedFd` is owned by
+                // the `AsyncFd` and stays open until it is dropped.
+                #[allow(deprecated)]
+                let async_fd = AsyncFd::with_interest(fd, Interest::READABLE)
+                    .map_err(|err| format!("Error registering ZMQ socket: {}", err))?;
+                entry.insert(async_fd);
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn _wait_readable(socket_fds: &HashMap<(ZMQConnectionHandle, usize), AsyncFd<OwnedFd>>) {
+    if socket_fds.is_empty() {
+        return future::pending().await;
+    }
+    let (ready, _, _) =
+        future::select_all(socket_fds.values().map(|fd| fd.readable().boxed())).await;
+    if let Ok(mut guard) = ready {
+        guard.clear_ready();
+    }
+}
+
+async fn _wait_timeout(timeout: i64) {
+    if timeout == i64::MAX {
+        future::pending().await
+    } else {
+        tokio::time::sleep(Duration::from_millis(timeout.max(0) as u64)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::super::types::{Message, RequestHandle, VerifierInfo};
+    use super::super::RequestExtEvent;
+    use super::*;
+    use crate::common::handle::ResourceHandle;
+
+    const NODE_ALIAS: &str = "Node1";
+    const REQ_ID: &str = "1";
+
+    fn _start_node() -> (Verifiers, thread::JoinHandle<()>) {
+        let ctx = zmq::Context::new();
+        let key_pair = zmq::CurveKeyPair::new().unwrap();
+        let socket = ctx.socket(zmq::SocketType::ROUTER).unwrap();
+        socket.set_curve_server(true).unwrap();
+        socket.set_curve_secretkey(&key_pair.secret_key).unwrap();
+        socket.set_linger(0).unwrap();
+        socket.bind("tcp://127.0.0.1:*").unwrap();
+        let client_addr = socket.get_last_endpoint().unwrap().unwrap();
+        let node = thread::spawn(move || {
+            let msg = socket.recv_multipart(0).unwrap();
+            assert_eq!(msg[1], b"request");
+            let reply = json!({"op": "REQACK", "reqId": 1}).to_string();
+            socket
+                .send_multipart([msg[0].as_slice(), reply.as_bytes()], 0)
+                .unwrap();
+        });
+        let verifiers = Verifiers::from_iter([(
+            NODE_ALIAS.to_string(),
+            VerifierInfo {
+                client_addr,
+                node_addr: String::new(),
+                public_key: String::new(),
+                enc_key: key_pair.public_key.to_vec(),
+                bls_key: None,
+            },
+        )]);
+        (verifiers, node)
+    }
+
+    #[test]
+    fn async_networker_dispatch_works() {
+        let (verifiers, node) = _start_node();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let networker = AsyncNetworkerFactory::new()
+                .make_networker(PoolConfig::default(), &verifiers)
+                .unwrap();
+            let handle = RequestHandle::next();
+            let (sender, mut events) = unbounded();
+            networker
+                .send(NetworkerEvent::NewRequest(
+                    handle,
+                    REQ_ID.to_string(),
+                    "request".to_string(),
+                    sender,
+                ))
+                .unwrap();
+            assert!(matches!(events.next().await, Some(RequestExtEvent::Init)));
+            networker
+                .send(NetworkerEvent::Dispatch(
+                    handle,
+                    vec![NODE_ALIAS.to_string()],
+                    10,
+                ))
+                .unwrap();
+            assert!(matches!(
+                events.next().await,
+                Some(RequestExtEvent::Sent(alias, _)) if alias == NODE_ALIAS
+            ));
+            assert!(matches!(
+                events.next().await,
+                Some(RequestExtEvent::Received(alias, _, Message::ReqACK(_), _)) if alias == NODE_ALIAS
+            ));
+            networker
+                .send(NetworkerEvent::FinishRequest(handle))
+                .unwrap();
+        });
+        node.join().unwrap();
+    }
+}
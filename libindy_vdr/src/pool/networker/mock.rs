@@ -0,0 +1,1416 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::iter::FromIterator;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use futures_channel::mpsc::UnboundedSender;
+use indy_blssignatures::{Bls, MultiSignature, ProofOfPossession, SignKey, VerKey as BlsVerKey};
+use serde_json::Value as SJsonValue;
+use sha2::{Digest, Sha256};
+
+use crate::common::error::prelude::*;
+use crate::config::constants::DEFAULT_GENERATOR;
+use crate::config::PoolConfig;
+use crate::ledger::constants;
+use crate::pool::PoolTransactions;
+use crate::state_proof::{
+    build_single_key_proof, get_cur_time, parse_key_from_request_for_builtin_sp,
+    parse_reply_for_proof_value,
+};
+use crate::utils::base58;
+use crate::utils::keys::PrivateKey;
+
+use super::types::{LedgerType, Message, ProtocolVersion, Verifiers};
+use super::{Networker, NetworkerEvent, NetworkerFactory, RequestExtEvent, RequestHandle};
+
+/// Transaction types recorded on the config ledger
+const CONFIG_LEDGER_TXNS: [&str; 9] = [
+    constants::TXN_AUTHR_AGRMT,
+    constants::TXN_AUTHR_AGRMT_AML,
+    constants::DISABLE_ALL_TXN_AUTHR_AGRMTS,
+    constants::LEDGERS_FREEZE,
+    constants::POOL_UPGRADE,
+    constants::POOL_CONFIG,
+    constants::AUTH_RULE,
+    constants::AUTH_RULES,
+    constants::FLAG,
+];
+
+/// Read requests with state proofs rooted in the config ledger state
+const CONFIG_LEDGER_READS: [&str; 4] = [
+    constants::GET_TXN_AUTHR_AGRMT,
+    constants::GET_TXN_AUTHR_AGRMT_AML,
+    constants::GET_AUTH_RULE,
+    constants::GET_FLAG,
+];
+
+/// Scripted behaviour of a single mock validator node
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum MockNodeBehaviour {
+    /// Acknowledge and answer requests from the ledger contents
+    #[default]
+    Normal,
+    /// Answer requests normally after the given delay
+    Delay(Duration),
+    /// Never answer, so that requests to this node time out
+    Timeout,
+    /// Acknowledge requests and reply with a malformed message
+    Garbage,
+    /// Reply with tampered data which does not match the state proof
+    Divergent,
+    /// Refuse requests with a `REQNACK` and the given reason
+    Nack(String),
+    /// Acknowledge requests and refuse them with a `REJECT` and the given reason
+    Reject(String),
+}
+
+/// An in-memory validator pool shared by `MockNetworker` instances
+///
+/// The ledger holds a pool ledger with generated validator nodes, along with
+/// domain and config ledgers populated by submitted write requests. Read replies
+/// carry state proofs multi-signed by the BLS keys of the mock nodes.
+///
+/// Only available with the `mock` feature, for use in tests.
+#[derive(Clone)]
+pub struct MockLedger {
+    inner: Arc<Mutex<MockLedgerState>>,
+}
+
+impl MockLedger {
+    /// Create a new mock ledger with `node_count` validator nodes named `Node1`, `Node2`, ...
+    pub fn new(node_count: usize) -> VdrResult<Self> {
+        if node_count == 0 {
+            return Err(input_err("Mock ledger requires at least one node"));
+        }
+        let mut state = MockLedgerState::new();
+        for idx in 1..=node_count {
+            state.add_node(format!("Node{}", idx))?;
+        }
+        Ok(Self {
+            inner: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Add a new validator node to the pool ledger
+    pub fn add_node(&self, alias: &str) -> VdrResult<()> {
+        self.inner.lock().unwrap().add_node(alias.to_string())
+    }
+
+    /// Get the current pool ledger transactions, for use as genesis transactions
+    pub fn pool_transactions(&self) -> VdrResult<PoolTransactions> {
+        let state = self.inner.lock().unwrap();
+        PoolTransactions::from_json_transactions(
+            state.ledgers[LedgerType::POOL as usize]
+                .txns
+                .iter()
+                .map(SJsonValue::to_string),
+        )
+    }
+
+    /// Get the aliases of the mock validator nodes
+    pub fn node_aliases(&self) -> Vec<String> {
+        self.inner.lock().unwrap().nodes.keys().cloned().collect()
+    }
+
+    /// Get the number of transactions recorded on a ledger
+    pub fn ledger_size(&self, ledger_type: LedgerType) -> usize {
+        self.inner.lock().unwrap().ledgers[ledger_type as usize]
+            .txns
+            .len()
+    }
+
+    /// Get the base58-encoded merkle root hash of a ledger
+    pub fn root_hash(&self, ledger_type: LedgerType) -> String {
+        let state = self.inner.lock().unwrap();
+        let ledger = &state.ledgers[ledger_type as usize];
+        ledger.root_hash(ledger.txns.len())
+    }
+
+    /// Set the scripted behaviour of a mock node
+    pub fn set_behaviour(&self, alias: &str, behaviour: MockNodeBehaviour) -> VdrResult<()> {
+        let mut state = self.inner.lock().unwrap();
+        let node = state
+            .nodes
+            .get_mut(alias)
+            .ok_or_else(|| input_err(format!("Unknown mock node: {}", alias)))?;
+        node.behaviour = behaviour;
+        Ok(())
+    }
+
+    /// Restore the normal behaviour of all mock nodes
+    pub fn reset_behaviours(&self) {
+        let mut state = self.inner.lock().unwrap();
+        for node in state.nodes.values_mut() {
+            node.behaviour = MockNodeBehaviour::Normal;
+        }
+    }
+
+    /// Get the number of messages dispatched to a mock node
+    pub fn requests_received(&self, alias: &str) -> usize {
+        self.inner
+            .lock()
+            .unwrap()
+            .nodes
+            .get(alias)
+            .map(|node| node.received)
+            .unwrap_or(0)
+    }
+
+    /// Produce the scheduled responses of a node to a dispatched message
+    fn respond(&self, alias: &str, body: &str, signers: &[String]) -> Vec<(Duration, String)> {
+        let mut state = self.inner.lock().unwrap();
+        let behaviour = match state.nodes.get_mut(alias) {
+            Some(node) => {
+                node.received += 1;
+                node.behaviour.clone()
+            }
+            None => {
+                warn!("Message dispatched to unknown mock node: {}", alias);
+                return vec![];
+            }
+        };
+        let message: SJsonValue = match serde_json::from_str(body) {
+            Ok(message) => message,
+            Err(err) => {
+                debug!("Mock node {} received malformed message: {}", alias, err);
+                return vec![];
+            }
+        };
+        let (delay, responses) = match behaviour {
+            MockNodeBehaviour::Normal => (
+                Duration::ZERO,
+                state.handle_message(alias, &message, signers, false),
+            ),
+            MockNodeBehaviour::Delay(delay) => {
+                (delay, state.handle_message(alias, &message, signers, false))
+            }
+            MockNodeBehaviour::Divergent => (
+                Duration::ZERO,
+                state.handle_message(alias, &message, signers, true),
+            ),
+            MockNodeBehaviour::Timeout => (Duration::ZERO, vec![]),
+            MockNodeBehaviour::Garbage => (
+                Duration::ZERO,
+                vec![
+                    _build_response("REQACK", &message, None),
+                    json!({"op": "REPLY", "garbage": base58::encode(Sha256::digest(body))}),
+                ],
+            ),
+            MockNodeBehaviour::Nack(reason) => (
+                Duration::ZERO,
+                vec![_build_response("REQNACK", &message, Some(reason))],
+            ),
+            MockNodeBehaviour::Reject(reason) => (
+                Duration::ZERO,
+                vec![
+                    _build_response("REQACK", &message, None),
+                    _build_response("REJECT", &message, Some(reason)),
+                ],
+            ),
+        };
+        responses
+            .into_iter()
+            .map(|response| (delay, response.to_string()))
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for MockLedger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockLedger")
+            .field("nodes", &self.node_aliases())
+            .finish()
+    }
+}
+
+struct MockNode {
+    sign_key: SignKey,
+    behaviour: MockNodeBehaviour,
+    received: usize,
+}
+
+#[derive(Default)]
+struct MockLedgerTxns {
+    txns: Vec<SJsonValue>,
+    leaf_hashes: Vec<Vec<u8>>,
+}
+
+impl MockLedgerTxns {
+    fn append(&mut self, txn: SJsonValue) -> VdrResult<()> {
+        let leaf = _txn_leaf(&txn)?;
+        self.leaf_hashes.push(_leaf_hash(&leaf));
+        self.txns.push(txn);
+        Ok(())
+    }
+
+    fn root_hash(&self, size: usize) -> String {
+        base58::encode(_tree_hash(&self.leaf_hashes[..size]))
+    }
+
+    fn audit_path(&self, seq_no: usize) -> Vec<String> {
+        _audit_path(&self.leaf_hashes, seq_no - 1)
+            .iter()
+            .map(base58::encode)
+            .collect()
+    }
+
+    fn consistency_proof(&self, from_size: usize, to_size: usize) -> Vec<String> {
+        if from_size == 0 || from_size >= to_size {
+            return vec![];
+        }
+        _subproof(from_size, &self.leaf_hashes[..to_size], true)
+            .iter()
+            .map(base58::encode)
+            .collect()
+    }
+}
+
+struct MockStateEntry {
+    data: SJsonValue,
+    seq_no: usize,
+    txn_time: u64,
+}
+
+struct MockLedgerState {
+    nodes: BTreeMap<String, MockNode>,
+    ledgers: [MockLedgerTxns; 3],
+    state: HashMap<Vec<u8>, MockStateEntry>,
+    writes: HashMap<String, SJsonValue>,
+    signatures: HashMap<Vec<u8>, String>,
+}
+
+impl MockLedgerState {
+    fn new() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+            ledgers: Default::default(),
+            state: HashMap::new(),
+            writes: HashMap::new(),
+            signatures: HashMap::new(),
+        }
+    }
+
+    fn add_node(&mut self, alias: String) -> VdrResult<()> {
+        if self.nodes.contains_key(&alias) {
+            return Err(input_err(format!("Duplicate mock node: {}", alias)));
+        }
+        let seed = Sha256::digest(format!("mock-node:{}", alias).as_bytes());
+        let node_key = PrivateKey::from_seed(&seed[..])?;
+        let node_verkey = node_key.public_key()?.as_base58()?;
+        let steward_did = base58::encode(&node_key.public_key()?.key_bytes()[..16]);
+
+        let sign_key =
+            SignKey::new(Some(&seed[..])).map_err(|_| input_err("Error creating BLS key"))?;
+        let bls_key = BlsVerKey::new(&DEFAULT_GENERATOR, &sign_key)
+            .map_err(|_| input_err("Error creating BLS key"))?;
+        let bls_pop = ProofOfPossession::new(&bls_key, &sign_key)
+            .map_err(|_| input_err("Error creating BLS proof of possession"))?;
+
+        let pool = &mut self.ledgers[LedgerType::POOL as usize];
+        let seq_no = pool.txns.len() + 1;
+        let txn = json!({
+            "reqSignature": {},
+            "txn": {
+                "data": {
+                    "data": {
+                        "alias": alias,
+                        "blskey": base58::encode(bls_key.as_bytes()),
+                        "blskey_pop": base58::encode(bls_pop.as_bytes()),
+                        "client_ip": "127.0.0.1",
+                        "client_port": 9700 + seq_no * 2,
+                        "node_ip": "127.0.0.1",
+                        "node_port": 9699 + seq_no * 2,
+                        "services": ["VALIDATOR"],
+                    },
+                    "dest": node_verkey.key,
+                },
+                "metadata": {"from": steward_did},
+                "type": constants::NODE,
+            },
+            "txnMetadata": {
+                "seqNo": seq_no,
+                "txnId": hex::encode(Sha256::digest(alias.as_bytes())),
+            },
+            "ver": "1",
+        });
+        pool.append(txn)?;
+        self.nodes.insert(
+            alias,
+            MockNode {
+                sign_key,
+                behaviour: MockNodeBehaviour::Normal,
+                received: 0,
+            },
+        );
+        Ok(())
+    }
+
+    fn handle_message(
+        &mut self,
+        alias: &str,
+        message: &SJsonValue,
+        signers: &[String],
+        divergent: bool,
+    ) -> Vec<SJsonValue> {
+        match message["op"].as_str() {
+            Some("LEDGER_STATUS") => vec![self.handle_ledger_status(message, divergent)],
+            Some("CATCHUP_REQ") => vec![self.handle_catchup(message, divergent)],
+            Some(op) => {
+                debug!("Mock node {} received unsupported message: {}", alias, op);
+                vec![]
+            }
+            None => {
+                let ack = _build_response("REQACK", message, None);
+                match self.handle_request(alias, message, signers) {
+                    Ok(mut result) => {
+                        if divergent {
+                            _tamper_result(&mut result);
+                        }
+                        vec![ack, json!({"op": "REPLY", "result": result})]
+                    }
+                    Err(reason) => vec![ack, _build_response("REJECT", message, Some(reason))],
+                }
+            }
+        }
+    }
+
+    fn handle_ledger_status(&mut self, message: &SJsonValue, divergent: bool) -> SJsonValue {
+        let ledger_id = message["ledgerId"].as_u64().unwrap_or(0) as usize;
+        let Some(ledger) = self.ledgers.get(ledger_id) else {
+            return _build_response("REQNACK", message, Some("Unknown ledger".to_string()));
+        };
+        let size = ledger.txns.len();
+        let mut root_hash = ledger.root_hash(size);
+        if divergent {
+            root_hash = base58::encode(Sha256::digest(root_hash.as_bytes()));
+        }
+        let from_size = message["txnSeqNo"].as_u64().unwrap_or(0) as usize;
+        if from_size > 0 && from_size < size {
+            json!({
+                "op": "CONSISTENCY_PROOF",
+                "ledgerId": ledger_id,
+                "seqNoStart": from_size,
+                "seqNoEnd": size,
+                "viewNo": 0,
+                "ppSeqNo": size,
+                "oldMerkleRoot": ledger.root_hash(from_size),
+                "newMerkleRoot": root_hash,
+                "hashes": ledger.consistency_proof(from_size, size),
+            })
+        } else {
+            json!({
+                "op": "LEDGER_STATUS",
+                "ledgerId": ledger_id,
+                "txnSeqNo": size,
+                "merkleRoot": root_hash,
+                "viewNo": 0,
+                "ppSeqNo": size,
+                "protocolVersion": message["protocolVersion"],
+            })
+        }
+    }
+
+    fn handle_catchup(&mut self, message: &SJsonValue, divergent: bool) -> SJsonValue {
+        let ledger_id = message["ledgerId"].as_u64().unwrap_or(0) as usize;
+        let start = message["seqNoStart"].as_u64().unwrap_or(0) as usize;
+        let end = message["seqNoEnd"].as_u64().unwrap_or(0) as usize;
+        let till = message["catchupTill"].as_u64().unwrap_or(0) as usize;
+        match self.ledgers.get(ledger_id) {
+            Some(ledger)
+                if start > 0 && start <= end && end <= till && till <= ledger.txns.len() =>
+            {
+                let mut txns = serde_json::Map::new();
+                for seq_no in start..=end {
+                    let mut txn = ledger.txns[seq_no - 1].clone();
+                    if divergent {
+                        txn["txnMetadata"]["divergent"] = SJsonValue::from(true);
+                    }
+                    txns.insert(seq_no.to_string(), txn);
+                }
+                json!({
+                    "op": "CATCHUP_REP",
+                    "ledgerId": ledger_id,
+                    "txns": txns,
+                    "consProof": ledger.consistency_proof(end, till),
+                })
+            }
+            _ => _build_response(
+                "REQNACK",
+                message,
+                Some("Invalid catchup range".to_string()),
+            ),
+        }
+    }
+
+    fn handle_request(
+        &mut self,
+        alias: &str,
+        request: &SJsonValue,
+        signers: &[String],
+    ) -> Result<SJsonValue, String> {
+        let operation = &request["operation"];
+        let txn_type = operation["type"]
+            .as_str()
+            .ok_or("Missing operation type")?
+            .to_string();
+        let txn_type = txn_type.as_str();
+
+        if txn_type == constants::GET_VALIDATOR_INFO || txn_type == constants::POOL_RESTART {
+            let mut result = _build_read_result(request, txn_type);
+            result["data"] = json!({
+                "alias": alias,
+                "Node_info": {"Name": alias},
+            });
+            Ok(result)
+        } else if txn_type == constants::GET_TXN {
+            self.handle_get_txn(request, signers)
+        } else if constants::READ_REQUESTS.contains(&txn_type)
+            || txn_type == constants::GET_FROZEN_LEDGERS
+        {
+            self.handle_read(request, txn_type, signers)
+        } else if constants::REQUESTS.contains(&txn_type) {
+            self.handle_write(request, txn_type)
+        } else {
+            Err(format!("Unknown transaction type: {}", txn_type))
+        }
+    }
+
+    fn handle_get_txn(
+        &mut self,
+        request: &SJsonValue,
+        signers: &[String],
+    ) -> Result<SJsonValue, String> {
+        let mut result = _build_read_result(request, constants::GET_TXN);
+        let ledger_id = request["operation"]["ledgerId"]
+            .as_u64()
+            .unwrap_or(LedgerType::DOMAIN as u64) as usize;
+        let seq_no = request["operation"]["data"].as_u64().unwrap_or(0) as usize;
+        result["seqNo"] = SJsonValue::from(seq_no);
+        result["data"] = SJsonValue::Null;
+        let found = match self.ledgers.get(ledger_id) {
+            Some(ledger) if seq_no > 0 && seq_no <= ledger.txns.len() => {
+                let size = ledger.txns.len();
+                let root_hash = ledger.root_hash(size);
+                let mut data = ledger.txns[seq_no - 1].clone();
+                data["auditPath"] = SJsonValue::from(ledger.audit_path(seq_no));
+                data["ledgerSize"] = SJsonValue::from(size);
+                data["rootHash"] = SJsonValue::from(root_hash.clone());
+                result["data"] = data;
+                Some(root_hash)
+            }
+            _ => None,
+        };
+        if let Some(root_hash) = found {
+            let (_, state_root) = build_single_key_proof(&[], None);
+            let multi_sig = self
+                .multi_signature(ledger_id, &state_root, &root_hash, signers)
+                .map_err(|err| err.to_string())?;
+            result["state_proof"] = json!({ "multi_signature": multi_sig });
+        }
+        Ok(result)
+    }
+
+    fn handle_read(
+        &mut self,
+        request: &SJsonValue,
+        txn_type: &str,
+        signers: &[String],
+    ) -> Result<SJsonValue, String> {
+        let mut result = _build_read_result(request, txn_type);
+        let sp_key = parse_key_from_request_for_builtin_sp(request, _protocol_version(request));
+        match sp_key.as_ref().and_then(|key| self.state.get(key)) {
+            Some(entry) => {
+                result["seqNo"] = SJsonValue::from(entry.seq_no);
+                result["txnTime"] = SJsonValue::from(entry.txn_time);
+                result["data"] = entry.data.clone();
+            }
+            None => {
+                result["seqNo"] = SJsonValue::Null;
+                result["txnTime"] = SJsonValue::Null;
+                result["data"] = SJsonValue::Null;
+            }
+        }
+        if let Some(key) = sp_key {
+            let value =
+                parse_reply_for_proof_value(&result, &key).map_err(|err| err.to_string())?;
+            let (proof_nodes, root_hash) = build_single_key_proof(&key, value.as_deref());
+            let ledger_id = if CONFIG_LEDGER_READS.contains(&txn_type) {
+                LedgerType::CONFIG
+            } else {
+                LedgerType::DOMAIN
+            } as usize;
+            let txn_root = {
+                let ledger = &self.ledgers[ledger_id];
+                ledger.root_hash(ledger.txns.len())
+            };
+            let multi_sig = self
+                .multi_signature(ledger_id, &root_hash, &txn_root, signers)
+                .map_err(|err| err.to_string())?;
+            result["state_proof"] = json!({
+                "multi_signature": multi_sig,
+                "proof_nodes": proof_nodes,
+                "root_hash": root_hash,
+            });
+        }
+        Ok(result)
+    }
+
+    fn handle_write(&mut self, request: &SJsonValue, txn_type: &str) -> Result<SJsonValue, String> {
+        let digest = hex::encode(Sha256::digest(request.to_string().as_bytes()));
+        if let Some(result) = self.writes.get(&digest) {
+            return Ok(result.clone());
+        }
+        let identifier = request["identifier"]
+            .as_str()
+            .ok_or("Missing request identifier")?;
+
+        let mut data = request["operation"].clone();
+        if let Some(data) = data.as_object_mut() {
+            data.remove("type");
+        }
+        let mut metadata = json!({
+            "digest": digest,
+            "from": identifier,
+            "reqId": request["reqId"],
+        });
+        if let Some(endorser) = request["endorser"].as_str() {
+            metadata["endorser"] = SJsonValue::from(endorser);
+        }
+        if !request["taaAcceptance"].is_null() {
+            metadata["taaAcceptance"] = request["taaAcceptance"].clone();
+        }
+        let req_signature = if let Some(signature) = request["signature"].as_str() {
+            json!({"type": "ED25519", "values": [{"from": identifier, "value": signature}]})
+        } else if let Some(signatures) = request["signatures"].as_object() {
+            let values = signatures
+                .iter()
+                .map(|(from, value)| json!({"from": from, "value": value}))
+                .collect::<Vec<_>>();
+            json!({"type": "ED25519", "values": values})
+        } else {
+            json!({})
+        };
+
+        let ledger_id = if txn_type == constants::NODE {
+            LedgerType::POOL
+        } else if CONFIG_LEDGER_TXNS.contains(&txn_type) {
+            LedgerType::CONFIG
+        } else {
+            LedgerType::DOMAIN
+        } as usize;
+        let ledger = &mut self.ledgers[ledger_id];
+        let seq_no = ledger.txns.len() + 1;
+        let txn_time = get_cur_time();
+        let txn = json!({
+            "reqSignature": req_signature,
+            "txn": {
+                "data": data,
+                "metadata": metadata,
+                "protocolVersion": request["protocolVersion"],
+                "type": txn_type,
+            },
+            "txnMetadata": {
+                "seqNo": seq_no,
+                "txnTime": txn_time,
+            },
+            "ver": "1",
+        });
+        ledger.append(txn.clone()).map_err(|err| err.to_string())?;
+
+        let mut result = txn;
+        result["auditPath"] = SJsonValue::from(ledger.audit_path(seq_no));
+        result["rootHash"] = SJsonValue::from(ledger.root_hash(seq_no));
+
+        self.update_state(request, txn_type, &data, identifier, seq_no, txn_time);
+        self.writes.insert(digest, result.clone());
+        Ok(result)
+    }
+
+    fn update_state(
+        &mut self,
+        request: &SJsonValue,
+        txn_type: &str,
+        data: &SJsonValue,
+        identifier: &str,
+        seq_no: usize,
+        txn_time: u64,
+    ) {
+        let (read_op, state_data) = match txn_type {
+            constants::NYM => {
+                let read_op = json!({"type": constants::GET_NYM, "dest": data["dest"]});
+                let key = _state_key(request, &read_op);
+                let mut nym = key
+                    .as_ref()
+                    .and_then(|key| self.state.get(key))
+                    .and_then(|entry| entry.data.as_str())
+                    .and_then(|prev| serde_json::from_str::<SJsonValue>(prev).ok())
+                    .unwrap_or_else(|| {
+                        json!({
+                            "dest": data["dest"],
+                            "identifier": identifier,
+                            "role": SJsonValue::Null,
+                            "verkey": SJsonValue::Null,
+                        })
+                    });
+                for field in ["role", "verkey", "diddocContent", "version"] {
+                    if data.get(field).is_some() {
+                        nym[field] = data[field].clone();
+                    }
+                }
+                nym["seqNo"] = SJsonValue::from(seq_no);
+                nym["txnTime"] = SJsonValue::from(txn_time);
+                (read_op, SJsonValue::from(nym.to_string()))
+            }
+            constants::ATTRIB => {
+                let (field, name, value) = if let Some(raw) = data["raw"].as_str() {
+                    let name = serde_json::from_str::<SJsonValue>(raw)
+                        .ok()
+                        .and_then(|raw| raw.as_object().and_then(|m| m.keys().next().cloned()));
+                    ("raw", name, raw)
+                } else if let Some(hash) = data["hash"].as_str() {
+                    ("hash", Some(hash.to_string()), hash)
+                } else if let Some(enc) = data["enc"].as_str() {
+                    ("enc", Some(enc.to_string()), enc)
+                } else {
+                    return;
+                };
+                let Some(name) = name else {
+                    return;
+                };
+                let mut read_op = json!({"type": constants::GET_ATTR, "dest": data["dest"]});
+                read_op[field] = SJsonValue::from(name);
+                (read_op, SJsonValue::from(value))
+            }
+            constants::SCHEMA => (
+                json!({
+                    "type": constants::GET_SCHEMA,
+                    "dest": identifier,
+                    "data": {
+                        "name": data["data"]["name"],
+                        "version": data["data"]["version"],
+                    },
+                }),
+                data["data"].clone(),
+            ),
+            constants::CRED_DEF => (
+                json!({
+                    "type": constants::GET_CRED_DEF,
+                    "origin": identifier,
+                    "ref": data["ref"],
+                    "signature_type": data["signature_type"],
+                    "tag": data["tag"],
+                }),
+                data["data"].clone(),
+            ),
+            _ => return,
+        };
+        if let Some(key) = _state_key(request, &read_op) {
+            self.state.insert(
+                key,
+                MockStateEntry {
+                    data: state_data,
+                    seq_no,
+                    txn_time,
+                },
+            );
+        }
+    }
+
+    fn multi_signature(
+        &mut self,
+        ledger_id: usize,
+        state_root: &str,
+        txn_root: &str,
+        signers: &[String],
+    ) -> VdrResult<SJsonValue> {
+        let pool = &self.ledgers[LedgerType::POOL as usize];
+        let value = json!({
+            "ledger_id": ledger_id,
+            "pool_state_root_hash": pool.root_hash(pool.txns.len()),
+            "state_root_hash": state_root,
+            "timestamp": get_cur_time(),
+            "txn_root_hash": txn_root,
+        });
+        let participants = signers
+            .iter()
+            .filter(|alias| self.nodes.contains_key(*alias))
+            .cloned()
+            .collect::<Vec<String>>();
+        let message =
+            rmp_serde::to_vec_named(&value).with_input_err("Error encoding multi signature")?;
+        let mut cache_key = message.clone();
+        cache_key.extend(participants.iter().flat_map(|p| p.bytes()));
+        let signature = if let Some(signature) = self.signatures.get(&cache_key) {
+            signature.clone()
+        } else {
+            let mut signatures = Vec::with_capacity(participants.len());
+            for alias in &participants {
+                signatures.push(
+                    Bls::sign(&message, &self.nodes[alias].sign_key)
+                        .map_err(|_| input_err("Error creating BLS signature"))?,
+                );
+            }
+            let multi_sig = MultiSignature::new(&signatures.iter().collect::<Vec<_>>())
+                .map_err(|_| input_err("Error creating BLS multi signature"))?;
+            let signature = base58::encode(multi_sig.as_bytes());
+            if self.signatures.len() > 64 {
+                self.signatures.clear();
+            }
+            self.signatures.insert(cache_key, signature.clone());
+            signature
+        };
+        Ok(json!({
+            "participants": participants,
+            "signature": signature,
+            "value": value,
+        }))
+    }
+}
+
+/// A `NetworkerFactory` producing networkers connected to a `MockLedger`
+#[derive(Clone, Debug)]
+pub struct MockNetworkerFactory {
+    ledger: MockLedger,
+}
+
+impl MockNetworkerFactory {
+    /// Create a new factory for the given mock ledger
+    pub fn new(ledger: MockLedger) -> Self {
+        Self { ledger }
+    }
+}
+
+impl NetworkerFactory for MockNetworkerFactory {
+    type Output = MockNetworker;
+    fn make_networker(
+        &self,
+        _config: PoolConfig,
+        verifiers: &Verifiers,
+    ) -> VdrResult<MockNetworker> {
+        let mut signers = verifiers.keys().cloned().collect::<Vec<String>>();
+        signers.sort();
+        let node_aliases = HashSet::from_iter(signers.iter().cloned());
+        let ledger = self.ledger.clone();
+        let (cmd_send, cmd_recv) = mpsc::channel::<MockCommand>();
+        let worker = thread::spawn(move || {
+            MockThread::new(ledger, signers, node_aliases, cmd_recv).work();
+            trace!("Mock networker worker exited");
+        });
+        Ok(MockNetworker {
+            cmd_send,
+            worker: Some(worker),
+        })
+    }
+}
+
+/// A `Networker` implementation answering requests from a `MockLedger`
+pub struct MockNetworker {
+    cmd_send: mpsc::Sender<MockCommand>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Networker for MockNetworker {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        self.cmd_send
+            .send(MockCommand::Event(event))
+            .with_err_msg(VdrErrorKind::Resource, "Error sending networker event")
+    }
+}
+
+impl Drop for MockNetworker {
+    fn drop(&mut self) {
+        if self.cmd_send.send(MockCommand::Exit).is_err() {
+            trace!("Mock networker thread already exited")
+        }
+        if let Some(worker) = self.worker.take() {
+            debug!("Drop mock networker thread");
+            worker.join().unwrap()
+        }
+    }
+}
+
+#[derive(Debug)]
+enum MockCommand {
+    Event(NetworkerEvent),
+    Exit,
+}
+
+struct PendingRequest {
+    body: String,
+    sender: UnboundedSender<RequestExtEvent>,
+}
+
+struct PendingReply {
+    due: Instant,
+    handle: RequestHandle,
+    node_alias: String,
+    message: String,
+}
+
+struct MockThread {
+    ledger: MockLedger,
+    signers: Vec<String>,
+    node_aliases: HashSet<String>,
+    cmd_recv: mpsc::Receiver<MockCommand>,
+    requests: BTreeMap<RequestHandle, PendingRequest>,
+    replies: Vec<PendingReply>,
+    timeouts: BTreeMap<(RequestHandle, String), Instant>,
+}
+
+impl MockThread {
+    fn new(
+        ledger: MockLedger,
+        signers: Vec<String>,
+        node_aliases: HashSet<String>,
+        cmd_recv: mpsc::Receiver<MockCommand>,
+    ) -> Self {
+        Self {
+            ledger,
+            signers,
+            node_aliases,
+            cmd_recv,
+            requests: BTreeMap::new(),
+            replies: Vec::new(),
+            timeouts: BTreeMap::new(),
+        }
+    }
+
+    fn work(&mut self) {
+        loop {
+            let next_due = self
+                .replies
+                .iter()
+                .map(|reply| reply.due)
+                .chain(self.timeouts.values().cloned())
+                .min();
+            let cmd = match next_due {
+                Some(due) => self
+                    .cmd_recv
+                    .recv_timeout(due.saturating_duration_since(Instant::now())),
+                None => self
+                    .cmd_recv
+                    .recv()
+                    .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            match cmd {
+                Ok(MockCommand::Event(event)) => self.process_event(event),
+                Ok(MockCommand::Exit) | Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Err(mpsc::RecvTimeoutError::Timeout) => (),
+            }
+            self.process_due();
+        }
+    }
+
+    fn process_event(&mut self, event: NetworkerEvent) {
+        match event {
+            NetworkerEvent::NewRequest(handle, _sub_id, body, sender) => {
+                trace!("New request {}", handle);
+                let pending = PendingRequest { body, sender };
+                if pending.sender.unbounded_send(RequestExtEvent::Init).is_ok() {
+                    self.requests.insert(handle, pending);
+                } else {
+                    trace!("Sender dropped before Init {}", handle);
+                }
+            }
+            NetworkerEvent::FinishRequest(handle) => {
+                trace!("Removing, finished {}", handle);
+                self.remove_request(handle);
+            }
+            NetworkerEvent::Dispatch(handle, node_aliases, timeout) => {
+                trace!("Dispatch {} {:?}", handle, node_aliases);
+                self.dispatch_request(handle, node_aliases, timeout);
+            }
+            NetworkerEvent::CleanTimeout(handle, node_alias) => {
+                self.timeouts.remove(&(handle, node_alias));
+            }
+            NetworkerEvent::ExtendTimeout(handle, node_alias, timeout) => {
+                if let Some(due) = self.timeouts.get_mut(&(handle, node_alias)) {
+                    *due = Instant::now() + Duration::from_secs(timeout.max(0) as u64);
+                }
+            }
+        }
+    }
+
+    fn dispatch_request(&mut self, handle: RequestHandle, node_aliases: Vec<String>, timeout: i64) {
+        let Some(request) = self.requests.get(&handle) else {
+            debug!("Unknown request ID for dispatch: {}", handle);
+            return;
+        };
+        let body = request.body.clone();
+        for node_alias in node_aliases {
+            if !self.node_aliases.contains(&node_alias) {
+                warn!("Cannot send to unknown node alias: {}", node_alias);
+                continue;
+            }
+            if !self.send_event(
+                handle,
+                RequestExtEvent::Sent(node_alias.clone(), SystemTime::now()),
+            ) {
+                break;
+            }
+            let now = Instant::now();
+            self.timeouts.insert(
+                (handle, node_alias.clone()),
+                now + Duration::from_secs(timeout.max(0) as u64),
+            );
+            for (delay, message) in self.ledger.respond(&node_alias, &body, &self.signers) {
+                self.replies.push(PendingReply {
+                    due: now + delay,
+                    handle,
+                    node_alias: node_alias.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    fn process_due(&mut self) {
+        let now = Instant::now();
+        let (mut due, pending): (Vec<PendingReply>, Vec<PendingReply>) =
+            self.replies.drain(..).partition(|reply| reply.due <= now);
+        self.replies = pending;
+        due.sort_by_key(|reply| reply.due);
+        for reply in due {
+            match Message::from_raw_str(&reply.message) {
+                Ok(parsed) => {
+                    if !matches!(parsed, Message::ReqACK(_)) {
+                        self.timeouts
+                            .remove(&(reply.handle, reply.node_alias.clone()));
+                    }
+                    self.send_event(
+                        reply.handle,
+                        RequestExtEvent::Received(
+                            reply.node_alias,
+                            reply.message,
+                            parsed,
+                            SystemTime::now(),
+                        ),
+                    );
+                }
+                Err(err) => debug!("Error parsing received message: {:?}", err),
+            }
+        }
+
+        let expired = self
+            .timeouts
+            .iter()
+            .filter(|(_, due)| **due <= now)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for (handle, node_alias) in expired {
+            self.timeouts.remove(&(handle, node_alias.clone()));
+            self.send_event(handle, RequestExtEvent::Timeout(node_alias));
+        }
+    }
+
+    fn send_event(&mut self, handle: RequestHandle, event: RequestExtEvent) -> bool {
+        if let Some(request) = self.requests.get(&handle) {
+            if request.sender.unbounded_send(event).is_ok() {
+                return true;
+            }
+            trace!("Removing, sender disconnected {}", handle);
+            self.remove_request(handle);
+        } else {
+            trace!("Request ID not found: {}", handle);
+        }
+        false
+    }
+
+    fn remove_request(&mut self, handle: RequestHandle) {
+        self.requests.remove(&handle);
+        self.replies.retain(|reply| reply.handle != handle);
+        self.timeouts
+            .retain(|(req_handle, _), _| *req_handle != handle);
+    }
+}
+
+fn _protocol_version(request: &SJsonValue) -> ProtocolVersion {
+    request["protocolVersion"]
+        .as_i64()
+        .and_then(|version| ProtocolVersion::from_id(version).ok())
+        .unwrap_or_default()
+}
+
+fn _state_key(request: &SJsonValue, read_op: &SJsonValue) -> Option<Vec<u8>> {
+    parse_key_from_request_for_builtin_sp(
+        &json!({ "operation": read_op }),
+        _protocol_version(request),
+    )
+}
+
+fn _build_read_result(request: &SJsonValue, txn_type: &str) -> SJsonValue {
+    let mut result = json!({
+        "identifier": request["identifier"],
+        "reqId": request["reqId"],
+    });
+    if let Some(operation) = request["operation"].as_object() {
+        for (key, value) in operation {
+            result[key] = value.clone();
+        }
+    }
+    result["type"] = SJsonValue::from(txn_type);
+    result
+}
+
+fn _build_response(op: &str, request: &SJsonValue, reason: Option<String>) -> SJsonValue {
+    json!({
+        "op": op,
+        "identifier": request["identifier"],
+        "reqId": request["reqId"].as_u64().unwrap_or(0),
+        "reason": reason,
+    })
+}
+
+fn _tamper_result(result: &mut SJsonValue) {
+    if let Some(seq_no) = result["seqNo"].as_u64() {
+        result["seqNo"] = SJsonValue::from(seq_no + 1);
+    }
+    let tampered = match result["data"].take() {
+        SJsonValue::String(data) => match serde_json::from_str::<SJsonValue>(&data) {
+            Ok(SJsonValue::Object(mut map)) => {
+                map.insert("divergent".to_string(), SJsonValue::from(true));
+                SJsonValue::from(SJsonValue::from(map).to_string())
+            }
+            _ => SJsonValue::from(format!("{}divergent", data)),
+        },
+        SJsonValue::Object(mut map) => {
+            match map.get_mut("txn").and_then(SJsonValue::as_object_mut) {
+                Some(txn) => {
+                    txn.insert("divergent".to_string(), SJsonValue::from(true));
+                }
+                None => {
+                    map.insert("divergent".to_string(), SJsonValue::from(true));
+                }
+            }
+            SJsonValue::from(map)
+        }
+        _ => SJsonValue::from(json!({"divergent": true}).to_string()),
+    };
+    result["data"] = tampered;
+    if !result["txnMetadata"].is_null() {
+        result["txnMetadata"]["divergent"] = SJsonValue::from(true);
+    }
+}
+
+/// Calculate the merkle tree leaf for a ledger transaction, as verified for `GET_TXN`
+fn _txn_leaf(txn: &SJsonValue) -> VdrResult<Vec<u8>> {
+    let reply = json!({"type": constants::GET_TXN, "data": txn});
+    let value = parse_reply_for_proof_value(&reply, &[])?
+        .ok_or_else(|| input_err("Invalid ledger transaction"))?;
+    let value: SJsonValue =
+        serde_json::from_str(&value).with_input_err("Invalid ledger transaction")?;
+    rmp_serde::to_vec_named(&value).with_input_err("Error encoding ledger transaction")
+}
+
+fn _leaf_hash(leaf: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(leaf);
+    hasher.finalize().to_vec()
+}
+
+fn _node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().to_vec()
+}
+
+// largest power of two smaller than `size`
+fn _split_point(size: usize) -> usize {
+    let mut split = 1;
+    while split * 2 < size {
+        split *= 2;
+    }
+    split
+}
+
+fn _tree_hash(hashes: &[Vec<u8>]) -> Vec<u8> {
+    match hashes.len() {
+        0 => Sha256::digest([]).to_vec(),
+        1 => hashes[0].clone(),
+        size => {
+            let split = _split_point(size);
+            _node_hash(&_tree_hash(&hashes[..split]), &_tree_hash(&hashes[split..]))
+        }
+    }
+}
+
+// RFC 6962 audit path, ordered from the leaf to the root
+fn _audit_path(hashes: &[Vec<u8>], idx: usize) -> Vec<Vec<u8>> {
+    if hashes.len() <= 1 {
+        return vec![];
+    }
+    let split = _split_point(hashes.len());
+    if idx < split {
+        let mut path = _audit_path(&hashes[..split], idx);
+        path.push(_tree_hash(&hashes[split..]));
+        path
+    } else {
+        let mut path = _audit_path(&hashes[split..], idx - split);
+        path.push(_tree_hash(&hashes[..split]));
+        path
+    }
+}
+
+// RFC 6962 consistency proof between the first `size` leaves and the full tree
+fn _subproof(size: usize, hashes: &[Vec<u8>], complete: bool) -> Vec<Vec<u8>> {
+    if size == hashes.len() {
+        return if complete {
+            vec![]
+        } else {
+            vec![_tree_hash(hashes)]
+        };
+    }
+    let split = _split_point(hashes.len());
+    if size <= split {
+        let mut proof = _subproof(size, &hashes[..split], complete);
+        proof.push(_tree_hash(&hashes[split..]));
+        proof
+    } else {
+        let mut proof = _subproof(size - split, &hashes[split..], false);
+        proof.push(_tree_hash(&hashes[..split]));
+        proof
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use futures_executor::block_on;
+
+    use super::{MockLedger, MockNetworkerFactory};
+    use crate::config::PoolConfig;
+    use crate::pool::helpers::perform_ledger_request;
+    use crate::pool::networker::MakeShared;
+    use crate::pool::{Pool, PreparedRequest, RequestResult, RequestResultMeta, SharedPool};
+    use crate::utils::did::{generate_did, DidValue};
+
+    /// Build a shared pool connected to the mock ledger
+    pub fn mock_pool(ledger: &MockLedger, config: PoolConfig) -> SharedPool {
+        let merkle_tree = ledger.pool_transactions().unwrap().merkle_tree().unwrap();
+        SharedPool::build(
+            config,
+            merkle_tree,
+            MakeShared(MockNetworkerFactory::new(ledger.clone())),
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    pub fn send(
+        pool: &SharedPool,
+        request: &PreparedRequest,
+    ) -> (RequestResult<String>, RequestResultMeta) {
+        block_on(perform_ledger_request(pool, request, None)).unwrap()
+    }
+
+    pub fn new_did() -> DidValue {
+        let (did, _, _) = generate_did(None, None).unwrap();
+        DidValue(did.to_string())
+    }
+
+    /// Write a self-signed NYM for the DID
+    pub fn write_nym(pool: &SharedPool, dest: &DidValue) -> RequestResult<String> {
+        let request = pool
+            .get_request_builder()
+            .build_nym_request(dest, dest, None, None, None, None, None)
+            .unwrap();
+        send(pool, &request).0
+    }
+
+    pub fn verified(meta: &RequestResultMeta) -> bool {
+        meta.state_proof.values().any(|res| res.is_verified())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::*;
+    use super::*;
+
+    use futures_executor::block_on;
+
+    use crate::common::merkle_tree::MerkleTree;
+    use crate::pool::helpers::{perform_get_txn, perform_refresh};
+    use crate::pool::{Pool, RequestResult, StateProofResult};
+
+    #[test]
+    fn merkle_helpers_match_merkle_tree() {
+        let leaves = (0..11u8).map(|idx| vec![idx]).collect::<Vec<_>>();
+        let hashes = leaves
+            .iter()
+            .map(|leaf| _leaf_hash(leaf))
+            .collect::<Vec<_>>();
+        for size in 1..=leaves.len() {
+            let tree = MerkleTree::from_vec(leaves[..size].to_vec()).unwrap();
+            assert_eq!(&_tree_hash(&hashes[..size]), tree.root_hash());
+            for from_size in 1..size {
+                let old_tree = MerkleTree::from_vec(leaves[..from_size].to_vec()).unwrap();
+                let proof = _subproof(from_size, &hashes[..size], true);
+                assert!(old_tree
+                    .consistency_proof(tree.root_hash(), size, &proof)
+                    .unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn write_and_read_nym_with_state_proof() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        let did = new_did();
+
+        let request = pool
+            .get_request_builder()
+            .build_get_nym_request(None, &did, None, None)
+            .unwrap();
+        let (result, meta) = send(&pool, &request);
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        let reply: SJsonValue = serde_json::from_str(&reply).unwrap();
+        assert!(reply["result"]["data"].is_null());
+        assert!(verified(&meta));
+
+        assert!(matches!(write_nym(&pool, &did), RequestResult::Reply(_)));
+        assert_eq!(ledger.ledger_size(LedgerType::DOMAIN), 1);
+
+        let (result, meta) = send(&pool, &request);
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        let reply: SJsonValue = serde_json::from_str(&reply).unwrap();
+        let data: SJsonValue =
+            serde_json::from_str(reply["result"]["data"].as_str().unwrap()).unwrap();
+        assert_eq!(data["dest"], SJsonValue::from(did.0));
+        assert_eq!(data["seqNo"], SJsonValue::from(1));
+        assert!(verified(&meta));
+    }
+
+    #[test]
+    fn get_txn_with_audit_proof() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        for _ in 0..3 {
+            write_nym(&pool, &new_did());
+        }
+        for (ledger_type, seq_no) in [(LedgerType::POOL, 3), (LedgerType::DOMAIN, 2)] {
+            let (result, meta) =
+                block_on(perform_get_txn(&pool, ledger_type.to_id(), seq_no, None)).unwrap();
+            assert!(matches!(result, RequestResult::Reply(_)));
+            assert!(verified(&meta));
+        }
+    }
+
+    #[test]
+    fn divergent_node_fails_state_proof() {
+        let ledger = MockLedger::new(4).unwrap();
+        // query every node, so that the divergent node is always asked and answers first
+        let config = PoolConfig {
+            request_read_nodes: 4,
+            ..PoolConfig::default()
+        };
+        let pool = mock_pool(&ledger, config);
+        let did = new_did();
+        write_nym(&pool, &did);
+        for alias in ledger.node_aliases() {
+            let behaviour = if alias == "Node1" {
+                MockNodeBehaviour::Divergent
+            } else {
+                MockNodeBehaviour::Delay(Duration::from_millis(50))
+            };
+            ledger.set_behaviour(&alias, behaviour).unwrap();
+        }
+
+        let request = pool
+            .get_request_builder()
+            .build_get_nym_request(None, &did, None, None)
+            .unwrap();
+        let (result, meta) = send(&pool, &request);
+        let RequestResult::Reply(reply) = result else {
+            panic!("Expected reply");
+        };
+        assert!(!reply.contains("divergent"));
+        assert!(verified(&meta));
+        assert!(matches!(
+            meta.state_proof.get("Node1"),
+            Some(StateProofResult::Invalid(..))
+        ));
+    }
+
+    #[test]
+    fn write_survives_faulty_node() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        ledger
+            .set_behaviour("Node1", MockNodeBehaviour::Garbage)
+            .unwrap();
+        ledger
+            .set_behaviour("Node2", MockNodeBehaviour::Delay(Duration::from_millis(50)))
+            .unwrap();
+        assert!(matches!(
+            write_nym(&pool, &new_did()),
+            RequestResult::Reply(_)
+        ));
+        assert_eq!(ledger.ledger_size(LedgerType::DOMAIN), 1);
+        assert_eq!(ledger.requests_received("Node1"), 1);
+    }
+
+    #[test]
+    fn nack_consensus_fails_request() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        for alias in ledger.node_aliases() {
+            ledger
+                .set_behaviour(&alias, MockNodeBehaviour::Nack("Denied".to_string()))
+                .unwrap();
+        }
+        let RequestResult::Failed(err) = write_nym(&pool, &new_did()) else {
+            panic!("Expected failure");
+        };
+        assert!(matches!(err.kind(), VdrErrorKind::PoolRequestFailed(_)));
+        assert_eq!(ledger.ledger_size(LedgerType::DOMAIN), 0);
+    }
+
+    #[test]
+    fn silent_nodes_time_out() {
+        let ledger = MockLedger::new(4).unwrap();
+        let config = PoolConfig {
+            ack_timeout: 1,
+            ..PoolConfig::default()
+        };
+        let pool = mock_pool(&ledger, config);
+        for alias in ledger.node_aliases() {
+            ledger
+                .set_behaviour(&alias, MockNodeBehaviour::Timeout)
+                .unwrap();
+        }
+        let RequestResult::Failed(err) = write_nym(&pool, &new_did()) else {
+            panic!("Expected failure");
+        };
+        assert!(matches!(err.kind(), VdrErrorKind::PoolTimeout));
+        let stats = pool.monitor().stats();
+        assert!(!stats.is_empty());
+        assert!(stats
+            .values()
+            .all(|node| node.timeouts > 0 && node.timeout_rate == 1.0));
+    }
+
+    #[test]
+    fn refresh_catches_up_pool_ledger() {
+        let ledger = MockLedger::new(4).unwrap();
+        let pool = mock_pool(&ledger, PoolConfig::default());
+        ledger.add_node("Node5").unwrap();
+
+        let (txns, _meta) = block_on(perform_refresh(&pool)).unwrap();
+        let txns = txns.expect("Expected new pool transactions");
+        assert_eq!(txns.len(), 5);
+        assert_eq!(
+            txns.root_hash().unwrap(),
+            ledger.pool_transactions().unwrap().root_hash().unwrap()
+        );
+    }
+}
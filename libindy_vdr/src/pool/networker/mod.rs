@@ -9,9 +9,44 @@ use crate::config::types::PoolConfig;
 use super::requests::RequestExtEvent;
 use super::types::{self, RequestHandle, Verifiers};
 
+// The async networker polls the file descriptors of the ZeroMQ sockets, which are
+// only available on unix platforms
+#[cfg(all(feature = "async-networker", not(unix)))]
+compile_error!("The `async-networker` feature is only supported on unix platforms");
+
+#[cfg(feature = "async-networker")]
+mod async_zmq;
+#[cfg(feature = "async-networker")]
+pub use self::async_zmq::{AsyncNetworker, AsyncNetworkerFactory};
+
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+#[cfg(test)]
+pub(crate) use self::mock::fixtures as mock_fixtures;
+#[cfg(any(test, feature = "mock"))]
+pub use self::mock::{MockLedger, MockNetworker, MockNetworkerFactory, MockNodeBehaviour};
+
+#[cfg(feature = "zmq")]
 mod zmq;
+#[cfg(feature = "zmq")]
 pub use self::zmq::{ZMQNetworker, ZMQNetworkerFactory};
 
+#[cfg(feature = "zmtp")]
+mod zmtp;
+#[cfg(feature = "zmtp")]
+pub use self::zmtp::{ZMTPNetworker, ZMTPNetworkerFactory};
+
+/// The `NetworkerFactory` used by `PoolBuilder`
+#[cfg(feature = "zmq")]
+pub type DefaultNetworkerFactory = ZMQNetworkerFactory;
+
+/// The `NetworkerFactory` used by `PoolBuilder`
+#[cfg(all(feature = "zmtp", not(feature = "zmq")))]
+pub type DefaultNetworkerFactory = ZMTPNetworkerFactory;
+
+#[cfg(not(any(feature = "zmq", feature = "zmtp")))]
+compile_error!("Either the `zmq` or `zmtp` feature must be enabled");
+
 /// Events used to drive a `Networker` instance
 #[derive(Debug)]
 pub enum NetworkerEvent {
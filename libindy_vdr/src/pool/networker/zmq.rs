@@ -42,8 +42,8 @@ impl NetworkerFactory for ZMQNetworkerFactory {
             _create_pair_of_sockets(&format!("zmqnet_{}", socket_handle));
         let (evt_send, evt_recv) = mpsc::channel::<NetworkerEvent>();
         let worker = thread::spawn(move || {
-            let mut zmq_thread = ZMQThread::new(config, zmq_ctx, cmd_recv, evt_recv, remotes);
-            if let Err(err) = zmq_thread.work() {
+            let mut zmq_thread = ZMQThread::new(config, zmq_ctx, remotes);
+            if let Err(err) = zmq_thread.work(&cmd_recv, &evt_recv) {
                 warn!("ZMQ worker exited with error: {}", err)
             } else {
                 trace!("ZMQ worker exited");
@@ -90,11 +90,9 @@ impl Drop for ZMQNetworker {
     }
 }
 
-struct ZMQThread {
+pub(super) struct ZMQThread {
     config: PoolConfig,
     zmq_ctx: zmq::Context,
-    cmd_recv: zmq::Socket,
-    evt_recv: mpsc::Receiver<NetworkerEvent>,
     node_aliases: HashSet<String>,
     remotes: Vec<RemoteNode>,
     requests: BTreeMap<RequestHandle, PendingRequest>,
@@ -103,19 +101,11 @@ struct ZMQThread {
 }
 
 impl ZMQThread {
-    pub fn new(
-        config: PoolConfig,
-        zmq_ctx: zmq::Context,
-        cmd_recv: zmq::Socket,
-        evt_recv: mpsc::Receiver<NetworkerEvent>,
-        remotes: Vec<RemoteNode>,
-    ) -> Self {
+    pub(super) fn new(config: PoolConfig, zmq_ctx: zmq::Context, remotes: Vec<RemoteNode>) -> Self {
         let node_aliases = HashSet::from_iter(remotes.iter().map(|r| r.name.clone()));
         ZMQThread {
             config,
             zmq_ctx,
-            cmd_recv,
-            evt_recv,
             node_aliases,
             remotes,
             requests: BTreeMap::new(),
@@ -124,10 +114,14 @@ impl ZMQThread {
         }
     }
 
-    pub fn work(&mut self) -> Result<(), String> {
+    pub fn work(
+        &mut self,
+        cmd_recv: &zmq::Socket,
+        evt_recv: &mpsc::Receiver<NetworkerEvent>,
+    ) -> Result<(), String> {
         loop {
-            while self.try_receive_request()? {}
-            match self.poll_connections()? {
+            while self.try_receive_request(evt_recv)? {}
+            match self.poll_connections(cmd_recv)? {
                 PollResult::NoSockets => {
                     // wait until a request is received
                     // thread::park()
@@ -148,26 +142,20 @@ impl ZMQThread {
         Ok(())
     }
 
-    fn poll_connections(&mut self) -> Result<PollResult, String> {
+    fn poll_connections(&mut self, cmd_recv: &zmq::Socket) -> Result<PollResult, String> {
         let (conn_idx, mut poll_items) = self.get_poll_items();
         let (conn_req, timeout) = self.get_timeout();
         let mut events = vec![];
-        poll_items.push(self.cmd_recv.as_poll_item(zmq::POLLIN));
+        poll_items.push(cmd_recv.as_poll_item(zmq::POLLIN));
         let poll_res = zmq::poll(&mut poll_items, ::std::cmp::max(timeout, 0))
             .map_err(|err| format!("Error polling ZMQ sockets: {:?}", err))?;
         if poll_res == 0 {
-            if let Some((conn_id, last_req)) = conn_req {
-                if let Some((req_id, node_alias)) = last_req {
-                    events.push((conn_id, ConnectionEvent::RequestTimeout(req_id, node_alias)));
-                } else {
-                    events.push((conn_id, ConnectionEvent::Timeout()));
-                }
-            }
+            events.extend(_timeout_event(conn_req));
         } else {
             self.fetch_events_into(conn_idx.as_slice(), poll_items.as_slice(), &mut events);
         }
         if poll_items[poll_items.len() - 1].is_readable() {
-            if let Ok(Ok(msg)) = self.cmd_recv.recv_string(zmq::DONTWAIT) {
+            if let Ok(Ok(msg)) = cmd_recv.recv_string(zmq::DONTWAIT) {
                 if msg == "exit" {
                     return Ok(PollResult::Exit);
                 }
@@ -188,7 +176,28 @@ impl ZMQThread {
         Ok(PollResult::Default)
     }
 
-    fn process_event(&mut self, conn_id: ZMQConnectionHandle, event: ConnectionEvent) {
+    /// Poll the connection sockets without blocking, returning any events
+    /// along with the remaining time in milliseconds before the next timeout
+    #[cfg(all(feature = "async-networker", unix))]
+    pub(super) fn poll_connections_now(
+        &mut self,
+    ) -> Result<(Vec<(ZMQConnectionHandle, ConnectionEvent)>, i64), String> {
+        let (conn_idx, mut poll_items) = self.get_poll_items();
+        let (conn_req, timeout) = self.get_timeout();
+        let mut events = vec![];
+        if !poll_items.is_empty()
+            && zmq::poll(&mut poll_items, 0)
+                .map_err(|err| format!("Error polling ZMQ sockets: {:?}", err))?
+                > 0
+        {
+            self.fetch_events_into(conn_idx.as_slice(), poll_items.as_slice(), &mut events);
+        } else if timeout <= 0 {
+            events.extend(_timeout_event(conn_req));
+        }
+        Ok((events, timeout))
+    }
+
+    pub(super) fn process_event(&mut self, conn_id: ZMQConnectionHandle, event: ConnectionEvent) {
         let (req_id, fwd) = match event {
             ConnectionEvent::Reply(message, meta, node_alias, time) => {
                 let req_id = meta.request_id().unwrap_or_default();
@@ -246,12 +255,20 @@ impl ZMQThread {
         }
     }
 
-    fn try_receive_request(&mut self) -> Result<bool, String> {
-        let event = match self.evt_recv.try_recv() {
+    fn try_receive_request(
+        &mut self,
+        evt_recv: &mpsc::Receiver<NetworkerEvent>,
+    ) -> Result<bool, String> {
+        let event = match evt_recv.try_recv() {
             Ok(request) => request,
             Err(mpsc::TryRecvError::Empty) => return Ok(false),
             Err(err) => return Err(err.to_string()),
         };
+        self.process_networker_event(event);
+        Ok(true)
+    }
+
+    pub(super) fn process_networker_event(&mut self, event: NetworkerEvent) {
         match event {
             NetworkerEvent::NewRequest(handle, sub_id, body, sender) => {
                 // FIXME improve error handling
@@ -262,27 +279,22 @@ impl ZMQThread {
                     trace!("Removing, sender dropped before Init {}", handle);
                     self.remove_request(handle);
                 }
-                Ok(true)
             }
             NetworkerEvent::FinishRequest(handle) => {
                 trace!("Removing, finished {}", handle);
                 self.remove_request(handle);
-                Ok(true)
             }
             NetworkerEvent::Dispatch(handle, node_aliases, timeout) => {
                 // FIXME improve error handling
                 trace!("Dispatch {} {:?}", handle, node_aliases);
                 self.dispatch_request(handle, node_aliases, timeout)
                     .unwrap();
-                Ok(true)
             }
             NetworkerEvent::CleanTimeout(handle, node_alias) => {
                 self.clean_timeout(handle, node_alias).unwrap();
-                Ok(true)
             }
             NetworkerEvent::ExtendTimeout(handle, node_alias, timeout) => {
                 self.extend_timeout(handle, node_alias, timeout).unwrap();
-                Ok(true)
             }
         }
     }
@@ -474,6 +486,17 @@ impl ZMQThread {
             .flat_map(|(handle, conn)| conn.get_poll_items(*handle))
             .unzip()
     }
+
+    /// Get the file descriptors signalling activity on the open sockets
+    #[cfg(all(feature = "async-networker", unix))]
+    pub(super) fn get_socket_fds(
+        &self,
+    ) -> Vec<((ZMQConnectionHandle, usize), std::os::unix::io::RawFd)> {
+        self.pool_connections
+            .iter()
+            .flat_map(|(handle, conn)| conn.get_socket_fds(*handle))
+            .collect()
+    }
 }
 
 pub struct ZMQConnection {
@@ -557,6 +580,22 @@ impl ZMQConnection {
             .collect()
     }
 
+    #[cfg(all(feature = "async-networker", unix))]
+    fn get_socket_fds(
+        &self,
+        handle: ZMQConnectionHandle,
+    ) -> Vec<((ZMQConnectionHandle, usize), std::os::unix::io::RawFd)> {
+        self.sockets
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, zs)| {
+                zs.as_ref()
+                    .and_then(|zs| zs.get_fd().ok())
+                    .map(|fd| ((handle, idx), fd))
+            })
+            .collect()
+    }
+
     fn get_timeout(&self) -> (Option<(String, String)>, i64) {
         let now = Instant::now();
         let (target, expiry) = {
@@ -716,7 +755,7 @@ impl ZMQConnection {
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
-struct RemoteNode {
+pub(super) struct RemoteNode {
     pub name: String,
     pub enc_key: Vec<u8>,
     pub zaddr: String,
@@ -777,7 +816,7 @@ fn _create_pair_of_sockets(addr: &str) -> (zmq::Context, zmq::Socket, zmq::Socke
     (zmq_ctx, send_cmd_sock, recv_cmd_sock)
 }
 
-fn _get_remotes(verifiers: &Verifiers) -> Vec<RemoteNode> {
+pub(super) fn _get_remotes(verifiers: &Verifiers) -> Vec<RemoteNode> {
     verifiers
         .iter()
         .map(|(alias, info)| RemoteNode {
@@ -797,8 +836,21 @@ enum PollResult {
     Exit,
 }
 
+#[allow(clippy::type_complexity)]
+fn _timeout_event(
+    conn_req: Option<(ZMQConnectionHandle, Option<(String, String)>)>,
+) -> Option<(ZMQConnectionHandle, ConnectionEvent)> {
+    conn_req.map(|(conn_id, last_req)| {
+        if let Some((req_id, node_alias)) = last_req {
+            (conn_id, ConnectionEvent::RequestTimeout(req_id, node_alias))
+        } else {
+            (conn_id, ConnectionEvent::Timeout())
+        }
+    })
+}
+
 #[derive(Debug)]
-enum ConnectionEvent {
+pub(super) enum ConnectionEvent {
     Reply(
         String,     // message
         Message,    // parsed
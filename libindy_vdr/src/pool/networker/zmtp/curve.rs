@@ -0,0 +1,89 @@
+//! The NaCl `crypto_box` construction (X25519, XSalsa20 and Poly1305) as required
+//! by the CurveZMQ handshake, provided by the `crypto_box` crate.
+//!
+//! Secret keys and precomputed box keys are zeroized when dropped.
+
+use std::sync::Arc;
+
+use crypto_box::aead::Aead;
+use crypto_box::{Nonce, PublicKey, SalsaBox, SecretKey};
+use rand::RngCore;
+
+pub const KEY_SIZE: usize = crypto_box::KEY_SIZE;
+pub const MAC_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 24;
+
+/// A Curve25519 key pair
+#[derive(Clone)]
+pub struct CurveKeyPair {
+    pub public_key: [u8; KEY_SIZE],
+    secret_key: SecretKey,
+}
+
+impl CurveKeyPair {
+    /// Generate a new random key pair
+    pub fn new() -> Self {
+        let secret_key = SecretKey::generate(&mut rand::thread_rng());
+        Self {
+            public_key: secret_key.public_key().to_bytes(),
+            secret_key,
+        }
+    }
+
+    /// Derive the shared key used to box messages for a peer
+    pub fn precompute(&self, peer_key: &[u8; KEY_SIZE]) -> BoxKey {
+        BoxKey(Arc::new(SalsaBox::new(
+            &PublicKey::from_bytes(*peer_key),
+            &self.secret_key,
+        )))
+    }
+}
+
+/// A precomputed `crypto_box` key shared between two parties
+#[derive(Clone)]
+pub struct BoxKey(Arc<SalsaBox>);
+
+impl BoxKey {
+    /// Encrypt and authenticate a message, returning the MAC followed by the ciphertext
+    pub fn seal(&self, nonce: &[u8; NONCE_SIZE], message: &[u8]) -> Vec<u8> {
+        // encryption only fails when the message exceeds the maximum length
+        self.0
+            .encrypt(Nonce::from_slice(nonce), message)
+            .expect("Error encrypting message")
+    }
+
+    /// Verify and decrypt a message produced by `seal`
+    pub fn open(&self, nonce: &[u8; NONCE_SIZE], boxed: &[u8]) -> Option<Vec<u8>> {
+        self.0.decrypt(Nonce::from_slice(nonce), boxed).ok()
+    }
+}
+
+/// Generate a random nonce suffix of the given size
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut result = [0u8; N];
+    rand::thread_rng().fill_bytes(&mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn box_round_trip() {
+        let alice = CurveKeyPair::new();
+        let bob = CurveKeyPair::new();
+        let nonce = random_bytes::<NONCE_SIZE>();
+        let message = vec![7u8; 131];
+        let boxed = alice.precompute(&bob.public_key).seal(&nonce, &message);
+        assert_eq!(boxed.len(), MAC_SIZE + message.len());
+        let key = bob.precompute(&alice.public_key);
+        assert_eq!(key.open(&nonce, &boxed).unwrap(), message);
+        let mut tampered = boxed.clone();
+        tampered[MAC_SIZE] ^= 1;
+        assert!(key.open(&nonce, &tampered).is_none());
+        let mut tampered = boxed;
+        tampered[0] ^= 1;
+        assert!(key.open(&nonce, &tampered).is_none());
+    }
+}
@@ -0,0 +1,803 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::iter::FromIterator;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use futures_channel::mpsc::UnboundedSender;
+use tokio::runtime::{Builder as RuntimeBuilder, Handle, Runtime};
+
+use crate::common::error::prelude::*;
+use crate::common::handle::ResourceHandle;
+use crate::config::PoolConfig;
+use crate::utils::{base58, base64};
+
+use super::types::{Message, Verifiers};
+use super::{Networker, NetworkerEvent, NetworkerFactory, RequestExtEvent, RequestHandle};
+
+mod curve;
+mod socket;
+
+use self::curve::{CurveKeyPair, KEY_SIZE};
+use self::socket::{SocketEvent, ZMTPSocket};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct ZMTPSocketHandle(pub i64);
+
+impl_sequence_handle!(ZMTPSocketHandle, ZMTPS_COUNTER);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct ZMTPConnectionHandle(pub i64);
+
+impl_sequence_handle!(ZMTPConnectionHandle, ZMTPC_COUNTER);
+
+/// Pure Rust ZeroMQ (ZMTP/CurveZMQ) `NetworkerFactory` implementation
+#[derive(Default)]
+pub struct ZMTPNetworkerFactory;
+
+impl NetworkerFactory for ZMTPNetworkerFactory {
+    type Output = ZMTPNetworker;
+    fn make_networker(
+        &self,
+        config: PoolConfig,
+        verifiers: &Verifiers,
+    ) -> VdrResult<ZMTPNetworker> {
+        let remotes = _get_remotes(verifiers)?;
+        // node connections are served by one task each on a dedicated runtime thread
+        let runtime = RuntimeBuilder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("zmtp-io")
+            .enable_io()
+            .enable_time()
+            .build()
+            .with_err_msg(VdrErrorKind::Resource, "Error creating networker runtime")?;
+        let (evt_send, evt_recv) = mpsc::channel::<WorkerEvent>();
+        let worker_send = evt_send.clone();
+        let worker = thread::spawn(move || {
+            let mut zmtp_thread = ZMTPThread::new(config, runtime, worker_send, evt_recv, remotes);
+            zmtp_thread.work();
+            trace!("ZMTP worker exited");
+        });
+        Ok(ZMTPNetworker {
+            evt_send,
+            worker: Some(worker),
+        })
+    }
+}
+
+/// Pure Rust ZeroMQ (ZMTP/CurveZMQ) `Networker` implementation
+pub struct ZMTPNetworker {
+    evt_send: mpsc::Sender<WorkerEvent>,
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Networker for ZMTPNetworker {
+    fn send(&self, event: NetworkerEvent) -> VdrResult<()> {
+        self.evt_send
+            .send(WorkerEvent::Networker(event))
+            .map_err(|_| err_msg(VdrErrorKind::Resource, "Error sending networker event"))
+    }
+}
+
+impl Drop for ZMTPNetworker {
+    fn drop(&mut self) {
+        if self.evt_send.send(WorkerEvent::Exit).is_err() {
+            trace!("Networker worker already exited")
+        } else {
+            trace!("Networker thread told to exit")
+        }
+        if let Some(worker) = self.worker.take() {
+            debug!("Drop networker thread");
+            worker.join().unwrap()
+        }
+    }
+}
+
+#[derive(Debug)]
+enum WorkerEvent {
+    Networker(NetworkerEvent),
+    Socket(ZMTPConnectionHandle, ZMTPSocketHandle, SocketEvent),
+    Exit,
+}
+
+struct ZMTPThread {
+    config: PoolConfig,
+    runtime: Runtime,
+    evt_send: mpsc::Sender<WorkerEvent>,
+    evt_recv: mpsc::Receiver<WorkerEvent>,
+    node_aliases: HashSet<String>,
+    remotes: Vec<RemoteNode>,
+    requests: BTreeMap<RequestHandle, PendingRequest>,
+    last_connection: Option<ZMTPConnectionHandle>,
+    pool_connections: BTreeMap<ZMTPConnectionHandle, ZMTPConnection>,
+}
+
+impl ZMTPThread {
+    fn new(
+        config: PoolConfig,
+        runtime: Runtime,
+        evt_send: mpsc::Sender<WorkerEvent>,
+        evt_recv: mpsc::Receiver<WorkerEvent>,
+        remotes: Vec<RemoteNode>,
+    ) -> Self {
+        let node_aliases = HashSet::from_iter(remotes.iter().map(|r| r.name.clone()));
+        ZMTPThread {
+            config,
+            runtime,
+            evt_send,
+            evt_recv,
+            node_aliases,
+            remotes,
+            requests: BTreeMap::new(),
+            last_connection: None,
+            pool_connections: BTreeMap::new(),
+        }
+    }
+
+    fn work(&mut self) {
+        loop {
+            let (conn_req, timeout) = self.get_timeout();
+            if timeout <= 0 {
+                if let Some((conn_id, last_req)) = conn_req {
+                    self.process_timeout(conn_id, last_req);
+                    continue;
+                }
+            }
+            let event = if timeout == i64::MAX {
+                self.evt_recv.recv().ok()
+            } else {
+                match self
+                    .evt_recv
+                    .recv_timeout(Duration::from_millis(timeout as u64))
+                {
+                    Ok(event) => Some(event),
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => None,
+                }
+            };
+            match event {
+                Some(WorkerEvent::Networker(event)) => self.process_networker_event(event),
+                Some(WorkerEvent::Socket(conn_id, socket_id, event)) => {
+                    self.process_socket_event(conn_id, socket_id, event)
+                }
+                Some(WorkerEvent::Exit) | None => {
+                    trace!("Networker thread ended");
+                    break;
+                }
+            }
+        }
+    }
+
+    fn process_timeout(
+        &mut self,
+        conn_id: ZMTPConnectionHandle,
+        last_req: Option<(String, String)>,
+    ) {
+        match last_req {
+            Some((req_id, node_alias)) if node_alias.is_empty() => {
+                if let Some(handle) = self.select_request(conn_id, &req_id) {
+                    trace!("Remove idle {}", handle);
+                    self.remove_request(handle);
+                } else if let Some(conn) = self.pool_connections.get_mut(&conn_id) {
+                    conn.clean_timeout(&req_id, None);
+                }
+            }
+            Some((req_id, node_alias)) => {
+                if let Some(conn) = self.pool_connections.get_mut(&conn_id) {
+                    conn.clean_timeout(&req_id, Some(node_alias.clone()));
+                }
+                if let Some(handle) = self.select_request(conn_id, &req_id) {
+                    self.process_reply(handle, RequestExtEvent::Timeout(node_alias))
+                }
+            }
+            None => {
+                self.check_remove_connection(conn_id, None);
+            }
+        }
+    }
+
+    fn process_socket_event(
+        &mut self,
+        conn_id: ZMTPConnectionHandle,
+        socket_id: ZMTPSocketHandle,
+        event: SocketEvent,
+    ) {
+        let conn = if let Some(conn) = self.pool_connections.get_mut(&conn_id) {
+            conn
+        } else {
+            trace!(
+                "Connection removed before socket could be read: {}",
+                conn_id
+            );
+            return;
+        };
+        let node_alias = if let Some(alias) = conn.socket_alias(socket_id) {
+            alias
+        } else {
+            trace!("Socket closed before it could be read: {}", socket_id);
+            return;
+        };
+        match event {
+            SocketEvent::Message(msg) => {
+                let msg = match String::from_utf8(msg) {
+                    Ok(msg) => msg,
+                    Err(_) => {
+                        debug!("Error decoding received message from {}", node_alias);
+                        return;
+                    }
+                };
+                trace!("Socket reply {} {}", &node_alias, &msg);
+                match Message::from_raw_str(msg.as_str()) {
+                    Ok(meta) => {
+                        let req_id = meta.request_id().unwrap_or_default();
+                        conn.clean_idle_timeout(&req_id);
+                        if let Some(handle) = self.select_request(conn_id, &req_id) {
+                            self.process_reply(
+                                handle,
+                                RequestExtEvent::Received(node_alias, msg, meta, SystemTime::now()),
+                            )
+                        } else {
+                            trace!("Unknown request ID: {}", req_id)
+                        }
+                    }
+                    Err(err) => debug!("Error parsing received message: {:?}", err),
+                }
+            }
+            SocketEvent::Closed(reason) => {
+                // the socket will be reopened if another request is sent to the node
+                debug!("Connection to {} closed: {}", node_alias, reason);
+                conn.close_socket(socket_id);
+            }
+        }
+    }
+
+    fn select_request(&self, conn_id: ZMTPConnectionHandle, sub_id: &str) -> Option<RequestHandle> {
+        self.requests.iter().find_map(|(handle, req)| {
+            if req.conn_id == conn_id && req.sub_id == sub_id {
+                Some(*handle)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn process_reply(&mut self, handle: RequestHandle, event: RequestExtEvent) {
+        if let Some(req) = self.requests.get_mut(&handle) {
+            if !req.send_event(event) {
+                trace!("Removing, sender disconnected {}", handle);
+                self.remove_request(handle)
+            }
+        } else {
+            trace!("Request ID not found: {}", handle);
+        }
+    }
+
+    fn process_networker_event(&mut self, event: NetworkerEvent) {
+        match event {
+            NetworkerEvent::NewRequest(handle, sub_id, body, sender) => {
+                trace!("New request {}", handle);
+                let pending = self.add_request(handle, sub_id, body, sender);
+                if !pending.send_event(RequestExtEvent::Init) {
+                    trace!("Removing, sender dropped before Init {}", handle);
+                    self.remove_request(handle);
+                }
+            }
+            NetworkerEvent::FinishRequest(handle) => {
+                trace!("Removing, finished {}", handle);
+                self.remove_request(handle);
+            }
+            NetworkerEvent::Dispatch(handle, node_aliases, timeout) => {
+                trace!("Dispatch {} {:?}", handle, node_aliases);
+                self.dispatch_request(handle, node_aliases, timeout);
+            }
+            NetworkerEvent::CleanTimeout(handle, node_alias) => {
+                if let Some(request) = self.requests.get(&handle) {
+                    if let Some(conn) = self.pool_connections.get_mut(&request.conn_id) {
+                        conn.clean_timeout(request.sub_id.as_str(), Some(node_alias))
+                    }
+                }
+            }
+            NetworkerEvent::ExtendTimeout(handle, node_alias, timeout) => {
+                if let Some(request) = self.requests.get(&handle) {
+                    if let Some(conn) = self.pool_connections.get_mut(&request.conn_id) {
+                        conn.extend_timeout(request.sub_id.as_str(), node_alias.as_str(), timeout)
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_request(
+        &mut self,
+        handle: RequestHandle,
+        sub_id: String,
+        body: String,
+        sender: UnboundedSender<RequestExtEvent>,
+    ) -> &mut PendingRequest {
+        let conn_id = self.get_active_connection(self.last_connection, sub_id.clone());
+        let pending = PendingRequest {
+            conn_id,
+            sender,
+            sub_id,
+            body,
+        };
+        self.requests.entry(handle).or_insert(pending)
+    }
+
+    fn remove_request(&mut self, handle: RequestHandle) {
+        if let Some(req) = self.requests.remove(&handle) {
+            self.check_remove_connection(req.conn_id, Some(&req.sub_id));
+        }
+    }
+
+    fn check_remove_connection(&mut self, handle: ZMTPConnectionHandle, sub_id: Option<&str>) {
+        if let Some(conn) = self.pool_connections.get_mut(&handle) {
+            if let Some(sub_id) = sub_id {
+                conn.clean_timeout(sub_id, None);
+            }
+            if conn.is_idle() {
+                trace!("Removing pool connection {}", handle);
+                self.pool_connections.remove(&handle);
+            }
+        }
+    }
+
+    fn dispatch_request(&mut self, handle: RequestHandle, node_aliases: Vec<String>, timeout: i64) {
+        let request = if let Some(request) = self.requests.get_mut(&handle) {
+            request
+        } else {
+            debug!("Unknown request ID for dispatch: {}", handle);
+            return;
+        };
+        let conn = if let Some(conn) = self.pool_connections.get_mut(&request.conn_id) {
+            conn
+        } else {
+            warn!("Removing, pool connection expired {}", handle);
+            self.remove_request(handle);
+            return;
+        };
+        for node_alias in node_aliases {
+            if !self.node_aliases.contains(&node_alias) {
+                warn!("Cannot send to unknown node alias: {}", node_alias);
+                continue;
+            }
+            conn.send_request(
+                request.conn_id,
+                &self.evt_send,
+                request.sub_id.clone(),
+                request.body.as_str(),
+                node_alias.clone(),
+                timeout,
+            );
+            if !request.send_event(RequestExtEvent::Sent(node_alias, SystemTime::now())) {
+                trace!("Removing, sender disconnected {}", handle);
+                self.remove_request(handle);
+                break;
+            }
+        }
+    }
+
+    fn get_active_connection(
+        &mut self,
+        conn_id: Option<ZMTPConnectionHandle>,
+        sub_id: String,
+    ) -> ZMTPConnectionHandle {
+        let req_limit = self.config.conn_request_limit;
+        let conn = conn_id
+            .and_then(|conn_id| self.pool_connections.get_mut(&conn_id))
+            .filter(|conn| {
+                conn.is_active() && conn.req_cnt < req_limit && !conn.seen_request(sub_id.as_str())
+            });
+        if let Some(conn) = conn {
+            conn.init_request(sub_id);
+            conn_id.unwrap()
+        } else {
+            let mut conn = ZMTPConnection::new(
+                self.runtime.handle().clone(),
+                self.remotes.clone(),
+                self.config.conn_active_timeout,
+                self.config.ack_timeout,
+                self.config.socks_proxy.clone(),
+            );
+            trace!("Created new pool connection");
+            conn.init_request(sub_id);
+            let pc_id = ZMTPConnectionHandle::next();
+            self.pool_connections.insert(pc_id, conn);
+            self.last_connection.replace(pc_id);
+            debug!("New {}", pc_id);
+            pc_id
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn get_timeout(
+        &self,
+    ) -> (
+        Option<(ZMTPConnectionHandle, Option<(String, String)>)>,
+        i64,
+    ) {
+        self.pool_connections
+            .iter()
+            .map(|(&handle, conn)| {
+                let (last_req, timeout) = conn.get_timeout();
+                (Some((handle, last_req)), timeout)
+            })
+            .min_by(|&(_, val1), &(_, val2)| val1.cmp(&val2))
+            .unwrap_or((None, i64::MAX))
+    }
+}
+
+struct ZMTPConnection {
+    runtime: Handle,
+    remotes: Vec<RemoteNode>,
+    sockets: Vec<Option<(ZMTPSocketHandle, ZMTPSocket)>>,
+    key_pair: CurveKeyPair,
+    idle_timeouts: HashMap<String, Instant>,
+    socket_timeouts: HashMap<(String, String), Instant>,
+    time_created: Instant,
+    req_cnt: usize,
+    req_log: HashSet<String>,
+    active_timeout: i64,
+    idle_timeout: i64,
+    socks_proxy: Option<String>,
+}
+
+impl ZMTPConnection {
+    fn new(
+        runtime: Handle,
+        remotes: Vec<RemoteNode>,
+        active_timeout: i64,
+        idle_timeout: i64,
+        socks_proxy: Option<String>,
+    ) -> Self {
+        trace!("ZMTPConnection::new: from remotes {:?}", remotes);
+        let sockets = remotes.iter().map(|_| None).collect();
+        Self {
+            runtime,
+            remotes,
+            sockets,
+            key_pair: CurveKeyPair::new(),
+            time_created: Instant::now(),
+            idle_timeouts: HashMap::new(),
+            socket_timeouts: HashMap::new(),
+            req_cnt: 0,
+            req_log: HashSet::new(),
+            active_timeout,
+            idle_timeout,
+            socks_proxy,
+        }
+    }
+
+    fn socket_alias(&self, socket_id: ZMTPSocketHandle) -> Option<String> {
+        self.sockets.iter().enumerate().find_map(|(idx, socket)| {
+            socket
+                .as_ref()
+                .filter(|(handle, _)| *handle == socket_id)
+                .map(|_| self.remotes[idx].name.clone())
+        })
+    }
+
+    fn close_socket(&mut self, socket_id: ZMTPSocketHandle) {
+        for socket in self.sockets.iter_mut() {
+            if matches!(socket, Some((handle, _)) if *handle == socket_id) {
+                socket.take();
+            }
+        }
+    }
+
+    fn get_timeout(&self) -> (Option<(String, String)>, i64) {
+        let now = Instant::now();
+        let (target, expiry) = {
+            let min_idle = self
+                .idle_timeouts
+                .iter()
+                .min_by(|&(_, inst1), &(_, inst2)| inst1.cmp(inst2))
+                .map(|(req_id, inst)| ((req_id.as_str(), ""), inst));
+            if let Some(((req_id, node_alias), timeout)) = self
+                .socket_timeouts
+                .iter()
+                .map(|((req_id, alias), inst)| ((req_id.as_str(), alias.as_str()), inst))
+                .chain(min_idle)
+                .min_by(|(_, ref val1), (_, ref val2)| val1.cmp(val2))
+            {
+                (Some((req_id.to_string(), node_alias.to_string())), *timeout)
+            } else {
+                (
+                    None,
+                    self.time_created + Duration::new(self.active_timeout.max(0) as u64, 0),
+                )
+            }
+        };
+        (
+            target,
+            expiry
+                .checked_duration_since(now)
+                .unwrap_or(Duration::new(0, 0))
+                .as_millis() as i64,
+        )
+    }
+
+    fn is_active(&self) -> bool {
+        self.time_created.elapsed() < Duration::from_secs(self.active_timeout.max(0) as u64)
+    }
+
+    fn send_request(
+        &mut self,
+        conn_id: ZMTPConnectionHandle,
+        evt_send: &mpsc::Sender<WorkerEvent>,
+        req_id: String,
+        msg: &str,
+        node_alias: String,
+        timeout: i64,
+    ) {
+        trace!("send_request >> req_id: {} node: {}", req_id, node_alias);
+        let node_index = self.remotes.iter().position(|node| node.name == node_alias);
+        if let Some(node_index) = node_index {
+            let socket = self.get_socket(conn_id, node_index, evt_send);
+            if let Err(err) = socket.send(msg.as_bytes()) {
+                // the request will time out for this node
+                debug!("Error sending to {}: {}", node_alias, err);
+            }
+        } else {
+            warn!("Cannot send to unknown node alias: {}", node_alias);
+            return;
+        }
+        if self.idle_timeouts.contains_key(&req_id) {
+            // will only be present if this request has received no responses
+            self.set_idle_timeout(req_id.clone());
+        }
+        self.add_timeout(req_id, node_alias, timeout);
+        trace!("send_request <<");
+    }
+
+    fn add_timeout(&mut self, req_id: String, alias: String, timeout: i64) {
+        self.socket_timeouts.insert(
+            (req_id, alias),
+            Instant::now() + Duration::from_secs(timeout.max(0) as u64),
+        );
+    }
+
+    fn extend_timeout(&mut self, req_id: &str, node_alias: &str, extended_timeout: i64) {
+        if let Some(timeout) = self
+            .socket_timeouts
+            .get_mut(&(req_id.to_string(), node_alias.to_string()))
+        {
+            *timeout = Instant::now() + Duration::from_secs(extended_timeout.max(0) as u64)
+        } else {
+            debug!("late REQACK for req_id {}, node {}", req_id, node_alias);
+        }
+    }
+
+    fn clean_timeout(&mut self, req_id: &str, node_alias: Option<String>) {
+        match node_alias {
+            Some(node_alias) => {
+                if self
+                    .socket_timeouts
+                    .remove(&(req_id.to_string(), node_alias))
+                    .is_some()
+                    && !self
+                        .socket_timeouts
+                        .keys()
+                        .any(|(ref req_id_timeout, _)| req_id == req_id_timeout)
+                {
+                    self.set_idle_timeout(req_id.to_string())
+                }
+            }
+            None => {
+                self.socket_timeouts
+                    .retain(|(req_id_timeout, _), _| req_id != req_id_timeout);
+                self.idle_timeouts.remove(req_id);
+            }
+        }
+    }
+
+    fn set_idle_timeout(&mut self, req_id: String) {
+        self.idle_timeouts.insert(
+            req_id,
+            Instant::now() + Duration::from_secs(self.idle_timeout.max(0) as u64),
+        );
+    }
+
+    fn clean_idle_timeout(&mut self, req_id: &str) {
+        self.idle_timeouts.remove(req_id);
+    }
+
+    fn init_request(&mut self, req_id: String) {
+        self.req_cnt += 1;
+        self.req_log.insert(req_id.clone());
+        self.set_idle_timeout(req_id);
+    }
+
+    fn seen_request(&self, req_id: &str) -> bool {
+        self.req_log.contains(req_id)
+    }
+
+    fn has_active_requests(&self) -> bool {
+        !(self.socket_timeouts.is_empty() && self.idle_timeouts.is_empty())
+    }
+
+    fn is_idle(&self) -> bool {
+        !self.is_active() && !self.has_active_requests()
+    }
+
+    fn get_socket(
+        &mut self,
+        conn_id: ZMTPConnectionHandle,
+        idx: usize,
+        evt_send: &mpsc::Sender<WorkerEvent>,
+    ) -> &ZMTPSocket {
+        let remote = &self.remotes[idx];
+        let runtime = &self.runtime;
+        let key_pair = &self.key_pair;
+        let socks_proxy = &self.socks_proxy;
+        let (_, socket) = self.sockets[idx].get_or_insert_with(|| {
+            debug!("Open new socket for node {}", &remote.name);
+            let socket_id = ZMTPSocketHandle::next();
+            let evt_send = evt_send.clone();
+            let socket = ZMTPSocket::connect(
+                runtime,
+                remote.zaddr.clone(),
+                remote.enc_key,
+                key_pair,
+                base64::encode(key_pair.public_key).into_bytes(),
+                socks_proxy.clone(),
+                move |event| {
+                    evt_send
+                        .send(WorkerEvent::Socket(conn_id, socket_id, event))
+                        .is_ok()
+                },
+            );
+            (socket_id, socket)
+        });
+        socket
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct RemoteNode {
+    pub name: String,
+    pub enc_key: [u8; KEY_SIZE],
+    pub zaddr: String,
+}
+
+impl std::fmt::Debug for RemoteNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pubkey = base58::encode(self.enc_key);
+        write!(
+            f,
+            "RemoteNode {{ name: {}, public_key: {}, zaddr: {} }}",
+            self.name, pubkey, self.zaddr
+        )
+    }
+}
+
+fn _get_remotes(verifiers: &Verifiers) -> VdrResult<Vec<RemoteNode>> {
+    verifiers
+        .iter()
+        .map(|(alias, info)| {
+            let enc_key =
+                info.enc_key.as_slice().try_into().map_err(|_| {
+                    input_err(format!("Invalid encryption key for node: {}", alias))
+                })?;
+            Ok(RemoteNode {
+                name: alias.clone(),
+                enc_key,
+                zaddr: info.client_addr.clone(),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct PendingRequest {
+    conn_id: ZMTPConnectionHandle,
+    sender: UnboundedSender<RequestExtEvent>,
+    sub_id: String,
+    body: String,
+}
+
+impl PendingRequest {
+    fn send_event(&mut self, event: RequestExtEvent) -> bool {
+        self.sender.unbounded_send(event).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_channel::mpsc::unbounded;
+    use futures_executor::block_on;
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::pool::VerifierInfo;
+
+    const NODE_ALIAS: &str = "Node1";
+
+    #[test]
+    fn get_remotes_checks_key_length() {
+        let verifiers = Verifiers::from_iter([(
+            NODE_ALIAS.to_string(),
+            VerifierInfo {
+                client_addr: "tcp://127.0.0.1:9702".to_string(),
+                node_addr: String::new(),
+                public_key: String::new(),
+                enc_key: vec![0u8; 16],
+                bls_key: None,
+            },
+        )]);
+        assert!(_get_remotes(&verifiers).is_err());
+    }
+
+    #[cfg(feature = "zmq")]
+    #[test]
+    fn networker_exchanges_messages_with_libzmq() {
+        let ctx = zmq::Context::new();
+        let key_pair = zmq::CurveKeyPair::new().unwrap();
+        let socket = ctx.socket(zmq::SocketType::ROUTER).unwrap();
+        socket.set_curve_server(true).unwrap();
+        socket.set_curve_secretkey(&key_pair.secret_key).unwrap();
+        socket.set_linger(0).unwrap();
+        socket.bind("tcp://127.0.0.1:*").unwrap();
+        let client_addr = socket.get_last_endpoint().unwrap().unwrap();
+        let node = thread::spawn(move || {
+            let msg = socket.recv_multipart(0).unwrap();
+            assert_eq!(msg[1], b"request");
+            // exceed the short frame size to exercise long frames
+            let reply = json!({"op": "REQACK", "reqId": 1, "padding": "x".repeat(300)});
+            let reply = reply.to_string();
+            socket
+                .send_multipart([msg[0].as_slice(), reply.as_bytes()], 0)
+                .unwrap();
+            msg[0].clone()
+        });
+        let verifiers = Verifiers::from_iter([(
+            NODE_ALIAS.to_string(),
+            VerifierInfo {
+                client_addr,
+                node_addr: String::new(),
+                public_key: String::new(),
+                enc_key: key_pair.public_key.to_vec(),
+                bls_key: None,
+            },
+        )]);
+
+        let networker = ZMTPNetworkerFactory
+            .make_networker(PoolConfig::default(), &verifiers)
+            .unwrap();
+        let handle = RequestHandle::next();
+        let (sender, mut events) = unbounded();
+        networker
+            .send(NetworkerEvent::NewRequest(
+                handle,
+                "1".to_string(),
+                "request".to_string(),
+                sender,
+            ))
+            .unwrap();
+        networker
+            .send(NetworkerEvent::Dispatch(
+                handle,
+                vec![NODE_ALIAS.to_string()],
+                10,
+            ))
+            .unwrap();
+        block_on(async {
+            assert!(matches!(events.next().await, Some(RequestExtEvent::Init)));
+            assert!(matches!(
+                events.next().await,
+                Some(RequestExtEvent::Sent(alias, _)) if alias == NODE_ALIAS
+            ));
+            assert!(matches!(
+                events.next().await,
+                Some(RequestExtEvent::Received(alias, _, Message::ReqACK(_), _)) if alias == NODE_ALIAS
+            ));
+        });
+        let identity = node.join().unwrap();
+        assert_eq!(identity.len(), 44);
+        networker
+            .send(NetworkerEvent::FinishRequest(handle))
+            .unwrap();
+    }
+}
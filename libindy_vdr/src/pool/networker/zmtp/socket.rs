@@ -0,0 +1,478 @@
+//! ZMTP 3.0 DEALER socket using the CURVE security mechanism
+
+use std::io;
+use std::time::Duration;
+
+use futures_util::future::{select, Either};
+use futures_util::pin_mut;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+use super::curve::{random_bytes, BoxKey, CurveKeyPair, KEY_SIZE, MAC_SIZE, NONCE_SIZE};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Upper bound on a single frame body, well above the largest ledger reply
+const MAX_FRAME_SIZE: u64 = 8 * 1024 * 1024;
+
+const FLAG_MORE: u8 = 0x01;
+const FLAG_LONG: u8 = 0x02;
+const FLAG_COMMAND: u8 = 0x04;
+
+const COOKIE_SIZE: usize = 96;
+
+/// An event produced by a socket task
+#[derive(Debug)]
+pub enum SocketEvent {
+    Message(Vec<u8>),
+    Closed(String),
+}
+
+/// A connection to a single remote node, with messages sent and received by
+/// a single task on the networker runtime
+pub struct ZMTPSocket {
+    send: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+impl ZMTPSocket {
+    /// Start connecting to the given address, delivering received messages
+    /// and connection failures to `on_event`
+    pub fn connect<F>(
+        runtime: &Handle,
+        addr: String,
+        server_key: [u8; KEY_SIZE],
+        key_pair: &CurveKeyPair,
+        identity: Vec<u8>,
+        socks_proxy: Option<String>,
+        mut on_event: F,
+    ) -> Self
+    where
+        F: FnMut(SocketEvent) -> bool + Send + 'static,
+    {
+        let (send, recv) = mpsc::unbounded_channel::<Vec<u8>>();
+        let handshake = Handshake {
+            server_key,
+            key_pair: key_pair.clone(),
+            identity,
+        };
+        runtime.spawn(async move {
+            let reason = _run(&addr, socks_proxy, handshake, recv, &mut on_event)
+                .await
+                .unwrap_or_else(|err| format!("Error on connection to {}: {}", addr, err));
+            on_event(SocketEvent::Closed(reason));
+        });
+        Self { send }
+    }
+
+    /// Queue a message for delivery
+    pub fn send(&self, message: &[u8]) -> Result<(), String> {
+        self.send
+            .send(message.to_vec())
+            .map_err(|_| "Socket closed".to_string())
+    }
+}
+
+/// Connect and perform the handshake, then exchange messages until the socket is
+/// dropped or the connection fails, returning the reason the connection was closed
+async fn _run<F>(
+    addr: &str,
+    socks_proxy: Option<String>,
+    handshake: Handshake,
+    mut recv: mpsc::UnboundedReceiver<Vec<u8>>,
+    on_event: &mut F,
+) -> io::Result<String>
+where
+    F: FnMut(SocketEvent) -> bool,
+{
+    let (mut stream, (mut send_state, mut recv_state)) = timeout(CONNECT_TIMEOUT, async {
+        let mut stream = _connect(addr, socks_proxy.as_deref()).await?;
+        let session = handshake.run(&mut stream).await?;
+        io::Result::Ok((stream, session))
+    })
+    .await
+    .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "Connection timed out"))??;
+    trace!("ZMTP handshake complete: {}", addr);
+
+    let mut buf = Vec::new();
+    let mut parts = Vec::new();
+    loop {
+        while let Some((flags, frame)) = _parse_frame(&mut buf)? {
+            if flags & FLAG_COMMAND != 0 && !frame.starts_with(b"\x07MESSAGE") {
+                if frame.starts_with(b"\x05ERROR") {
+                    return Ok(format!("Connection error: {}", _error_reason(&frame[6..])));
+                }
+                // ignore heartbeats and other commands
+                continue;
+            }
+            let (flags, message) = recv_state.decode(&frame)?;
+            if flags & FLAG_COMMAND != 0 {
+                continue;
+            }
+            parts.extend_from_slice(&message);
+            if flags & FLAG_MORE == 0 && !on_event(SocketEvent::Message(std::mem::take(&mut parts)))
+            {
+                return Ok("Receiver closed".to_string());
+            }
+        }
+        let next = {
+            // `read_buf` may be cancelled without losing received data
+            let message = recv.recv();
+            let read = stream.read_buf(&mut buf);
+            pin_mut!(message, read);
+            match select(message, read).await {
+                Either::Left((message, _)) => Either::Left(message),
+                Either::Right((read, _)) => Either::Right(read),
+            }
+        };
+        match next {
+            Either::Left(Some(message)) => {
+                let frame = send_state.encode(&message);
+                _write_frame(&mut stream, 0, &frame).await?;
+            }
+            Either::Left(None) => return Ok("Socket closed".to_string()),
+            Either::Right(read) => {
+                if read? == 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Connection closed by peer",
+                    ));
+                }
+            }
+        }
+    }
+}
+
+async fn _connect(addr: &str, socks_proxy: Option<&str>) -> io::Result<TcpStream> {
+    let target = addr.strip_prefix("tcp://").unwrap_or(addr);
+    let stream = if let Some(proxy) = socks_proxy {
+        let mut stream = TcpStream::connect(proxy).await?;
+        _socks_connect(&mut stream, target).await?;
+        stream
+    } else {
+        TcpStream::connect(target).await?
+    };
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+/// Establish a SOCKS5 tunnel to `target` without authentication
+async fn _socks_connect(stream: &mut TcpStream, target: &str) -> io::Result<()> {
+    let (host, port) = target
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+        .ok_or_else(|| _invalid_data("Invalid target address"))?;
+    if host.len() > 255 {
+        return Err(_invalid_data("Invalid target address"));
+    }
+    stream.write_all(&[5, 1, 0]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply != [5, 0] {
+        return Err(_invalid_data("SOCKS proxy authentication not supported"));
+    }
+    let mut request = vec![5, 1, 0, 3, host.len() as u8];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != 5 || reply[1] != 0 {
+        return Err(_invalid_data("SOCKS proxy connection failed"));
+    }
+    let addr_len = match reply[3] {
+        1 => 4,
+        4 => 16,
+        3 => stream.read_u8().await? as usize,
+        _ => return Err(_invalid_data("Invalid SOCKS proxy response")),
+    };
+    let mut bound = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+fn _invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+struct Handshake {
+    server_key: [u8; KEY_SIZE],
+    key_pair: CurveKeyPair,
+    identity: Vec<u8>,
+}
+
+impl Handshake {
+    async fn run<S>(self, stream: &mut S) -> io::Result<(SessionState, SessionState)>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        stream.write_all(&_greeting()).await?;
+        let mut greeting = [0u8; 64];
+        stream.read_exact(&mut greeting).await?;
+        if greeting[0] != 0xff || greeting[9] & 0x01 == 0 || greeting[10] < 3 {
+            return Err(_invalid_data("Unsupported ZMTP version"));
+        }
+        if &greeting[12..17] != b"CURVE" || greeting[17] != 0 {
+            return Err(_invalid_data("Unsupported security mechanism"));
+        }
+
+        // the client transient key pair
+        let transient = CurveKeyPair::new();
+        let transient_box = transient.precompute(&self.server_key);
+        let mut nonce = 1u64;
+
+        let mut hello = Vec::with_capacity(200);
+        hello.extend_from_slice(b"\x05HELLO\x01\x00");
+        hello.extend_from_slice(&[0u8; 72]);
+        hello.extend_from_slice(&transient.public_key);
+        hello.extend_from_slice(&nonce.to_be_bytes());
+        hello.extend_from_slice(
+            &transient_box.seal(&_short_nonce(b"CurveZMQHELLO---", nonce), &[0u8; 64]),
+        );
+        _write_frame(stream, FLAG_COMMAND, &hello).await?;
+        nonce += 1;
+
+        let welcome = _read_command(stream, b"WELCOME").await?;
+        if welcome.len() != 16 + MAC_SIZE + KEY_SIZE + COOKIE_SIZE {
+            return Err(_invalid_data("Invalid WELCOME command"));
+        }
+        let welcome_nonce = _long_nonce(b"WELCOME-", &welcome[..16]);
+        let welcome = transient_box
+            .open(&welcome_nonce, &welcome[16..])
+            .ok_or_else(|| _invalid_data("Invalid WELCOME box"))?;
+        let mut server_transient = [0u8; KEY_SIZE];
+        server_transient.copy_from_slice(&welcome[..KEY_SIZE]);
+        let cookie = &welcome[KEY_SIZE..];
+        let session_box = transient.precompute(&server_transient);
+
+        let vouch_nonce = random_bytes::<16>();
+        let mut vouch_data = Vec::with_capacity(KEY_SIZE * 2);
+        vouch_data.extend_from_slice(&transient.public_key);
+        vouch_data.extend_from_slice(&self.server_key);
+        let vouch = self
+            .key_pair
+            .precompute(&server_transient)
+            .seal(&_long_nonce(b"VOUCH---", &vouch_nonce), &vouch_data);
+        let mut initiate_data = Vec::new();
+        initiate_data.extend_from_slice(&self.key_pair.public_key);
+        initiate_data.extend_from_slice(&vouch_nonce);
+        initiate_data.extend_from_slice(&vouch);
+        _add_property(&mut initiate_data, "Socket-Type", b"DEALER");
+        _add_property(&mut initiate_data, "Identity", &self.identity);
+        let mut initiate = Vec::new();
+        initiate.extend_from_slice(b"\x08INITIATE");
+        initiate.extend_from_slice(cookie);
+        initiate.extend_from_slice(&nonce.to_be_bytes());
+        initiate.extend_from_slice(
+            &session_box.seal(&_short_nonce(b"CurveZMQINITIATE", nonce), &initiate_data),
+        );
+        _write_frame(stream, FLAG_COMMAND, &initiate).await?;
+        nonce += 1;
+
+        let ready = _read_command(stream, b"READY").await?;
+        if ready.len() < 8 + MAC_SIZE {
+            return Err(_invalid_data("Invalid READY command"));
+        }
+        let ready_nonce = u64::from_be_bytes(ready[..8].try_into().unwrap());
+        session_box
+            .open(&_short_nonce(b"CurveZMQREADY---", ready_nonce), &ready[8..])
+            .ok_or_else(|| _invalid_data("Invalid READY box"))?;
+
+        Ok((
+            SessionState {
+                key: session_box.clone(),
+                nonce,
+            },
+            SessionState {
+                key: session_box,
+                nonce: ready_nonce,
+            },
+        ))
+    }
+}
+
+struct SessionState {
+    key: BoxKey,
+    nonce: u64,
+}
+
+impl SessionState {
+    fn encode(&mut self, message: &[u8]) -> Vec<u8> {
+        let mut plain = Vec::with_capacity(message.len() + 1);
+        plain.push(0);
+        plain.extend_from_slice(message);
+        let mut result = Vec::with_capacity(16 + MAC_SIZE + plain.len());
+        result.extend_from_slice(b"\x07MESSAGE");
+        result.extend_from_slice(&self.nonce.to_be_bytes());
+        result.extend_from_slice(
+            &self
+                .key
+                .seal(&_short_nonce(b"CurveZMQMESSAGEC", self.nonce), &plain),
+        );
+        self.nonce += 1;
+        result
+    }
+
+    fn decode(&mut self, frame: &[u8]) -> io::Result<(u8, Vec<u8>)> {
+        if frame.len() < 16 + MAC_SIZE + 1 || &frame[..8] != b"\x07MESSAGE" {
+            return Err(_invalid_data("Invalid MESSAGE command"));
+        }
+        let nonce = u64::from_be_bytes(frame[8..16].try_into().unwrap());
+        if nonce <= self.nonce {
+            return Err(_invalid_data("Invalid MESSAGE nonce"));
+        }
+        self.nonce = nonce;
+        let mut plain = self
+            .key
+            .open(&_short_nonce(b"CurveZMQMESSAGES", nonce), &frame[16..])
+            .ok_or_else(|| _invalid_data("Invalid MESSAGE box"))?;
+        let flags = plain.remove(0);
+        Ok((flags, plain))
+    }
+}
+
+fn _greeting() -> [u8; 64] {
+    let mut greeting = [0u8; 64];
+    greeting[0] = 0xff;
+    greeting[9] = 0x7f;
+    greeting[10] = 3;
+    greeting[12..17].copy_from_slice(b"CURVE");
+    greeting
+}
+
+fn _short_nonce(prefix: &[u8; 16], nonce: u64) -> [u8; NONCE_SIZE] {
+    let mut result = [0u8; NONCE_SIZE];
+    result[..16].copy_from_slice(prefix);
+    result[16..].copy_from_slice(&nonce.to_be_bytes());
+    result
+}
+
+fn _long_nonce(prefix: &[u8; 8], nonce: &[u8]) -> [u8; NONCE_SIZE] {
+    let mut result = [0u8; NONCE_SIZE];
+    result[..8].copy_from_slice(prefix);
+    result[8..].copy_from_slice(nonce);
+    result
+}
+
+fn _add_property(buf: &mut Vec<u8>, name: &str, value: &[u8]) {
+    buf.push(name.len() as u8);
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    buf.extend_from_slice(value);
+}
+
+fn _error_reason(data: &[u8]) -> String {
+    data.split_first()
+        .map(|(len, reason)| {
+            String::from_utf8_lossy(&reason[..(*len as usize).min(reason.len())]).into_owned()
+        })
+        .unwrap_or_default()
+}
+
+async fn _read_command<S>(stream: &mut S, name: &[u8]) -> io::Result<Vec<u8>>
+where
+    S: AsyncRead + Unpin,
+{
+    let (flags, command) = _read_frame(stream).await?;
+    if flags & FLAG_COMMAND == 0 || command.is_empty() {
+        return Err(_invalid_data("Expected ZMTP command"));
+    }
+    let name_len = command[0] as usize;
+    let cmd_name = command.get(1..1 + name_len).unwrap_or_default();
+    let data = command.get(1 + name_len..).unwrap_or_default();
+    if cmd_name == b"ERROR" {
+        let reason = _error_reason(data);
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("Handshake rejected: {}", reason),
+        ));
+    }
+    if cmd_name != name {
+        return Err(_invalid_data("Unexpected ZMTP command"));
+    }
+    Ok(data.to_vec())
+}
+
+async fn _read_frame<S>(stream: &mut S) -> io::Result<(u8, Vec<u8>)>
+where
+    S: AsyncRead + Unpin,
+{
+    let flags = stream.read_u8().await?;
+    let size = if flags & FLAG_LONG != 0 {
+        stream.read_u64().await?
+    } else {
+        stream.read_u8().await? as u64
+    };
+    if size > MAX_FRAME_SIZE {
+        return Err(_invalid_data("ZMTP frame exceeds maximum size"));
+    }
+    let mut body = vec![0u8; size as usize];
+    stream.read_exact(&mut body).await?;
+    Ok((flags, body))
+}
+
+/// Remove the first complete frame from a buffer of received data
+fn _parse_frame(buf: &mut Vec<u8>) -> io::Result<Option<(u8, Vec<u8>)>> {
+    let (header_len, size) = match buf.first() {
+        None => return Ok(None),
+        Some(flags) if flags & FLAG_LONG != 0 => match buf.get(1..9) {
+            Some(size) => (9, u64::from_be_bytes(size.try_into().unwrap())),
+            None => return Ok(None),
+        },
+        Some(_) => match buf.get(1) {
+            Some(size) => (2, *size as u64),
+            None => return Ok(None),
+        },
+    };
+    if size > MAX_FRAME_SIZE {
+        return Err(_invalid_data("ZMTP frame exceeds maximum size"));
+    }
+    let frame_len = header_len + size as usize;
+    if buf.len() < frame_len {
+        return Ok(None);
+    }
+    let flags = buf[0];
+    let body = buf[header_len..frame_len].to_vec();
+    buf.drain(..frame_len);
+    Ok(Some((flags, body)))
+}
+
+async fn _write_frame<S>(stream: &mut S, flags: u8, body: &[u8]) -> io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let mut frame = Vec::with_capacity(body.len() + 9);
+    if body.len() > 255 {
+        frame.push(flags | FLAG_LONG);
+        frame.extend_from_slice(&(body.len() as u64).to_be_bytes());
+    } else {
+        frame.push(flags);
+        frame.push(body.len() as u8);
+    }
+    frame.extend_from_slice(body);
+    stream.write_all(&frame).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_frame_waits_for_complete_frames() {
+        let mut buf = vec![0, 3, b'a', b'b'];
+        assert!(_parse_frame(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(&[b'c', FLAG_LONG | FLAG_MORE, 0, 0, 0]);
+        assert_eq!(_parse_frame(&mut buf).unwrap(), Some((0, b"abc".to_vec())));
+        assert!(_parse_frame(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(&[0, 0, 0, 0, 1, b'd']);
+        assert_eq!(
+            _parse_frame(&mut buf).unwrap(),
+            Some((FLAG_LONG | FLAG_MORE, b"d".to_vec()))
+        );
+        assert!(buf.is_empty());
+
+        let mut buf = vec![FLAG_LONG, 0xff, 0, 0, 0, 0, 0, 0, 0];
+        assert!(_parse_frame(&mut buf).is_err());
+    }
+}
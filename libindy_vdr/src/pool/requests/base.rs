@@ -10,6 +10,7 @@ use pin_utils::unsafe_pinned;
 
 use crate::common::error::prelude::*;
 use crate::config::PoolConfig;
+use crate::pool::monitor::PoolMonitor;
 use crate::pool::types::StateProofResult;
 
 use super::networker::{Networker, NetworkerEvent};
@@ -40,6 +41,7 @@ pub trait PoolRequest: std::fmt::Debug + Stream<Item = RequestEvent> + FusedStre
 pub struct PoolRequestImpl<S: AsRef<PoolSetup>, T: Networker> {
     handle: RequestHandle,
     events: Option<UnboundedReceiver<RequestExtEvent>>,
+    monitor: PoolMonitor,
     node_order: Vec<String>,
     pool_setup: S,
     networker: T,
@@ -62,10 +64,12 @@ where
         pool_setup: S,
         networker: T,
         node_order: Vec<String>,
+        monitor: PoolMonitor,
     ) -> Self {
         Self {
             handle,
             events: Some(events),
+            monitor,
             pool_setup,
             networker,
             node_order,
@@ -109,6 +113,7 @@ where
         RequestResultMeta {
             state_proof: self.state_proof.clone(),
             timing: self.timing.result(),
+            cached: false,
         }
     }
 
@@ -270,17 +275,20 @@ where
                             Poll::Ready(val) => match val {
                                 Some(RequestExtEvent::Sent(alias, when)) => {
                                     trace!("{} was sent to {}", self.handle, alias);
+                                    self.monitor.record_sent(&alias);
                                     self.timing.sent(&alias, when)
                                 }
                                 Some(RequestExtEvent::Received(alias, message, meta, when)) => {
                                     trace!("{} response from {}", self.handle, alias);
-                                    self.timing.received(&alias, when);
+                                    let latency = self.timing.received(&alias, when);
+                                    self.monitor.record_received(&alias, &meta, latency, when);
                                     return Poll::Ready(Some(RequestEvent::Received(
                                         alias, message, meta,
                                     )));
                                 }
                                 Some(RequestExtEvent::Timeout(alias)) => {
                                     trace!("{} timed out {}", self.handle, alias);
+                                    self.monitor.record_timeout(&alias);
                                     return Poll::Ready(Some(RequestEvent::Timeout(alias)));
                                 }
                                 _ => {
@@ -12,6 +12,10 @@ pub use base::{PoolRequest, PoolRequestImpl};
 mod prepared_request;
 pub use prepared_request::{PreparedRequest, RequestMethod};
 
+/// Request signing interface
+mod signer;
+pub use signer::{Ed25519Signer, RequestSigner};
+
 /// Get a new unique request ID
 pub fn new_request_id() -> i64 {
     SystemTime::now()
@@ -88,14 +92,14 @@ impl RequestTiming {
             .insert(node_alias.to_owned(), (send_time, -1.0));
     }
 
-    pub fn received(&mut self, node_alias: &str, recv_time: SystemTime) {
-        if let Some(node) = self.replies.get_mut(node_alias) {
-            let duration = recv_time
-                .duration_since(node.0)
-                .unwrap_or(Duration::new(0, 0))
-                .as_secs_f32();
-            node.1 = duration;
-        }
+    pub fn received(&mut self, node_alias: &str, recv_time: SystemTime) -> Option<f32> {
+        let node = self.replies.get_mut(node_alias)?;
+        let duration = recv_time
+            .duration_since(node.0)
+            .unwrap_or(Duration::new(0, 0))
+            .as_secs_f32();
+        node.1 = duration;
+        Some(duration)
     }
 
     pub fn result(&self) -> Option<TimingResult> {
@@ -2,6 +2,7 @@ use serde_json::{self, Value as SJsonValue};
 use sha2::{Digest, Sha256};
 
 use super::new_request_id;
+use super::signer::{sign_checked, RequestSigner};
 use crate::common::error::prelude::*;
 use crate::ledger::constants::READ_REQUESTS;
 use crate::ledger::TxnAuthrAgrmtAcceptanceData;
@@ -60,6 +61,8 @@ pub struct PreparedRequest {
     pub req_json: SJsonValue,
     /// Determine the request handler to use
     pub method: RequestMethod,
+    /// Whether the reply to a read request may be fetched from or stored in the cache
+    pub use_cache: bool,
 }
 
 impl PreparedRequest {
@@ -78,6 +81,7 @@ impl PreparedRequest {
             req_id,
             req_json,
             method,
+            use_cache: true,
         }
     }
 
@@ -145,6 +149,55 @@ impl PreparedRequest {
         Ok(())
     }
 
+    /// Sign the prepared request using a `RequestSigner` for the request identifier
+    pub async fn sign_with<S: RequestSigner + ?Sized>(&mut self, signer: &S) -> VdrResult<()> {
+        self.check_signer(signer, false)?;
+        let sig_input = self.get_signature_input()?;
+        let signature = sign_checked(signer, sig_input.as_bytes()).await?;
+        self.set_signature(&signature)
+    }
+
+    /// Add signatures to the prepared request using a `RequestSigner` for the
+    /// request identifier and/or endorser
+    pub async fn multi_sign_with(&mut self, signers: &[&dyn RequestSigner]) -> VdrResult<()> {
+        for signer in signers {
+            self.check_signer(*signer, true)?;
+        }
+        let sig_input = self.get_signature_input()?;
+        let mut signatures = Vec::with_capacity(signers.len());
+        for signer in signers {
+            signatures.push(sign_checked(*signer, sig_input.as_bytes()).await?);
+        }
+        for (signer, signature) in signers.iter().zip(signatures) {
+            let did = DidValue(signer.did().to_short().to_string());
+            self.set_multi_signature(&did, &signature)?;
+        }
+        Ok(())
+    }
+
+    fn check_signer<S: RequestSigner + ?Sized>(
+        &self,
+        signer: &S,
+        allow_endorser: bool,
+    ) -> VdrResult<()> {
+        let did = signer.did().to_short().to_string();
+        if self.req_json["identifier"].as_str() == Some(did.as_str())
+            || (allow_endorser && self.req_json["endorser"].as_str() == Some(did.as_str()))
+        {
+            Ok(())
+        } else {
+            Err(input_err(format!(
+                "Signer DID does not match the request {}: {}",
+                if allow_endorser {
+                    "identifier or endorser"
+                } else {
+                    "identifier"
+                },
+                did
+            )))
+        }
+    }
+
     /// Decorate the prepared request with the transaction author agreement acceptance
     pub fn set_txn_author_agreement_acceptance(
         &mut self,
@@ -0,0 +1,161 @@
+use futures_util::future::{ready, BoxFuture, FutureExt};
+
+use crate::common::error::prelude::*;
+use crate::utils::did::{generate_did, DidValue};
+use crate::utils::keys::{PrivateKey, VerKey};
+
+/// A source of signatures for ledger requests
+///
+/// Signing is asynchronous to support signers backed by a wallet or hardware module.
+pub trait RequestSigner: Send + Sync {
+    /// The DID associated with the signing key
+    fn did(&self) -> &DidValue;
+
+    /// The verification key corresponding to the signing key
+    fn verkey(&self) -> &VerKey;
+
+    /// Sign the normalized representation of a request
+    fn sign<'a>(&'a self, message: &'a [u8]) -> BoxFuture<'a, VdrResult<Vec<u8>>>;
+}
+
+/// A `RequestSigner` holding an Ed25519 private key in memory
+pub struct Ed25519Signer {
+    did: DidValue,
+    verkey: VerKey,
+    private_key: PrivateKey,
+}
+
+impl Ed25519Signer {
+    /// Create a new signer for a DID and private key
+    pub fn new(did: DidValue, private_key: PrivateKey) -> VdrResult<Self> {
+        let verkey = private_key.public_key()?;
+        Ok(Self {
+            did,
+            verkey,
+            private_key,
+        })
+    }
+
+    /// Create a new signer with a self-certifying DID, derived from an optional seed
+    pub fn generate(seed: Option<&[u8]>, version: Option<usize>) -> VdrResult<Self> {
+        let (did, private_key, verkey) = generate_did(seed, version)?;
+        Ok(Self {
+            did: DidValue(did.to_string()),
+            verkey,
+            private_key,
+        })
+    }
+}
+
+impl RequestSigner for Ed25519Signer {
+    fn did(&self) -> &DidValue {
+        &self.did
+    }
+
+    fn verkey(&self) -> &VerKey {
+        &self.verkey
+    }
+
+    fn sign<'a>(&'a self, message: &'a [u8]) -> BoxFuture<'a, VdrResult<Vec<u8>>> {
+        ready(self.private_key.sign(message).map_err(VdrError::from)).boxed()
+    }
+}
+
+impl std::fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ed25519Signer")
+            .field("did", &self.did)
+            .field("verkey", &self.verkey)
+            .finish()
+    }
+}
+
+/// Produce a signature with a `RequestSigner`, checking it against the signer's verkey
+pub(crate) async fn sign_checked<S: RequestSigner + ?Sized>(
+    signer: &S,
+    message: &[u8],
+) -> VdrResult<Vec<u8>> {
+    let signature = signer.sign(message).await?;
+    if !signer.verkey().verify_signature(message, &signature)? {
+        return Err(input_err(format!(
+            "Signature does not match the verkey for signer: {}",
+            signer.did()
+        )));
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::ledger::RequestBuilder;
+    use crate::pool::{PreparedRequest, ProtocolVersion};
+    use crate::utils::base58;
+
+    fn _nym_request(signer: &Ed25519Signer) -> PreparedRequest {
+        RequestBuilder::new(ProtocolVersion::Node1_4)
+            .build_nym_request(signer.did(), signer.did(), None, None, None, None, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn sign_with_sets_verified_signature() {
+        let signer = Ed25519Signer::generate(None, None).unwrap();
+        let mut request = _nym_request(&signer);
+        block_on(request.sign_with(&signer)).unwrap();
+        let signature = base58::decode(request.req_json["signature"].as_str().unwrap()).unwrap();
+        let sig_input = request.get_signature_input().unwrap();
+        assert!(signer
+            .verkey()
+            .verify_signature(sig_input.as_bytes(), signature)
+            .unwrap());
+    }
+
+    #[test]
+    fn sign_with_checks_signer_did() {
+        let signer = Ed25519Signer::generate(None, None).unwrap();
+        let other = Ed25519Signer::generate(None, None).unwrap();
+        let mut request = _nym_request(&signer);
+        assert!(block_on(request.sign_with(&other)).is_err());
+        request.set_endorser(other.did()).unwrap();
+        assert!(block_on(request.sign_with(&other)).is_err());
+        block_on(request.multi_sign_with(&[&signer, &other])).unwrap();
+        let signatures = request.req_json["signatures"].as_object().unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert!(signatures.contains_key(&other.did().to_string()));
+    }
+
+    #[test]
+    fn sign_with_checks_signer_verkey() {
+        let signer = Ed25519Signer::generate(None, Some(2)).unwrap();
+        let mut request = _nym_request(&signer);
+        block_on(request.sign_with(&signer)).unwrap();
+
+        // the DID need not be derived from the verkey, as with a rotated key
+        let other = Ed25519Signer::generate(None, None).unwrap();
+        let rotated = Ed25519Signer::new(signer.did().clone(), other.private_key.clone()).unwrap();
+        let mut request = _nym_request(&signer);
+        block_on(request.sign_with(&rotated)).unwrap();
+
+        let mismatched = Ed25519Signer {
+            did: signer.did().clone(),
+            verkey: signer.verkey().clone(),
+            private_key: other.private_key.clone(),
+        };
+        let mut request = _nym_request(&signer);
+        assert!(block_on(request.sign_with(&mismatched)).is_err());
+        assert!(block_on(request.multi_sign_with(&[&mismatched])).is_err());
+    }
+
+    #[test]
+    fn sign_with_is_send() {
+        fn _assert_send<T: Send>(_: &T) {}
+        let signer = Ed25519Signer::generate(None, None).unwrap();
+        let mut request = _nym_request(&signer);
+        let fut = request.sign_with(&signer);
+        _assert_send(&fut);
+        block_on(fut).unwrap();
+    }
+}
@@ -8,7 +8,10 @@ use futures_util::stream::{FuturesUnordered, StreamExt};
 use futures_util::{select, FutureExt};
 
 use super::cache::Cache;
-use super::helpers::{perform_ledger_request, perform_refresh};
+use super::helpers::{
+    perform_get_txn_range, perform_get_txn_root, perform_ledger_request, perform_refresh,
+};
+use super::monitor::{PoolMonitor, PoolStats};
 use super::networker::{Networker, NetworkerFactory};
 use super::requests::PreparedRequest;
 use super::types::{RequestResult, RequestResultMeta, Verifiers};
@@ -37,6 +40,29 @@ impl PoolRunner {
         refreshed: bool,
         cache: Option<Cache<String, (String, RequestResultMeta)>>,
     ) -> Self
+    where
+        F: NetworkerFactory<Output = Rc<dyn Networker>> + Send + 'static,
+    {
+        Self::start(
+            config,
+            merkle_tree,
+            networker_factory,
+            node_weights,
+            refreshed,
+            cache,
+            PoolMonitor::new(),
+        )
+    }
+
+    pub(crate) fn start<F>(
+        config: PoolConfig,
+        merkle_tree: MerkleTree,
+        networker_factory: F,
+        node_weights: Option<HashMap<String, f32>>,
+        refreshed: bool,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+        monitor: PoolMonitor,
+    ) -> Self
     where
         F: NetworkerFactory<Output = Rc<dyn Networker>> + Send + 'static,
     {
@@ -50,7 +76,8 @@ impl PoolRunner {
                 node_weights,
                 refreshed,
             )
-            .unwrap();
+            .unwrap()
+            .with_monitor(monitor);
             let mut thread = PoolThread::new(pool, receiver, cache);
             thread.run();
             debug!("Pool thread ended")
@@ -76,12 +103,31 @@ impl PoolRunner {
         self.send_event(PoolEvent::GetVerifiers(callback))
     }
 
+    /// Fetch the statistics collected for each validator node.
+    pub fn get_stats(&self, callback: Callback<GetStatsResponse>) -> VdrResult<()> {
+        self.send_event(PoolEvent::GetStats(callback))
+    }
+
     /// Fetch the latest pool transactions and switch to the new validator
     /// pool if necessary.
     pub fn refresh(&self, callback: Callback<RefreshResponse>) -> VdrResult<()> {
         self.send_event(PoolEvent::Refresh(callback))
     }
 
+    /// Fetch a range of verified ledger transactions. Each transaction is verified
+    /// against the multi-signed ledger root fetched when the range is requested.
+    /// The callback is invoked for each transaction in turn, followed by a final
+    /// `None` value or an error.
+    pub fn get_txn_range(
+        &self,
+        ledger_type: i32,
+        from: i32,
+        to: i32,
+        callback: StreamCallback<GetTxnRangeResponse>,
+    ) -> VdrResult<()> {
+        self.send_event(PoolEvent::GetTxnRange(ledger_type, from, to, callback))
+    }
+
     /// Submit a request to the validator pool.
     pub fn send_request(
         &self,
@@ -91,6 +137,15 @@ impl PoolRunner {
         self.send_event(PoolEvent::SendRequest(request, callback))
     }
 
+    /// Replace the cache used for ledger read requests. Requests already
+    /// in progress continue to use the previous cache.
+    pub fn set_cache(
+        &self,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<()> {
+        self.send_event(PoolEvent::SetCache(cache))
+    }
+
     /// Send an event to the worker thread.
     fn send_event(&self, event: PoolEvent) -> VdrResult<()> {
         // FIXME error should indicate that the thread exited, so indicate such in result
@@ -121,12 +176,18 @@ impl Drop for PoolRunner {
 
 type Callback<R> = Box<dyn FnOnce(R) + Send>;
 
+type StreamCallback<R> = Box<dyn FnMut(R) + Send>;
+
 type GetStatusResponse = VdrResult<PoolRunnerStatus>;
 
 type GetTxnsResponse = VdrResult<Vec<String>>;
 
 type GetVerifiersResponse = VdrResult<Verifiers>;
 
+type GetStatsResponse = VdrResult<PoolStats>;
+
+type GetTxnRangeResponse = VdrResult<Option<(i32, String)>>;
+
 type RefreshResponse = VdrResult<(Option<PoolTransactions>, RequestResultMeta)>;
 
 type SendReqResponse = VdrResult<(RequestResult<String>, RequestResultMeta)>;
@@ -135,8 +196,11 @@ enum PoolEvent {
     GetStatus(Callback<GetStatusResponse>),
     GetTransactions(Callback<GetTxnsResponse>),
     GetVerifiers(Callback<GetVerifiersResponse>),
+    GetStats(Callback<GetStatsResponse>),
+    GetTxnRange(i32, i32, i32, StreamCallback<GetTxnRangeResponse>),
     Refresh(Callback<RefreshResponse>),
     SendRequest(PreparedRequest, Callback<SendReqResponse>),
+    SetCache(Option<Cache<String, (String, RequestResultMeta)>>),
 }
 
 /// The current status of a validator pool.
@@ -206,6 +270,13 @@ impl PoolThread {
                             let vers = self.pool.get_verifier_info();
                             callback(vers);
                         }
+                        Some(PoolEvent::GetStats(callback)) => {
+                            callback(Ok(self.pool.monitor().stats()));
+                        }
+                        Some(PoolEvent::GetTxnRange(ledger_type, from, to, callback)) => {
+                            let fut = _perform_get_txn_range(&self.pool, ledger_type, from, to, callback);
+                            futures.push(fut.boxed_local());
+                        }
                         Some(PoolEvent::Refresh(callback)) => {
                             let fut = _perform_refresh(&self.pool, callback);
                             futures.push(fut.boxed_local());
@@ -214,6 +285,9 @@ impl PoolThread {
                             let fut = _perform_ledger_request(&self.pool, request, callback, cache_ledger_request);
                             futures.push(fut.boxed_local());
                         }
+                        Some(PoolEvent::SetCache(cache)) => {
+                            self.cache = cache;
+                        }
                         None => { trace!("Pool runner sender dropped") }
                     }
                 }
@@ -234,6 +308,31 @@ async fn _perform_refresh(pool: &LocalPool, callback: Callback<RefreshResponse>)
     callback(result);
 }
 
+async fn _perform_get_txn_range(
+    pool: &LocalPool,
+    ledger_type: i32,
+    from: i32,
+    to: i32,
+    mut callback: StreamCallback<GetTxnRangeResponse>,
+) {
+    let (root_hash, ledger_size) = match perform_get_txn_root(pool, ledger_type).await {
+        Ok(root) => root,
+        Err(err) => return callback(Err(err)),
+    };
+    let mut txns = match perform_get_txn_range(pool, ledger_type, from, to, root_hash, ledger_size)
+    {
+        Ok(txns) => txns.boxed_local(),
+        Err(err) => return callback(Err(err)),
+    };
+    while let Some(result) = txns.next().await {
+        match result {
+            Ok(txn) => callback(Ok(Some(txn))),
+            Err(err) => return callback(Err(err)),
+        }
+    }
+    callback(Ok(None));
+}
+
 async fn _perform_ledger_request(
     pool: &LocalPool,
     request: PreparedRequest,
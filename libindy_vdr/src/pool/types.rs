@@ -723,6 +723,10 @@ pub type TimingResult = HashMap<String, f32>;
 pub struct RequestResultMeta {
     pub state_proof: HashMap<String, StateProofResult>,
     pub timing: Option<TimingResult>,
+    /// Whether the reply was served from the response cache, in which case the
+    /// state proof results and timing are those of the original request
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
 }
 
 /// The result of a request to a single validator node
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use percent_encoding::percent_decode;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::common::error::prelude::*;
@@ -10,6 +11,8 @@ use crate::utils::did::DidValue;
 
 // Patterns to build regular expressions for ledger objects
 static DID_INDY_PREFIX: &str = "did:indy";
+static DID_SOV_PREFIX: &str = "did:sov";
+static SOV_NAMESPACE_PATTERN: &str = "(?:([a-z0-9_-]+):)?";
 static NAMESPACE_PATTERN: &str = "((?:[a-z0-9_-]+:?){1,2})";
 // uses base58 alphabet
 static INDY_UNQUALIFIED_DID_PATTERN: &str =
@@ -27,7 +30,7 @@ static VERSION_PATTERN: &str = "((\\d*\\.){1,2}\\d*)";
 static DID_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         format!(
-            r"{}:{}:{}([^\?]+)?(?:\?(.+))?$",
+            r"{}:{}:{}([^\?#]+)?(?:\?([^#]+))?(?:#(.+))?$",
             DID_INDY_PREFIX, NAMESPACE_PATTERN, INDY_UNQUALIFIED_DID_PATTERN
         )
         .as_str(),
@@ -35,12 +38,53 @@ static DID_REGEX: Lazy<Regex> = Lazy::new(|| {
     .unwrap()
 });
 
+static DID_SOV_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        format!(
+            r"^{}:{}{}([^\?#]+)?(?:\?([^#]+))?(?:#(.+))?$",
+            DID_SOV_PREFIX, SOV_NAMESPACE_PATTERN, INDY_UNQUALIFIED_DID_PATTERN
+        )
+        .as_str(),
+    )
+    .unwrap()
+});
+
+static UNQUALIFIED_DID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(format!(r"^{}(?:#(.+))?$", INDY_UNQUALIFIED_DID_PATTERN).as_str()).unwrap()
+});
+
+/// The DID method of a parsed DID (URL)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DidMethod {
+    /// `did:indy:<namespace>:<id>`
+    #[default]
+    Indy,
+    /// `did:sov:[<sub-namespace>:]<id>`, resolved against the default ledger
+    Sov,
+    /// A legacy unqualified DID, resolved against the default ledger as a did:sov DID
+    Unqualified,
+}
+
+impl DidMethod {
+    /// The name of the DID method of the resolved DID document
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Indy => "indy",
+            Self::Sov | Self::Unqualified => "sov",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub enum QueryParameter {
     VersionId,
     VersionTime,
     From,
     To,
+    Service,
+    RelativeRef,
+    Hl,
 }
 
 impl QueryParameter {
@@ -50,6 +94,9 @@ impl QueryParameter {
             "versionTime" => Ok(QueryParameter::VersionTime),
             "from" => Ok(QueryParameter::From),
             "to" => Ok(QueryParameter::To),
+            "service" => Ok(QueryParameter::Service),
+            "relativeRef" => Ok(QueryParameter::RelativeRef),
+            "hl" => Ok(QueryParameter::Hl),
             _ => Err(err_msg(
                 VdrErrorKind::Resolver,
                 format!("Query parameter {} not supported", input),
@@ -350,11 +397,25 @@ pub struct DidUrl {
     pub id: DidValue,
     pub path: Option<String>,
     pub query: HashMap<QueryParameter, String>,
+    pub fragment: Option<String>,
     pub url: String,
 }
 
 impl DidUrl {
+    /// Parse a did:indy or did:sov DID (URL), or a legacy unqualified DID. The namespace
+    /// of did:sov DIDs is the optional sub-namespace, and empty for unqualified DIDs.
     pub fn parse(input: &str) -> VdrResult<DidUrl> {
+        if let Some(cap) = UNQUALIFIED_DID_REGEX.captures(input.trim()) {
+            return Ok(DidUrl {
+                namespace: String::new(),
+                id: DidValue::new(&cap[1], Option::None),
+                path: None,
+                query: HashMap::new(),
+                fragment: cap.get(2).map(|f| f.as_str().to_string()),
+                url: input.to_string(),
+            });
+        }
+
         let url = Url::parse(input)
             .map_err(|_| err_msg(VdrErrorKind::Resolver, "Could not parse DID Url"))?;
         let mut query_pairs: HashMap<QueryParameter, String> = HashMap::new();
@@ -366,7 +427,9 @@ impl DidUrl {
             query_pairs.insert(qp, v.to_string());
         }
 
-        let captures = DID_REGEX.captures(input.trim());
+        let captures = DID_REGEX
+            .captures(input.trim())
+            .or_else(|| DID_SOV_REGEX.captures(input.trim()));
         match captures {
             Some(cap) => {
                 let path = cap
@@ -379,10 +442,14 @@ impl DidUrl {
                     .transpose()
                     .map_err(|_| err_msg(VdrErrorKind::Resolver, "Invalid DID Url path"))?;
                 let did = DidUrl {
-                    namespace: cap[1].to_string(),
+                    namespace: cap
+                        .get(1)
+                        .map(|ns| ns.as_str().to_string())
+                        .unwrap_or_default(),
                     id: DidValue::new(&cap[2], Option::None),
                     path,
                     query: query_pairs,
+                    fragment: cap.get(5).map(|f| f.as_str().to_string()),
                     url: input.to_string(),
                 };
                 Ok(did)
@@ -393,6 +460,25 @@ impl DidUrl {
             )),
         }
     }
+
+    /// Whether the DID URL selects a part of the DID document, by a fragment or a
+    /// `service` query parameter, rather than the document itself
+    pub fn is_document_part(&self) -> bool {
+        self.path.is_none()
+            && (self.fragment.is_some() || self.query.contains_key(&QueryParameter::Service))
+    }
+
+    /// The DID method of the DID (URL)
+    pub fn method(&self) -> DidMethod {
+        let url = self.url.trim();
+        if url.starts_with(DID_INDY_PREFIX) {
+            DidMethod::Indy
+        } else if url.starts_with(DID_SOV_PREFIX) {
+            DidMethod::Sov
+        } else {
+            DidMethod::Unqualified
+        }
+    }
 }
 
 #[cfg(test)]
@@ -508,6 +594,7 @@ mod tests {
                     id: DidValue::new("BDrEcHc8Tb4Lb2VyQZWEDE", None),
                     path: None,
                     query: HashMap::new(),
+                    fragment: None,
                     url: String::from("did:indy:idunion:BDrEcHc8Tb4Lb2VyQZWEDE"),
                 }
             );
@@ -519,6 +606,7 @@ mod tests {
                     id: DidValue::new("6cgbu8ZPoWTnR5Rv5JcSMB", None),
                     path: None,
                     query: HashMap::new(),
+                    fragment: None,
                     url: String::from("did:indy:sovrin:staging:6cgbu8ZPoWTnR5Rv5JcSMB"),
                 }
             );
@@ -540,6 +628,50 @@ mod tests {
             let _err = DidUrl::parse("did:indy:test:lcgbu8ZPoWTnR5Rv5JcSMB").unwrap_err();
         }
 
+        #[test]
+        fn parse_did_sov() {
+            let did = DidUrl::parse("did:sov:BDrEcHc8Tb4Lb2VyQZWEDE").unwrap();
+            assert_eq!(did.namespace, "");
+            assert_eq!(did.id, DidValue::new("BDrEcHc8Tb4Lb2VyQZWEDE", None));
+            assert_eq!(did.method(), DidMethod::Sov);
+
+            let did = DidUrl::parse("did:sov:builder:BDrEcHc8Tb4Lb2VyQZWEDE?versionId=1").unwrap();
+            assert_eq!(did.namespace, "builder");
+            assert_eq!(did.query[&QueryParameter::VersionId], "1");
+            assert_eq!(did.method(), DidMethod::Sov);
+
+            let _err = DidUrl::parse("did:sov:a:b:BDrEcHc8Tb4Lb2VyQZWEDE").unwrap_err();
+            let _err = DidUrl::parse("did:sov:0cgbu8ZPoWTnR5Rv5JcSMB").unwrap_err();
+        }
+
+        #[test]
+        fn parse_unqualified_did() {
+            assert_eq!(
+                DidUrl::parse("BDrEcHc8Tb4Lb2VyQZWEDE").unwrap(),
+                DidUrl {
+                    namespace: String::new(),
+                    id: DidValue::new("BDrEcHc8Tb4Lb2VyQZWEDE", None),
+                    path: None,
+                    query: HashMap::new(),
+                    fragment: None,
+                    url: String::from("BDrEcHc8Tb4Lb2VyQZWEDE"),
+                }
+            );
+            assert_eq!(
+                DidUrl::parse("BDrEcHc8Tb4Lb2VyQZWEDE").unwrap().method(),
+                DidMethod::Unqualified
+            );
+            assert_eq!(
+                DidUrl::parse("did:indy:idunion:BDrEcHc8Tb4Lb2VyQZWEDE")
+                    .unwrap()
+                    .method(),
+                DidMethod::Indy
+            );
+
+            let _err = DidUrl::parse("12345678901234567890").unwrap_err();
+            let _err = DidUrl::parse("BDrEcHc8Tb4Lb2VyQZWEDE/anoncreds").unwrap_err();
+        }
+
         #[test]
         fn parse_did_url_with_query_parameter() {
             let mut q = HashMap::new();
@@ -552,11 +684,48 @@ mod tests {
                     id: DidValue::new("BDrEcHc8Tb4Lb2VyQZWEDE", None),
                     path: None,
                     query: q,
+                    fragment: None,
                     url: String::from("did:indy:idunion:BDrEcHc8Tb4Lb2VyQZWEDE?versionId=1"),
                 }
             );
         }
 
+        #[test]
+        fn parse_did_url_with_fragment() {
+            let did = DidUrl::parse("did:indy:idunion:BDrEcHc8Tb4Lb2VyQZWEDE#key-1").unwrap();
+            assert_eq!(did.namespace, "idunion");
+            assert_eq!(did.path, None);
+            assert_eq!(did.fragment.as_deref(), Some("key-1"));
+            assert!(did.is_document_part());
+
+            let did = DidUrl::parse("did:sov:BDrEcHc8Tb4Lb2VyQZWEDE?versionId=2#key-agreement-1")
+                .unwrap();
+            assert_eq!(did.query[&QueryParameter::VersionId], "2");
+            assert_eq!(did.fragment.as_deref(), Some("key-agreement-1"));
+
+            let did = DidUrl::parse("BDrEcHc8Tb4Lb2VyQZWEDE#key-1").unwrap();
+            assert_eq!(did.fragment.as_deref(), Some("key-1"));
+        }
+
+        #[test]
+        fn parse_did_url_with_service_query() {
+            let did = DidUrl::parse(
+                "did:indy:idunion:BDrEcHc8Tb4Lb2VyQZWEDE?service=agent&relativeRef=%2Fpath%3Fa%3D1",
+            )
+            .unwrap();
+            assert_eq!(did.path, None);
+            assert_eq!(did.query[&QueryParameter::Service], "agent");
+            assert_eq!(did.query[&QueryParameter::RelativeRef], "/path?a=1");
+            assert!(did.is_document_part());
+
+            let did = DidUrl::parse(
+                "did:indy:idunion:Dk1fRRTtNazyMuK2cr64wp/anoncreds/v0/SCHEMA/npdb/4.3.4?hl=zQmWvQxTqbG2Z9HPJgG57jjwR154cKhbtJenbyYTWkjgF3e",
+            )
+            .unwrap();
+            assert!(did.query.contains_key(&QueryParameter::Hl));
+            assert!(!did.is_document_part());
+        }
+
         #[test]
         fn parse_did_url_fails_with_arbitrary_query_parameter() {
             let _err =
@@ -573,6 +742,7 @@ mod tests {
                     id: DidValue::new("Dk1fRRTtNazyMuK2cr64wp", None),
                     path: Some(String::from("/anoncreds/v0/REV_REG_DEF/104/revocable/a4e25e54-e028-462b-a4d6-b1d1712d51a1")),
                     query: HashMap::new(),
+                    fragment: None,
                     url: String::from(
                         "did:indy:idunion:Dk1fRRTtNazyMuK2cr64wp/anoncreds/v0/REV_REG_DEF/104/revocable/a4e25e54-e028-462b-a4d6-b1d1712d51a1"
                     ),
@@ -593,6 +763,7 @@ mod tests {
                     id: DidValue::new("Dk1fRRTtNazyMuK2cr64wp", None),
                     path: Some(String::from("/anoncreds/v0/REV_REG_DEF/104/revocable/a4e25e54-e028-462b-a4d6-b1d1712d51a1")),
                     query: q,
+                    fragment: None,
                     url: String::from(
                         "did:indy:idunion:Dk1fRRTtNazyMuK2cr64wp/anoncreds/v0/REV_REG_DEF/104/revocable/a4e25e54-e028-462b-a4d6-b1d1712d51a1?versionTime=someXmlDateTime"
                     ),
@@ -1,11 +1,48 @@
+use super::did::DidMethod;
 use crate::common::error::prelude::*;
-use crate::ledger::responses::Endpoint;
+use crate::ledger::responses::{Endpoint, EndpointAttrib};
 use crate::utils::base58;
+use crate::utils::keys::EncodedVerKey;
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Value as SJsonValue};
+use std::collections::{HashMap, HashSet};
 
 pub const LEGACY_INDY_SERVICE: &str = "endpoint";
 pub const DID_CORE_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const DIDCOMM_MESSAGING_SERVICE: &str = "DIDCommMessaging";
+pub const ED25519_2018_CONTEXT: &str = "https://w3id.org/security/suites/ed25519-2018/v1";
+pub const X25519_2019_CONTEXT: &str = "https://w3id.org/security/suites/x25519-2019/v1";
+
+/// Service types created for a did:sov `endpoint` ATTRIB without a `types` list
+const SOV_DEFAULT_SERVICE_TYPES: &[&str] = &[LEGACY_INDY_SERVICE, "did-communication"];
+
+/// Top-level properties of `diddocContent` defined by DID core, others are logged
+const DIDDOC_CONTENT_PROPERTIES: &[&str] = &[
+    "@context",
+    "alsoKnownAs",
+    "controller",
+    "verificationMethod",
+    "authentication",
+    "assertionMethod",
+    "keyAgreement",
+    "capabilityInvocation",
+    "capabilityDelegation",
+    "service",
+];
+
+/// Verification relationships, which reference or embed verification methods
+pub(super) const VERIFICATION_RELATIONSHIPS: &[&str] = &[
+    "authentication",
+    "assertionMethod",
+    "keyAgreement",
+    "capabilityInvocation",
+    "capabilityDelegation",
+];
+
+/// Multicodec prefix of an Ed25519 public key in `publicKeyMultibase`
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+/// Multicodec prefix of an X25519 public key in `publicKeyMultibase`
+const X25519_MULTICODEC: [u8; 2] = [0xec, 0x01];
 
 #[derive(Serialize, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -54,6 +91,11 @@ pub struct DidDocument {
     verkey: String,
     pub endpoint: Option<Endpoint>,
     pub diddoc_content: Option<SJsonValue>,
+    #[serde(default)]
+    pub method: DidMethod,
+    /// The `routingKeys` and `types` of a did:sov endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    endpoint_attrib: Option<EndpointAttrib>,
 }
 
 impl DidDocument {
@@ -70,12 +112,32 @@ impl DidDocument {
             verkey: expand_verkey(id, verkey),
             endpoint,
             diddoc_content,
+            method: DidMethod::Indy,
+            endpoint_attrib: None,
         }
     }
 
+    /// Set the `endpoint` ATTRIB of the DID, keeping its non-string values for
+    /// rendering did:sov services
+    pub fn set_endpoint(&mut self, endpoint: Option<EndpointAttrib>) {
+        self.endpoint = endpoint.clone().map(Endpoint::from);
+        self.endpoint_attrib = endpoint;
+    }
+
+    /// Set the DID method of the document. did:sov and unqualified DIDs are rendered as a
+    /// did:sov DID document, which does not support `diddocContent`.
+    pub fn with_method(mut self, method: DidMethod) -> Self {
+        self.method = method;
+        self
+    }
+
     pub fn to_value(&self) -> VdrResult<SJsonValue> {
+        if self.method != DidMethod::Indy {
+            return Ok(self.to_sov_value());
+        }
+
         let mut doc = json!({
-             "id": format!("did:indy:{}:{}", self.namespace, self.id),
+            "id": format!("did:indy:{}:{}", self.namespace, self.id),
             "verificationMethod": [Ed25519VerificationKey2018 {
                 id: format!("did:indy:{}:{}#verkey", self.namespace, self.id),
                 type_: "Ed25519VerificationKey2018".to_string(),
@@ -85,14 +147,11 @@ impl DidDocument {
             "authentication": [format!("did:indy:{}:{}#verkey", self.namespace, self.id)],
         });
 
-        if self.diddoc_content.is_some() {
-            let is_valid = validate_diddoc_content(self.diddoc_content.as_ref().unwrap());
+        if let Some(diddoc_content) = self.diddoc_content.as_ref() {
+            let did = format!("did:indy:{}:{}", self.namespace, self.id);
+            validate_diddoc_content(&did, diddoc_content)?;
 
-            if !is_valid {
-                debug!("Invalid DIDdoc Content, but validation won't be enforced.");
-            }
-
-            merge_diddoc(&mut doc, self.diddoc_content.as_ref().unwrap());
+            merge_diddoc(&mut doc, diddoc_content);
 
             // Handling of legacy services
         } else if self.endpoint.is_some() {
@@ -122,38 +181,413 @@ impl DidDocument {
         Ok(doc)
     }
 
+    fn to_sov_value(&self) -> SJsonValue {
+        let did = if self.namespace.is_empty() {
+            format!("did:sov:{}", self.id)
+        } else {
+            format!("did:sov:{}:{}", self.namespace, self.id)
+        };
+        let verkey = EncodedVerKey::from_str_qualified(&self.verkey, Some(&self.id), None, None)
+            .map(|verkey| verkey.key.clone())
+            .unwrap_or_else(|_| self.verkey.clone());
+
+        let mut doc = json!({
+            "@context": [DID_CORE_CONTEXT, ED25519_2018_CONTEXT, X25519_2019_CONTEXT],
+            "id": did,
+            "verificationMethod": [Ed25519VerificationKey2018 {
+                id: format!("{}#key-1", did),
+                type_: "Ed25519VerificationKey2018".to_string(),
+                controller: did.clone(),
+                public_key_base58: verkey,
+            }],
+            "authentication": [format!("{}#key-1", did)],
+            "assertionMethod": [format!("{}#key-1", did)],
+        });
+
+        if let Ok(key_agreement) = key_agreement_key(&self.id, &self.verkey) {
+            doc["verificationMethod"]
+                .as_array_mut()
+                .unwrap()
+                .push(json!({
+                    "id": format!("{}#key-agreement-1", did),
+                    "type": "X25519KeyAgreementKey2019",
+                    "controller": did,
+                    "publicKeyBase58": key_agreement,
+                }));
+            doc["keyAgreement"] = json!([format!("{}#key-agreement-1", did)]);
+        }
+
+        if let Some(endpoint) = self.endpoint.as_ref() {
+            let services = sov_services(&did, endpoint, self.endpoint_attrib.as_ref());
+            if !services.is_empty() {
+                doc["service"] = SJsonValue::Array(services);
+            }
+        }
+
+        doc
+    }
+
     pub fn to_string(&self) -> VdrResult<String> {
         let doc = self.to_value()?;
         Ok(serde_json::to_string_pretty(&doc).unwrap())
     }
 }
 
-fn validate_context(context: &str) -> bool {
-    context == DID_CORE_CONTEXT
+/// Derive the base58 X25519 key agreement key from an Ed25519 verkey
+fn key_agreement_key(id: &str, verkey: &str) -> VdrResult<String> {
+    let verkey = EncodedVerKey::from_str_qualified(verkey, Some(id), None, None)?;
+    if verkey.key_bytes()?.len() != 32 {
+        return Err(input_err("Invalid verkey length"));
+    }
+    Ok(verkey.key_exchange_encoded()?.key.clone())
+}
+
+/// Convert a did:sov `endpoint` ATTRIB to services. The `types` list selects the services
+/// created for the `endpoint` URL, while any other named endpoints become generic services.
+fn sov_services(
+    did: &str,
+    endpoint: &Endpoint,
+    attrib: Option<&EndpointAttrib>,
+) -> Vec<SJsonValue> {
+    let endpoints = &endpoint.endpoint;
+    let list = |name: &str| {
+        attrib
+            .and_then(|attrib| attrib.endpoint.get(name))
+            .and_then(SJsonValue::as_array)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(SJsonValue::as_str)
+                    .collect::<Vec<_>>()
+            })
+    };
+    let routing_keys = list("routingKeys").unwrap_or_default();
+    let types = list("types").unwrap_or_else(|| SOV_DEFAULT_SERVICE_TYPES.to_vec());
+
+    let mut services = Vec::new();
+    if let Some(uri) = endpoints.get(LEGACY_INDY_SERVICE) {
+        for type_ in types {
+            match type_ {
+                LEGACY_INDY_SERVICE => services.push(json!({
+                    "id": format!("{}#endpoint", did),
+                    "type": LEGACY_INDY_SERVICE,
+                    "serviceEndpoint": uri,
+                })),
+                "did-communication" => services.push(json!({
+                    "id": format!("{}#did-communication", did),
+                    "type": "did-communication",
+                    "priority": 0,
+                    "recipientKeys": [format!("{}#key-1", did)],
+                    "routingKeys": routing_keys,
+                    "accept": ["didcomm/aip2;env=rfc19"],
+                    "serviceEndpoint": uri,
+                })),
+                "DIDComm" | DIDCOMM_MESSAGING_SERVICE => services.push(json!({
+                    "id": format!("{}#didcomm-1", did),
+                    "type": DIDCOMM_MESSAGING_SERVICE,
+                    "serviceEndpoint": {
+                        "uri": uri,
+                        "accept": ["didcomm/v2"],
+                        "routingKeys": routing_keys,
+                    },
+                })),
+                _ => (),
+            }
+        }
+    }
+
+    let mut names: Vec<&String> = endpoints
+        .keys()
+        .filter(|name| !matches!(name.as_str(), LEGACY_INDY_SERVICE | "routingKeys" | "types"))
+        .collect();
+    names.sort();
+    for name in names {
+        services.push(json!(GenericService {
+            id: format!("{}#{}", did, name),
+            type_: name.to_string(),
+            service_endpoint: endpoints[name].clone(),
+        }));
+    }
+    services
+}
+
+fn content_err<M: std::fmt::Display>(msg: M) -> VdrError {
+    err_msg(
+        VdrErrorKind::Resolver,
+        format!("Invalid diddocContent: {}", msg),
+    )
+}
+
+/// Treat a single value as a list of one entry
+fn as_entries(value: &SJsonValue) -> Vec<&SJsonValue> {
+    match value {
+        SJsonValue::Array(entries) => entries.iter().collect(),
+        value => vec![value],
+    }
+}
+
+/// Resolve a relative DID URL such as `#keys-1` against the DID
+pub(super) fn resolve_did_url(did: &str, did_url: &str) -> String {
+    if did_url.starts_with('#') {
+        format!("{}{}", did, did_url)
+    } else {
+        did_url.to_string()
+    }
+}
+
+fn validate_context(context: &SJsonValue) -> VdrResult<()> {
+    let has_core_context = match context {
+        SJsonValue::String(context) => context == DID_CORE_CONTEXT,
+        SJsonValue::Array(contexts) => contexts
+            .iter()
+            .any(|c| c.as_str() == Some(DID_CORE_CONTEXT)),
+        _ => false,
+    };
+    if has_core_context {
+        Ok(())
+    } else {
+        Err(content_err(format!(
+            "@context must include {}",
+            DID_CORE_CONTEXT
+        )))
+    }
+}
+
+/// Validate `diddocContent` for merging it into the base DID document. Only an `id`
+/// property or a context without the DID core context are rejected, while other
+/// departures from the rules of the did:indy method are logged
+fn validate_diddoc_content(did: &str, diddoc_content: &SJsonValue) -> VdrResult<()> {
+    let content = diddoc_content
+        .as_object()
+        .ok_or_else(|| content_err("expected a JSON object"))?;
+    if content.contains_key("id") {
+        return Err(content_err("the id property is not permitted"));
+    }
+    if let Some(context) = content.get("@context") {
+        validate_context(context)?;
+    }
+    for prop in content
+        .keys()
+        .filter(|k| !DIDDOC_CONTENT_PROPERTIES.contains(&k.as_str()))
+    {
+        warn!("diddocContent of {} has an unknown property {}", did, prop);
+    }
+    if let Err(err) = check_diddoc_content(did, diddoc_content) {
+        warn!("{}, but it won't be enforced", err);
+    }
+    Ok(())
 }
 
-fn validate_diddoc_content(diddoc_content: &SJsonValue) -> bool {
-    if diddoc_content.get("id").is_some() {
-        false
-    } else if diddoc_content.get("@context").is_some() {
-        let context = diddoc_content.get("@context").unwrap();
-
-        if context.is_string() {
-            validate_context(context.as_str().unwrap())
-        } else if context.is_array() {
-            let mut buf = false;
-            for c in context.as_array().unwrap() {
-                if buf {
-                    return buf;
+/// Check the verification methods, verification relationships and services of
+/// `diddocContent` against the rules of the did:indy method
+fn check_diddoc_content(did: &str, content: &SJsonValue) -> VdrResult<()> {
+    // Types of the verification methods defined by the document, by ID
+    let mut methods = HashMap::new();
+    methods.insert(
+        format!("{}#verkey", did),
+        "Ed25519VerificationKey2018".to_string(),
+    );
+    if let Some(vms) = content.get("verificationMethod") {
+        for method in as_entries(vms) {
+            add_verification_method(did, &mut methods, method)?;
+        }
+    }
+
+    for relationship in VERIFICATION_RELATIONSHIPS {
+        let entries = match content.get(*relationship) {
+            Some(entries) => as_entries(entries),
+            None => continue,
+        };
+        for entry in entries {
+            let type_ = match entry {
+                SJsonValue::String(reference) => {
+                    let id = resolve_did_url(did, reference);
+                    if id.starts_with(&format!("{}#", did)) {
+                        Some(methods.get(&id).cloned().ok_or_else(|| {
+                            content_err(format!(
+                                "{} references unknown verification method {}",
+                                relationship, reference
+                            ))
+                        })?)
+                    } else {
+                        // Verification method of another DID
+                        None
+                    }
+                }
+                SJsonValue::Object(_) => Some(add_verification_method(did, &mut methods, entry)?),
+                _ => {
+                    return Err(content_err(format!(
+                        "{} entries must be references or verification methods",
+                        relationship
+                    )))
                 }
-                buf = validate_context(c.as_str().unwrap());
+            };
+            if *relationship == "keyAgreement" && type_.map_or(false, |t| t.starts_with("Ed25519"))
+            {
+                return Err(content_err(
+                    "keyAgreement cannot use an Ed25519 signing key",
+                ));
             }
-            buf
-        } else {
-            false
         }
+    }
+
+    if let Some(services) = content.get("service") {
+        let services = services
+            .as_array()
+            .ok_or_else(|| content_err("service must be an array"))?;
+        let mut ids = HashSet::new();
+        for service in services {
+            let id = validate_service(did, service)?;
+            if !ids.insert(id.clone()) {
+                return Err(content_err(format!("duplicate service {}", id)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a verification method and record its type, rejecting duplicate IDs
+fn add_verification_method(
+    did: &str,
+    methods: &mut HashMap<String, String>,
+    method: &SJsonValue,
+) -> VdrResult<String> {
+    let (id, type_) = validate_verification_method(did, method)?;
+    if methods.contains_key(&id) {
+        return Err(content_err(format!("duplicate verification method {}", id)));
+    }
+    methods.insert(id, type_.clone());
+    Ok(type_)
+}
+
+/// Validate a verification method, returning its resolved ID and type
+fn validate_verification_method(did: &str, method: &SJsonValue) -> VdrResult<(String, String)> {
+    let id = method["id"]
+        .as_str()
+        .ok_or_else(|| content_err("verification method is missing an id"))?;
+    let type_ = method["type"]
+        .as_str()
+        .ok_or_else(|| content_err(format!("verification method {} is missing a type", id)))?;
+    if !method["controller"].is_string() {
+        return Err(content_err(format!(
+            "verification method {} is missing a controller",
+            id
+        )));
+    }
+    match type_ {
+        "Ed25519VerificationKey2018" | "X25519KeyAgreementKey2019" => {
+            let key = method["publicKeyBase58"]
+                .as_str()
+                .and_then(|key| base58::decode(key).ok())
+                .filter(|key| key.len() == 32);
+            if key.is_none() {
+                return Err(content_err(format!(
+                    "verification method {} must have a 32 byte publicKeyBase58",
+                    id
+                )));
+            }
+        }
+        "Ed25519VerificationKey2020" => validate_multibase_key(id, method, ED25519_MULTICODEC)?,
+        "X25519KeyAgreementKey2020" => validate_multibase_key(id, method, X25519_MULTICODEC)?,
+        "JsonWebKey2020" => validate_jwk(id, &method["publicKeyJwk"])?,
+        // Other verification method types are passed through unchanged
+        _ => (),
+    }
+    Ok((resolve_did_url(did, id), type_.to_string()))
+}
+
+/// Check for a base58-btc encoded `publicKeyMultibase` with the expected multicodec prefix
+fn validate_multibase_key(id: &str, method: &SJsonValue, codec: [u8; 2]) -> VdrResult<()> {
+    let key = method["publicKeyMultibase"]
+        .as_str()
+        .and_then(|key| key.strip_prefix('z'))
+        .and_then(|key| base58::decode(key).ok());
+    match key {
+        Some(key) if key.len() == 34 && key[..2] == codec => Ok(()),
+        _ => Err(content_err(format!(
+            "verification method {} has an invalid publicKeyMultibase",
+            id
+        ))),
+    }
+}
+
+fn validate_jwk(id: &str, jwk: &SJsonValue) -> VdrResult<()> {
+    let kty = jwk["kty"].as_str().ok_or_else(|| {
+        content_err(format!(
+            "verification method {} must have a publicKeyJwk with a kty",
+            id
+        ))
+    })?;
+    if !jwk["d"].is_null() {
+        return Err(content_err(format!(
+            "verification method {} must not contain a private key",
+            id
+        )));
+    }
+    if kty == "OKP"
+        && (!matches!(jwk["crv"].as_str(), Some("Ed25519") | Some("X25519"))
+            || !jwk["x"].is_string())
+    {
+        return Err(content_err(format!(
+            "verification method {} has an unsupported OKP key",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Validate a service entry, returning its resolved ID
+fn validate_service(did: &str, service: &SJsonValue) -> VdrResult<String> {
+    let id = service["id"]
+        .as_str()
+        .ok_or_else(|| content_err("service is missing an id"))?;
+    let types = as_entries(&service["type"]);
+    if service["type"].is_null() || !types.iter().all(|t| t.is_string()) {
+        return Err(content_err(format!("service {} is missing a type", id)));
+    }
+    let endpoint = &service["serviceEndpoint"];
+    if endpoint.is_null() {
+        return Err(content_err(format!(
+            "service {} is missing a serviceEndpoint",
+            id
+        )));
+    }
+    if types
+        .iter()
+        .any(|t| t.as_str() == Some(DIDCOMM_MESSAGING_SERVICE))
+    {
+        validate_didcomm_messaging(id, service)?;
+    }
+    Ok(resolve_did_url(did, id))
+}
+
+/// Validate the endpoint of a DIDComm Messaging (v2) service. The endpoint may be a
+/// URI, or one or more objects with a `uri` and optional `accept` and `routingKeys`.
+fn validate_didcomm_messaging(id: &str, service: &SJsonValue) -> VdrResult<()> {
+    let is_string_list = |value: &SJsonValue| {
+        value.is_null()
+            || value
+                .as_array()
+                .map_or(false, |v| v.iter().all(|s| s.is_string()))
+    };
+    let valid = match &service["serviceEndpoint"] {
+        SJsonValue::String(_) => {
+            is_string_list(&service["accept"]) && is_string_list(&service["routingKeys"])
+        }
+        endpoint => as_entries(endpoint).into_iter().all(|endpoint| {
+            endpoint["uri"].is_string()
+                && is_string_list(&endpoint["accept"])
+                && is_string_list(&endpoint["routingKeys"])
+        }),
+    };
+    if valid {
+        Ok(())
     } else {
-        true
+        Err(content_err(format!(
+            "service {} has an invalid DIDCommMessaging endpoint",
+            id
+        )))
     }
 }
 
@@ -161,10 +595,14 @@ fn merge_diddoc(base: &mut SJsonValue, content: &SJsonValue) {
     match (base, content) {
         (SJsonValue::Object(base), SJsonValue::Object(content)) => {
             for (k, v) in content {
-                if k == "authentication" || k == "verificationMethod" {
-                    let mut _tmp = base[k].as_array().unwrap().to_owned();
-                    _tmp.append(&mut v.as_array().unwrap_or(&vec![v.to_owned()]).to_owned());
-                    base[k] = SJsonValue::from(_tmp);
+                if k == "verificationMethod" || VERIFICATION_RELATIONSHIPS.contains(&k.as_str()) {
+                    let mut entries = base
+                        .get(k)
+                        .and_then(|e| e.as_array())
+                        .cloned()
+                        .unwrap_or_default();
+                    entries.extend(as_entries(v).into_iter().cloned());
+                    base.insert(k.clone(), SJsonValue::Array(entries));
                 } else {
                     merge_diddoc(base.entry(k).or_insert(SJsonValue::Null), v);
                 }
@@ -214,6 +652,25 @@ mod tests {
 
     use super::*;
 
+    const TEST_DID: &str = "did:indy:idunion:QowxFtwciWceMFr7WbwnM";
+
+    fn _multibase_key(codec: [u8; 2]) -> String {
+        let mut key = codec.to_vec();
+        key.extend([7u8; 32]);
+        format!("z{}", base58::encode(key))
+    }
+
+    fn _doc_with_content(diddoc_content: SJsonValue) -> VdrResult<SJsonValue> {
+        DidDocument::new(
+            "idunion",
+            "QowxFtwciWceMFr7WbwnM",
+            "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk",
+            None,
+            Some(diddoc_content),
+        )
+        .to_value()
+    }
+
     #[test]
     fn expand_verkey_no_type() {
         let id = "V4SGRU86Z58d6TV7PBUe6f";
@@ -575,7 +1032,7 @@ mod tests {
         let diddoc_content = json!({
             "@context" : "https://www.w3.org/ns/did/v1"
         });
-        assert!(validate_diddoc_content(&diddoc_content))
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
     }
 
     #[test]
@@ -591,7 +1048,7 @@ mod tests {
           }
         ]
         });
-        assert!(validate_diddoc_content(&diddoc_content))
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
     }
 
     #[test]
@@ -602,7 +1059,7 @@ mod tests {
                 "https://identity.foundation/didcomm-messaging/service-endpoint/v1"
         ],
         });
-        assert!(validate_diddoc_content(&diddoc_content))
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
     }
 
     #[test]
@@ -610,7 +1067,7 @@ mod tests {
         let diddoc_content = json!({
             "@context" : [],
         });
-        assert!(!validate_diddoc_content(&diddoc_content))
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_err())
     }
 
     #[test]
@@ -618,7 +1075,7 @@ mod tests {
         let diddoc_content = json!({
             "@context" : "",
         });
-        assert!(!validate_diddoc_content(&diddoc_content))
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_err())
     }
 
     #[test]
@@ -626,6 +1083,258 @@ mod tests {
         let diddoc_content = json!({
             "id" : "sg3535sd",
         });
-        assert!(!validate_diddoc_content(&diddoc_content))
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_err())
+    }
+
+    #[test]
+    fn validate_diddoc_with_unknown_property() {
+        let diddoc_content = json!({
+            "publicKey": [],
+        });
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
+    }
+
+    #[test]
+    fn validate_diddoc_with_duplicate_verkey_id() {
+        let diddoc_content = json!({
+            "verificationMethod": [{
+                "id": "#verkey",
+                "type": "Ed25519VerificationKey2018",
+                "controller": TEST_DID,
+                "publicKeyBase58": "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBc",
+            }],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_err());
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
+    }
+
+    #[test]
+    fn validate_diddoc_with_unknown_authentication_reference() {
+        let diddoc_content = json!({
+            "authentication": ["#keys-2"],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_err());
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok());
+
+        let diddoc_content = json!({
+            "authentication": ["did:indy:idunion:V4SGRU86Z58d6TV7PBUe6f#verkey"],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_ok())
+    }
+
+    #[test]
+    fn validate_diddoc_with_invalid_keys() {
+        let diddoc_content = json!({
+            "verificationMethod": [{
+                "id": "#keys-2",
+                "type": "Ed25519VerificationKey2020",
+                "controller": TEST_DID,
+                "publicKeyMultibase": _multibase_key(X25519_MULTICODEC),
+            }],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_err());
+
+        let diddoc_content = json!({
+            "verificationMethod": [{
+                "id": "#keys-2",
+                "type": "JsonWebKey2020",
+                "controller": TEST_DID,
+                "publicKeyJwk": {"kty": "OKP", "crv": "Ed25519", "x": "abc", "d": "def"},
+            }],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_err());
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
+    }
+
+    #[test]
+    fn validate_diddoc_with_ed25519_key_agreement() {
+        let diddoc_content = json!({
+            "keyAgreement": ["#verkey"],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_err());
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
+    }
+
+    #[test]
+    fn validate_diddoc_with_invalid_didcomm_messaging_service() {
+        let diddoc_content = json!({
+            "service": [{
+                "id": "#didcomm",
+                "type": "DIDCommMessaging",
+                "serviceEndpoint": {"accept": ["didcomm/v2"]},
+            }],
+        });
+        assert!(check_diddoc_content(TEST_DID, &diddoc_content).is_err());
+        assert!(validate_diddoc_content(TEST_DID, &diddoc_content).is_ok())
+    }
+
+    #[test]
+    fn serialize_diddoc_with_invalid_content_fails() {
+        let err = _doc_with_content(json!({"id": TEST_DID})).unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::Resolver))
+    }
+
+    #[test]
+    fn serialze_diddoc_with_key_agreement_and_didcomm_messaging() {
+        let x25519_key = _multibase_key(X25519_MULTICODEC);
+        let diddoc_content = json!({
+            "@context": [DID_CORE_CONTEXT, "https://w3id.org/security/suites/x25519-2020/v1"],
+            "verificationMethod": [{
+                "id": "#keys-2",
+                "type": "Ed25519VerificationKey2020",
+                "controller": TEST_DID,
+                "publicKeyMultibase": _multibase_key(ED25519_MULTICODEC),
+            }, {
+                "id": "#keys-3",
+                "type": "JsonWebKey2020",
+                "controller": TEST_DID,
+                "publicKeyJwk": {"kty": "OKP", "crv": "Ed25519", "x": "VCpo2LMLhn6iWku8MKvSLg2ZAoC-nlOyPVQaO3FxVeQ"},
+            }],
+            "assertionMethod": ["#keys-2", "#keys-3"],
+            "keyAgreement": [{
+                "id": "#key-agreement-1",
+                "type": "X25519KeyAgreementKey2020",
+                "controller": TEST_DID,
+                "publicKeyMultibase": x25519_key,
+            }],
+            "service": [{
+                "id": "#didcomm",
+                "type": "DIDCommMessaging",
+                "serviceEndpoint": {
+                    "uri": "https://example.com",
+                    "accept": ["didcomm/v2"],
+                    "routingKeys": [],
+                },
+            }],
+        });
+
+        let doc = _doc_with_content(diddoc_content).unwrap();
+
+        assert_eq!(doc["id"], TEST_DID);
+        assert_eq!(doc["verificationMethod"].as_array().unwrap().len(), 3);
+        assert_eq!(doc["assertionMethod"], json!(["#keys-2", "#keys-3"]));
+        assert_eq!(
+            doc["keyAgreement"][0]["publicKeyMultibase"].as_str(),
+            Some(x25519_key.as_str())
+        );
+        assert_eq!(doc["service"][0]["type"], DIDCOMM_MESSAGING_SERVICE);
+    }
+
+    fn _sov_endpoint(endpoint: SJsonValue) -> Option<EndpointAttrib> {
+        Some(serde_json::from_value(json!({ "endpoint": endpoint })).unwrap())
+    }
+
+    #[test]
+    fn serialize_sov_diddoc() {
+        let mut doc = DidDocument::new(
+            "",
+            "QowxFtwciWceMFr7WbwnM",
+            "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk",
+            None,
+            None,
+        )
+        .with_method(DidMethod::Sov);
+        doc.set_endpoint(_sov_endpoint(json!({"endpoint": "https://agent.com"})));
+        let doc = doc.to_value().unwrap();
+
+        let did = "did:sov:QowxFtwciWceMFr7WbwnM";
+        assert_eq!(doc["id"], did);
+        assert_eq!(
+            doc["@context"],
+            json!([DID_CORE_CONTEXT, ED25519_2018_CONTEXT, X25519_2019_CONTEXT])
+        );
+        assert_eq!(
+            doc["verificationMethod"][0],
+            json!({
+                "id": format!("{}#key-1", did),
+                "type": "Ed25519VerificationKey2018",
+                "controller": did,
+                "publicKeyBase58": "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk",
+            })
+        );
+        assert_eq!(
+            doc["verificationMethod"][1]["type"],
+            "X25519KeyAgreementKey2019"
+        );
+        assert_eq!(doc["authentication"], json!([format!("{}#key-1", did)]));
+        assert_eq!(doc["assertionMethod"], json!([format!("{}#key-1", did)]));
+        assert_eq!(
+            doc["keyAgreement"],
+            json!([format!("{}#key-agreement-1", did)])
+        );
+        assert_eq!(
+            doc["service"],
+            json!([{
+                "id": format!("{}#endpoint", did),
+                "type": "endpoint",
+                "serviceEndpoint": "https://agent.com",
+            }, {
+                "id": format!("{}#did-communication", did),
+                "type": "did-communication",
+                "priority": 0,
+                "recipientKeys": [format!("{}#key-1", did)],
+                "routingKeys": [],
+                "accept": ["didcomm/aip2;env=rfc19"],
+                "serviceEndpoint": "https://agent.com",
+            }])
+        );
+    }
+
+    #[test]
+    fn serialize_sov_diddoc_with_endpoint_types() {
+        let mut doc = DidDocument::new(
+            "staging",
+            "QowxFtwciWceMFr7WbwnM",
+            "~KqKiNn3jzuWi3vE3FkHqN3",
+            None,
+            None,
+        )
+        .with_method(DidMethod::Unqualified);
+        doc.set_endpoint(_sov_endpoint(json!({
+            "endpoint": "https://agent.com",
+            "routingKeys": ["3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"],
+            "types": ["DIDComm", "profile"],
+            "profile": "https://agent.com/profile",
+        })));
+        let doc = doc.to_value().unwrap();
+
+        let did = "did:sov:staging:QowxFtwciWceMFr7WbwnM";
+        assert_eq!(doc["id"], did);
+        assert_eq!(
+            doc["service"],
+            json!([{
+                "id": format!("{}#didcomm-1", did),
+                "type": DIDCOMM_MESSAGING_SERVICE,
+                "serviceEndpoint": {
+                    "uri": "https://agent.com",
+                    "accept": ["didcomm/v2"],
+                    "routingKeys": ["3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"],
+                },
+            }, {
+                "id": format!("{}#profile", did),
+                "type": "profile",
+                "serviceEndpoint": "https://agent.com/profile",
+            }])
+        );
+    }
+
+    #[test]
+    fn serialize_indy_diddoc_with_sov_endpoint() {
+        let mut doc = DidDocument::new(
+            "idunion",
+            "QowxFtwciWceMFr7WbwnM",
+            "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk",
+            None,
+            None,
+        );
+        doc.set_endpoint(_sov_endpoint(json!({
+            "endpoint": "https://agent.com",
+            "routingKeys": [],
+            "types": ["endpoint"],
+        })));
+        let doc = doc.to_value().unwrap();
+
+        assert_eq!(doc["service"].as_array().unwrap().len(), 1);
+        assert_eq!(doc["service"][0]["type"], "did-communication");
     }
 }
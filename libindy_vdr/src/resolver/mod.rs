@@ -2,7 +2,9 @@ pub mod pool;
 
 pub mod did;
 pub mod did_document;
+pub mod multi_pool;
 pub mod types;
 pub mod utils;
 
+pub use self::multi_pool::MultiPoolResolver;
 pub use self::pool::{handle_resolution_result, PoolResolver, PoolRunnerResolver};
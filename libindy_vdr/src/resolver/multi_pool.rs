@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use super::did::{DidMethod, DidUrl};
+use super::pool::PoolResolver;
+use super::types::{DidResolutionMetadata, ResolutionResult, ERROR_INTERNAL, ERROR_NOT_FOUND};
+use crate::common::error::prelude::*;
+use crate::config::PoolConfig;
+use crate::pool::cache::Cache;
+use crate::pool::{Pool, PoolBuilder, PoolTransactions, RequestResultMeta};
+
+/// The name of the genesis transactions file for each ledger in a networks folder
+pub const GENESIS_FILENAME: &str = "pool_transactions_genesis.json";
+
+/// A function creating a pool instance from a builder for the ledger of a namespace
+pub type PoolFactory<T> = Box<dyn Fn(PoolBuilder) -> VdrResult<T> + Send + Sync>;
+
+enum PoolEntry<T> {
+    Genesis(PathBuf),
+    Transactions(PoolTransactions),
+    Pool(T),
+}
+
+/// DID (URL) Resolver for multiple indy networks compliant with the did:indy method spec.
+/// Requests are dispatched to the pool registered for the namespace of the DID, such as
+/// `sovrin` or `bcovrin:test`. Pools registered by genesis transactions are only created
+/// when first used. did:sov and unqualified DIDs are resolved using the default namespace,
+/// which is prefixed to the sub-namespace of a did:sov DID such as `did:sov:staging:<id>`.
+pub struct MultiPoolResolver<T> {
+    config: PoolConfig,
+    factory: PoolFactory<T>,
+    pools: RwLock<HashMap<String, PoolEntry<T>>>,
+    default_namespace: RwLock<Option<String>>,
+}
+
+impl<T: Clone> MultiPoolResolver<T> {
+    /// Create a new resolver without any registered networks. The factory is used to
+    /// create a pool instance for each network, for example `PoolBuilder::into_shared`.
+    pub fn new<F>(config: PoolConfig, factory: F) -> Self
+    where
+        F: Fn(PoolBuilder) -> VdrResult<T> + Send + Sync + 'static,
+    {
+        Self {
+            config,
+            factory: Box::new(factory),
+            pools: RwLock::new(HashMap::new()),
+            default_namespace: RwLock::new(None),
+        }
+    }
+
+    /// Set the namespace used to resolve did:sov and unqualified DIDs, such as `sovrin`
+    pub fn set_default_namespace(&self, namespace: Option<&str>) -> VdrResult<()> {
+        *self
+            .default_namespace
+            .write()
+            .map_err(|_| err_msg(VdrErrorKind::Unexpected, "Error acquiring write lock"))? =
+            namespace.map(str::to_string);
+        Ok(())
+    }
+
+    /// Get the namespace of the pool used to resolve a DID (URL)
+    pub fn did_namespace(&self, did: &DidUrl) -> VdrResult<String> {
+        if did.method() == DidMethod::Indy {
+            return Ok(did.namespace.clone());
+        }
+        let default_namespace = self
+            .default_namespace
+            .read()
+            .map_err(|_| err_msg(VdrErrorKind::Unexpected, "Error acquiring read lock"))?;
+        let default_namespace = default_namespace.as_ref().ok_or_else(|| {
+            err_msg(
+                VdrErrorKind::Resolver,
+                format!("No default namespace to resolve DID: {}", did.url),
+            )
+        })?;
+        if did.namespace.is_empty() {
+            Ok(default_namespace.clone())
+        } else {
+            Ok(format!("{}:{}", default_namespace, did.namespace))
+        }
+    }
+
+    /// Register each network found in a folder structure of
+    /// `<namespace>/<sub_namespace>/pool_transactions_genesis.json`, as used by the proxy.
+    /// Returns the number of networks found.
+    pub fn add_networks_folder(&self, path: impl AsRef<Path>) -> VdrResult<usize> {
+        let networks = find_networks(path.as_ref())?;
+        let count = networks.len();
+        let mut pools = self.write_pools()?;
+        for (namespace, genesis) in networks {
+            pools.insert(namespace, PoolEntry::Genesis(genesis));
+        }
+        Ok(count)
+    }
+
+    /// Register a network by the path to its genesis transactions file
+    pub fn add_genesis_file(&self, namespace: &str, path: impl Into<PathBuf>) -> VdrResult<()> {
+        self.write_pools()?
+            .insert(namespace.to_string(), PoolEntry::Genesis(path.into()));
+        Ok(())
+    }
+
+    /// Register a network by its genesis transactions
+    pub fn add_transactions(
+        &self,
+        namespace: &str,
+        transactions: PoolTransactions,
+    ) -> VdrResult<()> {
+        self.write_pools()?
+            .insert(namespace.to_string(), PoolEntry::Transactions(transactions));
+        Ok(())
+    }
+
+    /// Register an existing pool instance for a network
+    pub fn add_pool(&self, namespace: &str, pool: T) -> VdrResult<()> {
+        self.write_pools()?
+            .insert(namespace.to_string(), PoolEntry::Pool(pool));
+        Ok(())
+    }
+
+    /// Remove a registered network, returning `true` if it was found
+    pub fn remove(&self, namespace: &str) -> VdrResult<bool> {
+        Ok(self.write_pools()?.remove(namespace).is_some())
+    }
+
+    /// Get the namespaces of the registered networks
+    pub fn namespaces(&self) -> VdrResult<Vec<String>> {
+        let mut namespaces: Vec<String> = self.read_pools()?.keys().cloned().collect();
+        namespaces.sort();
+        Ok(namespaces)
+    }
+
+    /// Get the pool for a namespace, creating it if it has not been used before
+    pub fn get_pool(&self, namespace: &str) -> VdrResult<T> {
+        let entry = match self.read_pools()?.get(namespace) {
+            Some(PoolEntry::Pool(pool)) => return Ok(pool.clone()),
+            Some(PoolEntry::Genesis(path)) => PoolEntry::Genesis(path.clone()),
+            Some(PoolEntry::Transactions(transactions)) => {
+                PoolEntry::Transactions(transactions.clone())
+            }
+            None => {
+                return Err(err_msg(
+                    VdrErrorKind::Resolver,
+                    format!("Unknown DID namespace: {}", namespace),
+                ))
+            }
+        };
+        // the pool is created without holding the lock, so that other lookups are not blocked
+        let transactions = match entry {
+            PoolEntry::Pool(pool) => return Ok(pool),
+            PoolEntry::Genesis(path) => PoolTransactions::from_json_file(path)?,
+            PoolEntry::Transactions(transactions) => transactions,
+        };
+        debug!("Creating pool for namespace {}", namespace);
+        let pool = (self.factory)(PoolBuilder::new(self.config.clone(), transactions))?;
+        match self.write_pools()?.get_mut(namespace) {
+            // another lookup created the pool in the meantime
+            Some(PoolEntry::Pool(existing)) => return Ok(existing.clone()),
+            Some(entry) => *entry = PoolEntry::Pool(pool.clone()),
+            // the network was removed in the meantime
+            None => (),
+        }
+        Ok(pool)
+    }
+
+    fn read_pools(
+        &self,
+    ) -> VdrResult<std::sync::RwLockReadGuard<'_, HashMap<String, PoolEntry<T>>>> {
+        self.pools
+            .read()
+            .map_err(|_| err_msg(VdrErrorKind::Unexpected, "Error acquiring read lock"))
+    }
+
+    fn write_pools(
+        &self,
+    ) -> VdrResult<std::sync::RwLockWriteGuard<'_, HashMap<String, PoolEntry<T>>>> {
+        self.pools
+            .write()
+            .map_err(|_| err_msg(VdrErrorKind::Unexpected, "Error acquiring write lock"))
+    }
+}
+
+impl<T: Pool> MultiPoolResolver<T> {
+    /// Dereference a DID Url using the pool for its namespace and return a serialized
+    /// `DereferencingResult`
+    pub async fn dereference(
+        &self,
+        did_url: &str,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<String> {
+        let pool = self.get_pool(&self.did_namespace(&DidUrl::parse(did_url)?)?)?;
+        PoolResolver::new(pool).dereference(did_url, cache).await
+    }
+
+    /// Resolve every version of the DID document of a NYM using the pool for its namespace
+    /// and return a serialized `DidDocumentHistory`
+    pub async fn resolve_history(
+        &self,
+        did: &str,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<String> {
+        let pool = self.get_pool(&self.did_namespace(&DidUrl::parse(did)?)?)?;
+        PoolResolver::new(pool).resolve_history(did, cache).await
+    }
+
+    /// Resolve a DID using the pool for its namespace and return a serialized `ResolutionResult`
+    pub async fn resolve(
+        &self,
+        did: &str,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<String> {
+        let result = self.resolve_result(did, None, cache).await;
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    /// Resolve a DID using the pool for its namespace, in the representation selected by
+    /// an optional `Accept` header. DIDs of unknown namespaces are reported as not found.
+    pub async fn resolve_result(
+        &self,
+        did: &str,
+        accept: Option<&str>,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> ResolutionResult {
+        let did_url = match DidUrl::parse(did) {
+            Ok(did_url) => did_url,
+            Err(err) => return ResolutionResult::invalid_did(did, err),
+        };
+        let pool = match self
+            .did_namespace(&did_url)
+            .and_then(|namespace| self.get_pool(&namespace))
+        {
+            Ok(pool) => pool,
+            Err(err) => {
+                let error = match err.kind() {
+                    VdrErrorKind::Resolver => ERROR_NOT_FOUND,
+                    _ => ERROR_INTERNAL,
+                };
+                return ResolutionResult::from_error(DidResolutionMetadata::default(), error, err);
+            }
+        };
+        PoolResolver::new(pool)
+            .resolve_result(did, accept, cache)
+            .await
+    }
+}
+
+/// Find the genesis transactions file for each network in a folder structure of
+/// `<namespace>/<sub_namespace>/pool_transactions_genesis.json`. A genesis file
+/// directly within a namespace folder is used for the namespace itself.
+pub fn find_networks(path: &Path) -> VdrResult<HashMap<String, PathBuf>> {
+    let mut networks = HashMap::new();
+
+    let read_dir = |path: &Path| {
+        fs::read_dir(path).map_err(|err| {
+            err_msg(
+                VdrErrorKind::FileSystem,
+                format!("Could not read networks folder: {:?}", path),
+            )
+            .with_source(err)
+        })
+    };
+
+    for entry in read_dir(path)? {
+        let entry = entry?;
+        let namespace = entry.file_name().to_string_lossy().into_owned();
+        // filter hidden directories starting with "." and files
+        if namespace.starts_with('.') || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        for sub_entry in read_dir(&entry.path())? {
+            let sub_path = sub_entry?.path();
+            if sub_path.is_dir() {
+                let sub_namespace = sub_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                networks.insert(
+                    format!("{}:{}", namespace, sub_namespace),
+                    sub_path.join(GENESIS_FILENAME),
+                );
+            } else {
+                networks.insert(namespace.clone(), entry.path().join(GENESIS_FILENAME));
+            }
+        }
+    }
+    Ok(networks)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use rand::random;
+
+    use super::*;
+    use crate::pool::networker::MockLedger;
+
+    fn _networks_folder() -> PathBuf {
+        let path = temp_dir().join(format!("vdr-test-{:020}", random::<u64>()));
+        for folder in ["sovrin", "sovrin/staging", "bcovrin/test", ".git/objects"] {
+            fs::create_dir_all(path.join(folder)).unwrap();
+        }
+        fs::write(path.join("sovrin").join(GENESIS_FILENAME), "").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find_networks() {
+        let path = _networks_folder();
+        let networks = find_networks(&path).unwrap();
+        let mut namespaces: Vec<&str> = networks.keys().map(String::as_str).collect();
+        namespaces.sort();
+        assert_eq!(namespaces, ["bcovrin:test", "sovrin", "sovrin:staging"]);
+        assert_eq!(
+            networks["bcovrin:test"],
+            path.join("bcovrin").join("test").join(GENESIS_FILENAME)
+        );
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resolver_registry() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        let resolver = MultiPoolResolver::new(PoolConfig::default(), move |_builder| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(String::from("created"))
+        });
+
+        let path = _networks_folder();
+        assert_eq!(resolver.add_networks_folder(&path).unwrap(), 3);
+        resolver
+            .add_pool("idunion", String::from("idunion"))
+            .unwrap();
+        assert_eq!(
+            resolver.namespaces().unwrap(),
+            ["bcovrin:test", "idunion", "sovrin", "sovrin:staging"]
+        );
+
+        assert_eq!(resolver.get_pool("idunion").unwrap(), "idunion");
+        let err = resolver.get_pool("indicio").unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::Resolver));
+        // The genesis file is only read when the pool is first used
+        let err = resolver.get_pool("bcovrin:test").unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::FileSystem));
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+
+        assert!(resolver.remove("bcovrin:test").unwrap());
+        assert!(!resolver.remove("bcovrin:test").unwrap());
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resolver_creates_pool_without_lock() {
+        let resolver: Arc<Mutex<Option<Arc<MultiPoolResolver<String>>>>> = Arc::default();
+        let unlocked = Arc::new(AtomicUsize::new(0));
+        let (factory_resolver, factory_unlocked) = (resolver.clone(), unlocked.clone());
+        let created = Arc::new(MultiPoolResolver::new(
+            PoolConfig::default(),
+            move |_builder| {
+                let resolver = factory_resolver.lock().unwrap().clone().unwrap();
+                if resolver.pools.try_write().is_ok() {
+                    factory_unlocked.fetch_add(1, Ordering::SeqCst);
+                }
+                Ok(String::from("created"))
+            },
+        ));
+        *resolver.lock().unwrap() = Some(created.clone());
+
+        let transactions = MockLedger::new(4).unwrap().pool_transactions().unwrap();
+        created.add_transactions("sovrin", transactions).unwrap();
+        assert_eq!(created.get_pool("sovrin").unwrap(), "created");
+        assert_eq!(unlocked.load(Ordering::SeqCst), 1);
+        // the created pool is reused
+        assert_eq!(created.get_pool("sovrin").unwrap(), "created");
+        assert_eq!(unlocked.load(Ordering::SeqCst), 1);
+        resolver.lock().unwrap().take();
+    }
+
+    #[test]
+    fn test_resolver_default_namespace() {
+        let resolver = MultiPoolResolver::new(PoolConfig::default(), |_builder| {
+            Ok(String::from("created"))
+        });
+        let did = DidUrl::parse("did:indy:idunion:BDrEcHc8Tb4Lb2VyQZWEDE").unwrap();
+        let sov_did = DidUrl::parse("did:sov:staging:BDrEcHc8Tb4Lb2VyQZWEDE").unwrap();
+        let unqualified_did = DidUrl::parse("BDrEcHc8Tb4Lb2VyQZWEDE").unwrap();
+
+        assert_eq!(resolver.did_namespace(&did).unwrap(), "idunion");
+        let err = resolver.did_namespace(&unqualified_did).unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::Resolver));
+
+        resolver.set_default_namespace(Some("sovrin")).unwrap();
+        assert_eq!(resolver.did_namespace(&did).unwrap(), "idunion");
+        assert_eq!(resolver.did_namespace(&sov_did).unwrap(), "sovrin:staging");
+        assert_eq!(resolver.did_namespace(&unqualified_did).unwrap(), "sovrin");
+    }
+}
@@ -1,13 +1,18 @@
-use super::did::DidUrl;
+use super::did::{DidMethod, DidUrl};
+use super::did_document::{DidDocument, LEGACY_INDY_SERVICE};
 use crate::common::error::prelude::*;
 
 use crate::ledger::RequestBuilder;
 use crate::pool::cache::Cache;
-use crate::pool::{Pool, PoolRunner, RequestResult, RequestResultMeta};
+use crate::pool::{Pool, PoolRunner, PreparedRequest, RequestResult, RequestResultMeta};
 
 use super::types::*;
 use super::utils::*;
 
+/// The maximum number of versions of a NYM, and of its `endpoint` ATTRIB, fetched when
+/// resolving the history of a DID document
+pub const MAX_HISTORY_VERSIONS: usize = 50;
+
 /// DID (URL) Resolver interface for a pool compliant with did:indy method spec
 /// The resolver interface is bound to a specific indy network and does not evaluate
 /// the namespace part of the DID. You need to create a resolver instance for each
@@ -22,7 +27,9 @@ impl<T: Pool> PoolResolver<T> {
         PoolResolver { pool }
     }
 
-    /// Dereference a DID Url and return a serialized `DereferencingResult`
+    /// Dereference a DID Url and return a serialized `DereferencingResult`. DID URLs
+    /// without a path dereference the DID document, or the verification method or service
+    /// selected by a fragment, or the service endpoint URL of a `service` query parameter.
     pub async fn dereference(
         &self,
         did_url: &str,
@@ -30,23 +37,38 @@ impl<T: Pool> PoolResolver<T> {
     ) -> VdrResult<String> {
         debug!("PoolResolver: Dereference DID Url {}", did_url);
         let did_url = DidUrl::parse(did_url)?;
-        let (data, metadata) = self._resolve(&did_url, cache).await?;
-
-        let content = match data {
-            Result::Content(c) => Some(c),
-            _ => None,
-        };
-
-        let md = if let Metadata::ContentMetadata(md) = metadata {
-            Some(md)
-        } else {
-            None
+        let (data, metadata) = self._resolve(&did_url, cache.clone()).await?;
+
+        let (content, md) = match (data, metadata) {
+            (Result::DidDocument(mut doc), Metadata::DidDocumentMetadata(md)) => {
+                self._fetch_legacy_endpoint(&did_url, &mut doc, &md, cache)
+                    .await;
+                let doc = doc.to_value()?;
+                let (content, object_type) = if did_url.is_document_part() {
+                    dereference_document(&did_url, &doc)?
+                } else {
+                    (doc, "NYM")
+                };
+                let md = ContentMetadata {
+                    node_response: md.node_response,
+                    object_type: object_type.to_string(),
+                };
+                (content, md)
+            }
+            (Result::Content(content), Metadata::ContentMetadata(md)) => (content, md),
+            _ => {
+                return Err(err_msg(
+                    VdrErrorKind::Unexpected,
+                    "Unexpected ledger result",
+                ))
+            }
         };
+        check_hashlink(&did_url, &content)?;
 
         let result = DereferencingResult {
             dereferencing_metadata: None,
-            content_stream: content,
-            content_metadata: md,
+            content_stream: Some(content),
+            content_metadata: Some(md),
         };
 
         Ok(serde_json::to_string_pretty(&result).unwrap())
@@ -58,9 +80,72 @@ impl<T: Pool> PoolResolver<T> {
         did: &str,
         cache: Option<Cache<String, (String, RequestResultMeta)>>,
     ) -> VdrResult<String> {
+        let result = self.resolve_result(did, None, cache).await;
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    /// Resolve a DID in the representation selected by an optional `Accept` header.
+    /// DIDs which cannot be resolved produce a result with an error in the
+    /// `did_resolution_metadata`.
+    pub async fn resolve_result(
+        &self,
+        did: &str,
+        accept: Option<&str>,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> ResolutionResult {
         debug!("PoolResolver: Resolve DID {}", did);
-        let did = DidUrl::parse(did)?;
-        let (data, metadata) = self._resolve(&did, cache.clone()).await?;
+        let did_url = match DidUrl::parse(did) {
+            Ok(did_url) if did_url.path.is_some() || did_url.is_document_part() => {
+                return ResolutionResult::invalid_did(did, "DID URLs must be dereferenced")
+            }
+            Ok(did_url) => did_url,
+            Err(err) => return ResolutionResult::invalid_did(did, err),
+        };
+        let representation = match did_representation(accept) {
+            Ok(representation) => representation,
+            Err(err) => {
+                return ResolutionResult::from_error(
+                    DidResolutionMetadata::default(),
+                    ERROR_REPRESENTATION_NOT_SUPPORTED,
+                    err,
+                )
+            }
+        };
+        match self._resolve_did(&did_url, representation, cache).await {
+            Ok(result) => result,
+            Err(err) => ResolutionResult::from_error(
+                DidResolutionMetadata {
+                    method: Some(did_url.method().name().to_string()),
+                    ..DidResolutionMetadata::default()
+                },
+                ERROR_INTERNAL,
+                err,
+            ),
+        }
+    }
+
+    async fn _resolve_did(
+        &self,
+        did: &DidUrl,
+        representation: Option<&str>,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<ResolutionResult> {
+        let builder = self.pool.get_request_builder();
+        let request = build_request(did, &builder)?;
+        let (result, meta) = request_transaction(&self.pool, &request, cache.clone()).await?;
+        let ledger_data = match result {
+            RequestResult::Reply(data) => data,
+            RequestResult::Failed(err) => return Err(err),
+        };
+        let resolution_md = DidResolutionMetadata::retrieved(did, &meta);
+        if is_not_found(&ledger_data) {
+            return Ok(ResolutionResult::from_error(
+                resolution_md,
+                ERROR_NOT_FOUND,
+                format!("DID not found: {}", did.url),
+            ));
+        }
+        let (data, metadata) = handle_internal_resolution_result(did, &ledger_data)?;
 
         let md = if let Metadata::DidDocumentMetadata(md) = metadata {
             Some(md)
@@ -68,51 +153,158 @@ impl<T: Pool> PoolResolver<T> {
             None
         };
 
+        let mut content_type = None;
         let diddoc = match data {
             Result::DidDocument(mut doc) => {
-                // Try to find legacy endpoint using a GET_ATTRIB txn if diddoc_content is none
-                if doc.diddoc_content.is_none() {
-                    let (seq_no, timestamp) = if let Some(md) = &md {
-                        let j_seq_no = &md.node_response["result"]["seqNo"];
-                        let j_timestamp = &md.node_response["result"]["timestamp"];
-
-                        let mut seq_no = None;
-                        let mut timestamp = None;
-
-                        if !j_seq_no.is_null() {
-                            seq_no = j_seq_no.as_u64().map(|v| v as i32);
-                        }
-
-                        if !j_timestamp.is_null() {
-                            timestamp = j_timestamp.as_u64();
-                            // Use timestamp if possible
-                            if timestamp.is_some() {
-                                seq_no = None;
-                            }
-                        }
-                        (seq_no, timestamp)
-                    } else {
-                        (None, None)
-                    };
-                    doc.endpoint =
-                        fetch_legacy_endpoint(&self.pool, &did.id, seq_no, timestamp, cache)
-                            .await
-                            .ok();
+                if let Some(md) = md.as_ref() {
+                    self._fetch_legacy_endpoint(did, &mut doc, md, cache).await;
                 }
-                Some(doc.to_value()?)
+                let mut doc = doc.to_value()?;
+                content_type = Some(represent_did_document(&mut doc, representation));
+                Some(doc)
             }
             _ => None,
         };
 
-        let result = ResolutionResult {
-            did_resolution_metadata: None,
+        Ok(ResolutionResult {
+            did_resolution_metadata: Some(DidResolutionMetadata {
+                content_type,
+                ..resolution_md
+            }),
             did_document: diddoc,
             did_document_metadata: md,
-        };
+        })
+    }
 
+    /// Resolve every version of the DID document of a NYM and return a serialized
+    /// `DidDocumentHistory`. Versions are found by walking back through the NYM and its
+    /// `endpoint` ATTRIB, requesting the state before the time of each transaction, so
+    /// that only the last of several updates within the same second is found. At most
+    /// `MAX_HISTORY_VERSIONS` of the latest versions of each are fetched.
+    pub async fn resolve_history(
+        &self,
+        did: &str,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<String> {
+        debug!("PoolResolver: Resolve DID document history {}", did);
+        let did_url = DidUrl::parse(did)?;
+        if did_url.path.is_some() || did_url.is_document_part() {
+            return Err(err_msg(
+                VdrErrorKind::Resolver,
+                "The history of a DID URL cannot be resolved",
+            ));
+        }
+        let builder = self.pool.get_request_builder();
+        let nyms = self
+            ._fetch_versions(
+                |timestamp| builder.build_get_nym_request(None, &did_url.id, None, timestamp),
+                cache.clone(),
+            )
+            .await?;
+        if nyms.is_empty() {
+            return Err(err_msg(
+                VdrErrorKind::Resolver,
+                format!("DID not found: {}", did),
+            ));
+        }
+        let endpoints = self
+            ._fetch_versions(
+                |timestamp| {
+                    builder.build_get_attrib_request(
+                        None,
+                        &did_url.id,
+                        Some(String::from(LEGACY_INDY_SERVICE)),
+                        None,
+                        None,
+                        None,
+                        timestamp,
+                    )
+                },
+                cache,
+            )
+            .await?;
+
+        let result = DidDocumentHistory {
+            did: did.to_string(),
+            versions: build_document_history(&did_url, &nyms, &endpoints)?,
+        };
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
+    // Walk back through the versions of a ledger object, from the current version,
+    // making one request per version up to MAX_HISTORY_VERSIONS
+    async fn _fetch_versions<F>(
+        &self,
+        build_request: F,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) -> VdrResult<Vec<LedgerVersion>>
+    where
+        F: Fn(Option<u64>) -> VdrResult<PreparedRequest>,
+    {
+        let mut versions: Vec<LedgerVersion> = Vec::new();
+        let mut timestamp = None;
+        while versions.len() < MAX_HISTORY_VERSIONS {
+            let request = build_request(timestamp)?;
+            let ledger_data = handle_request(&self.pool, &request, cache.clone()).await?;
+            let version = match LedgerVersion::from_reply(ledger_data) {
+                Some(version) => version,
+                None => break,
+            };
+            // stop unless the versions are moving back in time
+            if versions
+                .last()
+                .map(|last| version.seq_no >= last.seq_no)
+                .unwrap_or(false)
+            {
+                break;
+            }
+            let txn_time = version.txn_time;
+            versions.push(version);
+            if txn_time == 0 {
+                break;
+            }
+            timestamp = Some(txn_time - 1);
+        }
+        versions.reverse();
+        Ok(versions)
+    }
+
+    // Try to find legacy endpoint using a GET_ATTRIB txn if diddoc_content is none,
+    // did:sov DID documents always include the endpoint services
+    async fn _fetch_legacy_endpoint(
+        &self,
+        did: &DidUrl,
+        doc: &mut DidDocument,
+        md: &DidDocumentMetadata,
+        cache: Option<Cache<String, (String, RequestResultMeta)>>,
+    ) {
+        if doc.diddoc_content.is_some() && did.method() == DidMethod::Indy {
+            return;
+        }
+        let j_seq_no = &md.node_response["result"]["seqNo"];
+        let j_timestamp = &md.node_response["result"]["timestamp"];
+
+        let mut seq_no = None;
+        let mut timestamp = None;
+
+        if !j_seq_no.is_null() {
+            seq_no = j_seq_no.as_u64().map(|v| v as i32);
+        }
+
+        if !j_timestamp.is_null() {
+            timestamp = j_timestamp.as_u64();
+            // Use timestamp if possible
+            if timestamp.is_some() {
+                seq_no = None;
+            }
+        }
+        doc.set_endpoint(
+            fetch_endpoint_attrib(&self.pool, &did.id, seq_no, timestamp, cache)
+                .await
+                .ok(),
+        );
+    }
+
     // Internal method to resolve and dereference
     async fn _resolve(
         &self,
@@ -126,8 +318,7 @@ impl<T: Pool> PoolResolver<T> {
             did_url.id, request
         );
         let ledger_data = handle_request(&self.pool, &request, cache).await?;
-        let namespace = did_url.namespace.clone();
-        let result = handle_internal_resolution_result(namespace.as_str(), &ledger_data)?;
+        let result = handle_internal_resolution_result(did_url, &ledger_data)?;
 
         Ok(result)
     }
@@ -176,24 +367,58 @@ type SendReqResponse = VdrResult<(RequestResult<String>, RequestResultMeta)>;
 
 pub fn handle_resolution_result(result: SendReqResponse, did_url: String) -> VdrResult<String> {
     let did = DidUrl::parse(did_url.as_str())?;
-    let (req_result, _meta) = result?;
+    let (req_result, meta) = result?;
 
     let ledger_data = match req_result {
         RequestResult::Reply(reply_data) => Ok(reply_data),
         RequestResult::Failed(err) => Err(err),
     }?;
 
-    let namespace = did.namespace;
+    let resolution_md = DidResolutionMetadata::retrieved(&did, &meta);
+    if did.path.is_none() && is_not_found(&ledger_data) {
+        let result = ResolutionResult::from_error(
+            resolution_md,
+            ERROR_NOT_FOUND,
+            format!("DID not found: {}", did.url),
+        );
+        return serde_json::to_string_pretty(&result)
+            .map_err(|err| err_msg(VdrErrorKind::Unexpected, err));
+    }
 
-    let (data, metadata) = handle_internal_resolution_result(namespace.as_str(), &ledger_data)?;
+    let (data, metadata) = handle_internal_resolution_result(&did, &ledger_data)?;
 
+    if let (Result::DidDocument(doc), Metadata::DidDocumentMetadata(md)) = (&data, &metadata) {
+        if did.is_document_part() {
+            let (content, object_type) = dereference_document(&did, &doc.to_value()?)?;
+            check_hashlink(&did, &content)?;
+            let result = DereferencingResult {
+                dereferencing_metadata: None,
+                content_stream: Some(content),
+                content_metadata: Some(ContentMetadata {
+                    node_response: md.node_response.clone(),
+                    object_type: object_type.to_string(),
+                }),
+            };
+            return serde_json::to_string_pretty(&result)
+                .map_err(|err| err_msg(VdrErrorKind::Unexpected, err));
+        }
+    }
+
+    let mut content_type = None;
     let content = match data {
         Result::Content(c) => Some(c),
-        Result::DidDocument(doc) => doc.to_value().ok(),
+        Result::DidDocument(doc) => {
+            let mut doc = doc.to_value()?;
+            content_type = Some(represent_did_document(&mut doc, None));
+            Some(doc)
+        }
     };
 
     match metadata {
         Metadata::ContentMetadata(md) => {
+            if let Some(content) = content.as_ref() {
+                check_hashlink(&did, content)?;
+            }
             let result = DereferencingResult {
                 dereferencing_metadata: None,
                 content_stream: content,
@@ -205,7 +430,10 @@ pub fn handle_resolution_result(result: SendReqResponse, did_url: String) -> Vdr
         }
         Metadata::DidDocumentMetadata(md) => {
             let result = ResolutionResult {
-                did_resolution_metadata: None,
+                did_resolution_metadata: Some(DidResolutionMetadata {
+                    content_type,
+                    ..resolution_md
+                }),
                 did_document: content,
                 did_document_metadata: Some(md),
             };
@@ -1,6 +1,23 @@
+use std::fmt::Display;
+
 use serde_json::Value as SJsonValue;
+use time::OffsetDateTime;
 
+use super::did::DidUrl;
 use super::did_document::DidDocument;
+use crate::pool::{RequestResultMeta, StateProofResult};
+
+/// Media type of a DID document in the JSON representation
+pub const DID_JSON: &str = "application/did+json";
+/// Media type of a DID document in the JSON-LD representation
+pub const DID_LD_JSON: &str = "application/did+ld+json";
+
+// Error codes of the DID resolution metadata
+pub const ERROR_INVALID_DID: &str = "invalidDid";
+pub const ERROR_NOT_FOUND: &str = "notFound";
+pub const ERROR_METHOD_NOT_SUPPORTED: &str = "methodNotSupported";
+pub const ERROR_REPRESENTATION_NOT_SUPPORTED: &str = "representationNotSupported";
+pub const ERROR_INTERNAL: &str = "internalError";
 
 pub type Callback<R> = Box<dyn FnOnce(R) + Send>;
 
@@ -33,14 +50,105 @@ pub struct DidDocumentMetadata {
     pub self_certification_version: Option<i32>,
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DidResolutionMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retrieved: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// The outcome of the state proof verification: `verified`, `invalid`, `expired` or
+    /// `missing`. Absent if the reply was accepted by consensus without a state proof.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_proof: Option<String>,
+}
+
+impl DidResolutionMetadata {
+    /// Metadata for a DID retrieved from the ledger now
+    pub fn retrieved(did: &DidUrl, meta: &RequestResultMeta) -> Self {
+        let now = OffsetDateTime::now_utc();
+        let retrieved = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        Self {
+            retrieved: Some(retrieved),
+            method: Some(did.method().name().to_string()),
+            state_proof: state_proof_status(meta).map(str::to_string),
+            ..Self::default()
+        }
+    }
+}
+
+/// Summarize the state proof verification of the node replies, reporting a verified
+/// state proof if any node provided one
+fn state_proof_status(meta: &RequestResultMeta) -> Option<&'static str> {
+    meta.state_proof
+        .values()
+        .map(|result| match result {
+            StateProofResult::Verified(_) => (3, "verified"),
+            StateProofResult::Invalid(..) => (2, "invalid"),
+            StateProofResult::Expired(_) => (1, "expired"),
+            StateProofResult::Missing => (0, "missing"),
+        })
+        .max()
+        .map(|(_, status)| status)
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ResolutionResult {
-    pub did_resolution_metadata: Option<String>,
+    pub did_resolution_metadata: Option<DidResolutionMetadata>,
     pub did_document: Option<SJsonValue>,
     pub did_document_metadata: Option<DidDocumentMetadata>,
 }
 
+impl ResolutionResult {
+    /// A result for a DID which could not be resolved
+    pub fn from_error(metadata: DidResolutionMetadata, error: &str, message: impl Display) -> Self {
+        Self {
+            did_resolution_metadata: Some(DidResolutionMetadata {
+                error: Some(error.to_string()),
+                error_message: Some(message.to_string()),
+                ..metadata
+            }),
+            did_document: None,
+            did_document_metadata: None,
+        }
+    }
+
+    /// A result for a DID which could not be parsed, reporting DIDs of other methods
+    /// as not supported
+    pub fn invalid_did(did: &str, message: impl Display) -> Self {
+        let did = did.trim();
+        let error = match did.strip_prefix("did:") {
+            Some(rest) if !rest.starts_with("indy:") && !rest.starts_with("sov:") => {
+                ERROR_METHOD_NOT_SUPPORTED
+            }
+            _ => ERROR_INVALID_DID,
+        };
+        Self::from_error(DidResolutionMetadata::default(), error, message)
+    }
+
+    /// The error code of a DID which could not be resolved
+    pub fn error(&self) -> Option<&str> {
+        self.did_resolution_metadata
+            .as_ref()
+            .and_then(|md| md.error.as_deref())
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DereferencingResult {
@@ -48,3 +156,112 @@ pub struct DereferencingResult {
     pub content_stream: Option<SJsonValue>,
     pub content_metadata: Option<ContentMetadata>,
 }
+
+/// A version of the DID document of a NYM, created by a NYM or `endpoint` ATTRIB transaction
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocumentVersion {
+    pub seq_no: u64,
+    pub txn_time: u64,
+    pub object_type: String,
+    pub did_document: SJsonValue,
+}
+
+/// The versions of the DID document of a NYM, ordered from the oldest version
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocumentHistory {
+    pub did: String,
+    pub versions: Vec<DidDocumentVersion>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::StateProofAssertions;
+    use rstest::*;
+    use time::format_description::well_known::Rfc3339;
+
+    fn _meta(results: &[StateProofResult]) -> RequestResultMeta {
+        RequestResultMeta {
+            state_proof: results
+                .iter()
+                .enumerate()
+                .map(|(idx, result)| (format!("Node{}", idx + 1), result.clone()))
+                .collect(),
+            timing: None,
+            cached: false,
+        }
+    }
+
+    fn _assertions() -> StateProofAssertions {
+        StateProofAssertions {
+            ledger_id: 1,
+            pool_state_root_hash: String::new(),
+            state_root_hash: String::new(),
+            timestamp: 0,
+            txn_root_hash: String::new(),
+        }
+    }
+
+    #[rstest]
+    fn state_proof_status_reports_the_best_result() {
+        let invalid = StateProofResult::Invalid("bad".to_owned(), None);
+        let expired = StateProofResult::Expired(_assertions());
+        let verified = StateProofResult::Verified(_assertions());
+        assert_eq!(state_proof_status(&_meta(&[])), None);
+        assert_eq!(
+            state_proof_status(&_meta(&[StateProofResult::Missing])),
+            Some("missing")
+        );
+        assert_eq!(
+            state_proof_status(&_meta(&[StateProofResult::Missing, expired.clone()])),
+            Some("expired")
+        );
+        assert_eq!(
+            state_proof_status(&_meta(&[expired.clone(), invalid.clone()])),
+            Some("invalid")
+        );
+        assert_eq!(
+            state_proof_status(&_meta(&[invalid, verified, expired])),
+            Some("verified")
+        );
+    }
+
+    #[rstest]
+    fn retrieved_metadata() {
+        let before = OffsetDateTime::now_utc().unix_timestamp();
+        let did = DidUrl::parse("did:indy:sovrin:QowxFtwciWceMFr7WbwnM").unwrap();
+        let md = DidResolutionMetadata::retrieved(&did, &_meta(&[]));
+        assert_eq!(md.method.as_deref(), Some("indy"));
+        assert_eq!(md.state_proof, None);
+        assert_eq!(md.error, None);
+
+        let retrieved = md.retrieved.unwrap();
+        assert_eq!(retrieved.len(), 20);
+        let retrieved = OffsetDateTime::parse(&retrieved, &Rfc3339)
+            .unwrap()
+            .unix_timestamp();
+        assert!(retrieved >= before && retrieved <= OffsetDateTime::now_utc().unix_timestamp());
+    }
+
+    #[rstest]
+    #[case("did:web:example.com", ERROR_METHOD_NOT_SUPPORTED)]
+    #[case(
+        " did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK ",
+        ERROR_METHOD_NOT_SUPPORTED
+    )]
+    #[case("did:indy:sovrin:0cgbu8ZPoWTnR5Rv5JcSMB", ERROR_INVALID_DID)]
+    #[case("did:sov:0cgbu8ZPoWTnR5Rv5JcSMB", ERROR_INVALID_DID)]
+    #[case("did:indyx", ERROR_METHOD_NOT_SUPPORTED)]
+    #[case("QowxFtwciWceMFr7WbwnM:extra", ERROR_INVALID_DID)]
+    #[case("", ERROR_INVALID_DID)]
+    fn invalid_did_error(#[case] did: &str, #[case] error: &str) {
+        let result = ResolutionResult::invalid_did(did, "Invalid DID");
+        assert_eq!(result.error(), Some(error));
+        let md = result.did_resolution_metadata.unwrap();
+        assert_eq!(md.error_message.as_deref(), Some("Invalid DID"));
+        assert!(result.did_document.is_none());
+        assert!(result.did_document_metadata.is_none());
+    }
+}
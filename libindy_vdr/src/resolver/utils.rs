@@ -1,22 +1,30 @@
 use serde_json::Value as SJsonValue;
+use sha2::{Digest, Sha256};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
+use url::Url;
 
 use super::did::{DidUrl, LedgerObject, QueryParameter};
-use super::did_document::{DidDocument, LEGACY_INDY_SERVICE};
+use super::did_document::{
+    resolve_did_url, DidDocument, DID_CORE_CONTEXT, LEGACY_INDY_SERVICE, VERIFICATION_RELATIONSHIPS,
+};
 use super::types::*;
 
 use crate::common::error::prelude::*;
 use crate::ledger::constants;
 use crate::ledger::identifiers::{CredentialDefinitionId, RevocationRegistryId, SchemaId};
-use crate::ledger::responses::{Endpoint, GetNymResultV1};
+use crate::ledger::responses::{Endpoint, EndpointAttrib, GetNymResultV1};
 use crate::ledger::RequestBuilder;
 use crate::pool::cache::Cache;
 use crate::pool::helpers::perform_ledger_request;
 use crate::pool::{Pool, PreparedRequest, RequestResult, RequestResultMeta};
+use crate::utils::base58;
 use crate::utils::did::DidValue;
 use crate::utils::Qualifiable;
 
+/// Multihash prefix of a sha2-256 digest in a hashlink
+const SHA2_256_MULTIHASH: [u8; 2] = [0x12, 0x20];
+
 pub fn build_request(did: &DidUrl, builder: &RequestBuilder) -> VdrResult<PreparedRequest> {
     let request = if did.path.is_some() {
         match LedgerObject::parse(did.path.as_ref().unwrap().as_str())? {
@@ -155,7 +163,7 @@ pub fn build_request(did: &DidUrl, builder: &RequestBuilder) -> VdrResult<Prepar
 }
 
 pub fn handle_internal_resolution_result(
-    namespace: &str,
+    did_url: &DidUrl,
     ledger_data: &str,
 ) -> VdrResult<(Result, Metadata)> {
     let (node_response, txn_type, data) = parse_ledger_data(ledger_data)?;
@@ -169,15 +177,25 @@ pub fn handle_internal_resolution_result(
             let get_nym_result: GetNymResultV1 = serde_json::from_str(data.as_str().unwrap())
                 .map_err(|_| err_msg(VdrErrorKind::Resolver, "Could not parse NYM data"))?;
 
+            let diddoc_content = get_nym_result
+                .diddoc_content
+                .map(|v| serde_json::from_str(&v))
+                .transpose()
+                .map_err(|_| err_msg(VdrErrorKind::Resolver, "Could not parse diddocContent"))?;
+
+            let verkey = get_nym_result
+                .verkey
+                .as_deref()
+                .ok_or_else(|| err_msg(VdrErrorKind::Resolver, "NYM has no verkey"))?;
+
             let did_document = DidDocument::new(
-                namespace,
+                &did_url.namespace,
                 &get_nym_result.dest,
-                &get_nym_result.verkey,
+                verkey,
                 None,
-                get_nym_result
-                    .diddoc_content
-                    .map(|v| serde_json::from_str(&v).unwrap()),
-            );
+                diddoc_content,
+            )
+            .with_method(did_url.method());
 
             let metadata = Metadata::DidDocumentMetadata(DidDocumentMetadata {
                 node_response,
@@ -237,6 +255,171 @@ pub fn parse_ledger_data(ledger_data: &str) -> VdrResult<(SJsonValue, SJsonValue
     }
 }
 
+/// Whether a ledger reply to a read request did not find the requested object
+pub fn is_not_found(ledger_data: &str) -> bool {
+    serde_json::from_str::<SJsonValue>(ledger_data)
+        .map(|v| v["result"].is_object() && v["result"]["data"].is_null())
+        .unwrap_or(false)
+}
+
+/// Select the DID document representation from the media ranges of an `Accept` header.
+/// Returns `None` if any representation is acceptable.
+pub fn did_representation(accept: Option<&str>) -> VdrResult<Option<&'static str>> {
+    let accept = match accept.map(str::trim) {
+        None | Some("") => return Ok(None),
+        Some(accept) => accept,
+    };
+    for media_range in accept.split(',') {
+        let media_type = media_range.split(';').next().unwrap_or_default().trim();
+        match media_type {
+            "*/*" | "application/*" | "application/json" => return Ok(None),
+            DID_JSON => return Ok(Some(DID_JSON)),
+            DID_LD_JSON | "application/ld+json" => return Ok(Some(DID_LD_JSON)),
+            _ => (),
+        }
+    }
+    Err(err_msg(
+        VdrErrorKind::Resolver,
+        format!("Representation not supported: {}", accept),
+    ))
+}
+
+/// Convert a DID document to a representation, returning its content type. The JSON-LD
+/// representation requires a `@context`, which is omitted from the JSON representation.
+pub fn represent_did_document(doc: &mut SJsonValue, representation: Option<&str>) -> String {
+    let content_type = match representation {
+        Some(content_type) => content_type,
+        None if doc.get("@context").is_some() => DID_LD_JSON,
+        None => DID_JSON,
+    };
+    if let SJsonValue::Object(map) = doc {
+        if content_type == DID_JSON {
+            map.remove("@context");
+        } else if !map.contains_key("@context") {
+            map.insert("@context".to_string(), json!(DID_CORE_CONTEXT));
+        }
+    }
+    content_type.to_string()
+}
+
+/// Dereference the part of a DID document selected by a DID URL: the service endpoint URL
+/// of the `service` query parameter, joined with any `relativeRef`, or the verification
+/// method or service identified by the fragment. Returns the content and its object type.
+pub fn dereference_document(
+    did_url: &DidUrl,
+    doc: &SJsonValue,
+) -> VdrResult<(SJsonValue, &'static str)> {
+    let did = doc["id"].as_str().unwrap_or_default();
+
+    if let Some(service_id) = did_url.query.get(&QueryParameter::Service) {
+        let service = find_document_entry(did, &doc["service"], service_id).ok_or_else(|| {
+            err_msg(
+                VdrErrorKind::Resolver,
+                format!("Service not found: {}", service_id),
+            )
+        })?;
+        let endpoint = service_endpoint_uri(&service["serviceEndpoint"]).ok_or_else(|| {
+            err_msg(
+                VdrErrorKind::Resolver,
+                format!("Service has no endpoint URL: {}", service_id),
+            )
+        })?;
+        let mut url = match did_url.query.get(&QueryParameter::RelativeRef) {
+            Some(relative_ref) => Url::parse(endpoint)
+                .and_then(|endpoint| endpoint.join(relative_ref))
+                .map_err(|_| {
+                    err_msg(
+                        VdrErrorKind::Resolver,
+                        format!("Invalid relativeRef for service endpoint: {}", relative_ref),
+                    )
+                })?
+                .to_string(),
+            None => endpoint.to_string(),
+        };
+        if let Some(fragment) = did_url.fragment.as_ref() {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        return Ok((SJsonValue::String(url), "SERVICE_ENDPOINT"));
+    }
+
+    let fragment = did_url.fragment.as_deref().unwrap_or_default();
+    let methods =
+        std::iter::once("verificationMethod").chain(VERIFICATION_RELATIONSHIPS.iter().copied());
+    for section in methods {
+        if let Some(method) = find_document_entry(did, &doc[section], fragment) {
+            return Ok((method.clone(), "VERIFICATION_METHOD"));
+        }
+    }
+    if let Some(service) = find_document_entry(did, &doc["service"], fragment) {
+        return Ok((service.clone(), "SERVICE"));
+    }
+    Err(err_msg(
+        VdrErrorKind::Resolver,
+        format!("DID URL not found in DID document: {}", did_url.url),
+    ))
+}
+
+/// Find an embedded entry of a DID document section by the fragment of its identifier
+fn find_document_entry<'a>(
+    did: &str,
+    section: &'a SJsonValue,
+    fragment: &str,
+) -> Option<&'a SJsonValue> {
+    let id = format!("{}#{}", did, fragment);
+    section.as_array()?.iter().find(|entry| {
+        entry["id"]
+            .as_str()
+            .map(|entry_id| resolve_did_url(did, entry_id) == id)
+            .unwrap_or(false)
+    })
+}
+
+/// Get the URL of a service endpoint, which may be a string, a map containing a `uri`
+/// or a list of either
+fn service_endpoint_uri(endpoint: &SJsonValue) -> Option<&str> {
+    match endpoint {
+        SJsonValue::String(uri) => Some(uri),
+        SJsonValue::Object(map) => map.get("uri").and_then(SJsonValue::as_str),
+        SJsonValue::Array(endpoints) => endpoints.first().and_then(service_endpoint_uri),
+        _ => None,
+    }
+}
+
+/// Check the content dereferenced from a DID URL against its `hl` hashlink parameter,
+/// a multibase base58 encoded sha2-256 multihash. JSON strings are hashed as is, and
+/// other content as its JSON serialization with sorted keys.
+pub fn check_hashlink(did_url: &DidUrl, content: &SJsonValue) -> VdrResult<()> {
+    let hashlink = match did_url.query.get(&QueryParameter::Hl) {
+        Some(hashlink) => hashlink,
+        None => return Ok(()),
+    };
+    let multihash = hashlink
+        .strip_prefix('z')
+        .and_then(|hash| base58::decode(hash).ok())
+        .filter(|hash| hash.len() == 34 && hash[..2] == SHA2_256_MULTIHASH)
+        .ok_or_else(|| {
+            err_msg(
+                VdrErrorKind::Resolver,
+                format!("Unsupported hashlink: {}", hashlink),
+            )
+        })?;
+    let digest = match content {
+        SJsonValue::String(content) => Sha256::digest(content.as_bytes()),
+        _ => Sha256::digest(
+            serde_json::to_vec(content)
+                .with_err_msg(VdrErrorKind::Unexpected, "Error serializing JSON")?,
+        ),
+    };
+    if digest[..] != multihash[2..] {
+        return Err(err_msg(
+            VdrErrorKind::Resolver,
+            format!("Content does not match hashlink: {}", hashlink),
+        ));
+    }
+    Ok(())
+}
+
 pub fn parse_or_now(datetime: Option<&String>) -> VdrResult<i64> {
     match datetime {
         Some(datetime) => {
@@ -280,6 +463,19 @@ pub async fn fetch_legacy_endpoint<T: Pool>(
     timestamp: Option<u64>,
     cache: Option<Cache<String, (String, RequestResultMeta)>>,
 ) -> VdrResult<Endpoint> {
+    fetch_endpoint_attrib(pool, did, seq_no, timestamp, cache)
+        .await
+        .map(Endpoint::from)
+}
+
+/// Fetch the legacy service endpoint ATTRIB including its non-string values
+pub async fn fetch_endpoint_attrib<T: Pool>(
+    pool: &T,
+    did: &DidValue,
+    seq_no: Option<i32>,
+    timestamp: Option<u64>,
+    cache: Option<Cache<String, (String, RequestResultMeta)>>,
+) -> VdrResult<EndpointAttrib> {
     let builder = pool.get_request_builder();
     let request = builder.build_get_attrib_request(
         None,
@@ -295,10 +491,91 @@ pub async fn fetch_legacy_endpoint<T: Pool>(
         did, request
     );
     let ledger_data = handle_request(pool, &request, cache).await?;
-    let (_, _, endpoint_data) = parse_ledger_data(&ledger_data)?;
-    let endpoint_data: Endpoint = serde_json::from_str(endpoint_data.as_str().unwrap())
-        .map_err(|_| err_msg(VdrErrorKind::Resolver, "Could not parse endpoint data"))?;
-    Ok(endpoint_data)
+    parse_endpoint(&ledger_data)
+}
+
+/// Parse the legacy service endpoint from a ledger reply to a GET_ATTRIB request
+pub fn parse_endpoint(ledger_data: &str) -> VdrResult<EndpointAttrib> {
+    let (_, _, endpoint_data) = parse_ledger_data(ledger_data)?;
+    endpoint_data
+        .as_str()
+        .and_then(|data| serde_json::from_str(data).ok())
+        .ok_or_else(|| err_msg(VdrErrorKind::Resolver, "Could not parse endpoint data"))
+}
+
+/// A ledger reply for a version of a NYM or of its `endpoint` ATTRIB
+#[derive(Clone, Debug)]
+pub struct LedgerVersion {
+    pub seq_no: u64,
+    pub txn_time: u64,
+    pub ledger_data: String,
+}
+
+impl LedgerVersion {
+    /// Read the sequence number and time of the transaction in a ledger reply, returning
+    /// `None` if the object was not found
+    pub fn from_reply(ledger_data: String) -> Option<Self> {
+        let v: SJsonValue = serde_json::from_str(&ledger_data).ok()?;
+        if v["result"]["data"].is_null() {
+            return None;
+        }
+        Some(Self {
+            seq_no: v["result"]["seqNo"].as_u64()?,
+            txn_time: v["result"]["txnTime"].as_u64()?,
+            ledger_data,
+        })
+    }
+}
+
+/// Build the versions of a DID document from the versions of its NYM and `endpoint` ATTRIB.
+/// Each transaction produces a version from the latest NYM and ATTRIB at its sequence number,
+/// except for ATTRIB transactions which do not change the DID document.
+pub fn build_document_history(
+    did: &DidUrl,
+    nyms: &[LedgerVersion],
+    endpoints: &[LedgerVersion],
+) -> VdrResult<Vec<DidDocumentVersion>> {
+    let mut txns: Vec<(&LedgerVersion, &str)> = nyms
+        .iter()
+        .map(|nym| (nym, "NYM"))
+        .chain(endpoints.iter().map(|endpoint| (endpoint, "ATTRIB")))
+        .collect();
+    txns.sort_by_key(|(txn, _)| txn.seq_no);
+
+    let mut versions: Vec<DidDocumentVersion> = Vec::new();
+    for (txn, object_type) in txns {
+        let latest = |txns: &'_ [LedgerVersion]| {
+            txns.iter()
+                .filter(|version| version.seq_no <= txn.seq_no)
+                .max_by_key(|version| version.seq_no)
+                .map(|version| version.ledger_data.clone())
+        };
+        let nym = match latest(nyms) {
+            Some(nym) => nym,
+            None => continue,
+        };
+        let mut doc = match handle_internal_resolution_result(did, &nym)? {
+            (Result::DidDocument(doc), _) => doc,
+            _ => return Err(err_msg(VdrErrorKind::Resolver, "Could not parse NYM data")),
+        };
+        doc.set_endpoint(latest(endpoints).and_then(|endpoint| parse_endpoint(&endpoint).ok()));
+        let did_document = doc.to_value()?;
+        if object_type == "ATTRIB"
+            && versions
+                .last()
+                .map(|version| version.did_document == did_document)
+                .unwrap_or(false)
+        {
+            continue;
+        }
+        versions.push(DidDocumentVersion {
+            seq_no: txn.seq_no,
+            txn_time: txn.txn_time,
+            object_type: object_type.to_string(),
+            did_document,
+        });
+    }
+    Ok(versions)
 }
 
 #[cfg(test)]
@@ -432,4 +709,347 @@ mod tests {
             .unwrap();
         assert_eq!(schema_name, name);
     }
+
+    #[rstest]
+    #[case(None, Some(None))]
+    #[case(Some("*/*"), Some(None))]
+    #[case(Some("application/did+json"), Some(Some(DID_JSON)))]
+    #[case(
+        Some("text/html,application/did+ld+json;q=0.9"),
+        Some(Some(DID_LD_JSON))
+    )]
+    #[case(
+        Some("application/ld+json;profile=\"https://w3id.org/did-resolution\""),
+        Some(Some(DID_LD_JSON))
+    )]
+    #[case(Some("application/did+cbor"), None)]
+    fn select_did_representation(
+        #[case] accept: Option<&str>,
+        #[case] representation: Option<Option<&str>>,
+    ) {
+        assert_eq!(did_representation(accept).ok(), representation);
+    }
+
+    #[rstest]
+    fn represent_did_document_with_context() {
+        let mut doc = json!({"id": "did:indy:test:QowxFtwciWceMFr7WbwnM"});
+        assert_eq!(represent_did_document(&mut doc, None), DID_JSON);
+        assert!(doc.get("@context").is_none());
+
+        assert_eq!(
+            represent_did_document(&mut doc, Some(DID_LD_JSON)),
+            DID_LD_JSON
+        );
+        assert_eq!(doc["@context"], DID_CORE_CONTEXT);
+        assert_eq!(represent_did_document(&mut doc, None), DID_LD_JSON);
+
+        assert_eq!(represent_did_document(&mut doc, Some(DID_JSON)), DID_JSON);
+        assert!(doc.get("@context").is_none());
+    }
+
+    #[rstest]
+    fn detect_not_found_reply() {
+        assert!(is_not_found(
+            r#"{"op": "REPLY", "result": {"type": "105", "data": null}}"#
+        ));
+        assert!(!is_not_found(
+            r#"{"op": "REPLY", "result": {"type": "105", "data": "{}"}}"#
+        ));
+        assert!(!is_not_found("invalid"));
+    }
+
+    #[rstest]
+    fn resolution_result_errors() {
+        let result = ResolutionResult::invalid_did("did:web:example.com", "Invalid DID");
+        assert_eq!(result.error(), Some(ERROR_METHOD_NOT_SUPPORTED));
+        assert!(result.did_document.is_none());
+
+        let result = ResolutionResult::invalid_did("did:sov:0cgbu8ZPoWTnR5Rv5JcSMB", "Invalid DID");
+        assert_eq!(result.error(), Some(ERROR_INVALID_DID));
+
+        let did = DidUrl::parse("did:sov:QowxFtwciWceMFr7WbwnM").unwrap();
+        let meta = RequestResultMeta {
+            state_proof: [
+                (
+                    String::from("Node1"),
+                    crate::pool::StateProofResult::Missing,
+                ),
+                (
+                    String::from("Node2"),
+                    crate::pool::StateProofResult::Invalid(String::from("bad"), None),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            timing: None,
+            cached: false,
+        };
+        let md = DidResolutionMetadata::retrieved(&did, &meta);
+        assert_eq!(md.method.as_deref(), Some("sov"));
+        assert_eq!(md.state_proof.as_deref(), Some("invalid"));
+        assert!(md.retrieved.unwrap().ends_with('Z'));
+
+        let result = ResolutionResult::from_error(
+            DidResolutionMetadata::default(),
+            ERROR_NOT_FOUND,
+            "DID not found",
+        );
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["didResolutionMetadata"],
+            json!({"error": "notFound", "errorMessage": "DID not found"})
+        );
+    }
+
+    fn _document() -> SJsonValue {
+        json!({
+            "id": "did:indy:idunion:QowxFtwciWceMFr7WbwnM",
+            "verificationMethod": [{
+                "id": "did:indy:idunion:QowxFtwciWceMFr7WbwnM#verkey",
+                "type": "Ed25519VerificationKey2018",
+            }],
+            "authentication": ["did:indy:idunion:QowxFtwciWceMFr7WbwnM#verkey"],
+            "keyAgreement": [{"id": "#key-agreement-1", "type": "X25519KeyAgreementKey2019"}],
+            "service": [{
+                "id": "#agent",
+                "type": "DIDCommMessaging",
+                "serviceEndpoint": {"uri": "https://agent.com/didcomm/", "accept": ["didcomm/v2"]},
+            }],
+        })
+    }
+
+    #[rstest]
+    #[case("did:indy:idunion:QowxFtwciWceMFr7WbwnM#verkey", "VERIFICATION_METHOD", json!("Ed25519VerificationKey2018"))]
+    #[case("did:indy:idunion:QowxFtwciWceMFr7WbwnM#key-agreement-1", "VERIFICATION_METHOD", json!("X25519KeyAgreementKey2019"))]
+    #[case("did:indy:idunion:QowxFtwciWceMFr7WbwnM#agent", "SERVICE", json!("DIDCommMessaging"))]
+    fn dereference_document_fragment(
+        #[case] did_url: &str,
+        #[case] object_type: &str,
+        #[case] type_: SJsonValue,
+    ) {
+        let did_url = DidUrl::parse(did_url).unwrap();
+        let (content, content_type) = dereference_document(&did_url, &_document()).unwrap();
+        assert_eq!(content_type, object_type);
+        assert_eq!(content["type"], type_);
+    }
+
+    #[rstest]
+    #[case(
+        "did:indy:idunion:QowxFtwciWceMFr7WbwnM?service=agent",
+        "https://agent.com/didcomm/"
+    )]
+    #[case(
+        "did:indy:idunion:QowxFtwciWceMFr7WbwnM?service=agent&relativeRef=inbox%3Fid%3D1",
+        "https://agent.com/didcomm/inbox?id=1"
+    )]
+    #[case(
+        "did:indy:idunion:QowxFtwciWceMFr7WbwnM?service=agent&relativeRef=%2Fprofile#name",
+        "https://agent.com/profile#name"
+    )]
+    fn dereference_document_service(#[case] did_url: &str, #[case] url: &str) {
+        let did_url = DidUrl::parse(did_url).unwrap();
+        let (content, content_type) = dereference_document(&did_url, &_document()).unwrap();
+        assert_eq!(content_type, "SERVICE_ENDPOINT");
+        assert_eq!(content, json!(url));
+    }
+
+    #[rstest]
+    #[case("did:indy:idunion:QowxFtwciWceMFr7WbwnM#key-1")]
+    #[case("did:indy:idunion:QowxFtwciWceMFr7WbwnM?service=profile")]
+    fn dereference_document_not_found(#[case] did_url: &str) {
+        let did_url = DidUrl::parse(did_url).unwrap();
+        let err = dereference_document(&did_url, &_document()).unwrap_err();
+        assert!(matches!(err.kind(), VdrErrorKind::Resolver));
+    }
+
+    #[rstest]
+    fn check_content_hashlink() {
+        let content = json!({"name": "npdb", "version": "4.3.4"});
+        let mut multihash = SHA2_256_MULTIHASH.to_vec();
+        multihash.extend(Sha256::digest(serde_json::to_vec(&content).unwrap()));
+        let hashlink = format!("z{}", base58::encode(multihash));
+
+        let did_url = format!(
+            "did:indy:idunion:Dk1fRRTtNazyMuK2cr64wp/anoncreds/v0/SCHEMA/npdb/4.3.4?hl={}",
+            hashlink
+        );
+        let did_url = DidUrl::parse(&did_url).unwrap();
+        check_hashlink(&did_url, &content).unwrap();
+        check_hashlink(&did_url, &json!({"name": "npdb"})).unwrap_err();
+
+        let did_url = DidUrl::parse(
+            "did:indy:idunion:Dk1fRRTtNazyMuK2cr64wp/anoncreds/v0/SCHEMA/npdb/4.3.4?hl=uEiA",
+        )
+        .unwrap();
+        check_hashlink(&did_url, &content).unwrap_err();
+    }
+
+    fn _ledger_version(
+        txn_type: &str,
+        seq_no: u64,
+        txn_time: u64,
+        data: SJsonValue,
+    ) -> LedgerVersion {
+        let reply = json!({
+            "op": "REPLY",
+            "result": {"type": txn_type, "seqNo": seq_no, "txnTime": txn_time, "data": data.to_string()},
+        });
+        LedgerVersion::from_reply(reply.to_string()).unwrap()
+    }
+
+    fn _nym_version(seq_no: u64, verkey: &str) -> LedgerVersion {
+        _ledger_version(
+            constants::GET_NYM,
+            seq_no,
+            seq_no * 100,
+            json!({"dest": "QowxFtwciWceMFr7WbwnM", "verkey": verkey}),
+        )
+    }
+
+    fn _endpoint_version(seq_no: u64, endpoint: &str) -> LedgerVersion {
+        _ledger_version(
+            constants::GET_ATTR,
+            seq_no,
+            seq_no * 100,
+            json!({"endpoint": {"endpoint": endpoint}}),
+        )
+    }
+
+    #[rstest]
+    fn ledger_version_from_reply() {
+        let version = _nym_version(5, "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk");
+        assert_eq!((version.seq_no, version.txn_time), (5, 500));
+        assert!(LedgerVersion::from_reply(
+            json!({"op": "REPLY", "result": {"type": "105", "seqNo": null, "data": null}})
+                .to_string()
+        )
+        .is_none());
+    }
+
+    #[rstest]
+    fn build_did_document_history() {
+        let did = DidUrl::parse("did:sov:QowxFtwciWceMFr7WbwnM").unwrap();
+        let nyms = [
+            _nym_version(10, "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk"),
+            _nym_version(30, "3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"),
+        ];
+        let endpoints = [
+            _endpoint_version(20, "https://agent.com"),
+            _endpoint_version(25, "https://agent.com"),
+            _endpoint_version(40, "https://other.com"),
+        ];
+
+        let versions = build_document_history(&did, &nyms, &endpoints).unwrap();
+        let summary: Vec<(u64, u64, &str)> = versions
+            .iter()
+            .map(|version| {
+                (
+                    version.seq_no,
+                    version.txn_time,
+                    version.object_type.as_str(),
+                )
+            })
+            .collect();
+        // the ATTRIB at 25 does not change the DID document
+        assert_eq!(
+            summary,
+            [
+                (10, 1000, "NYM"),
+                (20, 2000, "ATTRIB"),
+                (30, 3000, "NYM"),
+                (40, 4000, "ATTRIB")
+            ]
+        );
+
+        let doc = &versions[0].did_document;
+        assert_eq!(
+            doc["verificationMethod"][0]["publicKeyBase58"],
+            "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk"
+        );
+        assert!(doc.get("service").is_none());
+        let doc = &versions[2].did_document;
+        assert_eq!(
+            doc["verificationMethod"][0]["publicKeyBase58"],
+            "3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"
+        );
+        assert_eq!(doc["service"][0]["serviceEndpoint"], "https://agent.com");
+        assert_eq!(
+            versions[3].did_document["service"][0]["serviceEndpoint"],
+            "https://other.com"
+        );
+    }
+
+    #[rstest]
+    fn ledger_version_from_reply_requires_txn() {
+        let reply = json!({
+            "op": "REPLY",
+            "result": {"type": "105", "seqNo": 5, "txnTime": 500, "data": "{}"},
+        })
+        .to_string();
+        let version = LedgerVersion::from_reply(reply.clone()).unwrap();
+        assert_eq!(version.ledger_data, reply);
+
+        let reply = json!({"op": "REPLY", "result": {"type": "105", "seqNo": 5, "data": "{}"}});
+        assert!(LedgerVersion::from_reply(reply.to_string()).is_none());
+        assert!(LedgerVersion::from_reply("invalid".to_owned()).is_none());
+    }
+
+    #[rstest]
+    fn build_did_document_history_starts_at_first_nym() {
+        let did = DidUrl::parse("did:sov:QowxFtwciWceMFr7WbwnM").unwrap();
+        let nyms = [_nym_version(
+            10,
+            "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk",
+        )];
+        let endpoints = [_endpoint_version(5, "https://agent.com")];
+
+        // the ATTRIB written before the NYM only appears in the version of the NYM
+        let versions = build_document_history(&did, &nyms, &endpoints).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(
+            (versions[0].seq_no, versions[0].object_type.as_str()),
+            (10, "NYM")
+        );
+        assert_eq!(
+            versions[0].did_document["service"][0]["serviceEndpoint"],
+            "https://agent.com"
+        );
+
+        assert!(build_document_history(&did, &[], &endpoints)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[rstest]
+    fn build_did_document_history_with_routing_keys() {
+        let did = DidUrl::parse("did:sov:QowxFtwciWceMFr7WbwnM").unwrap();
+        let nyms = [_nym_version(
+            10,
+            "67yDXtw6MK2D7V2kFSL7uMH6qTtrEbNtkdiTkbk9YJBk",
+        )];
+        let endpoints = [_ledger_version(
+            constants::GET_ATTR,
+            20,
+            2000,
+            json!({"endpoint": {
+                "endpoint": "https://agent.com",
+                "routingKeys": ["3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"],
+                "types": ["DIDComm"],
+            }}),
+        )];
+
+        let versions = build_document_history(&did, &nyms, &endpoints).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(
+            versions[1].did_document["service"],
+            json!([{
+                "id": "did:sov:QowxFtwciWceMFr7WbwnM#didcomm-1",
+                "type": "DIDCommMessaging",
+                "serviceEndpoint": {
+                    "uri": "https://agent.com",
+                    "accept": ["didcomm/v2"],
+                    "routingKeys": ["3LYuxJBJkngDbvJj4zjx13DBUdZ2P96eNybwd2n9L9AU"],
+                },
+            }])
+        );
+    }
 }
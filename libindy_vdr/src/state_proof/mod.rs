@@ -248,6 +248,70 @@ pub(crate) fn result_without_state_proof(result: &SJsonValue) -> SJsonValue {
     result_without_proof
 }
 
+/// Extract the expected state value for a built-in read reply, as stored in the ledger state
+pub(crate) fn parse_reply_for_proof_value(
+    json_msg: &SJsonValue,
+    sp_key: &[u8],
+) -> VdrResult<Option<String>> {
+    let type_ = json_msg["type"]
+        .as_str()
+        .ok_or_else(|| input_err("No type field in reply"))?;
+    let (data, parsed_data) =
+        _parse_reply_data(json_msg).ok_or_else(|| input_err("Invalid data field in reply"))?;
+    _parse_reply_for_proof_value(json_msg, data.as_deref(), &parsed_data, type_, sp_key)
+}
+
+/// Build the encoded proof nodes and root hash of a state trie holding
+/// only the given key, or no keys at all if no value is provided
+#[cfg(any(test, feature = "mock"))]
+pub(crate) fn build_single_key_proof(key: &[u8], value: Option<&str>) -> (String, String) {
+    let node = match value {
+        Some(value) => Node::new_leaf(key, value.as_bytes()),
+        None => Node::Blank,
+    };
+    let root_hash = node.get_hash();
+    (
+        base64::encode(rlp::encode_list(&[node])),
+        base58::encode(root_hash),
+    )
+}
+
+/// Verify the audit path of a GET_TXN reply against a trusted ledger root hash and size
+///
+/// The proof is parsed as for the GET_TXN state proof check, but it must be rooted
+/// at the trusted root rather than the root hash given in the reply.
+pub(crate) fn verify_txn_audit_path(
+    json_msg: &SJsonValue,
+    seq_no: u64,
+    root_hash: &[u8],
+    ledger_size: u64,
+) -> VdrResult<bool> {
+    let sp_key = seq_no.to_string();
+    let parsed_sp = _parse_reply_for_builtin_sp(json_msg, constants::GET_TXN, sp_key.as_bytes())
+        .and_then(|mut parsed_sps| parsed_sps.pop())
+        .ok_or_else(|| input_err("No audit proof in reply"))?;
+    let KeyValuesInSP::Simple(KeyValueSimpleData {
+        kvs,
+        verification_type: KeyValueSimpleDataVerificationType::MerkleTree(length),
+    }) = parsed_sp.kvs_to_verify
+    else {
+        return Err(input_err("Unexpected audit proof format"));
+    };
+    if length != ledger_size
+        || base58::decode(&parsed_sp.root_hash).ok().as_deref() != Some(root_hash)
+    {
+        return Ok(false);
+    }
+    let proof_nodes = base64::decode(&parsed_sp.proof_nodes)
+        .map_err(|_| input_err("Error decoding audit proof"))?;
+    Ok(_verify_merkle_tree(
+        &proof_nodes,
+        root_hash,
+        &kvs,
+        ledger_size,
+    ))
+}
+
 pub(crate) fn parse_generic_reply_for_proof_checking(
     json_msg: &SJsonValue,
     raw_msg: &str,
@@ -646,71 +710,76 @@ fn _parse_reply_for_builtin_sp(
 
     assert!(REQUESTS_FOR_STATE_PROOFS.contains(&type_));
 
+    let (data, parsed_data) = _parse_reply_data(json_msg)?;
+
+    let mut state_proofs = vec![];
+
+    match _parse_reply_for_sp(json_msg, data.as_deref(), &parsed_data, type_, key) {
+        Ok(state_proof) => state_proofs.push(state_proof),
+        Err(err) => {
+            debug!("_parse_reply_for_sp: <<<  {}", err);
+            return None;
+        }
+    }
+
+    if REQUESTS_FOR_MULTI_STATE_PROOFS.contains(&type_) {
+        match _parse_reply_for_multi_sp(json_msg, data.as_deref(), &parsed_data, type_, key) {
+            Ok(Some(state_proof)) => {
+                trace!("_parse_reply_for_multi_sp: proof: {:?}", state_proof);
+                state_proofs.push(state_proof);
+            }
+            Ok(None) => {
+                trace!("_parse_reply_for_multi_sp: <<<  No proof");
+            }
+            Err(err) => {
+                debug!("_parse_reply_for_multi_sp: <<<  {}", err);
+                return None;
+            }
+        }
+    }
+
+    Some(state_proofs)
+}
+
+fn _parse_reply_data(json_msg: &SJsonValue) -> Option<(Option<String>, SJsonValue)> {
     // TODO: FIXME: It is a workaround for Node's problem. Node returns some transactions as strings and some as objects.
     // If node returns marshaled json it can contain spaces and it can cause invalid hash.
     // So we have to save the original string too.
     // See https://jira.hyperledger.org/browse/INDY-699
-    let (data, parsed_data): (Option<String>, SJsonValue) = match json_msg["data"] {
+    let res = match json_msg["data"] {
         SJsonValue::Null => {
-            trace!("parse_reply_for_builtin_sp: Data is null");
+            trace!("parse_reply_data: Data is null");
             (None, SJsonValue::Null)
         }
         SJsonValue::String(ref strval) => {
-            trace!("parse_reply_for_builtin_sp: Data is string");
+            trace!("parse_reply_data: Data is string");
             if let Ok(parsed_data) = serde_json::from_str(strval) {
                 (Some(strval.to_owned()), parsed_data)
             } else {
-                trace!("parse_reply_for_builtin_sp: <<< Data field is invalid json");
+                trace!("parse_reply_data: <<< Data field is invalid json");
                 return None;
             }
         }
         SJsonValue::Object(ref map) => {
-            trace!("parse_reply_for_builtin_sp: Data is object");
+            trace!("parse_reply_data: Data is object");
             (
                 Some(json_msg["data"].to_string()),
                 SJsonValue::from(map.clone()),
             )
         }
         SJsonValue::Array(ref array) => {
-            trace!("parse_reply_for_builtin_sp: Data is array");
+            trace!("parse_reply_data: Data is array");
             (
                 Some(json_msg["data"].to_string()),
                 SJsonValue::from(array.clone()),
             )
         }
         _ => {
-            trace!("parse_reply_for_builtin_sp: <<< Data field is invalid type");
+            trace!("parse_reply_data: <<< Data field is invalid type");
             return None;
         }
     };
-
-    let mut state_proofs = vec![];
-
-    match _parse_reply_for_sp(json_msg, data.as_deref(), &parsed_data, type_, key) {
-        Ok(state_proof) => state_proofs.push(state_proof),
-        Err(err) => {
-            debug!("_parse_reply_for_sp: <<<  {}", err);
-            return None;
-        }
-    }
-
-    if REQUESTS_FOR_MULTI_STATE_PROOFS.contains(&type_) {
-        match _parse_reply_for_multi_sp(json_msg, data.as_deref(), &parsed_data, type_, key) {
-            Ok(Some(state_proof)) => {
-                trace!("_parse_reply_for_multi_sp: proof: {:?}", state_proof);
-                state_proofs.push(state_proof);
-            }
-            Ok(None) => {
-                trace!("_parse_reply_for_multi_sp: <<<  No proof");
-            }
-            Err(err) => {
-                debug!("_parse_reply_for_multi_sp: <<<  {}", err);
-                return None;
-            }
-        }
-    }
-
-    Some(state_proofs)
+    Some(res)
 }
 
 fn _parse_reply_for_sp(
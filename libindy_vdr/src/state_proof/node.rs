@@ -146,6 +146,18 @@ pub type TrieDB<'a> = HashMap<NodeHash, &'a Node>;
 
 #[allow(clippy::needless_lifetimes)]
 impl Node {
+    /// Create a leaf node storing `value` at the full `key` path
+    #[cfg(any(test, feature = "mock"))]
+    pub(crate) fn new_leaf(key: &[u8], value: &[u8]) -> Node {
+        let mut path = Vec::with_capacity(key.len() + 1);
+        path.push(Node::IS_LEAF_MASK);
+        path.extend_from_slice(key);
+        Node::Leaf(Leaf {
+            path,
+            value: rlp::encode_list::<Vec<u8>, _>(&[value.to_vec()]).to_vec(),
+        })
+    }
+
     pub fn get_hash(&self) -> NodeHash {
         let encoded = rlp::encode(self);
         Sha3_256::digest(encoded.to_vec().as_slice())
@@ -3,6 +3,7 @@ mod macros;
 
 pub mod base58;
 pub mod base64;
+pub mod self_cert;
 pub mod txn_signature;
 
 // re-exports
@@ -0,0 +1,104 @@
+use sha2::{Digest, Sha256};
+
+use super::base58;
+use super::did::{generate_did, ShortDidValue};
+use super::keys::{EncodedVerKey, PrivateKey, VerKey};
+use crate::common::error::prelude::*;
+use crate::ledger::constants::{CERT_DEFAULT, CERT_DID_INDY, CERT_DID_SOV, SELF_CERT_VERSIONS};
+
+/// Derive the unqualified DID for a verkey according to a self-certification version
+///
+/// `CERT_DEFAULT` does not enforce self-certification and uses the legacy
+/// `did:sov` derivation, taking the first 16 bytes of the verkey. `CERT_DID_INDY`
+/// takes the first 16 bytes of the SHA-256 hash of the verkey.
+pub fn did_for_verkey(verkey: &VerKey, version: i32) -> VdrResult<ShortDidValue> {
+    let key = verkey.as_ref();
+    if key.len() != 32 {
+        return Err(input_err("Expected 32-byte verkey"));
+    }
+    let id = match version {
+        CERT_DEFAULT | CERT_DID_SOV => base58::encode(&key[..16]),
+        CERT_DID_INDY => base58::encode(&Sha256::digest(key)[..16]),
+        _ => return Err(_unknown_version(version)),
+    };
+    Ok(ShortDidValue(id))
+}
+
+/// Generate a new Ed25519 key pair and the matching DID for a self-certification version
+///
+/// The DID is returned unqualified, as it appears in ledger transactions. Callers
+/// qualify it for the intended method, for example with `qualify(Some("sov".into()))`
+/// or as `did:indy:<namespace>:<id>`.
+pub fn generate_self_certified_did(
+    seed: Option<&[u8]>,
+    version: i32,
+) -> VdrResult<(ShortDidValue, PrivateKey, VerKey)> {
+    let did_version = match version {
+        CERT_DEFAULT | CERT_DID_SOV => 1,
+        CERT_DID_INDY => 2,
+        _ => return Err(_unknown_version(version)),
+    };
+    Ok(generate_did(seed, Some(did_version))?)
+}
+
+/// Check that a NYM destination and verkey satisfy a self-certification version
+///
+/// The verkey may be provided in abbreviated form.
+pub fn check_self_certification(
+    dest: &ShortDidValue,
+    verkey: Option<&str>,
+    version: i32,
+) -> VdrResult<()> {
+    if !SELF_CERT_VERSIONS.contains(&version) {
+        return Err(_unknown_version(version));
+    }
+    if version == CERT_DEFAULT {
+        return Ok(());
+    }
+    let verkey = verkey.ok_or_else(|| {
+        input_err("A verkey is required for self-certification of the NYM destination")
+    })?;
+    let verkey = EncodedVerKey::from_str_qualified(verkey, Some(dest), None, None)?.decode()?;
+    if did_for_verkey(&verkey, version)?.0 != dest.0 {
+        return Err(input_err(format!(
+            "NYM destination is not self-certifying for version {}: {}",
+            version, dest.0
+        )));
+    }
+    Ok(())
+}
+
+fn _unknown_version(version: i32) -> VdrError {
+    input_err(format!(
+        "Unsupported self-certification version: {}, expected one of {:?}",
+        version, SELF_CERT_VERSIONS
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &[u8; 32] = b"000000000000000000000000Trustee1";
+
+    #[test]
+    fn generated_dids_are_self_certifying() {
+        for version in SELF_CERT_VERSIONS {
+            let (did, _, verkey) = generate_self_certified_did(Some(SEED), version).unwrap();
+            let verkey = verkey.as_base58().unwrap().to_string();
+            check_self_certification(&did, Some(&verkey), version).unwrap();
+        }
+        let (sov_did, _, _) = generate_self_certified_did(Some(SEED), CERT_DID_SOV).unwrap();
+        let (indy_did, _, verkey) = generate_self_certified_did(Some(SEED), CERT_DID_INDY).unwrap();
+        assert_eq!(sov_did.0, "V4SGRU86Z58d6TV7PBUe6f");
+        assert_ne!(sov_did, indy_did);
+
+        let verkey = verkey.as_base58().unwrap().to_string();
+        check_self_certification(&sov_did, Some(&verkey), CERT_DEFAULT).unwrap();
+        check_self_certification(&sov_did, Some(&verkey), CERT_DID_SOV).unwrap();
+        check_self_certification(&sov_did, Some("~CoRER63DVYnWZtK8uAzNbx"), CERT_DID_SOV).unwrap();
+        assert!(check_self_certification(&sov_did, Some(&verkey), CERT_DID_INDY).is_err());
+        assert!(check_self_certification(&indy_did, None, CERT_DID_INDY).is_err());
+        assert!(check_self_certification(&indy_did, Some(&verkey), 3).is_err());
+    }
+}
@@ -314,7 +314,7 @@ mod send {
         identity: Identity,
     ) {
         // Send NYM
-        let mut nym_request = pool
+        let _err = pool
             .request_builder()
             .build_nym_request(
                 &trustee.did,
@@ -325,9 +325,7 @@ mod send {
                 None,
                 Some(2), // Claim Self-cert version 2
             )
-            .unwrap();
-
-        let _err = helpers::sign_and_send_request(&trustee, &pool, &mut nym_request).unwrap_err();
+            .unwrap_err();
     }
 
     #[rstest]
@@ -338,7 +336,7 @@ mod send {
     ) {
         // Send NYM
         let identity = non_self_cert_identity;
-        let mut nym_request = pool
+        let _err = pool
             .request_builder()
             .build_nym_request(
                 &trustee.did,
@@ -349,9 +347,7 @@ mod send {
                 None,
                 Some(2), // Claim Self-cert version 2
             )
-            .unwrap();
-
-        let _err = helpers::sign_and_send_request(&trustee, &pool, &mut nym_request).unwrap_err();
+            .unwrap_err();
     }
 
     #[rstest]
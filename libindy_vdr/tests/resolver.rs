@@ -117,4 +117,47 @@ mod send_resolver {
         assert_eq!("NYM", metadata["objectType"]);
         assert_ne!(&serde_json::Value::Null, diddoc);
     }
+
+    #[rstest]
+    fn test_pool_resolve_did_history(pool: TestPool, trustee: Identity, identity: Identity) {
+        // Send NYM
+        let mut nym_request = pool
+            .request_builder()
+            .build_nym_request(
+                &trustee.did,
+                &identity.did,
+                Some(identity.verkey.to_string()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let nym_response =
+            helpers::sign_and_send_request(&trustee, &pool, &mut nym_request).unwrap();
+
+        // Get NYM to make sure it was written before it gets resolved
+        let get_nym_request = pool
+            .request_builder()
+            .build_get_nym_request(None, &identity.did, None, None)
+            .unwrap();
+
+        let response = pool
+            .send_request_with_retries(&get_nym_request, &nym_response)
+            .unwrap();
+        let seq_no = TestPool::extract_seq_no_from_reply(response.as_str()).unwrap();
+
+        // Resolve DID history
+        let resolver = Resolver::new(pool.pool);
+        let qualified_did = format!("did:indy:test:{}", &identity.did);
+        let result = block_on(resolver.resolve_history(&qualified_did, None)).unwrap();
+
+        let v: serde_json::Value = serde_json::from_str(result.as_str()).unwrap();
+
+        let versions = v["versions"].as_array().unwrap();
+        assert_eq!(1, versions.len());
+        assert_eq!(seq_no, versions[0]["seqNo"].as_u64().unwrap());
+        assert_eq!("NYM", versions[0]["objectType"]);
+    }
 }
@@ -1,4 +1,4 @@
-import type { NativeCallback, NativeCallbackWithResponse } from './ffi'
+import type { NativeCallback, NativeCallbackWithResponse, NativeCallbackWithSeqNo } from './ffi'
 import type {
   AcceptanceMechanismsRequestOptions,
   AttribRequestOptions,
@@ -20,6 +20,7 @@ import type {
   IndyVdrErrorObject,
   NymRequestOptions,
   PoolCreateOptions,
+  PoolStats,
   PoolStatus,
   PoolSubmitActionOptions,
   PoolSubmitRequestOptions,
@@ -28,10 +29,12 @@ import type {
   RequestSetMultiSignatureOptions,
   RequestSetSignatureOptions,
   RequestSetTxnAuthorAgreementAcceptanceOptions,
+  ResolverHandle,
   RevocationRegistryDefinitionRequestOptions,
   RevocationRegistryEntryRequestOptions,
   SchemaRequestOptions,
   TransactionAuthorAgreementRequestOptions,
+  TransactionRange,
   Transactions,
   Verifiers,
 } from '@hyperledger/indy-vdr-shared'
@@ -42,8 +45,10 @@ import {
   deallocateCallback,
   allocateHandle,
   allocateString,
+  allocateInt64,
   toNativeCallback,
   toNativeCallbackWithResponse,
+  toNativeCallbackWithSeqNo,
   serializeArguments,
 } from './ffi'
 import { getNativeIndyVdr } from './library'
@@ -146,6 +151,28 @@ export class NodeJSIndyVdr implements IndyVdr {
     this.handleError(this.nativeIndyVdr.indy_vdr_set_ledger_txn_cache(capacity, expiry_offset_ms, path))
   }
 
+  public setLedgerTxnCachePolicy(options: { pinned_expiry_ms?: number; current_expiry_ms?: number }): void {
+    const { pinned_expiry_ms = -1, current_expiry_ms = -1 } = options
+    this.handleError(this.nativeIndyVdr.indy_vdr_set_ledger_txn_cache_policy(pinned_expiry_ms, current_expiry_ms))
+  }
+
+  public getLedgerTxnCacheStats(): Record<string, unknown> {
+    const output = allocateString()
+
+    this.handleError(this.nativeIndyVdr.indy_vdr_get_ledger_txn_cache_stats(output))
+
+    return JSON.parse(handleReturnPointer<string>(output)) as Record<string, unknown>
+  }
+
+  public flushLedgerTxnCache(options: { keyPrefix?: string; txnType?: string }): number {
+    const output = allocateInt64()
+    const { keyPrefix, txnType } = serializeArguments(options)
+
+    this.handleError(this.nativeIndyVdr.indy_vdr_flush_ledger_txn_cache(keyPrefix, txnType, output))
+
+    return handleReturnPointer<number>(output)
+  }
+
   public setDefaultLogger(): void {
     this.handleError(this.nativeIndyVdr.indy_vdr_set_default_logger())
   }
@@ -470,6 +497,16 @@ export class NodeJSIndyVdr implements IndyVdr {
     return this.promisify((cbPtr, id) => this.nativeIndyVdr.indy_vdr_pool_refresh(poolHandle, cbPtr, id))
   }
 
+  public async poolGetStats(options: { poolHandle: number }): Promise<PoolStats> {
+    const { poolHandle } = serializeArguments(options)
+
+    const poolStats = await this.promisifyWithResponse<PoolStats>((cbPtr, id) =>
+      this.nativeIndyVdr.indy_vdr_pool_get_stats(poolHandle, cbPtr, id)
+    )
+
+    return handleInvalidNullResponse(poolStats)
+  }
+
   public async poolGetStatus(options: { poolHandle: number }): Promise<PoolStatus> {
     const { poolHandle } = serializeArguments(options)
 
@@ -501,6 +538,50 @@ export class NodeJSIndyVdr implements IndyVdr {
     return handleInvalidNullResponse(verifiers)
   }
 
+  public async poolGetTxnRange(options: {
+    poolHandle: number
+    ledgerType: number
+    seqNoFrom: number
+    seqNoTo: number
+  }): Promise<TransactionRange> {
+    const { poolHandle, ledgerType, seqNoFrom, seqNoTo } = serializeArguments(options)
+
+    return new Promise((resolve, reject) => {
+      const transactions: TransactionRange = []
+      let error: unknown
+      // invoked for each transaction, followed by a final call without a sequence number
+      const cb: NativeCallbackWithSeqNo = (id, errorCode, seqNo, response) => {
+        try {
+          this.handleError(errorCode)
+          if (seqNo !== 0) {
+            const { result } = JSON.parse(response) as { result: Record<string, unknown> }
+            transactions.push({ seqNo, transaction: result })
+          }
+        } catch (e) {
+          error = error ?? e
+        }
+        if (errorCode === 0 && seqNo !== 0) return
+
+        deallocateCallback(id)
+        if (error) return reject(error)
+        resolve(transactions)
+      }
+      const { nativeCallback, id } = toNativeCallbackWithSeqNo(cb)
+      const errorCode = this.nativeIndyVdr.indy_vdr_pool_get_txn_range(
+        poolHandle,
+        ledgerType,
+        seqNoFrom,
+        seqNoTo,
+        nativeCallback,
+        +id
+      )
+      if (errorCode !== 0) {
+        deallocateCallback(+id)
+        this.handleError(errorCode)
+      }
+    })
+  }
+
   public async poolSubmitAction<T>(options: PoolSubmitActionOptions & { poolHandle: number }): Promise<T> {
     const { requestHandle, poolHandle, nodes, timeout } = serializeArguments(options)
 
@@ -527,6 +608,66 @@ export class NodeJSIndyVdr implements IndyVdr {
     this.handleError(this.nativeIndyVdr.indy_vdr_pool_close(poolHandle))
   }
 
+  public resolverCreate(options: { networksPath?: string }): ResolverHandle {
+    const resolverHandle = allocateHandle()
+    const { networksPath } = serializeArguments(options)
+
+    this.handleError(this.nativeIndyVdr.indy_vdr_resolver_create(networksPath, resolverHandle))
+
+    return handleReturnPointer<number>(resolverHandle)
+  }
+
+  public resolverAddNetwork(options: {
+    resolverHandle: ResolverHandle
+    namespace: string
+    transactions?: string
+    transactionsPath?: string
+  }): void {
+    const { resolverHandle, namespace, transactions, transactionsPath } = serializeArguments(options)
+
+    this.handleError(
+      this.nativeIndyVdr.indy_vdr_resolver_add_network(resolverHandle, namespace, transactions, transactionsPath)
+    )
+  }
+
+  public resolverSetDefaultNamespace(options: { resolverHandle: ResolverHandle; namespace?: string }): void {
+    const { resolverHandle, namespace } = serializeArguments(options)
+
+    this.handleError(this.nativeIndyVdr.indy_vdr_resolver_set_default_namespace(resolverHandle, namespace))
+  }
+
+  public async resolverResolve(options: {
+    resolverHandle: ResolverHandle
+    did: string
+  }): Promise<Record<string, unknown>> {
+    const { resolverHandle, did } = serializeArguments(options)
+
+    const result = await this.promisifyWithResponse<Record<string, unknown>>((cbPtr, id) =>
+      this.nativeIndyVdr.indy_vdr_resolver_resolve(resolverHandle, did, cbPtr, id)
+    )
+
+    return handleInvalidNullResponse(result)
+  }
+
+  public async resolverDereference(options: {
+    resolverHandle: ResolverHandle
+    didUrl: string
+  }): Promise<Record<string, unknown>> {
+    const { resolverHandle, didUrl } = serializeArguments(options)
+
+    const result = await this.promisifyWithResponse<Record<string, unknown>>((cbPtr, id) =>
+      this.nativeIndyVdr.indy_vdr_resolver_dereference(resolverHandle, didUrl, cbPtr, id)
+    )
+
+    return handleInvalidNullResponse(result)
+  }
+
+  public resolverClose(options: { resolverHandle: ResolverHandle }): void {
+    const { resolverHandle } = serializeArguments(options)
+
+    this.handleError(this.nativeIndyVdr.indy_vdr_resolver_close(resolverHandle))
+  }
+
   public prepareTxnAuthorAgreementAcceptance(options: PrepareTxnAuthorAgreementAcceptanceOptions): string {
     const output = allocateString()
     const { acceptanceMechanismType, time, taaDigest, text, version } = serializeArguments(options)
@@ -575,6 +716,15 @@ export class NodeJSIndyVdr implements IndyVdr {
     this.handleError(this.nativeIndyVdr.indy_vdr_request_set_endorser(requestHandle, endorser))
   }
 
+  public requestSetUseCache(options: { requestHandle: number; useCache: boolean }): void {
+    const { requestHandle, useCache } = serializeArguments({
+      requestHandle: options.requestHandle,
+      useCache: options.useCache ? 1 : 0,
+    })
+
+    this.handleError(this.nativeIndyVdr.indy_vdr_request_set_use_cache(requestHandle, useCache))
+  }
+
   public requestSetMultiSignature(options: RequestSetMultiSignatureOptions & { requestHandle: number }): void {
     const { identifier, requestHandle, signature } = serializeArguments(options)
 
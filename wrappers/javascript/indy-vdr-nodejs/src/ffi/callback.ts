@@ -1,7 +1,7 @@
 import { Callback } from '@2060.io/ffi-napi'
 
 import { allocateCallback } from './alloc'
-import { FFI_VOID, FFI_CALLBACK_ID, FFI_ERROR_CODE, FFI_STRING } from './primitives'
+import { FFI_VOID, FFI_CALLBACK_ID, FFI_ERROR_CODE, FFI_INT32, FFI_STRING } from './primitives'
 
 export type NativeCallback = (id: number, errorCode: number) => void
 export const toNativeCallback = (cb: NativeCallback) => {
@@ -16,3 +16,10 @@ export const toNativeCallbackWithResponse = (cb: NativeCallbackWithResponse) =>
   const id = allocateCallback(nativeCallback)
   return { nativeCallback, id }
 }
+
+export type NativeCallbackWithSeqNo = (id: number, errorCode: number, seqNo: number, response: string) => void
+export const toNativeCallbackWithSeqNo = (cb: NativeCallbackWithSeqNo) => {
+  const nativeCallback = Callback(FFI_VOID, [FFI_CALLBACK_ID, FFI_ERROR_CODE, FFI_INT32, FFI_STRING], cb)
+  const id = allocateCallback(nativeCallback)
+  return { nativeCallback, id }
+}
@@ -17,6 +17,7 @@ export const FFI_POINTER = 'pointer'
 export const FFI_INT8_PTR = refType(FFI_INT8)
 export const FFI_STRING_PTR = refType(FFI_STRING)
 export const FFI_INT32_PTR = refType(FFI_INT32)
+export const FFI_INT64_PTR = refType(FFI_INT64)
 
 // Custom
 
@@ -30,3 +31,4 @@ export const FFI_HANDLE_POINTER = 'int64*'
 export const FFI_REQUEST_HANDLE = FFI_HANDLE
 export const FFI_REQUEST_HANDLE_POINTER = FFI_HANDLE_POINTER
 export const FFI_POOL_HANDLE = FFI_HANDLE
+export const FFI_RESOLVER_HANDLE = FFI_HANDLE
@@ -4,6 +4,9 @@ export interface NativeMethods {
   indy_vdr_set_config: (arg0: string) => number
   indy_vdr_set_cache_directory: (arg0: string) => number
   indy_vdr_set_ledger_txn_cache: (arg0: number, arg1: number, arg2?: string) => number
+  indy_vdr_get_ledger_txn_cache_stats: (outputPtr: Buffer) => number
+  indy_vdr_flush_ledger_txn_cache: (arg0: string, arg1: string, countPtr: Buffer) => number
+  indy_vdr_set_ledger_txn_cache_policy: (arg0: number, arg1: number) => number
   indy_vdr_set_default_logger: () => number
   indy_vdr_set_protocol_version: (arg0: number) => number
   indy_vdr_set_socks_proxy: (arg0: string) => number
@@ -110,9 +113,18 @@ export interface NativeMethods {
     callBackPtr: Buffer,
     callbackId: number
   ) => number
+  indy_vdr_pool_get_stats: (poolHandle: number, callBackPtr: Buffer, callbackId: number) => number
   indy_vdr_pool_get_status: (poolHandle: number, callBackPtr: Buffer, callbackId: number) => number
   indy_vdr_pool_get_transactions: (poolHandle: number, callBackPtr: Buffer, callbackId: number) => number
   indy_vdr_pool_get_verifiers: (poolHandle: number, callBackPtr: Buffer, callbackId: number) => number
+  indy_vdr_pool_get_txn_range: (
+    poolHandle: number,
+    arg0: number,
+    arg1: number,
+    arg2: number,
+    callBackPtr: Buffer,
+    callbackId: number
+  ) => number
   indy_vdr_prepare_txn_author_agreement_acceptance: (
     arg0: string,
     arg1: string,
@@ -125,7 +137,19 @@ export interface NativeMethods {
   indy_vdr_request_get_body: (requestHanlde: number, outputPtr: Buffer) => number
   indy_vdr_request_get_signature_input: (requestHandle: number, outputPtr: Buffer) => number
   indy_vdr_request_set_endorser: (requestHandle: number, arg0: string) => number
+  indy_vdr_request_set_use_cache: (requestHandle: number, arg0: number) => number
   indy_vdr_request_set_multi_signature: (requestHandle: number, arg0: string, arg1: typeof ByteBuffer) => number
   indy_vdr_request_set_signature: (requestHandle: number, arg0: typeof ByteBuffer) => number
   indy_vdr_request_set_txn_author_agreement_acceptance: (requestHandle: number, arg0: string) => number
+  indy_vdr_resolver_create: (arg0: string, resolverHandlePtr: Buffer) => number
+  indy_vdr_resolver_add_network: (resolverHandle: number, arg0: string, arg1?: string, arg2?: string) => number
+  indy_vdr_resolver_set_default_namespace: (resolverHandle: number, arg0?: string) => number
+  indy_vdr_resolver_resolve: (resolverHandle: number, arg0: string, callBackPtr: Buffer, callbackId: number) => number
+  indy_vdr_resolver_dereference: (
+    resolverHandle: number,
+    arg0: string,
+    callBackPtr: Buffer,
+    callbackId: number
+  ) => number
+  indy_vdr_resolver_close: (resolverHandle: number) => number
 }
@@ -7,10 +7,13 @@ import {
   FFI_INT32,
   FFI_HANDLE_POINTER,
   FFI_POOL_HANDLE,
+  FFI_RESOLVER_HANDLE,
   FFI_CALLBACK_PTR,
   FFI_CALLBACK_ID,
   FFI_REQUEST_HANDLE,
   FFI_UINT64,
+  FFI_INT8,
+  FFI_INT64_PTR,
   ByteBuffer,
 } from '../ffi'
 
@@ -18,6 +21,9 @@ export const nativeBindings = {
   // first element is method return type, second element is list of method argument types
   indy_vdr_set_config: [FFI_ERROR_CODE, [FFI_STRING]],
   indy_vdr_set_default_logger: [FFI_ERROR_CODE, []],
+  indy_vdr_get_ledger_txn_cache_stats: [FFI_ERROR_CODE, [FFI_STRING_PTR]],
+  indy_vdr_flush_ledger_txn_cache: [FFI_ERROR_CODE, [FFI_STRING, FFI_STRING, FFI_INT64_PTR]],
+  indy_vdr_set_ledger_txn_cache_policy: [FFI_ERROR_CODE, [FFI_INT64, FFI_INT64]],
   indy_vdr_set_protocol_version: [FFI_ERROR_CODE, [FFI_INT64]],
   indy_vdr_set_socks_proxy: [FFI_ERROR_CODE, [FFI_STRING]],
   indy_vdr_version: [FFI_STRING, []],
@@ -91,9 +97,14 @@ export const nativeBindings = {
     FFI_ERROR_CODE,
     [FFI_POOL_HANDLE, FFI_REQUEST_HANDLE, FFI_CALLBACK_PTR, FFI_CALLBACK_ID],
   ],
+  indy_vdr_pool_get_stats: [FFI_ERROR_CODE, [FFI_POOL_HANDLE, FFI_CALLBACK_PTR, FFI_CALLBACK_ID]],
   indy_vdr_pool_get_status: [FFI_ERROR_CODE, [FFI_POOL_HANDLE, FFI_CALLBACK_PTR, FFI_CALLBACK_ID]],
   indy_vdr_pool_get_transactions: [FFI_ERROR_CODE, [FFI_POOL_HANDLE, FFI_CALLBACK_PTR, FFI_CALLBACK_ID]],
   indy_vdr_pool_get_verifiers: [FFI_ERROR_CODE, [FFI_POOL_HANDLE, FFI_CALLBACK_PTR, FFI_CALLBACK_ID]],
+  indy_vdr_pool_get_txn_range: [
+    FFI_ERROR_CODE,
+    [FFI_POOL_HANDLE, FFI_INT32, FFI_INT32, FFI_INT32, FFI_CALLBACK_PTR, FFI_CALLBACK_ID],
+  ],
 
   indy_vdr_prepare_txn_author_agreement_acceptance: [
     FFI_ERROR_CODE,
@@ -103,7 +114,16 @@ export const nativeBindings = {
   indy_vdr_request_get_body: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, FFI_STRING_PTR]],
   indy_vdr_request_get_signature_input: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, FFI_STRING_PTR]],
   indy_vdr_request_set_endorser: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, FFI_STRING]],
+  indy_vdr_request_set_use_cache: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, FFI_INT8]],
   indy_vdr_request_set_multi_signature: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, FFI_STRING, ByteBuffer]],
   indy_vdr_request_set_signature: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, ByteBuffer]],
   indy_vdr_request_set_txn_author_agreement_acceptance: [FFI_ERROR_CODE, [FFI_REQUEST_HANDLE, FFI_STRING]],
+
+  // resolver
+  indy_vdr_resolver_create: [FFI_ERROR_CODE, [FFI_STRING, FFI_HANDLE_POINTER]],
+  indy_vdr_resolver_add_network: [FFI_ERROR_CODE, [FFI_RESOLVER_HANDLE, FFI_STRING, FFI_STRING, FFI_STRING]],
+  indy_vdr_resolver_set_default_namespace: [FFI_ERROR_CODE, [FFI_RESOLVER_HANDLE, FFI_STRING]],
+  indy_vdr_resolver_resolve: [FFI_ERROR_CODE, [FFI_RESOLVER_HANDLE, FFI_STRING, FFI_CALLBACK_PTR, FFI_CALLBACK_ID]],
+  indy_vdr_resolver_dereference: [FFI_ERROR_CODE, [FFI_RESOLVER_HANDLE, FFI_STRING, FFI_CALLBACK_PTR, FFI_CALLBACK_ID]],
+  indy_vdr_resolver_close: [FFI_ERROR_CODE, [FFI_RESOLVER_HANDLE]],
 } as const
@@ -14,6 +14,12 @@ FunctionMap IndyVdrTurboModuleHostObject::functionMapping(jsi::Runtime &rt) {
   fMap.insert(std::make_tuple("setConfig", &indyVdr::setConfig));
   fMap.insert(std::make_tuple("setCacheDirectory", &indyVdr::setCacheDirectory));
   fMap.insert(std::make_tuple("setLedgerTxnCache", &indyVdr::setLedgerTxnCache));
+  fMap.insert(std::make_tuple("setLedgerTxnCachePolicy",
+                              &indyVdr::setLedgerTxnCachePolicy));
+  fMap.insert(std::make_tuple("getLedgerTxnCacheStats",
+                              &indyVdr::getLedgerTxnCacheStats));
+  fMap.insert(
+      std::make_tuple("flushLedgerTxnCache", &indyVdr::flushLedgerTxnCache));
   fMap.insert(std::make_tuple("setDefaultLogger", &indyVdr::setDefaultLogger));
   fMap.insert(
       std::make_tuple("setProtocolVersion", &indyVdr::setProtocolVersion));
@@ -64,15 +70,27 @@ FunctionMap IndyVdrTurboModuleHostObject::functionMapping(jsi::Runtime &rt) {
 
   fMap.insert(std::make_tuple("poolCreate", &indyVdr::poolCreate));
   fMap.insert(std::make_tuple("poolRefresh", &indyVdr::poolRefresh));
+  fMap.insert(std::make_tuple("poolGetStats", &indyVdr::poolGetStats));
   fMap.insert(std::make_tuple("poolGetStatus", &indyVdr::poolGetStatus));
   fMap.insert(
       std::make_tuple("poolGetTransactions", &indyVdr::poolGetTransactions));
   fMap.insert(std::make_tuple("poolGetVerifiers", &indyVdr::poolGetVerifiers));
+  fMap.insert(std::make_tuple("poolGetTxnRange", &indyVdr::poolGetTxnRange));
   fMap.insert(std::make_tuple("poolSubmitAction", &indyVdr::poolSubmitAction));
   fMap.insert(
       std::make_tuple("poolSubmitRequest", &indyVdr::poolSubmitRequest));
   fMap.insert(std::make_tuple("poolClose", &indyVdr::poolClose));
 
+  fMap.insert(std::make_tuple("resolverCreate", &indyVdr::resolverCreate));
+  fMap.insert(
+      std::make_tuple("resolverAddNetwork", &indyVdr::resolverAddNetwork));
+  fMap.insert(std::make_tuple("resolverSetDefaultNamespace",
+                              &indyVdr::resolverSetDefaultNamespace));
+  fMap.insert(std::make_tuple("resolverResolve", &indyVdr::resolverResolve));
+  fMap.insert(
+      std::make_tuple("resolverDereference", &indyVdr::resolverDereference));
+  fMap.insert(std::make_tuple("resolverClose", &indyVdr::resolverClose));
+
   fMap.insert(
       std::make_tuple("requestSetEndorser", &indyVdr::requestSetEndorser));
   fMap.insert(std::make_tuple("requestSetMultiSignature",
@@ -87,6 +105,8 @@ FunctionMap IndyVdrTurboModuleHostObject::functionMapping(jsi::Runtime &rt) {
   fMap.insert(std::make_tuple("prepareTxnAuthorAgreementAcceptance",
                               &indyVdr::prepareTxnAuthorAgreementAcceptance));
   fMap.insert(std::make_tuple("requestGetBody", &indyVdr::requestGetBody));
+  fMap.insert(
+      std::make_tuple("requestSetUseCache", &indyVdr::requestSetUseCache));
   fMap.insert(std::make_tuple("requestGetSignatureInput",
                               &indyVdr::requestGetSignatureInput));
 
@@ -134,6 +134,8 @@ typedef int64_t RequestHandle;
 
 typedef int64_t PoolHandle;
 
+typedef int64_t ResolverHandle;
+
 typedef int64_t CallbackId;
 
 /**
@@ -394,16 +396,34 @@ ErrorCode indy_vdr_dereference(PoolHandle pool_handle,
                                void (*cb)(int64_t cb_id, ErrorCode err, const char *response),
                                int64_t cb_id);
 
+ErrorCode indy_vdr_flush_ledger_txn_cache(FfiStr key_prefix, FfiStr txn_type, int64_t *count_p);
+
 ErrorCode indy_vdr_get_current_error(const char **error_json_p);
 
+ErrorCode indy_vdr_get_ledger_txn_cache_stats(const char **stats_p);
+
 ErrorCode indy_vdr_pool_close(PoolHandle pool_handle);
 
 ErrorCode indy_vdr_pool_create(FfiStr params, PoolHandle *handle_p);
 
+ErrorCode indy_vdr_pool_get_stats(PoolHandle pool_handle,
+                                  void (*cb)(CallbackId cb_id, ErrorCode err, const char *response),
+                                  CallbackId cb_id);
+
 ErrorCode indy_vdr_pool_get_status(PoolHandle pool_handle,
                                    void (*cb)(CallbackId cb_id, ErrorCode err, const char *response),
                                    CallbackId cb_id);
 
+ErrorCode indy_vdr_pool_get_txn_range(PoolHandle pool_handle,
+                                      int32_t ledger_type,
+                                      int32_t seq_no_from,
+                                      int32_t seq_no_to,
+                                      void (*cb)(CallbackId cb_id,
+                                                 ErrorCode err,
+                                                 int32_t seq_no,
+                                                 const char *response),
+                                      CallbackId cb_id);
+
 ErrorCode indy_vdr_pool_get_transactions(PoolHandle pool_handle,
                                          void (*cb)(CallbackId cb_id,
                                                     ErrorCode err,
@@ -474,15 +494,46 @@ ErrorCode indy_vdr_request_set_signature(RequestHandle request_handle, struct By
 ErrorCode indy_vdr_request_set_txn_author_agreement_acceptance(RequestHandle request_handle,
                                                                FfiStr acceptance);
 
+ErrorCode indy_vdr_request_set_use_cache(RequestHandle request_handle, int8_t use_cache);
+
 ErrorCode indy_vdr_resolve(PoolHandle pool_handle,
                            FfiStr did,
                            void (*cb)(int64_t cb_id, ErrorCode err, const char *response),
                            int64_t cb_id);
 
+ErrorCode indy_vdr_resolver_add_network(ResolverHandle resolver_handle,
+                                        FfiStr namespace_,
+                                        FfiStr transactions,
+                                        FfiStr transactions_path);
+
+ErrorCode indy_vdr_resolver_close(ResolverHandle resolver_handle);
+
+/**
+ * Create a resolver which dispatches requests to a pool for each DID namespace.
+ * The pools are created when first used, from the networks found in an optional
+ * folder structure of `<namespace>/<sub_namespace>/pool_transactions_genesis.json`.
+ */
+ErrorCode indy_vdr_resolver_create(FfiStr networks_path, ResolverHandle *handle_p);
+
+ErrorCode indy_vdr_resolver_dereference(ResolverHandle resolver_handle,
+                                        FfiStr did_url,
+                                        void (*cb)(int64_t cb_id, ErrorCode err, const char *response),
+                                        int64_t cb_id);
+
+ErrorCode indy_vdr_resolver_resolve(ResolverHandle resolver_handle,
+                                    FfiStr did,
+                                    void (*cb)(int64_t cb_id, ErrorCode err, const char *response),
+                                    int64_t cb_id);
+
+ErrorCode indy_vdr_resolver_set_default_namespace(ResolverHandle resolver_handle,
+                                                  FfiStr namespace_);
+
 ErrorCode indy_vdr_set_cache_directory(FfiStr path);
 
 ErrorCode indy_vdr_set_ledger_txn_cache(int32_t capacity, int64_t expiry_offset_ms, FfiStr path);
 
+ErrorCode indy_vdr_set_ledger_txn_cache_policy(int64_t pinned_expiry_ms, int64_t current_expiry_ms);
+
 ErrorCode indy_vdr_set_config(FfiStr config);
 
 ErrorCode indy_vdr_set_default_logger(void);
@@ -42,6 +42,36 @@ jsi::Value setLedgerTxnCache(jsi::Runtime &rt, jsi::Object options) {
   return createReturnValue(rt, code, nullptr);
 };
 
+jsi::Value setLedgerTxnCachePolicy(jsi::Runtime &rt, jsi::Object options) {
+  auto pinnedExpiryMs = jsiToValue<int64_t>(rt, options, "pinned_expiry_ms");
+  auto currentExpiryMs = jsiToValue<int64_t>(rt, options, "current_expiry_ms");
+
+  ErrorCode code = indy_vdr_set_ledger_txn_cache_policy(pinnedExpiryMs, currentExpiryMs);
+
+  return createReturnValue(rt, code, nullptr);
+};
+
+jsi::Value getLedgerTxnCacheStats(jsi::Runtime &rt, jsi::Object options) {
+  const char *out;
+
+  ErrorCode code = indy_vdr_get_ledger_txn_cache_stats(&out);
+
+  return createReturnValue(rt, code, &out);
+};
+
+jsi::Value flushLedgerTxnCache(jsi::Runtime &rt, jsi::Object options) {
+  auto keyPrefix = jsiToValue<std::string>(rt, options, "keyPrefix", true);
+  auto txnType = jsiToValue<std::string>(rt, options, "txnType", true);
+
+  int64_t out;
+
+  ErrorCode code = indy_vdr_flush_ledger_txn_cache(
+      keyPrefix.length() > 0 ? keyPrefix.c_str() : nullptr,
+      txnType.length() > 0 ? txnType.c_str() : nullptr, &out);
+
+  return createReturnValue(rt, code, &out);
+};
+
 jsi::Value setDefaultLogger(jsi::Runtime &rt, jsi::Object options) {
   ErrorCode code = indy_vdr_set_default_logger();
 
@@ -404,6 +434,19 @@ jsi::Value poolRefresh(jsi::Runtime &rt, jsi::Object options) {
   return createReturnValue(rt, code, nullptr);
 };
 
+jsi::Value poolGetStats(jsi::Runtime &rt, jsi::Object options) {
+  auto poolHandle = jsiToValue<PoolHandle>(rt, options, "poolHandle");
+
+  jsi::Function cb = options.getPropertyAsFunction(rt, "cb");
+  State *state = new State(&cb);
+  state->rt = &rt;
+
+  ErrorCode code = indy_vdr_pool_get_stats(poolHandle, callbackWithResponse,
+                                           CallbackId(state));
+
+  return createReturnValue(rt, code, nullptr);
+};
+
 jsi::Value poolGetStatus(jsi::Runtime &rt, jsi::Object options) {
   auto poolHandle = jsiToValue<PoolHandle>(rt, options, "poolHandle");
 
@@ -442,6 +485,23 @@ jsi::Value poolGetVerifiers(jsi::Runtime &rt, jsi::Object options) {
   return createReturnValue(rt, code, nullptr);
 };
 
+jsi::Value poolGetTxnRange(jsi::Runtime &rt, jsi::Object options) {
+  auto poolHandle = jsiToValue<PoolHandle>(rt, options, "poolHandle");
+  auto ledgerType = jsiToValue<int32_t>(rt, options, "ledgerType");
+  auto seqNoFrom = jsiToValue<int32_t>(rt, options, "seqNoFrom");
+  auto seqNoTo = jsiToValue<int32_t>(rt, options, "seqNoTo");
+
+  jsi::Function cb = options.getPropertyAsFunction(rt, "cb");
+  State *state = new State(&cb);
+  state->rt = &rt;
+
+  ErrorCode code =
+      indy_vdr_pool_get_txn_range(poolHandle, ledgerType, seqNoFrom, seqNoTo,
+                                  callbackWithSeqNo, CallbackId(state));
+
+  return createReturnValue(rt, code, nullptr);
+};
+
 jsi::Value poolSubmitAction(jsi::Runtime &rt, jsi::Object options) {
   auto poolHandle = jsiToValue<PoolHandle>(rt, options, "poolHandle");
   auto requestHandle = jsiToValue<RequestHandle>(rt, options, "requestHandle");
@@ -481,6 +541,84 @@ jsi::Value poolClose(jsi::Runtime &rt, jsi::Object options) {
   return createReturnValue(rt, code, nullptr);
 };
 
+jsi::Value resolverCreate(jsi::Runtime &rt, jsi::Object options) {
+  auto networksPath =
+      jsiToValue<std::string>(rt, options, "networksPath", true);
+
+  ResolverHandle out;
+  ErrorCode code = indy_vdr_resolver_create(
+      networksPath.length() > 0 ? networksPath.c_str() : nullptr, &out);
+
+  return createReturnValue(rt, code, &out);
+};
+
+jsi::Value resolverAddNetwork(jsi::Runtime &rt, jsi::Object options) {
+  auto resolverHandle =
+      jsiToValue<ResolverHandle>(rt, options, "resolverHandle");
+  auto namespace_ = jsiToValue<std::string>(rt, options, "namespace");
+  auto transactions =
+      jsiToValue<std::string>(rt, options, "transactions", true);
+  auto transactionsPath =
+      jsiToValue<std::string>(rt, options, "transactionsPath", true);
+
+  ErrorCode code = indy_vdr_resolver_add_network(
+      resolverHandle, namespace_.c_str(),
+      transactions.length() > 0 ? transactions.c_str() : nullptr,
+      transactionsPath.length() > 0 ? transactionsPath.c_str() : nullptr);
+
+  return createReturnValue(rt, code, nullptr);
+};
+
+jsi::Value resolverSetDefaultNamespace(jsi::Runtime &rt, jsi::Object options) {
+  auto resolverHandle =
+      jsiToValue<ResolverHandle>(rt, options, "resolverHandle");
+  auto namespace_ = jsiToValue<std::string>(rt, options, "namespace", true);
+
+  ErrorCode code = indy_vdr_resolver_set_default_namespace(
+      resolverHandle, namespace_.length() > 0 ? namespace_.c_str() : nullptr);
+
+  return createReturnValue(rt, code, nullptr);
+};
+
+jsi::Value resolverResolve(jsi::Runtime &rt, jsi::Object options) {
+  auto resolverHandle =
+      jsiToValue<ResolverHandle>(rt, options, "resolverHandle");
+  auto did = jsiToValue<std::string>(rt, options, "did");
+
+  jsi::Function cb = options.getPropertyAsFunction(rt, "cb");
+  State *state = new State(&cb);
+  state->rt = &rt;
+
+  ErrorCode code = indy_vdr_resolver_resolve(
+      resolverHandle, did.c_str(), callbackWithResponse, CallbackId(state));
+
+  return createReturnValue(rt, code, nullptr);
+};
+
+jsi::Value resolverDereference(jsi::Runtime &rt, jsi::Object options) {
+  auto resolverHandle =
+      jsiToValue<ResolverHandle>(rt, options, "resolverHandle");
+  auto didUrl = jsiToValue<std::string>(rt, options, "didUrl");
+
+  jsi::Function cb = options.getPropertyAsFunction(rt, "cb");
+  State *state = new State(&cb);
+  state->rt = &rt;
+
+  ErrorCode code = indy_vdr_resolver_dereference(
+      resolverHandle, didUrl.c_str(), callbackWithResponse, CallbackId(state));
+
+  return createReturnValue(rt, code, nullptr);
+};
+
+jsi::Value resolverClose(jsi::Runtime &rt, jsi::Object options) {
+  auto resolverHandle =
+      jsiToValue<ResolverHandle>(rt, options, "resolverHandle");
+
+  ErrorCode code = indy_vdr_resolver_close(resolverHandle);
+
+  return createReturnValue(rt, code, nullptr);
+};
+
 jsi::Value prepareTxnAuthorAgreementAcceptance(jsi::Runtime &rt,
                                                jsi::Object options) {
   auto text = jsiToValue<std::string>(rt, options, "text");
@@ -536,6 +674,15 @@ jsi::Value requestSetEndorser(jsi::Runtime &rt, jsi::Object options) {
 
   return createReturnValue(rt, code, nullptr);
 };
+jsi::Value requestSetUseCache(jsi::Runtime &rt, jsi::Object options) {
+  auto requestHandle = jsiToValue<RequestHandle>(rt, options, "requestHandle");
+  auto useCache = jsiToValue<int32_t>(rt, options, "useCache");
+
+  ErrorCode code = indy_vdr_request_set_use_cache(requestHandle, useCache);
+
+  return createReturnValue(rt, code, nullptr);
+};
+
 jsi::Value requestSetMultiSignature(jsi::Runtime &rt, jsi::Object options) {
   auto requestHandle = jsiToValue<RequestHandle>(rt, options, "requestHandle");
   auto identifier = jsiToValue<std::string>(rt, options, "identifier");
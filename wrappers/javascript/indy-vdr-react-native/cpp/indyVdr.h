@@ -14,6 +14,9 @@ jsi::Value getCurrentError(jsi::Runtime &rt, jsi::Object options);
 jsi::Value setConfig(jsi::Runtime &rt, jsi::Object options);
 jsi::Value setCacheDirectory(jsi::Runtime &rt, jsi::Object options);
 jsi::Value setLedgerTxnCache(jsi::Runtime &rt, jsi::Object options);
+jsi::Value setLedgerTxnCachePolicy(jsi::Runtime &rt, jsi::Object options);
+jsi::Value getLedgerTxnCacheStats(jsi::Runtime &rt, jsi::Object options);
+jsi::Value flushLedgerTxnCache(jsi::Runtime &rt, jsi::Object options);
 jsi::Value setDefaultLogger(jsi::Runtime &rt, jsi::Object options);
 jsi::Value setProtocolVersion(jsi::Runtime &rt, jsi::Object options);
 jsi::Value setSocksProxy(jsi::Runtime &rt, jsi::Object options);
@@ -47,18 +50,28 @@ jsi::Value buildTxnAuthorAgreementRequest(jsi::Runtime &rt,
 
 jsi::Value poolCreate(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolRefresh(jsi::Runtime &rt, jsi::Object options);
+jsi::Value poolGetStats(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolGetStatus(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolGetTransactions(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolGetVerifiers(jsi::Runtime &rt, jsi::Object options);
+jsi::Value poolGetTxnRange(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolSubmitAction(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolSubmitRequest(jsi::Runtime &rt, jsi::Object options);
 jsi::Value poolClose(jsi::Runtime &rt, jsi::Object options);
 
+jsi::Value resolverCreate(jsi::Runtime &rt, jsi::Object options);
+jsi::Value resolverAddNetwork(jsi::Runtime &rt, jsi::Object options);
+jsi::Value resolverSetDefaultNamespace(jsi::Runtime &rt, jsi::Object options);
+jsi::Value resolverResolve(jsi::Runtime &rt, jsi::Object options);
+jsi::Value resolverDereference(jsi::Runtime &rt, jsi::Object options);
+jsi::Value resolverClose(jsi::Runtime &rt, jsi::Object options);
+
 jsi::Value requestSetEndorser(jsi::Runtime &rt, jsi::Object options);
 jsi::Value requestSetMultiSignature(jsi::Runtime &rt, jsi::Object options);
 jsi::Value requestSetSignature(jsi::Runtime &rt, jsi::Object options);
 jsi::Value requestSetTxnAuthorAgreementAcceptance(jsi::Runtime &rt,
                                                   jsi::Object options);
+jsi::Value requestSetUseCache(jsi::Runtime &rt, jsi::Object options);
 jsi::Value requestFree(jsi::Runtime &rt, jsi::Object options);
 
 jsi::Value prepareTxnAuthorAgreementAcceptance(jsi::Runtime &rt,
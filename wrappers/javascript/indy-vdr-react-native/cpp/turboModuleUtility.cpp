@@ -111,6 +111,29 @@ void callbackWithResponse(CallbackId result, ErrorCode code,
   });
 }
 
+void callbackWithSeqNo(CallbackId result, ErrorCode code, int32_t seqNo,
+                       const char *response) {
+  // the response is released once the callback returns
+  std::string value = response == nullptr ? std::string() : response;
+  invoker->invokeAsync([result, code, seqNo, value]() {
+    State *_state = reinterpret_cast<State *>(result);
+    State *state = static_cast<State *>(_state);
+    jsi::Function *cb = &state->cb;
+    jsi::Runtime *rt = reinterpret_cast<jsi::Runtime *>(state->rt);
+
+    auto object = jsi::Object(*rt);
+    if (code == ErrorCode::Success) {
+      auto out = jsi::Object(*rt);
+      out.setProperty(*rt, "seqNo", int(seqNo));
+      out.setProperty(*rt, "response",
+                      jsi::String::createFromUtf8(*rt, value));
+      object.setProperty(*rt, "value", out);
+    }
+    object.setProperty(*rt, "errorCode", int(code));
+    cb->call(*rt, object);
+  });
+}
+
 template <>
 uint8_t jsiToValue(jsi::Runtime &rt, jsi::Object &options, const char *name,
                    bool optional) {
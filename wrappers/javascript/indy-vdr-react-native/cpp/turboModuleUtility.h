@@ -55,4 +55,9 @@ void callback(CallbackId result, ErrorCode code);
 void callbackWithResponse(CallbackId result, ErrorCode code,
                           const char *response);
 
+// Callback function which is invoked once for each transaction in a range,
+// followed by a final call with a sequence number of zero
+void callbackWithSeqNo(CallbackId result, ErrorCode code, int32_t seqNo,
+                       const char *response);
+
 } // namespace indyVdrTurboModuleUtility
@@ -3,6 +3,7 @@ import type { Transactions } from '@hyperledger/indy-vdr-shared'
 
 export type PoolHandle = number
 export type RequestHandle = number
+export type ResolverHandle = number
 
 export interface NativeBindings {
   version(options: Record<string, never>): string
@@ -15,6 +16,12 @@ export interface NativeBindings {
 
   setLedgerTxnCache(options: { capacity: number; expiry_offset_ms: number; path?: string }): ReturnObject<never>
 
+  setLedgerTxnCachePolicy(options: { pinned_expiry_ms: number; current_expiry_ms: number }): ReturnObject<never>
+
+  getLedgerTxnCacheStats(options: Record<string, never>): ReturnObject<string>
+
+  flushLedgerTxnCache(options: { keyPrefix?: string; txnType?: string }): ReturnObject<number>
+
   setDefaultLogger(options: Record<string, never>): ReturnObject<never>
 
   setProtocolVersion(options: { version: number }): ReturnObject<never>
@@ -130,12 +137,22 @@ export interface NativeBindings {
 
   poolRefresh(options: { poolHandle: PoolHandle; cb: Callback }): ReturnObject<never>
 
+  poolGetStats(options: { poolHandle: PoolHandle; cb: CallbackWithResponse<string> }): ReturnObject<never>
+
   poolGetStatus(options: { poolHandle: PoolHandle; cb: CallbackWithResponse<string> }): ReturnObject<never>
 
   poolGetTransactions(options: { poolHandle: PoolHandle; cb: CallbackWithResponse<Transactions> }): ReturnObject<never>
 
   poolGetVerifiers(options: { poolHandle: PoolHandle; cb: CallbackWithResponse<string> }): ReturnObject<never>
 
+  poolGetTxnRange(options: {
+    poolHandle: PoolHandle
+    ledgerType: number
+    seqNoFrom: number
+    seqNoTo: number
+    cb: CallbackWithResponse<{ seqNo: number; response: string }>
+  }): ReturnObject<never>
+
   poolSubmitAction(options: {
     poolHandle: PoolHandle
     requestHandle: number
@@ -152,6 +169,31 @@ export interface NativeBindings {
 
   poolClose(options: { poolHandle: PoolHandle }): ReturnObject<never>
 
+  resolverCreate(options: { networksPath?: string }): ReturnObject<ResolverHandle>
+
+  resolverAddNetwork(options: {
+    resolverHandle: ResolverHandle
+    namespace: string
+    transactions?: string
+    transactionsPath?: string
+  }): ReturnObject<never>
+
+  resolverSetDefaultNamespace(options: { resolverHandle: ResolverHandle; namespace?: string }): ReturnObject<never>
+
+  resolverResolve(options: {
+    resolverHandle: ResolverHandle
+    did: string
+    cb: CallbackWithResponse<string>
+  }): ReturnObject<never>
+
+  resolverDereference(options: {
+    resolverHandle: ResolverHandle
+    didUrl: string
+    cb: CallbackWithResponse<string>
+  }): ReturnObject<never>
+
+  resolverClose(options: { resolverHandle: ResolverHandle }): ReturnObject<never>
+
   prepareTxnAuthorAgreementAcceptance(options: {
     text?: string
     version?: string
@@ -177,4 +219,6 @@ export interface NativeBindings {
   requestSetSignature(options: { requestHandle: number; signature: ArrayBuffer }): ReturnObject<never>
 
   requestSetTxnAuthorAgreementAcceptance(options: { requestHandle: number; acceptance: string }): ReturnObject<never>
+
+  requestSetUseCache(options: { requestHandle: number; useCache: number }): ReturnObject<never>
 }
@@ -22,6 +22,7 @@ import type {
   NymRequestOptions,
   PoolCreateOptions,
   PoolHandle,
+  PoolStats,
   PoolStatus,
   PoolSubmitActionOptions,
   PoolSubmitRequestOptions,
@@ -31,10 +32,12 @@ import type {
   RequestSetMultiSignatureOptions,
   RequestSetSignatureOptions,
   RequestSetTxnAuthorAgreementAcceptanceOptions,
+  ResolverHandle,
   RevocationRegistryDefinitionRequestOptions,
   RevocationRegistryEntryRequestOptions,
   SchemaRequestOptions,
   TransactionAuthorAgreementRequestOptions,
+  TransactionRange,
   Transactions,
   Verifiers,
 } from '@hyperledger/indy-vdr-shared'
@@ -115,6 +118,21 @@ export class ReactNativeIndyVdr implements IndyVdr {
     this.indyVdr.setLedgerTxnCache(serializedOptions)
   }
 
+  public setLedgerTxnCachePolicy(options: { pinned_expiry_ms?: number; current_expiry_ms?: number }): void {
+    const { pinned_expiry_ms = -1, current_expiry_ms = -1 } = options
+    this.handleError(this.indyVdr.setLedgerTxnCachePolicy({ pinned_expiry_ms, current_expiry_ms }))
+  }
+
+  public getLedgerTxnCacheStats(): Record<string, unknown> {
+    const stats = handleInvalidNullResponse(this.handleError(this.indyVdr.getLedgerTxnCacheStats({})))
+    return JSON.parse(stats) as Record<string, unknown>
+  }
+
+  public flushLedgerTxnCache(options: { keyPrefix?: string; txnType?: string }): number {
+    const serializedOptions = serializeArguments(options)
+    return handleInvalidNullResponse(this.handleError(this.indyVdr.flushLedgerTxnCache(serializedOptions)))
+  }
+
   public setDefaultLogger(): void {
     this.handleError(this.indyVdr.setDefaultLogger({}))
   }
@@ -252,6 +270,15 @@ export class ReactNativeIndyVdr implements IndyVdr {
     return this.promisify((cb) => this.handleError(this.indyVdr.poolRefresh({ cb, poolHandle })))
   }
 
+  public async poolGetStats(options: { poolHandle: PoolHandle }): Promise<PoolStats> {
+    const { poolHandle } = serializeArguments(options)
+    const result = handleInvalidNullResponse(
+      await this.promisifyWithResponse<string>((cb) => this.indyVdr.poolGetStats({ cb, poolHandle }))
+    )
+
+    return JSON.parse(result) as PoolStats
+  }
+
   public async poolGetStatus(options: { poolHandle: PoolHandle }): Promise<PoolStatus> {
     const { poolHandle } = serializeArguments(options)
     const result = handleInvalidNullResponse(
@@ -279,6 +306,38 @@ export class ReactNativeIndyVdr implements IndyVdr {
     return JSON.parse(result) as Verifiers
   }
 
+  public async poolGetTxnRange(options: {
+    poolHandle: PoolHandle
+    ledgerType: number
+    seqNoFrom: number
+    seqNoTo: number
+  }): Promise<TransactionRange> {
+    const serializedOptions = serializeArguments(options)
+
+    return new Promise((resolve, reject) => {
+      const transactions: TransactionRange = []
+      let error: unknown
+      // invoked for each transaction, followed by a final call without a sequence number
+      const cb: CallbackWithResponse<{ seqNo: number; response: string }> = ({ errorCode, value }) => {
+        try {
+          if (errorCode !== 0) throw new IndyVdrError(JSON.parse(this.getCurrentError()) as IndyVdrErrorObject)
+          if (value && value.seqNo !== 0) {
+            const { result } = JSON.parse(value.response) as { result: Record<string, unknown> }
+            transactions.push({ seqNo: value.seqNo, transaction: result })
+          }
+        } catch (e) {
+          error = error ?? e
+        }
+        if (errorCode === 0 && value && value.seqNo !== 0) return
+
+        if (error) return reject(error)
+        resolve(transactions)
+      }
+
+      this.handleError(this.indyVdr.poolGetTxnRange({ cb, ...serializedOptions }))
+    })
+  }
+
   public async poolSubmitAction<T extends Record<string, unknown>>(
     options: PoolSubmitActionOptions & { poolHandle: PoolHandle }
   ): Promise<T> {
@@ -306,6 +365,59 @@ export class ReactNativeIndyVdr implements IndyVdr {
     this.indyVdr.poolClose(serializedOptions)
   }
 
+  public resolverCreate(options: { networksPath?: string }): ResolverHandle {
+    const serializedOptions = serializeArguments(options)
+    return handleInvalidNullResponse(this.handleError(this.indyVdr.resolverCreate(serializedOptions)))
+  }
+
+  public resolverAddNetwork(options: {
+    resolverHandle: ResolverHandle
+    namespace: string
+    transactions?: string
+    transactionsPath?: string
+  }): void {
+    const serializedOptions = serializeArguments(options)
+    this.handleError(this.indyVdr.resolverAddNetwork(serializedOptions))
+  }
+
+  public resolverSetDefaultNamespace(options: { resolverHandle: ResolverHandle; namespace?: string }): void {
+    const serializedOptions = serializeArguments(options)
+    this.handleError(this.indyVdr.resolverSetDefaultNamespace(serializedOptions))
+  }
+
+  public async resolverResolve(options: {
+    resolverHandle: ResolverHandle
+    did: string
+  }): Promise<Record<string, unknown>> {
+    const { resolverHandle, did } = serializeArguments(options)
+    const result = handleInvalidNullResponse(
+      await this.promisifyWithResponse<string>((cb) =>
+        this.handleError(this.indyVdr.resolverResolve({ cb, resolverHandle, did }))
+      )
+    )
+
+    return JSON.parse(result) as Record<string, unknown>
+  }
+
+  public async resolverDereference(options: {
+    resolverHandle: ResolverHandle
+    didUrl: string
+  }): Promise<Record<string, unknown>> {
+    const { resolverHandle, didUrl } = serializeArguments(options)
+    const result = handleInvalidNullResponse(
+      await this.promisifyWithResponse<string>((cb) =>
+        this.handleError(this.indyVdr.resolverDereference({ cb, resolverHandle, didUrl }))
+      )
+    )
+
+    return JSON.parse(result) as Record<string, unknown>
+  }
+
+  public resolverClose(options: { resolverHandle: ResolverHandle }): void {
+    const serializedOptions = serializeArguments(options)
+    this.handleError(this.indyVdr.resolverClose(serializedOptions))
+  }
+
   public prepareTxnAuthorAgreementAcceptance(options: PrepareTxnAuthorAgreementAcceptanceOptions): string {
     const serializedOptions = serializeArguments(options)
     return handleInvalidNullResponse(
@@ -333,6 +445,14 @@ export class ReactNativeIndyVdr implements IndyVdr {
     this.indyVdr.requestSetEndorser(serializedOptions)
   }
 
+  public requestSetUseCache(options: { requestHandle: RequestHandle; useCache: boolean }): void {
+    const serializedOptions = serializeArguments({
+      requestHandle: options.requestHandle,
+      useCache: options.useCache ? 1 : 0,
+    })
+    this.handleError(this.indyVdr.requestSetUseCache(serializedOptions))
+  }
+
   public requestSetMultiSignature(options: RequestSetMultiSignatureOptions & { requestHandle: RequestHandle }): void {
     const serializedOptions = serializeArguments(options)
     this.indyVdr.requestSetMultiSignature(serializedOptions)
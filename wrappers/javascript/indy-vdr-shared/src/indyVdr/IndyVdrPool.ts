@@ -1,5 +1,5 @@
 import type { IndyVdrRequest, RequestResponseType } from './IndyVdrRequest'
-import type { PoolStatus, Transactions, Verifiers } from '../types'
+import type { PoolStats, PoolStatus, TransactionRange, Transactions, Verifiers } from '../types'
 
 import { indyVdr } from './indyVdr'
 
@@ -25,6 +25,10 @@ export class IndyVdrPool {
     return indyVdr.poolGetStatus({ poolHandle: this.handle })
   }
 
+  public get stats(): Promise<PoolStats> {
+    return indyVdr.poolGetStats({ poolHandle: this.handle })
+  }
+
   public get verifiers(): Promise<Verifiers> {
     return indyVdr.poolGetVerifiers({ poolHandle: this.handle })
   }
@@ -33,6 +37,18 @@ export class IndyVdrPool {
     return indyVdr.poolGetTransactions({ poolHandle: this.handle })
   }
 
+  /**
+   * Fetch a range of ledger transactions, each verified against the ledger root
+   * multi-signed by the validator nodes when the range is requested
+   */
+  public async getTxnRange(options: {
+    ledgerType: number
+    seqNoFrom: number
+    seqNoTo: number
+  }): Promise<TransactionRange> {
+    return indyVdr.poolGetTxnRange({ poolHandle: this.handle, ...options })
+  }
+
   public async refresh(): Promise<void> {
     await indyVdr.poolRefresh({ poolHandle: this.handle })
   }
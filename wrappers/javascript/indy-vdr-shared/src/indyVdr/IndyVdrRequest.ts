@@ -60,6 +60,10 @@ export class IndyVdrRequest<ResponseType extends Record<string, unknown> = Recor
     indyVdr.requestSetTxnAuthorAgreementAcceptance({ requestHandle: this.handle, ...options })
   }
 
+  public setUseCache(useCache: boolean): void {
+    indyVdr.requestSetUseCache({ requestHandle: this.handle, useCache })
+  }
+
   public free(): void {
     indyVdr.requestFree({ requestHandle: this.handle })
   }
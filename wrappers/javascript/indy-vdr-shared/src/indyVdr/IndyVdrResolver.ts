@@ -0,0 +1,46 @@
+import type { ResolverHandle } from '../types'
+
+import { indyVdr } from './indyVdr'
+
+/**
+ * Resolves DIDs using the pool registered for the namespace of each DID, such as `sovrin`
+ * or `bcovrin:test`. Pools are only created when first used
+ */
+export class IndyVdrResolver {
+  private _handle: ResolverHandle
+
+  /**
+   * Create a resolver, registering the networks found in an optional folder structure of
+   * `<namespace>/<sub_namespace>/pool_transactions_genesis.json`
+   */
+  public constructor(options: { networksPath?: string } = {}) {
+    this._handle = indyVdr.resolverCreate(options)
+  }
+
+  public get handle(): ResolverHandle {
+    return this._handle
+  }
+
+  public addNetwork(options: { namespace: string; transactions?: string; transactionsPath?: string }): void {
+    indyVdr.resolverAddNetwork({ resolverHandle: this.handle, ...options })
+  }
+
+  /**
+   * Set the namespace used to resolve did:sov and unqualified DIDs
+   */
+  public setDefaultNamespace(options: { namespace?: string }): void {
+    indyVdr.resolverSetDefaultNamespace({ resolverHandle: this.handle, ...options })
+  }
+
+  public async resolve(options: { did: string }): Promise<Record<string, unknown>> {
+    return indyVdr.resolverResolve({ resolverHandle: this.handle, ...options })
+  }
+
+  public async dereference(options: { didUrl: string }): Promise<Record<string, unknown>> {
+    return indyVdr.resolverDereference({ resolverHandle: this.handle, ...options })
+  }
+
+  public close(): void {
+    indyVdr.resolverClose({ resolverHandle: this.handle })
+  }
+}
@@ -1,3 +1,4 @@
 export * from './IndyVdrPool'
 export * from './IndyVdrRequest'
+export * from './IndyVdrResolver'
 export * from './indyVdr'
@@ -1,10 +1,13 @@
 import type { PrepareTxnAuthorAgreementAcceptanceOptions } from './builderTypes'
 import type {
   PoolHandle,
+  PoolStats,
   PoolStatus,
   PoolSubmitActionOptions,
   PoolSubmitRequestOptions,
   RequestHandle,
+  ResolverHandle,
+  TransactionRange,
   Transactions,
   Verifiers,
 } from './types'
@@ -51,6 +54,12 @@ export interface IndyVdr {
 
   setLedgerTxnCache(options: { capacity: number; expiry_offset_ms: number; path?: string }): void
 
+  setLedgerTxnCachePolicy(options: { pinned_expiry_ms?: number; current_expiry_ms?: number }): void
+
+  getLedgerTxnCacheStats(): Record<string, unknown>
+
+  flushLedgerTxnCache(options: { keyPrefix?: string; txnType?: string }): number
+
   setDefaultLogger(): void
 
   setProtocolVersion(options: { version: number }): void
@@ -103,18 +112,44 @@ export interface IndyVdr {
 
   poolRefresh(options: { poolHandle: PoolHandle }): Promise<void>
 
+  poolGetStats(options: { poolHandle: PoolHandle }): Promise<PoolStats>
+
   poolGetStatus(options: { poolHandle: PoolHandle }): Promise<PoolStatus>
 
   poolGetTransactions(options: { poolHandle: PoolHandle }): Promise<Transactions>
 
   poolGetVerifiers(options: { poolHandle: PoolHandle }): Promise<Verifiers>
 
+  poolGetTxnRange(options: {
+    poolHandle: PoolHandle
+    ledgerType: number
+    seqNoFrom: number
+    seqNoTo: number
+  }): Promise<TransactionRange>
+
   poolSubmitAction<T extends Record<string, unknown>>(options: PoolSubmitActionOptions): Promise<T>
 
   poolSubmitRequest<T extends Record<string, unknown>>(options: PoolSubmitRequestOptions): Promise<T>
 
   poolClose(options: { poolHandle: number }): void
 
+  resolverCreate(options: { networksPath?: string }): ResolverHandle
+
+  resolverAddNetwork(options: {
+    resolverHandle: ResolverHandle
+    namespace: string
+    transactions?: string
+    transactionsPath?: string
+  }): void
+
+  resolverSetDefaultNamespace(options: { resolverHandle: ResolverHandle; namespace?: string }): void
+
+  resolverResolve(options: { resolverHandle: ResolverHandle; did: string }): Promise<Record<string, unknown>>
+
+  resolverDereference(options: { resolverHandle: ResolverHandle; didUrl: string }): Promise<Record<string, unknown>>
+
+  resolverClose(options: { resolverHandle: ResolverHandle }): void
+
   prepareTxnAuthorAgreementAcceptance(options: PrepareTxnAuthorAgreementAcceptanceOptions): string
 
   requestFree(options: { requestHandle: number }): void
@@ -132,4 +167,6 @@ export interface IndyVdr {
   requestSetTxnAuthorAgreementAcceptance(
     options: RequestSetTxnAuthorAgreementAcceptanceOptions & { requestHandle: RequestHandle }
   ): void
+
+  requestSetUseCache(options: { requestHandle: RequestHandle; useCache: boolean }): void
 }
@@ -1,5 +1,6 @@
 export type PoolHandle = number
 export type RequestHandle = number
+export type ResolverHandle = number
 
 export type Raw = Record<string, unknown>
 
@@ -51,6 +52,24 @@ type VerifierInfo = {
 
 export type Verifiers = Record<string, VerifierInfo>
 
+type NodeStats = {
+  requests: number
+  replies: number
+  nacks: number
+  rejects: number
+  timeouts: number
+  latency_p50?: number
+  latency_p95?: number
+  latency_ewma?: number
+  timeout_rate: number
+  nack_rate: number
+  last_seen?: number
+}
+
+export type PoolStats = Record<string, NodeStats>
+
+export type TransactionRange = Array<{ seqNo: number; transaction: Record<string, unknown> }>
+
 export type PoolSubmitRequestOptions = {
   requestHandle: RequestHandle
   poolHandle: PoolHandle
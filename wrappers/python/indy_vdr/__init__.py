@@ -1,21 +1,34 @@
 """indy-vdr Python wrapper library"""
 
-from .bindings import set_cache_directory, set_ledger_txn_cache, set_config, set_protocol_version, version
+from .bindings import (
+    flush_ledger_txn_cache,
+    get_ledger_txn_cache_stats,
+    set_cache_directory,
+    set_ledger_txn_cache,
+    set_ledger_txn_cache_policy,
+    set_config,
+    set_protocol_version,
+    version,
+)
 from .error import VdrError, VdrErrorCode
 from .ledger import LedgerType
 from .pool import Pool, open_pool
 from .request import Request
-from .resolver import Resolver
+from .resolver import MultiPoolResolver, Resolver
 
 __all__ = [
+    "flush_ledger_txn_cache",
+    "get_ledger_txn_cache_stats",
     "open_pool",
     "set_cache_directory",
     "set_ledger_txn_cache",
+    "set_ledger_txn_cache_policy",
     "set_config",
     "set_protocol_version",
     "set_socks_proxy",
     "version",
     "LedgerType",
+    "MultiPoolResolver",
     "Pool",
     "Request",
     "Resolver",
@@ -14,11 +14,12 @@ from ctypes import (
     c_char_p,
     c_int32,
     c_int64,
+    c_int8,
     c_ubyte,
     c_void_p,
 )
 from ctypes.util import find_library
-from typing import Optional, Sequence, Union
+from typing import AsyncIterator, Optional, Sequence, Tuple, Union
 
 from .error import VdrError, VdrErrorCode
 
@@ -256,18 +257,79 @@ def pool_create(params: Union[str, bytes, dict]) -> PoolHandle:
     return handle
 
 
-def resolver_create(pool_handle: PoolHandle) -> ResolverHandle:
-    """Create a new resolver instance."""
+def resolver_create(networks_path: str = None) -> ResolverHandle:
+    """Create a new multiple pool resolver instance.
+
+    Args:
+        networks_path: An optional folder of `<namespace>/<sub_namespace>/pool_transactions_genesis.json`
+            files, registering the network for each namespace
+    """
     handle = ResolverHandle()
-    do_call("indy_vdr_resolver_create", pool_handle, byref(handle))
+    do_call("indy_vdr_resolver_create", encode_str(networks_path), byref(handle))
     return handle
 
 
-def resolve(resolver_handle: ResolverHandle, did: str) -> asyncio.Future:
+def resolver_add_network(
+    resolver_handle: ResolverHandle,
+    namespace: str,
+    transactions: str = None,
+    transactions_path: str = None,
+):
+    """Register the genesis transactions for a namespace of a resolver instance."""
+    do_call(
+        "indy_vdr_resolver_add_network",
+        resolver_handle,
+        encode_str(namespace),
+        encode_str(transactions),
+        encode_str(transactions_path),
+    )
+
+
+def resolver_set_default_namespace(
+    resolver_handle: ResolverHandle, namespace: str = None
+):
+    """Set the namespace used to resolve did:sov and unqualified DIDs."""
+    do_call(
+        "indy_vdr_resolver_set_default_namespace",
+        resolver_handle,
+        encode_str(namespace),
+    )
+
+
+def resolver_resolve(resolver_handle: ResolverHandle, did: str) -> asyncio.Future:
+    """Resolve a DID using the pool for its namespace."""
+    return do_call_async(
+        "indy_vdr_resolver_resolve",
+        resolver_handle,
+        encode_str(did),
+        return_type=lib_string,
+        post_process=str,
+    )
+
+
+def resolver_dereference(
+    resolver_handle: ResolverHandle, did_url: str
+) -> asyncio.Future:
+    """Dereference a DID Url using the pool for its namespace."""
+    return do_call_async(
+        "indy_vdr_resolver_dereference",
+        resolver_handle,
+        encode_str(did_url),
+        return_type=lib_string,
+        post_process=str,
+    )
+
+
+def resolver_close(resolver_handle: ResolverHandle):
+    """Close and free a resolver instance and its pools."""
+    do_call("indy_vdr_resolver_close", resolver_handle)
+
+
+def resolve(pool_handle: PoolHandle, did: str) -> asyncio.Future:
     """Resolve a DID to retrieve a DID Doc."""
     return do_call_async(
         "indy_vdr_resolve",
-        resolver_handle,
+        pool_handle,
         encode_str(did),
         return_type=lib_string,
         post_process=str,
@@ -285,6 +347,16 @@ def dereference(pool_handle: PoolHandle, did_url: str) -> asyncio.Future:
     )
 
 
+def pool_get_stats(pool_handle: PoolHandle) -> asyncio.Future:
+    """Get the statistics collected for each verifier node of a pool instance."""
+    return do_call_async(
+        "indy_vdr_pool_get_stats",
+        pool_handle,
+        return_type=lib_string,
+        post_process=str,
+    )
+
+
 def pool_get_status(pool_handle: PoolHandle) -> asyncio.Future:
     """Get the status of an opened pool instance."""
     return do_call_async(
@@ -348,6 +420,51 @@ def pool_get_transactions(pool_handle: PoolHandle) -> asyncio.Future:
     )
 
 
+async def pool_get_txn_range(
+    pool_handle: PoolHandle, ledger_type: int, seq_no_from: int, seq_no_to: int
+) -> AsyncIterator[Tuple[int, str]]:
+    """Fetch a range of verified ledger transactions in order of sequence number."""
+    lib_fn = get_library().indy_vdr_pool_get_txn_range
+    lib_fn.restype = c_int64
+    loop = asyncio.get_event_loop()
+    queue = asyncio.Queue()
+
+    def _cb(id: int, err: int, seq_no: int, response: lib_string):
+        """Queue each transaction, followed by None or an error."""
+        if err:
+            item = get_current_error(True)
+        elif seq_no:
+            item = (seq_no, str(response))
+        else:
+            item = None
+        if not isinstance(item, tuple):
+            CALLBACKS.pop(queue, None)
+        loop.call_soon_threadsafe(queue.put_nowait, item)
+
+    cb_res = CFUNCTYPE(None, c_int64, c_int64, c_int32, lib_string)(_cb)
+    # keep a reference to the callback function to avoid it being freed
+    CALLBACKS[queue] = (loop, cb_res)
+    result = lib_fn(
+        pool_handle,
+        c_int32(ledger_type),
+        c_int32(seq_no_from),
+        c_int32(seq_no_to),
+        cb_res,
+        c_int64(0),
+    )
+    if result:
+        # callback will not be executed
+        del CALLBACKS[queue]
+        raise get_current_error(True)
+    while True:
+        item = await queue.get()
+        if item is None:
+            return
+        if isinstance(item, VdrError):
+            raise item
+        yield item
+
+
 def pool_get_verifiers(pool_handle: PoolHandle) -> asyncio.Future:
     """Fetch the set of active verifiers for an opened pool instance."""
     return do_call_async(
@@ -421,6 +538,11 @@ def request_set_txn_author_agreement_acceptance(
     )
 
 
+def request_set_use_cache(handle: RequestHandle, use_cache: bool):
+    """Set whether a prepared read request may use the ledger transactions cache."""
+    do_call("indy_vdr_request_set_use_cache", handle, c_int8(use_cache))
+
+
 def set_cache_directory(path: str):
     """Set the library configuration."""
     do_call("indy_vdr_set_cache_directory", encode_str(path))
@@ -429,6 +551,44 @@ def set_ledger_txn_cache(capacity: int, expiry_offset_ms: int, path: str):
     """Set the library configuration."""
     do_call("indy_vdr_set_ledger_txn_cache", c_size_t(capacity), c_ulong(expiry_offset_ms), encode_str(path))
 
+def set_ledger_txn_cache_policy(
+    pinned_expiry_ms: Optional[int] = None, current_expiry_ms: Optional[int] = None
+):
+    """Set the expiry of immutable and historical replies, and of current state replies.
+
+    The expiry of the ledger transactions cache is used when not provided.
+    """
+    do_call(
+        "indy_vdr_set_ledger_txn_cache_policy",
+        c_int64(-1 if pinned_expiry_ms is None else pinned_expiry_ms),
+        c_int64(-1 if current_expiry_ms is None else current_expiry_ms),
+    )
+
+def get_ledger_txn_cache_stats() -> dict:
+    """Get the hit and miss counts and usage of the ledger transactions cache."""
+    stats = lib_string()
+    do_call("indy_vdr_get_ledger_txn_cache_stats", byref(stats))
+    return json.loads(stats.value.decode("utf-8"))
+
+
+def flush_ledger_txn_cache(
+    key_prefix: Optional[str] = None, txn_type: Optional[str] = None
+) -> int:
+    """Remove entries from the ledger transactions cache, returning the number removed.
+
+    All entries are removed unless a key prefix, such as the `mt_root` in the
+    status of a pool, or a transaction type such as `GET_NYM` is provided.
+    """
+    count = c_int64()
+    do_call(
+        "indy_vdr_flush_ledger_txn_cache",
+        encode_str(key_prefix),
+        encode_str(txn_type),
+        byref(count),
+    )
+    return count.value
+
+
 def set_config(config: dict):
     """Set the library configuration."""
     do_call("indy_vdr_set_config", encode_json(config))
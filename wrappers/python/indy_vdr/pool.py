@@ -2,11 +2,11 @@
 
 import json
 from datetime import datetime
-from typing import Dict, Mapping, Sequence, Union
+from typing import AsyncIterator, Dict, Mapping, Sequence, Tuple, Union
 
 from . import bindings
 from .error import VdrError, VdrErrorCode
-from .ledger import Request, build_custom_request
+from .ledger import LedgerType, Request, build_custom_request
 from .utils import get_genesis_txns_from_did_indy_repo_by_name
 
 
@@ -33,12 +33,36 @@ class Pool:
         self.last_status = json.loads(result)
         return result
 
+    async def get_stats(self) -> dict:
+        """Get the statistics collected for each verifier node of the pool instance."""
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "pool is closed")
+        return json.loads(await bindings.pool_get_stats(self.handle))
+
     async def get_transactions(self) -> str:
         """Get the current pool transactions of the pool instance."""
         if not self.handle:
             raise VdrError(VdrErrorCode.WRAPPER, "pool is closed")
         return await bindings.pool_get_transactions(self.handle)
 
+    async def get_txn_range(
+        self,
+        ledger_type: Union[int, str, LedgerType],
+        seq_no_from: int,
+        seq_no_to: int,
+    ) -> AsyncIterator[Tuple[int, dict]]:
+        """Fetch a range of verified ledger transactions in order of sequence number.
+
+        Each transaction is verified against the ledger root multi-signed by the
+        verifier nodes when the range is requested.
+        """
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "pool is closed")
+        async for seq_no, reply in bindings.pool_get_txn_range(
+            self.handle, LedgerType.from_value(ledger_type), seq_no_from, seq_no_to
+        ):
+            yield seq_no, json.loads(reply)["result"]
+
     async def get_verifiers(self) -> dict:
         """Get the current set of active verifiers for the pool instance."""
         if not self.handle:
@@ -94,6 +94,16 @@ class Request:
             raise VdrError(VdrErrorCode.WRAPPER, "no request handle")
         bindings.request_set_txn_author_agreement_acceptance(self.handle, acceptance)
 
+    def set_use_cache(self, use_cache: bool):
+        """Set whether the reply to a read request may use the ledger transactions cache.
+
+        Args:
+            use_cache: False to always fetch the reply from the ledger
+        """
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "no request handle")
+        bindings.request_set_use_cache(self.handle, use_cache)
+
     def __del__(self):
         """Release the pool instance."""
         self.free()
@@ -143,6 +143,59 @@ class Resolver:
         return json.loads(result)
 
 
+class MultiPoolResolver:
+    """did:indy compliant resolver which routes requests by DID namespace.
+
+    The pool for each namespace is created by the library when first used.
+
+    Args:
+        networks_path: An optional folder of genesis transactions, in the layout
+            `<namespace>/<sub_namespace>/pool_transactions_genesis.json`
+    """
+
+    def __init__(self, networks_path: str = None):
+        """Initialize the resolver instance."""
+        self.handle = bindings.resolver_create(networks_path)
+
+    def add_network(
+        self, namespace: str, transactions: str = None, transactions_path: str = None
+    ):
+        """Register the genesis transactions for a namespace."""
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "resolver is closed")
+        bindings.resolver_add_network(
+            self.handle, namespace, transactions, transactions_path
+        )
+
+    def set_default_namespace(self, namespace: str = None):
+        """Set the namespace used to resolve did:sov and unqualified DIDs."""
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "resolver is closed")
+        bindings.resolver_set_default_namespace(self.handle, namespace)
+
+    async def resolve(self, did: str) -> Dict:
+        """Resolve a DID to retrieve a DID Doc."""
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "resolver is closed")
+        return json.loads(await bindings.resolver_resolve(self.handle, did))
+
+    async def dereference(self, did_url: str) -> Dict:
+        """Dereference a DID Url to retrieve a ledger object."""
+        if not self.handle:
+            raise VdrError(VdrErrorCode.WRAPPER, "resolver is closed")
+        return json.loads(await bindings.resolver_dereference(self.handle, did_url))
+
+    def close(self):
+        """Close and free the resolver instance."""
+        if hasattr(self, "handle") and self.handle:
+            bindings.resolver_close(self.handle)
+            self.handle = None
+
+    def __del__(self):
+        """Close the resolver instance when there are no more references to this object."""
+        self.close()
+
+
 def get_namespace(did: str) -> str:
     matched = DID_PATTERN.match(did)
     if not matched: